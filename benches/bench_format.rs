@@ -0,0 +1,21 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use spreadsheet_ods::test_util::{formatted_workbook, rendered_workbook};
+use spreadsheet_ods::{write_ods_buf, write_ods_buf_uncompressed};
+
+fn criterion_benchmark(c: &mut Criterion) {
+    // Writing renders the cell text from the value formats.
+    let mut wb = formatted_workbook(10000);
+    c.bench_function("write formatted 10000x5", |b| {
+        b.iter(|| write_ods_buf(&mut wb, Vec::new()).expect("write"))
+    });
+
+    // Only dates and currencies and no compression, this is mostly
+    // the rendering.
+    let mut wb = rendered_workbook(10000);
+    c.bench_function("write rendered 10000x10", |b| {
+        b.iter(|| write_ods_buf_uncompressed(&mut wb, Vec::new()).expect("write"))
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
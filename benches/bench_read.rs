@@ -1,14 +1,15 @@
-// use criterion::{black_box, criterion_group, criterion_main, Criterion};
-// use spreadsheet_ods::{read_ods, OdsError};
-//
-// fn read_orders() -> Result<(), OdsError> {
-//     let mut wb = read_ods("tests/orders.ods")?;
-//     Ok(())
-// }
-//
-// fn criterion_benchmark(c: &mut Criterion) {
-//     c.bench_function("read orders", |b| b.iter(|| read_orders()));
-// }
-//
-// criterion_group!(benches, criterion_benchmark);
-// criterion_main!(benches);
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use spreadsheet_ods::test_util::dense_workbook;
+use spreadsheet_ods::{read_ods_buf, write_ods_buf};
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut wb = dense_workbook(10000, 20);
+    let buf = write_ods_buf(&mut wb, Vec::new()).expect("write");
+
+    c.bench_function("read dense 10000x20", |b| {
+        b.iter(|| read_ods_buf(black_box(&buf)).expect("read"))
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,23 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use spreadsheet_ods::test_util::{dense_workbook, sparse_workbook, styled_workbook};
+use spreadsheet_ods::write_ods_buf;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut wb = dense_workbook(10000, 20);
+    c.bench_function("write dense 10000x20", |b| {
+        b.iter(|| write_ods_buf(&mut wb, Vec::new()).expect("write"))
+    });
+
+    let mut wb = sparse_workbook(100000, 1000, 100);
+    c.bench_function("write sparse 100000x1000/100", |b| {
+        b.iter(|| write_ods_buf(&mut wb, Vec::new()).expect("write"))
+    });
+
+    let mut wb = styled_workbook(1000, 20, 1000);
+    c.bench_function("write styled 1000x20 1000 styles", |b| {
+        b.iter(|| write_ods_buf(&mut wb, Vec::new()).expect("write"))
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
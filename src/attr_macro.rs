@@ -593,6 +593,16 @@ macro_rules! fo_break {
         pub fn set_break_after(&mut self, pagebreak: PageBreak) {
             self.$acc.set_attr("fo:break-after", pagebreak.to_string());
         }
+
+        /// Parses fo:break-before.
+        pub fn break_before(&self) -> Result<Option<PageBreak>, OdsError> {
+            PageBreak::parse_attr(self.$acc.attr("fo:break-before"))
+        }
+
+        /// Parses fo:break-after.
+        pub fn break_after(&self) -> Result<Option<PageBreak>, OdsError> {
+            PageBreak::parse_attr(self.$acc.attr("fo:break-after"))
+        }
     };
 }
 
@@ -1036,6 +1046,11 @@ macro_rules! style_page_number {
             self.$acc
                 .set_attr("style:page-number", page_number.to_string());
         }
+
+        /// Parses the page number.
+        pub fn page_number(&self) -> Result<Option<PageNumber>, OdsError> {
+            PageNumber::parse_attr(self.$acc.attr("style:page-number"))
+        }
     };
 }
 
@@ -1166,6 +1181,11 @@ macro_rules! style_writing_mode {
             self.$acc
                 .set_attr("style:writing-mode", writing_mode.to_string());
         }
+
+        /// Parses the writing mode.
+        pub fn writing_mode(&self) -> Result<Option<WritingMode>, OdsError> {
+            WritingMode::parse_attr(self.$acc.attr("style:writing-mode"))
+        }
     };
 }
 
@@ -2455,6 +2475,11 @@ macro_rules! style_may_break_between_rows {
             self.$acc
                 .set_attr("style:may-break-between-rows", br.to_string());
         }
+
+        /// Parses the flag. Defaults to true.
+        pub fn may_break_between_rows(&self) -> Result<bool, OdsError> {
+            bool::parse_attr_def(self.$acc.attr("style:may-break-between-rows"), true)
+        }
     };
 }
 
@@ -2486,6 +2511,11 @@ macro_rules! style_width {
         pub fn set_width(&mut self, width: Length) {
             self.$acc.set_attr("style:width", width.to_string());
         }
+
+        /// Parses the width.
+        pub fn width(&self) -> Result<Length, OdsError> {
+            Length::parse_attr_def(self.$acc.attr("style:width"), Length::Default)
+        }
     };
 }
 
@@ -2501,6 +2531,11 @@ macro_rules! table_align {
         pub fn set_align(&mut self, align: TableAlign) {
             self.$acc.set_attr("table:align", align.to_string());
         }
+
+        /// Parses the alignment.
+        pub fn align(&self) -> Result<Option<TableAlign>, OdsError> {
+            TableAlign::parse_attr(self.$acc.attr("table:align"))
+        }
     };
 }
 
@@ -2519,6 +2554,11 @@ macro_rules! table_border_model {
         pub fn set_border_model(&mut self, border: TableBorderModel) {
             self.$acc.set_attr("table:border-model", border.to_string());
         }
+
+        /// Parses the border model.
+        pub fn border_model(&self) -> Result<Option<TableBorderModel>, OdsError> {
+            TableBorderModel::parse_attr(self.$acc.attr("table:border-model"))
+        }
     };
 }
 
@@ -2531,6 +2571,11 @@ macro_rules! table_display {
         pub fn set_display(&mut self, display: bool) {
             self.$acc.set_attr("table:display", display.to_string())
         }
+
+        /// Parses the flag. Defaults to true.
+        pub fn display(&self) -> Result<bool, OdsError> {
+            bool::parse_attr_def(self.$acc.attr("table:display"), true)
+        }
     };
 }
 
@@ -2541,6 +2586,11 @@ macro_rules! table_tab_color {
         pub fn set_tab_color(&mut self, color: Rgb<u8>) {
             self.$acc.set_attr("table:tab-color", color_string(color));
         }
+
+        /// Parses the color of the tab.
+        pub fn tab_color(&self) -> Result<Option<Rgb<u8>>, OdsError> {
+            Rgb::parse_attr(self.$acc.attr("table:tab-color"))
+        }
     };
 }
 
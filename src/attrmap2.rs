@@ -5,12 +5,41 @@
 //!
 
 use std::collections::{hash_map, HashMap};
+use std::mem::size_of;
+#[cfg(feature = "use_string_cache")]
 use string_cache::DefaultAtom;
 
+/// Attribute names are interned with the feature "use_string_cache".
+#[cfg(feature = "use_string_cache")]
+pub(crate) type AttrName = DefaultAtom;
+/// Attribute names are interned with the feature "use_string_cache".
+#[cfg(not(feature = "use_string_cache"))]
+pub(crate) type AttrName = String;
+
 /// Container type for attributes.
 #[derive(Default, Clone, Debug)]
 pub struct AttrMap2 {
-    map: Option<HashMap<DefaultAtom, String>>,
+    map: Option<HashMap<AttrName, String>>,
+}
+
+#[cfg(feature = "use_string_cache")]
+fn get<'a>(map: &'a HashMap<AttrName, String>, name: &str) -> Option<&'a String> {
+    map.get(&DefaultAtom::from(name))
+}
+
+#[cfg(not(feature = "use_string_cache"))]
+fn get<'a>(map: &'a HashMap<AttrName, String>, name: &str) -> Option<&'a String> {
+    map.get(name)
+}
+
+#[cfg(feature = "use_string_cache")]
+fn remove(map: &mut HashMap<AttrName, String>, name: &str) -> Option<String> {
+    map.remove(&DefaultAtom::from(name))
+}
+
+#[cfg(not(feature = "use_string_cache"))]
+fn remove(map: &mut HashMap<AttrName, String>, name: &str) -> Option<String> {
+    map.remove(name)
 }
 
 impl AttrMap2 {
@@ -30,7 +59,7 @@ impl AttrMap2 {
     pub fn add_all(&mut self, data: &[(&str, String)]) {
         let attr = self.map.get_or_insert_with(HashMap::new);
         for (name, value) in data {
-            attr.insert(DefaultAtom::from(*name), value.to_string());
+            attr.insert(AttrName::from(*name), value.to_string());
         }
     }
 
@@ -38,13 +67,13 @@ impl AttrMap2 {
     pub fn set_attr(&mut self, name: &str, value: String) {
         self.map
             .get_or_insert_with(HashMap::new)
-            .insert(DefaultAtom::from(name), value);
+            .insert(AttrName::from(name), value);
     }
 
     /// Removes an attribute.
     pub fn clear_attr(&mut self, name: &str) -> Option<String> {
         if let Some(ref mut attr) = self.map {
-            attr.remove(&DefaultAtom::from(name))
+            remove(attr, name)
         } else {
             None
         }
@@ -53,7 +82,7 @@ impl AttrMap2 {
     /// Returns the attribute.
     pub fn attr(&self, name: &str) -> Option<&String> {
         if let Some(ref prp) = self.map {
-            prp.get(&DefaultAtom::from(name))
+            get(prp, name)
         } else {
             None
         }
@@ -65,7 +94,7 @@ impl AttrMap2 {
         S: Into<&'a str>,
     {
         if let Some(ref prp) = self.map {
-            if let Some(value) = prp.get(&DefaultAtom::from(name)) {
+            if let Some(value) = get(prp, name) {
                 value.as_ref()
             } else {
                 default.into()
@@ -78,12 +107,22 @@ impl AttrMap2 {
     pub fn iter(&self) -> AttrMapIter<'_> {
         From::from(self)
     }
+
+    /// Estimated heap memory.
+    pub(crate) fn heap_size(&self) -> usize {
+        if let Some(map) = &self.map {
+            map.capacity() * (size_of::<AttrName>() + size_of::<String>())
+                + map.values().map(|v| v.capacity()).sum::<usize>()
+        } else {
+            0
+        }
+    }
 }
 
 /// Iterator for an AttrMap.
 #[derive(Debug)]
 pub struct AttrMapIter<'a> {
-    it: Option<hash_map::Iter<'a, DefaultAtom, String>>,
+    it: Option<hash_map::Iter<'a, AttrName, String>>,
 }
 
 impl<'a> From<&'a AttrMap2> for AttrMapIter<'a> {
@@ -99,11 +138,11 @@ impl<'a> From<&'a AttrMap2> for AttrMapIter<'a> {
 }
 
 impl<'a> Iterator for AttrMapIter<'a> {
-    type Item = (&'a DefaultAtom, &'a String);
+    type Item = (&'a str, &'a String);
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(it) = &mut self.it {
-            it.next()
+            it.next().map(|(k, v)| (AsRef::<str>::as_ref(k), v))
         } else {
             None
         }
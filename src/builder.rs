@@ -0,0 +1,173 @@
+//!
+//! Builds a workbook in one expression.
+//!
+//! Handy for examples, tests and small scripts. The rows are appended one
+//! after the other, the first row of a sheet is usually the header.
+//! Rows with values of different types use the values! macro.
+//!
+//! ```
+//! use spreadsheet_ods::builder::{SheetBuilder, WorkBookBuilder};
+//! use spreadsheet_ods::style::CellStyle;
+//! use spreadsheet_ods::{values, CellStyleRef};
+//!
+//! let mut header = CellStyle::new_empty();
+//! header.set_name("header");
+//! header.set_font_bold();
+//!
+//! let wb = WorkBookBuilder::new()
+//!     .cellstyle(header)
+//!     .sheet(
+//!         SheetBuilder::new("Orders")
+//!             .styled_row(["name", "amount"], &CellStyleRef::from("header"))
+//!             .freeze_header()
+//!             .row(values!["apples", 3])
+//!             .row(values!["pears", 5]),
+//!     )
+//!     .build();
+//!
+//! assert_eq!(wb.sheet(0).value(2, 1).as_i32_or(0), 5);
+//! ```
+//!
+
+use crate::style::CellStyle;
+use crate::theme::Theme;
+use crate::{CellStyleRef, Length, Sheet, Value, WorkBook};
+
+/// Builds a WorkBook.
+#[derive(Debug)]
+pub struct WorkBookBuilder {
+    book: WorkBook,
+}
+
+impl Default for WorkBookBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WorkBookBuilder {
+    /// Starts with an empty workbook, see WorkBook::new_empty().
+    pub fn new() -> Self {
+        Self {
+            book: WorkBook::new_empty(),
+        }
+    }
+
+    /// Starts with an existing workbook, e.g. one with the default
+    /// styles of a locale.
+    pub fn from_workbook(book: WorkBook) -> Self {
+        Self { book }
+    }
+
+    /// Adds a cell style.
+    pub fn cellstyle(mut self, style: CellStyle) -> Self {
+        self.book.add_cellstyle(style);
+        self
+    }
+
+    /// Installs a theme.
+    pub fn theme(mut self, theme: &Theme) -> Self {
+        self.book.install_theme(theme);
+        self
+    }
+
+    /// Appends a sheet.
+    pub fn sheet(mut self, sheet: SheetBuilder) -> Self {
+        self.book.push_sheet(sheet.build());
+        self
+    }
+
+    /// The workbook.
+    pub fn build(self) -> WorkBook {
+        self.book
+    }
+}
+
+/// Builds a Sheet row by row.
+#[derive(Debug)]
+pub struct SheetBuilder {
+    sheet: Sheet,
+    row: u32,
+}
+
+impl SheetBuilder {
+    /// Empty sheet.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            sheet: Sheet::new(name),
+            row: 0,
+        }
+    }
+
+    /// Appends a row. The values start with the first column.
+    pub fn row<I, V>(self, values: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        self.append(values, None)
+    }
+
+    /// Appends a row where all cells have the style.
+    pub fn styled_row<I, V>(self, values: I, style: &CellStyleRef) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        self.append(values, Some(style))
+    }
+
+    /// Appends empty rows.
+    pub fn skip_rows(mut self, n: u32) -> Self {
+        self.row += n;
+        self
+    }
+
+    /// Sets the width of a column.
+    pub fn col_width(mut self, col: u32, width: Length) -> Self {
+        self.sheet.set_col_width(col, width);
+        self
+    }
+
+    /// Freezes the first row. See Sheet::freeze_header_row().
+    pub fn freeze_header(mut self) -> Self {
+        self.sheet.freeze_header_row();
+        self
+    }
+
+    /// The sheet.
+    pub fn build(self) -> Sheet {
+        self.sheet
+    }
+
+    fn append<I, V>(mut self, values: I, style: Option<&CellStyleRef>) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        for (col, value) in values.into_iter().enumerate() {
+            let col = col as u32;
+            match style {
+                Some(style) => self.sheet.set_styled_value(self.row, col, value, style),
+                None => self.sheet.set_value(self.row, col, value),
+            }
+        }
+        self.row += 1;
+        self
+    }
+}
+
+/// A row of values of different types for SheetBuilder.
+///
+/// ```
+/// use spreadsheet_ods::{values, Value};
+///
+/// let row: Vec<Value> = values!["apples", 3, 1.5, true];
+/// assert_eq!(row.len(), 4);
+/// ```
+#[macro_export]
+macro_rules! values {
+    ($($v:expr),* $(,)?) => {
+        vec![$($crate::Value::from($v)),*]
+    };
+}
@@ -0,0 +1,394 @@
+//!
+//! Finds the parts of a workbook that are not plain ODF 1.2 or 1.3.
+//!
+//! Other applications, Excel in particular, ignore the extensions of
+//! LibreOffice (loext, calcext, tableooo, ...). The report lists the
+//! attributes, elements and functions that rely on them, and the
+//! attributes that were only added with ODF 1.3.
+//!
+//! ```
+//! use spreadsheet_ods::compat::Conformance;
+//! use spreadsheet_ods::{Sheet, WorkBook};
+//!
+//! let mut wb = WorkBook::new_empty();
+//! let mut sheet = Sheet::new("1");
+//! sheet.set_formula(0, 0, "of:=ORG.LIBREOFFICE.RAWSUBTRACT(1;2)");
+//! wb.push_sheet(sheet);
+//!
+//! let report = wb.compat_report();
+//! assert!(report.uses_extensions());
+//! for issue in report.issues() {
+//!     assert_eq!(issue.conformance, Conformance::Extension("ORG.LIBREOFFICE".to_string()));
+//! }
+//! ```
+//!
+
+use crate::attrmap2::AttrMap2;
+use crate::format::ValueFormatTrait;
+use crate::xmltree::{XmlContent, XmlTag};
+use crate::{CellRef, WorkBook};
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+/// What is needed by a part of the workbook.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Conformance {
+    /// Was added with ODF 1.3.
+    Odf13,
+    /// Extension outside of the ODF standard. Contains the namespace
+    /// prefix like "loext" or the namespace of the function like
+    /// "ORG.LIBREOFFICE".
+    Extension(String),
+}
+
+/// One part of the workbook that is not plain ODF 1.2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompatIssue {
+    /// What is needed.
+    pub conformance: Conformance,
+    /// Where it is used. A style, a format, a sheet or a cell.
+    pub location: String,
+    /// Name of the attribute, element or function.
+    pub name: String,
+}
+
+impl Display for CompatIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.conformance {
+            Conformance::Odf13 => write!(f, "{}: {} needs ODF 1.3", self.location, self.name),
+            Conformance::Extension(ns) => write!(
+                f,
+                "{}: {} is an extension ({})",
+                self.location, self.name, ns
+            ),
+        }
+    }
+}
+
+/// Adapts the output of the writer for other applications.
+/// See WorkBook::set_write_profile().
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteProfile {
+    /// Everything is written as it is.
+    #[default]
+    Default,
+    /// For the ODS import of Excel.
+    ///
+    /// * Attributes and elements of extensions like loext or calcext are
+    ///   left out, this includes the custom metadata of the cells.
+    /// * The ODF 1.3 attributes of value formats are left out and the
+    ///   office:version is "1.2".
+    /// * Formulas without a namespace prefix get "of:".
+    /// * Excel shows the cached value of a formula until the sheet is
+    ///   recalculated. Use Sheet::set_formula_value() to write one,
+    ///   a formula cell without a value stays empty.
+    Excel,
+}
+
+/// Result of WorkBook::compat_report().
+#[derive(Debug, Clone, Default)]
+pub struct CompatReport {
+    version: String,
+    issues: Vec<CompatIssue>,
+}
+
+impl CompatReport {
+    /// The office:version of the workbook.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// All issues.
+    pub fn issues(&self) -> &Vec<CompatIssue> {
+        &self.issues
+    }
+
+    /// Any extensions used?
+    pub fn uses_extensions(&self) -> bool {
+        self.issues
+            .iter()
+            .any(|v| matches!(v.conformance, Conformance::Extension(_)))
+    }
+
+    /// Namespaces of the extensions that are used.
+    pub fn extensions(&self) -> BTreeSet<&str> {
+        self.issues
+            .iter()
+            .filter_map(|v| match &v.conformance {
+                Conformance::Extension(ns) => Some(ns.as_str()),
+                Conformance::Odf13 => None,
+            })
+            .collect()
+    }
+
+    /// The lowest ODF version that contains all the features
+    /// apart from the extensions. Either "1.2" or "1.3".
+    pub fn min_version(&self) -> &'static str {
+        if self
+            .issues
+            .iter()
+            .any(|v| v.conformance == Conformance::Odf13)
+        {
+            "1.3"
+        } else {
+            "1.2"
+        }
+    }
+
+    /// The workbook uses no extensions, the office:version is
+    /// "1.2" or "1.3" and it covers all the features used.
+    pub fn is_strict(&self) -> bool {
+        !self.uses_extensions()
+            && match self.version.as_str() {
+                "1.3" => true,
+                "1.2" => self.min_version() == "1.2",
+                _ => false,
+            }
+    }
+}
+
+// Namespaces of ODF 1.2 and the ones it uses from other standards.
+const ODF_PREFIXES: &[&str] = &[
+    "anim",
+    "chart",
+    "config",
+    "db",
+    "dc",
+    "dom",
+    "dr3d",
+    "draw",
+    "fo",
+    "form",
+    "grddl",
+    "math",
+    "meta",
+    "number",
+    "of",
+    "office",
+    "presentation",
+    "script",
+    "smil",
+    "style",
+    "svg",
+    "table",
+    "text",
+    "xforms",
+    "xhtml",
+    "xlink",
+    "xml",
+    "xmlns",
+    "xsd",
+    "xsi",
+];
+
+// Attributes that were added with ODF 1.3.
+const ODF13_ATTRS: &[&str] = &[
+    "number:exponent-interval",
+    "number:forced-exponent-sign",
+    "number:max-denominator-value",
+    "number:min-decimal-places",
+];
+
+// Function namespaces outside of OpenFormula.
+const FUNCTION_PREFIXES: &[&str] = &["ORG.LIBREOFFICE.", "COM.MICROSOFT."];
+
+/// Attributes and elements that are not written with WriteProfile::Excel.
+pub(crate) fn excel_skips(name: &str) -> bool {
+    match name.split_once(':') {
+        Some((prefix, _)) => !ODF_PREFIXES.contains(&prefix) || ODF13_ATTRS.contains(&name),
+        None => false,
+    }
+}
+
+/// Checks the whole workbook.
+pub(crate) fn compat_report(book: &WorkBook) -> CompatReport {
+    let mut report = CompatReport {
+        version: book.version().clone(),
+        issues: Vec::new(),
+    };
+
+    for (name, font) in &book.fonts {
+        check_attrs(&mut report, &format!("font {}", name), font.attrmap());
+    }
+
+    for (name, style) in &book.tablestyles {
+        let location = format!("table style {}", name);
+        check_attrs(&mut report, &location, style.attrmap());
+        check_attrs(&mut report, &location, style.tablestyle());
+    }
+    for (name, style) in &book.rowstyles {
+        let location = format!("row style {}", name);
+        check_attrs(&mut report, &location, style.attrmap());
+        check_attrs(&mut report, &location, style.rowstyle());
+    }
+    for (name, style) in &book.colstyles {
+        let location = format!("column style {}", name);
+        check_attrs(&mut report, &location, style.attrmap());
+        check_attrs(&mut report, &location, style.colstyle());
+    }
+    for (name, style) in &book.cellstyles {
+        let location = format!("cell style {}", name);
+        check_attrs(&mut report, &location, style.attrmap());
+        check_attrs(&mut report, &location, style.cellstyle());
+        check_attrs(&mut report, &location, style.paragraphstyle());
+        check_attrs(&mut report, &location, style.textstyle());
+    }
+    for (name, style) in &book.paragraphstyles {
+        let location = format!("paragraph style {}", name);
+        check_attrs(&mut report, &location, style.attrmap());
+        check_attrs(&mut report, &location, style.paragraphstyle());
+        check_attrs(&mut report, &location, style.textstyle());
+    }
+    for (name, style) in &book.textstyles {
+        let location = format!("text style {}", name);
+        check_attrs(&mut report, &location, style.attrmap());
+        check_attrs(&mut report, &location, style.textstyle());
+    }
+    for (name, style) in &book.graphicstyles {
+        let location = format!("graphic style {}", name);
+        check_attrs(&mut report, &location, style.attrmap());
+        check_attrs(&mut report, &location, style.graphicstyle());
+        check_attrs(&mut report, &location, style.paragraphstyle());
+        check_attrs(&mut report, &location, style.textstyle());
+    }
+    for (name, style) in &book.pagestyles {
+        let location = format!("page style {}", name);
+        check_attrs(&mut report, &location, style.style());
+        check_attrs(&mut report, &location, style.headerstyle().style());
+        check_attrs(&mut report, &location, style.footerstyle().style());
+    }
+
+    check_formats(&mut report, book.formats_boolean.values());
+    check_formats(&mut report, book.formats_number.values());
+    check_formats(&mut report, book.formats_percentage.values());
+    check_formats(&mut report, book.formats_currency.values());
+    check_formats(&mut report, book.formats_text.values());
+    check_formats(&mut report, book.formats_datetime.values());
+    check_formats(&mut report, book.formats_timeduration.values());
+
+    for sheet in book.sheets.iter() {
+        for ((row, col), cell) in sheet.data.iter() {
+            let location = CellRef::remote(sheet.name(), *row, *col).to_string();
+            if let Some(formula) = &cell.formula {
+                check_formula(&mut report, &location, formula);
+            }
+            if let Some(extra) = &cell.extra {
+                for key in extra.metadata.keys() {
+                    report.issues.push(CompatIssue {
+                        conformance: Conformance::Extension("sods".to_string()),
+                        location: location.clone(),
+                        name: format!("sods:{}", key),
+                    });
+                }
+                if extra.content_type.is_some() {
+                    report.issues.push(CompatIssue {
+                        conformance: Conformance::Extension("calcext".to_string()),
+                        location: location.clone(),
+                        name: "calcext:value-type".to_string(),
+                    });
+                }
+                for tag in extra.shapes.iter() {
+                    check_tag(&mut report, &location, tag);
+                }
+            }
+        }
+        let location = format!("sheet {}", sheet.name());
+        for tag in sheet.extra.iter() {
+            check_tag(&mut report, &location, tag);
+        }
+    }
+
+    for tag in book.extra.iter() {
+        check_tag(&mut report, "workbook", tag);
+    }
+
+    report
+}
+
+fn check_formats<'a, T: ValueFormatTrait + 'a>(
+    report: &mut CompatReport,
+    formats: impl Iterator<Item = &'a T>,
+) {
+    for format in formats {
+        let location = format!("value format {}", format.name());
+        check_attrs(report, &location, format.attrmap());
+        for part in format.parts() {
+            check_attrs(report, &location, part.attrmap());
+        }
+    }
+}
+
+fn check_attrs(report: &mut CompatReport, location: &str, attrmap: &AttrMap2) {
+    for (k, _) in attrmap.iter() {
+        check_name(report, location, k);
+    }
+}
+
+fn check_tag(report: &mut CompatReport, location: &str, tag: &XmlTag) {
+    check_name(report, location, tag.name());
+    for (k, _) in tag.attrmap().iter() {
+        check_name(report, location, k);
+    }
+    for v in tag.content() {
+        if let XmlContent::Tag(t) = v {
+            check_tag(report, location, t);
+        }
+    }
+}
+
+fn check_name(report: &mut CompatReport, location: &str, name: &str) {
+    if let Some((prefix, _)) = name.split_once(':') {
+        if !ODF_PREFIXES.contains(&prefix) {
+            report.issues.push(CompatIssue {
+                conformance: Conformance::Extension(prefix.to_string()),
+                location: location.to_string(),
+                name: name.to_string(),
+            });
+        } else if ODF13_ATTRS.contains(&name) {
+            report.issues.push(CompatIssue {
+                conformance: Conformance::Odf13,
+                location: location.to_string(),
+                name: name.to_string(),
+            });
+        }
+    }
+}
+
+fn check_formula(report: &mut CompatReport, location: &str, formula: &str) {
+    // Formulas in the old syntax of OpenOffice.
+    if let Some((prefix @ ("oooc" | "msoxl"), _)) = formula.split_once(':') {
+        report.issues.push(CompatIssue {
+            conformance: Conformance::Extension(prefix.to_string()),
+            location: location.to_string(),
+            name: formula.to_string(),
+        });
+        return;
+    }
+
+    let mut chars = formula.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            // Skip strings and references.
+            '"' => chars.by_ref().take_while(|(_, c)| *c != '"').for_each(drop),
+            '[' => chars.by_ref().take_while(|(_, c)| *c != ']').for_each(drop),
+            _ => {
+                let rest = &formula[idx..];
+                if let Some(prefix) = FUNCTION_PREFIXES.iter().find(|v| rest.starts_with(*v)) {
+                    let len = rest
+                        .find(|c: char| !(c.is_alphanumeric() || c == '.' || c == '_'))
+                        .unwrap_or(rest.len());
+                    report.issues.push(CompatIssue {
+                        conformance: Conformance::Extension(
+                            prefix.trim_end_matches('.').to_string(),
+                        ),
+                        location: location.to_string(),
+                        name: rest[..len].to_string(),
+                    });
+                    while matches!(chars.peek(), Some((i, _)) if *i < idx + len) {
+                        chars.next();
+                    }
+                }
+            }
+        }
+    }
+}
@@ -87,6 +87,49 @@ from_x_conditionvalue!(f32);
 from_x_conditionvalue!(f64);
 from_x_conditionvalue!(bool);
 
+/// Comparison of a ValueCondition.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Lte,
+    Gte,
+}
+
+impl Display for CompareOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let op = match self {
+            CompareOp::Eq => "=",
+            CompareOp::Ne => "!=",
+            CompareOp::Lt => "<",
+            CompareOp::Gt => ">",
+            CompareOp::Lte => "<=",
+            CompareOp::Gte => ">=",
+        };
+        write!(f, "{}", op)
+    }
+}
+
+/// The parts of a ValueCondition, see ValueCondition::kind(). The values
+/// are kept as they are written in the condition, text includes the
+/// quotes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValueConditionKind {
+    /// Compares the cell-content with a value.
+    Content(CompareOp, String),
+    /// Compares the value with a value. Used in value formats.
+    Value(CompareOp, String),
+    /// Range check of the cell-content.
+    ContentIsBetween(String, String),
+    /// Range check of the cell-content.
+    ContentIsNotBetween(String, String),
+    /// Formula.
+    TrueFormula(String),
+}
+
 /// Defines a condition that compares the cell-content with a value.
 #[derive(Clone, Debug)]
 pub struct ValueCondition {
@@ -183,6 +226,74 @@ impl ValueCondition {
         buf.push(')');
         ValueCondition { cond: buf }
     }
+
+    /// Compares the value with a value. This is the condition for the
+    /// style-maps of value formats, e.g. "value()>=0".
+    pub fn value_cmp<V: Into<Value>>(op: CompareOp, value: V) -> ValueCondition {
+        ValueCondition {
+            cond: format!("value(){}{}", op, value.into()),
+        }
+    }
+
+    /// Splits the condition into its parts. Returns None if the condition
+    /// is not one of the known forms.
+    pub fn kind(&self) -> Option<ValueConditionKind> {
+        let cond = self.cond.trim();
+        if let Some(v) = cond.strip_prefix("cell-content()") {
+            let (op, v) = split_op(v)?;
+            Some(ValueConditionKind::Content(op, v.trim().to_string()))
+        } else if let Some(v) = cond.strip_prefix("value()") {
+            let (op, v) = split_op(v)?;
+            Some(ValueConditionKind::Value(op, v.trim().to_string()))
+        } else if let Some(v) = strip_fn(cond, "cell-content-is-between(") {
+            let (from, to) = split_args(v)?;
+            Some(ValueConditionKind::ContentIsBetween(from, to))
+        } else if let Some(v) = strip_fn(cond, "cell-content-is-not-between(") {
+            let (from, to) = split_args(v)?;
+            Some(ValueConditionKind::ContentIsNotBetween(from, to))
+        } else {
+            strip_fn(cond, "is-true-formula(")
+                .map(|v| ValueConditionKind::TrueFormula(v.to_string()))
+        }
+    }
+}
+
+// Comparison operator at the start.
+fn split_op(v: &str) -> Option<(CompareOp, &str)> {
+    let v = v.trim_start();
+    for (op, s) in [
+        (CompareOp::Lte, "<="),
+        (CompareOp::Gte, ">="),
+        (CompareOp::Ne, "!="),
+        (CompareOp::Lt, "<"),
+        (CompareOp::Gt, ">"),
+        (CompareOp::Eq, "="),
+    ] {
+        if let Some(v) = v.strip_prefix(s) {
+            return Some((op, v));
+        }
+    }
+    None
+}
+
+// Content of a function call.
+fn strip_fn<'a>(v: &'a str, name: &str) -> Option<&'a str> {
+    v.strip_prefix(name).and_then(|v| v.strip_suffix(')'))
+}
+
+// Splits at the first comma outside of a string.
+fn split_args(v: &str) -> Option<(String, String)> {
+    let mut quoted = false;
+    for (idx, c) in v.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                return Some((v[..idx].trim().to_string(), v[idx + 1..].trim().to_string()))
+            }
+            _ => {}
+        }
+    }
+    None
 }
 
 /// Defines a condition for a cell-validation.
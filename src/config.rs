@@ -132,6 +132,38 @@ impl ConfigMap {
         }
     }
 
+    /// Removes a ConfigItem.
+    pub(crate) fn remove<S>(&mut self, name: S) -> Option<ConfigItem>
+    where
+        S: AsRef<str>,
+    {
+        let idx = self.key_index.remove(name.as_ref())?;
+        let (_, item) = self.values.remove(idx);
+        for v in self.key_index.values_mut() {
+            if *v > idx {
+                *v -= 1;
+            }
+        }
+        Some(item)
+    }
+
+    /// Changes the name of a ConfigItem and keeps its position. An item
+    /// with the new name is replaced.
+    pub(crate) fn rename<S, T>(&mut self, name: S, new_name: T)
+    where
+        S: AsRef<str>,
+        T: AsRef<str>,
+    {
+        if name.as_ref() == new_name.as_ref() || !self.key_index.contains_key(name.as_ref()) {
+            return;
+        }
+        self.remove(new_name.as_ref());
+        if let Some(idx) = self.key_index.remove(name.as_ref()) {
+            self.values[idx].0 = new_name.as_ref().to_string();
+            self.key_index.insert(new_name.as_ref().to_string(), idx);
+        }
+    }
+
     /// Returns a ConfigItem or creates it.
     pub(crate) fn get_or_create<S, F>(&mut self, name: S, default: F) -> &mut ConfigItem
     where
@@ -368,6 +400,37 @@ impl ConfigItem {
         }
     }
 
+    /// Removes a ConfigItem from this map.
+    ///
+    /// Panics
+    /// If this is not a map-like ConfigItem.
+    pub(crate) fn remove<S>(&mut self, name: S) -> Option<ConfigItem>
+    where
+        S: AsRef<str>,
+    {
+        if let Some(m) = self.as_map_mut() {
+            m.remove(name)
+        } else {
+            panic!();
+        }
+    }
+
+    /// Changes the name of a ConfigItem in this map.
+    ///
+    /// Panics
+    /// If this is not a map-like ConfigItem.
+    pub(crate) fn rename<S, T>(&mut self, name: S, new_name: T)
+    where
+        S: AsRef<str>,
+        T: AsRef<str>,
+    {
+        if let Some(m) = self.as_map_mut() {
+            m.rename(name, new_name);
+        } else {
+            panic!();
+        }
+    }
+
     /// Recursively creates all maps along the given path and
     /// returns the last map-like ConfigItem.
     ///
@@ -585,6 +648,31 @@ mod tests {
         assert_eq!(v, &ConfigItem::Entry(ConfigMap::new()));
     }
 
+    #[test]
+    fn test_rename() {
+        let mut config = setup_config();
+
+        let v = config.create_path(&[
+            ("ooo:configuration-settings", ConfigItemType::Set),
+            ("ScriptConfiguration", ConfigItemType::Map),
+        ]);
+        v.insert("Tabelle2", ConfigItem::new_entry());
+        v.insert("Tabelle3", ConfigItem::new_entry());
+        v.rename("Tabelle1", "Tabelle3");
+
+        let names: Vec<_> = v.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(names, vec!["Tabelle3", "Tabelle2"]);
+        assert_eq!(
+            config.get_value(&[
+                "ooo:configuration-settings",
+                "ScriptConfiguration",
+                "Tabelle3",
+                "CodeName"
+            ]),
+            Some(&ConfigValue::String("Tabelle1".to_string()))
+        );
+    }
+
     #[test]
     #[should_panic]
     fn test_create_path() {
@@ -0,0 +1,80 @@
+//!
+//! Read only access to the DDE connections of a document.
+//!
+//! DDE sources are kept as xml and written back unchanged. A sheet can
+//! be linked via DDE, the DDE() function uses table:dde-links and text
+//! fields use text:dde-connection-decls.
+//!
+//! ```
+//! use spreadsheet_ods::WorkBook;
+//!
+//! fn report(book: &WorkBook) {
+//!     for dde in book.dde_sources() {
+//!         println!(
+//!             "{:?} {:?} {:?}",
+//!             dde.application(),
+//!             dde.topic(),
+//!             dde.item()
+//!         );
+//!     }
+//! }
+//! ```
+//!
+
+use crate::xmltree::{XmlContent, XmlTag};
+
+/// An office:dde-source or text:dde-connection-decl.
+#[derive(Debug, Clone, Copy)]
+pub struct DdeSource<'a> {
+    tag: &'a XmlTag,
+}
+
+impl<'a> DdeSource<'a> {
+    /// Name of the connection.
+    pub fn name(&self) -> Option<&'a str> {
+        self.attr("office:name")
+    }
+
+    /// Application that provides the data.
+    pub fn application(&self) -> Option<&'a str> {
+        self.attr("office:dde-application")
+    }
+
+    /// Topic, usually a file.
+    pub fn topic(&self) -> Option<&'a str> {
+        self.attr("office:dde-topic")
+    }
+
+    /// Item within the topic, e.g. a cell range.
+    pub fn item(&self) -> Option<&'a str> {
+        self.attr("office:dde-item")
+    }
+
+    /// Is the data updated automatically. Defaults to true.
+    pub fn automatic_update(&self) -> bool {
+        self.attr("office:automatic-update") != Some("false")
+    }
+
+    /// Any attribute.
+    pub fn attr(&self, name: &str) -> Option<&'a str> {
+        self.tag.attrmap().attr(name).map(|v| v.as_str())
+    }
+
+    /// The DDE source as xml.
+    pub fn xml(&self) -> &'a XmlTag {
+        self.tag
+    }
+}
+
+/// Finds all DDE sources below the tag.
+pub(crate) fn dde_sources<'a>(tag: &'a XmlTag, res: &mut Vec<DdeSource<'a>>) {
+    if tag.name() == "office:dde-source" || tag.name() == "text:dde-connection-decl" {
+        res.push(DdeSource { tag });
+    } else {
+        for v in tag.content() {
+            if let XmlContent::Tag(t) = v {
+                dde_sources(t, res);
+            }
+        }
+    }
+}
@@ -3,7 +3,7 @@
 //!
 
 use crate::format::ValueFormatRef;
-use crate::style::CellStyle;
+use crate::style::{CellStyle, GraphicStyleRef, TextStyleRef};
 use crate::{format, CellStyleRef, ValueType, WorkBook};
 use icu_locid::locale;
 
@@ -109,6 +109,24 @@ impl DefaultStyle {
     pub fn time_interval() -> CellStyleRef {
         CellStyleRef::from("default-interval")
     }
+
+    /// Named text style "Internet Link" of LibreOffice. Used for
+    /// hyperlinks without a text:style-name. Must be a named style in
+    /// styles.xml.
+    pub fn internet_link() -> TextStyleRef {
+        TextStyleRef::from("Internet_20_link")
+    }
+
+    /// Named text style "Visited Internet Link" of LibreOffice.
+    pub fn visited_internet_link() -> TextStyleRef {
+        TextStyleRef::from("Visited_20_Internet_20_Link")
+    }
+
+    /// Named graphic style "Note" of LibreOffice. Used for the
+    /// annotations of cells.
+    pub fn note() -> GraphicStyleRef {
+        GraphicStyleRef::from("Note")
+    }
 }
 
 /// Replaced with WorkBook::locale_settings() or WorkBook::new(l: Locale).
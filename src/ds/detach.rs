@@ -1,9 +1,13 @@
 ///! Allows to detach data and reattach it later.
+///!
+///! The data is shared between clones and copied on the first
+///! mutable access.
 use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
 
 #[derive(Debug)]
 pub(crate) struct Detach<T> {
-    val: Option<Box<T>>,
+    val: Option<Arc<T>>,
 }
 
 impl<T> Default for Detach<T> {
@@ -12,17 +16,10 @@ impl<T> Default for Detach<T> {
     }
 }
 
-impl<T> Clone for Detach<T>
-where
-    T: Clone,
-{
+impl<T> Clone for Detach<T> {
     fn clone(&self) -> Self {
-        if let Some(t) = &self.val {
-            Detach {
-                val: Some(t.clone()),
-            }
-        } else {
-            Detach { val: None }
+        Detach {
+            val: self.val.clone(),
         }
     }
 }
@@ -35,9 +32,9 @@ impl<T> Deref for Detach<T> {
     }
 }
 
-impl<T> DerefMut for Detach<T> {
+impl<T: Clone> DerefMut for Detach<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.val.as_mut().expect("already detached")
+        Arc::make_mut(self.val.as_mut().expect("already detached"))
     }
 }
 
@@ -45,7 +42,7 @@ impl<T> Detach<T> {
     #[allow(dead_code)]
     pub(crate) fn new(val: T) -> Self {
         Self {
-            val: Some(Box::new(val)),
+            val: Some(Arc::new(val)),
         }
     }
 
@@ -55,6 +52,15 @@ impl<T> Detach<T> {
         self.val.is_none()
     }
 
+    /// Both share the same data.
+    #[allow(dead_code)]
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.val, &other.val) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+
     /// Detaches the data and links it with a key for reattaching.
     /// The key is not used here, but contains information for reattaching
     /// where ever this is used.
@@ -62,15 +68,19 @@ impl<T> Detach<T> {
     /// Panics
     ///
     /// Panics if the data was already detached.
-    pub(crate) fn detach<K: Copy>(&mut self, key: K) -> Detached<K, T> {
+    pub(crate) fn detach<K: Copy>(&mut self, key: K) -> Detached<K, T>
+    where
+        T: Clone,
+    {
         let val = self.val.take().expect("already detached");
-        Detached::new(key, val)
+        let val = Arc::try_unwrap(val).unwrap_or_else(|v| (*v).clone());
+        Detached::new(key, Box::new(val))
     }
 
     /// Reattaches the data.
     pub(crate) fn attach<K: Copy>(&mut self, detached: Detached<K, T>) {
         let Detached { key: _, val } = detached;
-        self.val.replace(val);
+        self.val.replace(Arc::from(val));
     }
 
     /// Returns a reference to the data.
@@ -87,8 +97,11 @@ impl<T> Detach<T> {
     /// Panics
     ///
     /// Panics if the data was detached.
-    pub(crate) fn as_mut(&mut self) -> &mut T {
-        self.val.as_mut().expect("already detached")
+    pub(crate) fn as_mut(&mut self) -> &mut T
+    where
+        T: Clone,
+    {
+        Arc::make_mut(self.val.as_mut().expect("already detached"))
     }
 
     /// Dissolves this container.
@@ -96,15 +109,31 @@ impl<T> Detach<T> {
     /// Panics
     ///
     /// Panics if the data was detached.
-    pub(crate) fn take(mut self) -> T {
-        *self.val.take().expect("already detached")
+    pub(crate) fn take(mut self) -> T
+    where
+        T: Clone,
+    {
+        let val = self.val.take().expect("already detached");
+        Arc::try_unwrap(val).unwrap_or_else(|v| (*v).clone())
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Detach<T>
+where
+    &'a T: IntoIterator,
+{
+    type Item = <&'a T as IntoIterator>::Item;
+    type IntoIter = <&'a T as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_ref().into_iter()
     }
 }
 
 impl<T> From<T> for Detach<T> {
     fn from(val: T) -> Self {
         Self {
-            val: Some(Box::new(val)),
+            val: Some(Arc::new(val)),
         }
     }
 }
@@ -0,0 +1,82 @@
+//!
+//! Event listeners of the document and the sheets.
+//!
+//! These bind events like "dom:load" to macros or scripts. They are kept
+//! as xml and can be listed and removed.
+//!
+//! ```
+//! use spreadsheet_ods::WorkBook;
+//!
+//! fn scrub(book: &mut WorkBook) {
+//!     for listener in book.event_listeners() {
+//!         println!("{:?} -> {:?}", listener.event_name(), listener.href());
+//!     }
+//!     book.clear_event_listeners();
+//!     for idx in 0..book.num_sheets() {
+//!         book.sheet_mut(idx).clear_event_listeners();
+//!     }
+//! }
+//! ```
+//!
+
+use crate::xmltree::{XmlContent, XmlTag};
+
+/// A script:event-listener or presentation:event-listener.
+#[derive(Debug, Clone, Copy)]
+pub struct EventListener<'a> {
+    tag: &'a XmlTag,
+}
+
+impl<'a> EventListener<'a> {
+    /// Name of the event, e.g. "dom:load".
+    pub fn event_name(&self) -> Option<&'a str> {
+        self.attr("script:event-name")
+    }
+
+    /// Script language, e.g. "ooo:script".
+    pub fn language(&self) -> Option<&'a str> {
+        self.attr("script:language")
+    }
+
+    /// Macro name for the language "ooo:basic".
+    pub fn macro_name(&self) -> Option<&'a str> {
+        self.attr("script:macro-name")
+    }
+
+    /// Script URL, e.g. "vnd.sun.star.script:Standard.Module1.Main?...".
+    pub fn href(&self) -> Option<&'a str> {
+        self.attr("xlink:href")
+    }
+
+    /// Any attribute.
+    pub fn attr(&self, name: &str) -> Option<&'a str> {
+        self.tag.attrmap().attr(name).map(|v| v.as_str())
+    }
+
+    /// The listener as xml.
+    pub fn xml(&self) -> &'a XmlTag {
+        self.tag
+    }
+}
+
+/// Collects the listeners of an office:event-listeners tag.
+pub(crate) fn event_listeners<'a>(listeners: &'a XmlTag, res: &mut Vec<EventListener<'a>>) {
+    for v in listeners.content() {
+        if let XmlContent::Tag(tag) = v {
+            res.push(EventListener { tag });
+        }
+    }
+}
+
+/// Removes all listeners for the event. Returns the number of removed
+/// listeners.
+pub(crate) fn remove_event_listeners(listeners: &mut XmlTag, event_name: &str) -> usize {
+    let len = listeners.content().len();
+    listeners.content_mut().retain(|v| match v {
+        XmlContent::Tag(tag) => {
+            tag.attrmap().attr("script:event-name").map(|v| v.as_str()) != Some(event_name)
+        }
+        XmlContent::Text(_) => true,
+    });
+    len - listeners.content().len()
+}
@@ -0,0 +1,61 @@
+//!
+//! Series for Sheet::fill_series().
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use spreadsheet_ods::fill::Series;
+//! use spreadsheet_ods::Sheet;
+//!
+//! let mut sheet = Sheet::new("1");
+//! sheet.fill_series(0, 0, 3, &Series::Linear(1.0, 0.5));
+//! sheet.fill_series(0, 1, 3, &Series::Months(NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(), 1));
+//!
+//! assert_eq!(sheet.value(2, 0).as_f64_or(0.0), 2.0);
+//! assert_eq!(
+//!     sheet.value(1, 1).as_date_opt(),
+//!     NaiveDate::from_ymd_opt(2024, 2, 29)
+//! );
+//! ```
+//!
+
+use crate::Value;
+use chrono::{Datelike, Duration, NaiveDate};
+
+/// A series of values.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Series {
+    /// Numbers, starting value and step.
+    Linear(f64, f64),
+    /// Dates, starting date and step in days.
+    Days(NaiveDate, i64),
+    /// Dates, starting date and step in months. The day is clamped to
+    /// the last day of the month, but each value is calculated from the
+    /// starting date, so 31.1. is followed by 29.2. and 31.3.
+    Months(NaiveDate, i32),
+}
+
+impl Series {
+    /// The n-th value of the series. None if a date would be out of range.
+    pub fn nth(&self, n: u32) -> Option<Value> {
+        match self {
+            Series::Linear(start, step) => Some(Value::Number(start + step * f64::from(n))),
+            Series::Days(start, step) => step
+                .checked_mul(i64::from(n))
+                .and_then(|v| start.checked_add_signed(Duration::days(v)))
+                .map(Value::from),
+            Series::Months(start, step) => step
+                .checked_mul(i32::try_from(n).ok()?)
+                .and_then(|v| add_months(*start, v))
+                .map(Value::from),
+        }
+    }
+}
+
+/// Adds months and clamps the day.
+fn add_months(date: NaiveDate, months: i32) -> Option<NaiveDate> {
+    let m = date.year() * 12 + date.month0() as i32 + months;
+    let (year, month) = (m.div_euclid(12), m.rem_euclid(12) as u32 + 1);
+    (1..=date.day())
+        .rev()
+        .find_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+}
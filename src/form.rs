@@ -0,0 +1,107 @@
+//!
+//! Read only access to the form controls of a sheet.
+//!
+//! The forms and controls are kept as xml and written back unchanged.
+//! Sheet::form_controls() lists the controls, which gives access to the
+//! cell a control is bound to. The state of a checkbox can then be
+//! read from that cell.
+//!
+//! ```
+//! use spreadsheet_ods::Sheet;
+//!
+//! # fn checkboxes(sheet: &Sheet) -> Result<(), spreadsheet_ods::OdsError> {
+//! for control in sheet.form_controls() {
+//!     if control.kind() == "form:checkbox" {
+//!         if let Some(cell) = control.linked_cell()? {
+//!             let checked = sheet.value(cell.row(), cell.col()).as_bool_or(false);
+//!             println!("{:?} {}", control.name(), checked);
+//!         }
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+
+use crate::xmltree::{XmlContent, XmlTag};
+use crate::{CellRef, OdsError};
+use std::convert::TryFrom;
+
+/// A form control of a sheet.
+#[derive(Debug, Clone, Copy)]
+pub struct FormControl<'a> {
+    form: &'a XmlTag,
+    control: &'a XmlTag,
+}
+
+impl<'a> FormControl<'a> {
+    /// Element name of the control, e.g. "form:checkbox" or "form:button".
+    pub fn kind(&self) -> &'a str {
+        self.control.name()
+    }
+
+    /// Name of the control.
+    pub fn name(&self) -> Option<&'a str> {
+        self.attr("form:name")
+    }
+
+    /// Id of the control. The draw:control element that places the
+    /// control on the sheet refers to this id.
+    pub fn id(&self) -> Option<&'a str> {
+        self.attr("form:id").or_else(|| self.attr("xml:id"))
+    }
+
+    /// Name of the form that contains the control.
+    pub fn form_name(&self) -> Option<&'a str> {
+        self.form.attrmap().attr("form:name").map(|v| v.as_str())
+    }
+
+    /// The cell that is bound to the control.
+    pub fn linked_cell(&self) -> Result<Option<CellRef>, OdsError> {
+        match self.attr("form:linked-cell") {
+            Some(v) => Ok(Some(CellRef::try_from(v)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// State of a checkbox or radio button as stored in the document.
+    /// One of "checked", "unchecked" or "unknown".
+    pub fn current_state(&self) -> Option<&'a str> {
+        self.attr("form:current-state")
+    }
+
+    /// Any attribute of the control.
+    pub fn attr(&self, name: &str) -> Option<&'a str> {
+        self.control.attrmap().attr(name).map(|v| v.as_str())
+    }
+
+    /// The control as xml.
+    pub fn xml(&self) -> &'a XmlTag {
+        self.control
+    }
+}
+
+/// Collects the controls of all forms in office:forms.
+pub(crate) fn form_controls<'a>(forms: &'a XmlTag, controls: &mut Vec<FormControl<'a>>) {
+    for form in child_tags(forms).filter(|v| v.name() == "form:form") {
+        form_controls_of(form, controls);
+    }
+}
+
+fn form_controls_of<'a>(form: &'a XmlTag, controls: &mut Vec<FormControl<'a>>) {
+    for tag in child_tags(form) {
+        match tag.name() {
+            // nested form
+            "form:form" => form_controls_of(tag, controls),
+            "form:properties" | "office:event-listeners" => {}
+            _ => controls.push(FormControl { form, control: tag }),
+        }
+    }
+}
+
+fn child_tags(tag: &XmlTag) -> impl Iterator<Item = &XmlTag> {
+    tag.content().iter().filter_map(|v| match v {
+        XmlContent::Tag(t) => Some(t),
+        XmlContent::Text(_) => None,
+    })
+}
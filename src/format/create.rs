@@ -1,7 +1,7 @@
 use crate::format::FormatNumberStyle;
 use crate::{
     ValueFormatBoolean, ValueFormatCurrency, ValueFormatDateTime, ValueFormatNumber,
-    ValueFormatPercentage, ValueFormatTimeDuration,
+    ValueFormatPercentage, ValueFormatText, ValueFormatTimeDuration,
 };
 use icu_locid::Locale;
 
@@ -71,11 +71,6 @@ where
         .symbol(symbol.into())
         .build();
     v.part_text(" ").build();
-    v.part_number()
-        .decimal_places(2)
-        .min_decimal_places(2)
-        .grouping()
-        .build();
     v.part_number()
         .decimal_places(2)
         .min_decimal_places(2)
@@ -210,6 +205,38 @@ pub fn create_number_format_fixed<S: Into<String>>(
     v
 }
 
+/// Creates a new number format in scientific notation.
+pub fn create_scientific_format<S: Into<String>>(
+    name: S,
+    decimal: u8,
+    min_exponent_digits: u8,
+) -> ValueFormatNumber {
+    let mut v = ValueFormatNumber::new_named(name.into());
+    v.part_scientific()
+        .decimal_places(decimal)
+        .min_integer_digits(1)
+        .min_exponent_digits(min_exponent_digits)
+        .build();
+    v
+}
+
+/// Creates a new number format in engineering notation.
+/// The exponent is always a multiple of 3.
+pub fn create_engineering_format<S: Into<String>>(
+    name: S,
+    decimal: u8,
+    min_exponent_digits: u8,
+) -> ValueFormatNumber {
+    let mut v = ValueFormatNumber::new_named(name.into());
+    v.part_scientific()
+        .decimal_places(decimal)
+        .min_integer_digits(1)
+        .min_exponent_digits(min_exponent_digits)
+        .expontent_interval(3)
+        .build();
+    v
+}
+
 /// Creates a new percentage format.
 pub fn create_percentage_format<S: Into<String>>(name: S, decimal: u8) -> ValueFormatPercentage {
     let mut v = ValueFormatPercentage::new_named(name.into());
@@ -258,6 +285,35 @@ where
     v
 }
 
+/// Creates a new text format. The cell text is shown as is and
+/// not interpreted as a number.
+pub fn create_text_format<S: Into<String>>(name: S) -> ValueFormatText {
+    let mut v = ValueFormatText::new_named(name.into());
+    v.part_text_content().build();
+    v
+}
+
+/// Creates a new text format with a text before and after the cell text.
+pub fn create_text_format_affix<S1, S2, S3>(name: S1, prefix: S2, suffix: S3) -> ValueFormatText
+where
+    S1: Into<String>,
+    S2: Into<String>,
+    S3: Into<String>,
+{
+    let prefix = prefix.into();
+    let suffix = suffix.into();
+
+    let mut v = ValueFormatText::new_named(name.into());
+    if !prefix.is_empty() {
+        v.part_text(prefix).build();
+    }
+    v.part_text_content().build();
+    if !suffix.is_empty() {
+        v.part_text(suffix).build();
+    }
+    v
+}
+
 /// Creates a new date format YYYY-MM-DD
 pub fn create_date_iso_format<S: Into<String>>(name: S) -> ValueFormatDateTime {
     let mut v = ValueFormatDateTime::new_named(name.into());
@@ -269,6 +325,22 @@ pub fn create_date_iso_format<S: Into<String>>(name: S) -> ValueFormatDateTime {
     v
 }
 
+/// Creates a new week date format Y-Www-D, eg "2020-W53-6" for 2021-01-02.
+///
+/// The cell text written by this crate uses the ISO 8601 week-year and
+/// the ISO weekday 1 (Monday) to 7 (Sunday). A value format has no element
+/// for either, so a spreadsheet application shows the calendar year and
+/// the abbreviated name of the day instead.
+pub fn create_week_date_format<S: Into<String>>(name: S) -> ValueFormatDateTime {
+    let mut v = ValueFormatDateTime::new_named(name.into());
+    v.part_year().style(FormatNumberStyle::Long).build();
+    v.part_text("-W").build();
+    v.part_week_of_year().build();
+    v.part_text("-").build();
+    v.part_day_of_week().style(FormatNumberStyle::Short).build();
+    v
+}
+
 /// Creates a new date format D.M.Y
 pub fn create_date_dmy_format<S: Into<String>>(name: S) -> ValueFormatDateTime {
     let mut v = ValueFormatDateTime::new_named(name.into());
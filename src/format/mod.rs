@@ -36,9 +36,13 @@
 
 mod builder;
 mod create;
+mod sections;
 
 pub use builder::*;
 pub use create::*;
+pub use sections::*;
+
+pub use crate::io::format::{CompiledCurrency, CompiledDateTime, RenderContext};
 
 use crate::attrmap2::AttrMap2;
 use crate::style::stylemap::StyleMap;
@@ -122,7 +126,11 @@ pub trait ValueFormatTrait {
     fn stylemaps_mut(&mut self) -> &mut Vec<StyleMap>;
 }
 
-valueformat!(ValueFormatBoolean, ValueType::Boolean);
+valueformat!(
+    ValueFormatBoolean,
+    ValueType::Boolean,
+    "number:boolean-style"
+);
 
 // 16.29.24 <number:boolean-style>
 impl ValueFormatBoolean {
@@ -132,7 +140,7 @@ impl ValueFormatBoolean {
 }
 
 // 16.29.2 <number:number-style>
-valueformat!(ValueFormatNumber, ValueType::Number);
+valueformat!(ValueFormatNumber, ValueType::Number, "number:number-style");
 
 impl ValueFormatNumber {
     part_fill_character!();
@@ -149,7 +157,11 @@ impl ValueFormatNumber {
 }
 
 // 16.29.10 <number:percentage-style>
-valueformat!(ValueFormatPercentage, ValueType::Percentage);
+valueformat!(
+    ValueFormatPercentage,
+    ValueType::Percentage,
+    "number:percentage-style"
+);
 
 impl ValueFormatPercentage {
     part_fill_character!();
@@ -162,7 +174,11 @@ impl ValueFormatPercentage {
 }
 
 // 16.29.8 <number:currency-style>
-valueformat!(ValueFormatCurrency, ValueType::Currency);
+valueformat!(
+    ValueFormatCurrency,
+    ValueType::Currency,
+    "number:currency-style"
+);
 
 impl ValueFormatCurrency {
     number_automatic_order!(attr);
@@ -179,7 +195,7 @@ impl ValueFormatCurrency {
 }
 
 // 16.29.26 <number:text-style>
-valueformat!(ValueFormatText, ValueType::Text);
+valueformat!(ValueFormatText, ValueType::Text, "number:text-style");
 
 impl ValueFormatText {
     part_fill_character!();
@@ -191,7 +207,11 @@ impl ValueFormatText {
 }
 
 // 16.29.11 <number:date-style>
-valueformat!(ValueFormatDateTime, ValueType::DateTime);
+valueformat!(
+    ValueFormatDateTime,
+    ValueType::DateTime,
+    "number:date-style"
+);
 
 impl ValueFormatDateTime {
     number_automatic_order!(attr);
@@ -226,7 +246,11 @@ impl ValueFormatDateTime {
 }
 
 // 16.29.19 <number:time-style>
-valueformat!(ValueFormatTimeDuration, ValueType::TimeDuration);
+valueformat!(
+    ValueFormatTimeDuration,
+    ValueType::TimeDuration,
+    "number:time-style"
+);
 
 impl ValueFormatTimeDuration {
     number_format_source!(attr);
@@ -379,6 +403,105 @@ impl FormatPart {
         self.attr.attr_def(name, default)
     }
 
+    /// Returns a property.
+    ///
+    /// All attributes that are read are kept, including the LibreOffice
+    /// extensions in the loext namespace. They are written back unchanged.
+    pub fn attr(&self, name: &str) -> Option<&String> {
+        self.attr.attr(name)
+    }
+
+    // Attributes that are part of ODF 1.3 but were written by LibreOffice
+    // with the loext prefix before. Looks for both.
+    fn attr_ext(&self, name: &str) -> Option<&String> {
+        self.attr
+            .attr(format!("number:{}", name).as_str())
+            .or_else(|| self.attr.attr(format!("loext:{}", name).as_str()))
+    }
+
+    // Sets the ODF 1.3 attribute. An existing loext attribute is kept in sync.
+    fn set_attr_ext(&mut self, name: &str, value: String) {
+        let loext = format!("loext:{}", name);
+        if self.attr.attr(loext.as_str()).is_some() {
+            self.attr.set_attr(loext.as_str(), value.clone());
+        }
+        self.attr
+            .set_attr(format!("number:{}", name).as_str(), value);
+    }
+
+    /// Minimum number of decimal places for a number part.
+    /// Reads number:min-decimal-places or loext:min-decimal-places.
+    pub fn min_decimal_places(&self) -> Option<u8> {
+        self.attr_ext("min-decimal-places")
+            .and_then(|v| v.parse().ok())
+    }
+
+    /// Minimum number of decimal places for a number part.
+    pub fn set_min_decimal_places(&mut self, v: u8) {
+        self.set_attr_ext("min-decimal-places", v.to_string());
+    }
+
+    /// Valid exponents are multiples of this interval for a scientific number part.
+    /// Reads number:exponent-interval or loext:exponent-interval.
+    pub fn exponent_interval(&self) -> Option<u8> {
+        self.attr_ext("exponent-interval")
+            .and_then(|v| v.parse().ok())
+    }
+
+    /// Valid exponents are multiples of this interval for a scientific number part.
+    pub fn set_exponent_interval(&mut self, v: u8) {
+        self.set_attr_ext("exponent-interval", v.to_string());
+    }
+
+    /// The sign of the exponent is always shown for a scientific number part.
+    /// Reads number:forced-exponent-sign or loext:forced-exponent-sign.
+    pub fn forced_exponent_sign(&self) -> Option<bool> {
+        self.attr_ext("forced-exponent-sign")
+            .and_then(|v| v.parse().ok())
+    }
+
+    /// The sign of the exponent is always shown for a scientific number part.
+    pub fn set_forced_exponent_sign(&mut self, v: bool) {
+        self.set_attr_ext("forced-exponent-sign", v.to_string());
+    }
+
+    /// Maximum denominator for a fraction part.
+    /// Reads number:max-denominator-value or loext:max-denominator-value.
+    pub fn max_denominator_value(&self) -> Option<u32> {
+        self.attr_ext("max-denominator-value")
+            .and_then(|v| v.parse().ok())
+    }
+
+    /// Maximum denominator for a fraction part.
+    pub fn set_max_denominator_value(&mut self, v: u32) {
+        self.set_attr_ext("max-denominator-value", v.to_string());
+    }
+
+    /// Maximum number of digits of the numerator of a fraction part.
+    /// This is the LibreOffice extension loext:max-numerator-digits.
+    pub fn max_numerator_digits(&self) -> Option<u8> {
+        self.attr
+            .attr("loext:max-numerator-digits")
+            .and_then(|v| v.parse().ok())
+    }
+
+    /// Maximum number of digits of the numerator of a fraction part.
+    pub fn set_max_numerator_digits(&mut self, v: u8) {
+        self.attr
+            .set_attr("loext:max-numerator-digits", v.to_string());
+    }
+
+    /// A text part is replaced by blanks of the width of this character.
+    /// This is the LibreOffice extension loext:blank-width-char.
+    pub fn blank_width_char(&self) -> Option<&str> {
+        self.attr.attr("loext:blank-width-char").map(|v| v.as_str())
+    }
+
+    /// A text part is replaced by blanks of the width of this character.
+    pub fn set_blank_width_char<S: Into<String>>(&mut self, v: S) {
+        self.attr.set_attr("loext:blank-width-char", v.into());
+    }
+
     /// Sets the position for embedded text in a number format part.
     pub fn set_position(&mut self, pos: u32) {
         self.position = pos;
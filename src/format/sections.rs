@@ -0,0 +1,157 @@
+use crate::condition::{CompareOp, ValueCondition};
+use crate::format::ValueFormatTrait;
+use crate::style::stylemap::StyleMap;
+use crate::{CellRef, ValueFormatText};
+
+/// A value format with up to four sections, like the format code
+/// "#,##0.00;[RED]-#,##0.00;"zero";@" of other tools.
+///
+/// In ODS each section is a value format of its own. The main format
+/// references the others via style-maps with "value()" conditions.
+/// The sections are named "{name}P0", "{name}P1" and "{name}P2" like
+/// LibreOffice does.
+///
+/// * positive: Used for all numbers if there is no other section.
+/// * negative: Numbers less than zero. The value is shown without sign,
+///   the section must contain the "-" if one is wanted.
+/// * zero: Zero.
+/// * text: Text values. Contains a text-content part for the text.
+///
+/// ```
+/// use spreadsheet_ods::format::{create_number_format, FormatSections};
+/// use spreadsheet_ods::{ValueFormatNumber, WorkBook};
+///
+/// let mut wb = WorkBook::new_empty();
+///
+/// let mut negative = ValueFormatNumber::new_empty();
+/// negative.part_text("-").build();
+/// negative.part_number().fixed_decimal_places(2).build();
+/// negative.set_color(color::Rgb::new(255, 0, 0));
+///
+/// let mut zero = ValueFormatNumber::new_empty();
+/// zero.part_text("-").build();
+///
+/// let sections = FormatSections::new("amount", create_number_format("", 2, true))
+///     .negative(negative)
+///     .zero(zero);
+/// let amount = wb.add_number_sections(sections);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FormatSections<T> {
+    name: String,
+    positive: T,
+    negative: Option<T>,
+    zero: Option<T>,
+    text: Option<ValueFormatText>,
+}
+
+impl<T: ValueFormatTrait> FormatSections<T> {
+    /// Format with only the positive section. The name is used
+    /// for the main format, the names of the sections are derived from it.
+    pub fn new<S: Into<String>>(name: S, positive: T) -> Self {
+        Self {
+            name: name.into(),
+            positive,
+            negative: None,
+            zero: None,
+            text: None,
+        }
+    }
+
+    /// Name of the main format.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Section for negative numbers.
+    pub fn negative(mut self, negative: T) -> Self {
+        self.negative = Some(negative);
+        self
+    }
+
+    /// Section for zero.
+    pub fn zero(mut self, zero: T) -> Self {
+        self.zero = Some(zero);
+        self
+    }
+
+    /// Section for text values.
+    pub fn text(mut self, text: ValueFormatText) -> Self {
+        self.text = Some(text);
+        self
+    }
+
+    /// Names the sections and links them via style-maps. Returns the number
+    /// formats and the text format. The main format is the text format if
+    /// there is one, otherwise the last number format.
+    pub(crate) fn link(self) -> (Vec<T>, Option<ValueFormatText>) {
+        let positive_cmp = if self.zero.is_some() {
+            CompareOp::Gt
+        } else {
+            CompareOp::Gte
+        };
+        let has_negative = self.negative.is_some();
+
+        let mut sections = Vec::new();
+        sections.push((ValueCondition::value_cmp(positive_cmp, 0), self.positive));
+        if let Some(negative) = self.negative {
+            sections.push((ValueCondition::value_cmp(CompareOp::Lt, 0), negative));
+        }
+        if let Some(zero) = self.zero {
+            sections.push((ValueCondition::value_cmp(CompareOp::Eq, 0), zero));
+        }
+
+        // Without a text section the last section is used if no
+        // condition matches.
+        let main = if self.text.is_none() {
+            sections.pop().map(|(_, v)| v)
+        } else {
+            None
+        };
+
+        let mut stylemaps = Vec::new();
+        let mut formats = Vec::new();
+        for (idx, (condition, mut format)) in sections.into_iter().enumerate() {
+            format.set_name(format!("{}P{}", self.name, idx));
+            format
+                .attrmap_mut()
+                .set_attr("style:volatile", "true".to_string());
+            stylemaps.push(StyleMap::new(
+                condition,
+                format.name().as_str(),
+                CellRef::default(),
+            ));
+            formats.push(format);
+        }
+        // Negative numbers use the positive section too.
+        if !has_negative && !stylemaps.is_empty() {
+            stylemaps.push(StyleMap::new(
+                ValueCondition::value_cmp(CompareOp::Lt, 0),
+                format!("{}P0", self.name),
+                CellRef::default(),
+            ));
+        }
+
+        let text = match self.text {
+            Some(mut text) => {
+                text.set_name(self.name.as_str());
+                for sm in stylemaps {
+                    text.push_stylemap(sm);
+                }
+                Some(text)
+            }
+            None => {
+                if let Some(mut main) = main {
+                    main.set_name(self.name.as_str());
+                    for sm in stylemaps {
+                        main.push_stylemap(sm);
+                    }
+                    formats.push(main);
+                }
+                None
+            }
+        };
+
+        (formats, text)
+    }
+}
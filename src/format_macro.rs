@@ -1,6 +1,6 @@
 /// Generates the common features of all value formats.
 macro_rules! valueformat {
-    ($format:ident, $valuetype:expr) => {
+    ($format:ident, $valuetype:expr, $tag:literal) => {
         /// Formatting for Boolean.
         #[derive(Debug, Clone)]
         pub struct $format {
@@ -76,6 +76,8 @@ macro_rules! valueformat {
             style_display_name!(attr);
             style_volatile!(attr);
 
+            raw_attr!($tag => attr, "style:text-properties" => textstyle);
+
             fo_background_color!(textstyle);
             fo_color!(textstyle);
             // fo_locale!(textstyle);
@@ -2,7 +2,10 @@
 //! For now defines functions to create cell references for formulas.
 //!
 
-use crate::refs::{CellRange, CellRef};
+use crate::refs::{
+    parse_cellrange, parse_cellref, push_cellrange, push_cellref, CellRange, CellRef,
+};
+use std::ops::Range;
 
 // TODO: more formula stuff. parsing?
 
@@ -78,6 +81,16 @@ pub fn frangerefa(row: u32, col: u32, row_to: u32, col_to: u32) -> String {
         .to_formula()
 }
 
+/// Creates a reference to whole columns for use in formulas, e.g. [.A:.B].
+pub fn fcolsref(col: u32, col_to: u32) -> String {
+    CellRange::whole_cols(col, col_to).to_formula()
+}
+
+/// Creates a reference to whole rows for use in formulas, e.g. [.1:.3].
+pub fn frowsref(row: u32, row_to: u32) -> String {
+    CellRange::whole_rows(row, row_to).to_formula()
+}
+
 /// Creates a cellrange-reference for use in formulas.
 pub fn frangeref_table<S: Into<String>>(
     table: S,
@@ -127,3 +140,337 @@ pub fn frangerefa_table<S: Into<String>>(
         .absolute()
         .to_formula()
 }
+
+/// Rewrites the row references to the given table in a formula.
+///
+/// References without a table name refer to the given table too.
+/// The mapping gets the first and last row of the reference, for a single
+/// cell both are the same. If it returns None the reference is replaced
+/// with #REF!. References that can't be parsed are not changed.
+pub(crate) fn map_row_refs<F>(formula: &str, table: &str, map: F) -> String
+where
+    F: Fn(u32, u32) -> Option<(u32, u32)>,
+{
+    map_refs(formula, table, true, map)
+}
+
+/// Rewrites the column references to the given table in a formula.
+/// Works the same as map_row_refs().
+pub(crate) fn map_col_refs<F>(formula: &str, table: &str, map: F) -> String
+where
+    F: Fn(u32, u32) -> Option<(u32, u32)>,
+{
+    map_refs(formula, table, false, map)
+}
+
+/// Moves the relative parts of all references by the given number of rows
+/// and columns, the same as copying the formula to another cell.
+/// Absolute parts stay as they are. A reference that would leave the
+/// sheet is replaced with #REF!.
+pub(crate) fn shift_refs(formula: &str, dr: i64, dc: i64) -> String {
+    let shift = |pos: u32, abs: bool, d: i64| -> Option<u32> {
+        if abs {
+            Some(pos)
+        } else {
+            u32::try_from(i64::from(pos) + d).ok()
+        }
+    };
+
+    map_bracket_refs(formula, |refstr| {
+        let mut buf = String::new();
+
+        let mut pos = 0;
+        if let Ok(mut cellref) = parse_cellref(refstr, &mut pos) {
+            if pos == refstr.len() {
+                match (
+                    shift(cellref.row(), cellref.row_abs(), dr),
+                    shift(cellref.col(), cellref.col_abs(), dc),
+                ) {
+                    (Some(row), Some(col)) => {
+                        cellref.set_row(row);
+                        cellref.set_col(col);
+                        buf.push('[');
+                        push_cellref(&mut buf, &cellref);
+                        buf.push(']');
+                    }
+                    _ => buf.push_str("#REF!"),
+                }
+                return Some(buf);
+            }
+        }
+
+        let mut pos = 0;
+        match parse_cellrange(refstr, &mut pos) {
+            Ok(mut range) if pos == refstr.len() => {
+                // The open end of whole columns or rows doesn't move.
+                let dr = if range.is_whole_cols() { 0 } else { dr };
+                let dc = if range.is_whole_rows() { 0 } else { dc };
+                match (
+                    shift(range.row(), range.row_abs(), dr),
+                    shift(range.col(), range.col_abs(), dc),
+                    shift(range.to_row(), range.to_row_abs(), dr),
+                    shift(range.to_col(), range.to_col_abs(), dc),
+                ) {
+                    (Some(row), Some(col), Some(to_row), Some(to_col)) => {
+                        range.set_row(row);
+                        range.set_col(col);
+                        range.set_to_row(to_row);
+                        range.set_to_col(to_col);
+                        buf.push('[');
+                        push_cellrange(&mut buf, &range);
+                        buf.push(']');
+                    }
+                    _ => buf.push_str("#REF!"),
+                }
+                Some(buf)
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Replaces the table name in all references to the table. References
+/// without a table name are not changed.
+pub(crate) fn rename_table_refs(formula: &str, table: &str, new_name: &str) -> String {
+    map_bracket_refs(formula, |refstr| {
+        let mut buf = String::new();
+
+        let mut pos = 0;
+        if let Ok(mut cellref) = parse_cellref(refstr, &mut pos) {
+            if pos == refstr.len() {
+                if cellref.table().map(|v| v.as_str()) != Some(table) {
+                    return None;
+                }
+                cellref.set_table(new_name);
+                buf.push('[');
+                push_cellref(&mut buf, &cellref);
+                buf.push(']');
+                return Some(buf);
+            }
+        }
+
+        let mut pos = 0;
+        match parse_cellrange(refstr, &mut pos) {
+            Ok(mut range)
+                if pos == refstr.len() && range.table().map(|v| v.as_str()) == Some(table) =>
+            {
+                range.set_table(new_name);
+                buf.push('[');
+                push_cellrange(&mut buf, &range);
+                buf.push(']');
+                Some(buf)
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Replaces the name of a named range or named expression. Names are
+/// not case-sensitive.
+pub(crate) fn rename_name(formula: &str, name: &str, new_name: &str) -> String {
+    let mut buf = String::new();
+    let mut last = 0;
+    for (range, token) in tokens(formula) {
+        if let Token::Name(v) = token {
+            if v.eq_ignore_ascii_case(name) {
+                buf.push_str(&formula[last..range.start]);
+                buf.push_str(new_name);
+                last = range.end;
+            }
+        }
+    }
+    buf.push_str(&formula[last..]);
+    buf
+}
+
+/// Replaces all references to the table with #REF!. References without
+/// a table name are not changed.
+pub(crate) fn invalidate_table_refs(formula: &str, table: &str) -> String {
+    map_bracket_refs(formula, |refstr| {
+        if ref_table(refstr).as_deref() == Some(table) {
+            Some("#REF!".to_string())
+        } else {
+            None
+        }
+    })
+}
+
+// Table of one reference without the brackets.
+fn ref_table(refstr: &str) -> Option<String> {
+    let mut pos = 0;
+    if let Ok(cellref) = parse_cellref(refstr, &mut pos) {
+        if pos == refstr.len() {
+            return cellref.table().cloned();
+        }
+    }
+    let mut pos = 0;
+    match parse_cellrange(refstr, &mut pos) {
+        Ok(range) if pos == refstr.len() => range.table().cloned(),
+        _ => None,
+    }
+}
+
+fn map_refs<F>(formula: &str, table: &str, rows: bool, map: F) -> String
+where
+    F: Fn(u32, u32) -> Option<(u32, u32)>,
+{
+    map_bracket_refs(formula, |refstr| map_ref(refstr, table, rows, &map))
+}
+
+/// Parts of a formula that are outside of string literals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    /// Function name as written, may contain a namespace like
+    /// COM.MICROSOFT.WEBSERVICE.
+    Function(&'a str),
+    /// Reference without the brackets.
+    Ref(&'a str),
+    /// Any other identifier, usually a named range or named expression.
+    Name(&'a str),
+}
+
+/// Splits the function calls, references and names out of a formula.
+/// Returns the byte range of each token in the formula.
+pub(crate) fn tokens(formula: &str) -> Vec<(Range<usize>, Token<'_>)> {
+    let mut tokens = Vec::new();
+
+    let mut in_string = false;
+    let mut last = 0;
+    for (pos, c) in formula.char_indices() {
+        if pos < last {
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = !in_string;
+            }
+            '[' if !in_string => {
+                if let Some(len) = formula[pos..].find(']') {
+                    tokens.push((pos..pos + len + 1, Token::Ref(&formula[pos + 1..pos + len])));
+                    last = pos + len + 1;
+                }
+            }
+            c if !in_string && (c.is_alphabetic() || c == '_') => {
+                let len = formula[pos..]
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+                    .unwrap_or(formula.len() - pos);
+                let rest = formula[pos + len..].trim_start();
+                if rest.starts_with('(') {
+                    tokens.push((pos..pos + len, Token::Function(&formula[pos..pos + len])));
+                } else if !rest.starts_with(":=") {
+                    // "of:=" is the namespace of the formula.
+                    tokens.push((pos..pos + len, Token::Name(&formula[pos..pos + len])));
+                }
+                last = pos + len;
+            }
+            c if !in_string && c.is_ascii_digit() => {
+                // Numbers like 1E5 are no names.
+                let len = formula[pos..]
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+                    .unwrap_or(formula.len() - pos);
+                last = pos + len;
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+// Replaces each [reference] outside of strings with the result of the
+// mapping, None leaves it as it is.
+fn map_bracket_refs<F>(formula: &str, map: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut buf = String::with_capacity(formula.len());
+
+    let mut last = 0;
+    for (range, token) in tokens(formula) {
+        if let Token::Ref(refstr) = token {
+            if let Some(mapped) = map(refstr) {
+                buf.push_str(&formula[last..range.start]);
+                buf.push_str(&mapped);
+                last = range.end;
+            }
+        }
+    }
+    buf.push_str(&formula[last..]);
+
+    buf
+}
+
+// No table is the current table.
+fn is_table(ref_table: Option<&String>, table: &str) -> bool {
+    match ref_table {
+        None => true,
+        Some(v) => v == table,
+    }
+}
+
+// Maps one reference without the brackets. None if nothing changes.
+fn map_ref<F>(refstr: &str, table: &str, rows: bool, map: &F) -> Option<String>
+where
+    F: Fn(u32, u32) -> Option<(u32, u32)>,
+{
+    let mut buf = String::new();
+
+    let mut pos = 0;
+    match parse_cellref(refstr, &mut pos) {
+        Ok(mut cellref) if pos == refstr.len() => {
+            if !is_table(cellref.table(), table) {
+                return None;
+            }
+            let pos = if rows { cellref.row() } else { cellref.col() };
+            if let Some((pos, _)) = map(pos, pos) {
+                if rows {
+                    cellref.set_row(pos);
+                } else {
+                    cellref.set_col(pos);
+                }
+                buf.push('[');
+                push_cellref(&mut buf, &cellref);
+                buf.push(']');
+            } else {
+                buf.push_str("#REF!");
+            }
+            return Some(buf);
+        }
+        _ => {}
+    }
+
+    let mut pos = 0;
+    match parse_cellrange(refstr, &mut pos) {
+        Ok(range)
+            if pos == refstr.len()
+                && (rows && range.is_whole_cols() || !rows && range.is_whole_rows()) =>
+        {
+            // Whole columns don't change when rows are inserted and
+            // vice versa.
+            None
+        }
+        Ok(mut range) if pos == refstr.len() && is_table(range.table(), table) => {
+            let mapped = if rows {
+                map(range.row(), range.to_row())
+            } else {
+                map(range.col(), range.to_col())
+            };
+            if let Some((pos, to_pos)) = mapped {
+                if rows {
+                    range.set_row(pos);
+                    range.set_to_row(to_pos);
+                } else {
+                    range.set_col(pos);
+                    range.set_to_col(to_pos);
+                }
+                buf.push('[');
+                push_cellrange(&mut buf, &range);
+                buf.push(']');
+            } else {
+                buf.push_str("#REF!");
+            }
+            Some(buf)
+        }
+        _ => None,
+    }
+}
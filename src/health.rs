@@ -0,0 +1,306 @@
+//!
+//! Report about the state of a file that was read leniently.
+//!
+//! read_ods_lenient() and read_ods_buf_lenient() don't fail on invalid
+//! attribute values of tables, rows and cells. The values are replaced
+//! with a default and listed in the report, together with the elements
+//! and attributes the reader doesn't know, and the styles that are used
+//! but not defined.
+//!
+//! ```no_run
+//! use spreadsheet_ods::read_ods_lenient;
+//!
+//! let (wb, health) = read_ods_lenient("legacy.ods").expect("read");
+//! if !health.is_healthy() {
+//!     println!("{}", health);
+//!     for v in health.coerced() {
+//!         println!("{}: {}={:?}", v.location, v.attr, v.value);
+//!     }
+//! }
+//! ```
+//!
+//! Independent of the lenient read, every read records the elements it
+//! skipped in WorkBook::dropped_elements(). These are lost when the
+//! workbook is written again.
+//!
+//! ```no_run
+//! use spreadsheet_ods::read_ods;
+//!
+//! let wb = read_ods("legacy.ods").expect("read");
+//! for v in wb.dropped_elements() {
+//!     println!("{} x{} in {}", v.name, v.count, v.location());
+//! }
+//! ```
+//!
+
+use crate::{CellRef, ValueType, WorkBook};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// An attribute value that couldn't be parsed and was replaced with
+/// a default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoercedValue {
+    /// Cell, row or table where the value was found.
+    pub location: String,
+    /// Name of the attribute.
+    pub attr: String,
+    /// The original value.
+    pub value: String,
+}
+
+/// A style, format or validation that is used but not defined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingStyle {
+    /// "cell style", "row style", "column style", "table style",
+    /// "master page", "page style", "value format" or "validation".
+    pub kind: &'static str,
+    /// Name of the missing style.
+    pub name: String,
+    /// How often it is used.
+    pub uses: u32,
+}
+
+/// A value format that can't be used for the text of a cell. The text
+/// is written as if the cell-style had no value format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatWarning {
+    /// Name of the cell-style.
+    pub style: String,
+    /// Name of the value format.
+    pub format: String,
+    /// Value type of the cell.
+    pub value_type: ValueType,
+    /// Value type of the format if it exists for another value type.
+    /// None if there is no such format.
+    pub found: Option<ValueType>,
+}
+
+/// An element that was skipped by the reader. Elements with the same
+/// name and position are counted together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DroppedElement {
+    /// Name of the element.
+    pub name: String,
+    /// Sheet that contains the element.
+    pub sheet: Option<String>,
+    /// Row and column of the cell that contains the element.
+    pub cell: Option<(u32, u32)>,
+    /// How often it was skipped.
+    pub count: u32,
+}
+
+impl DroppedElement {
+    /// The cell, the sheet or "document".
+    pub fn location(&self) -> String {
+        match (&self.sheet, self.cell) {
+            (Some(sheet), Some((row, col))) => cell_location(sheet, row, col),
+            (Some(sheet), None) => format!("table {}", sheet),
+            (None, _) => "document".to_string(),
+        }
+    }
+}
+
+/// Result of a lenient read.
+#[derive(Debug, Clone, Default)]
+pub struct OdsHealthReport {
+    unknown_elements: BTreeMap<String, u32>,
+    unknown_attrs: BTreeMap<String, u32>,
+    missing_styles: Vec<MissingStyle>,
+    coerced: Vec<CoercedValue>,
+}
+
+impl Display for OdsHealthReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} unknown elements, {} unknown attributes, {} missing styles, {} coerced values",
+            self.unknown_element_count(),
+            self.unknown_attr_count(),
+            self.missing_styles.len(),
+            self.coerced.len()
+        )
+    }
+}
+
+impl OdsHealthReport {
+    /// Nothing was skipped, missing or coerced.
+    pub fn is_healthy(&self) -> bool {
+        self.unknown_elements.is_empty()
+            && self.unknown_attrs.is_empty()
+            && self.missing_styles.is_empty()
+            && self.coerced.is_empty()
+    }
+
+    /// Skipped elements by name with the number of occurrences.
+    /// The children of a skipped element are counted too.
+    pub fn unknown_elements(&self) -> &BTreeMap<String, u32> {
+        &self.unknown_elements
+    }
+
+    /// Total number of skipped elements.
+    pub fn unknown_element_count(&self) -> u32 {
+        self.unknown_elements.values().sum()
+    }
+
+    /// Skipped attributes as "element@attribute" with the number
+    /// of occurrences.
+    pub fn unknown_attrs(&self) -> &BTreeMap<String, u32> {
+        &self.unknown_attrs
+    }
+
+    /// Total number of skipped attributes.
+    pub fn unknown_attr_count(&self) -> u32 {
+        self.unknown_attrs.values().sum()
+    }
+
+    /// Styles that are used but not defined.
+    pub fn missing_styles(&self) -> &Vec<MissingStyle> {
+        &self.missing_styles
+    }
+
+    /// Invalid values that were replaced.
+    pub fn coerced(&self) -> &Vec<CoercedValue> {
+        &self.coerced
+    }
+
+    pub(crate) fn add_unknown_element(&mut self, name: &str) {
+        *self.unknown_elements.entry(name.to_string()).or_default() += 1;
+    }
+
+    pub(crate) fn add_unknown_attr(&mut self, tag: &str, attr: &str) {
+        *self
+            .unknown_attrs
+            .entry(format!("{}@{}", tag, attr))
+            .or_default() += 1;
+    }
+
+    pub(crate) fn add_coerced(&mut self, location: String, attr: &str, value: &str) {
+        self.coerced.push(CoercedValue {
+            location,
+            attr: attr.to_string(),
+            value: value.to_string(),
+        });
+    }
+
+    /// Collects the styles that are used but not defined.
+    pub(crate) fn check_styles(&mut self, book: &WorkBook) {
+        let mut missing: BTreeMap<(&'static str, String), u32> = BTreeMap::new();
+        let mut check = |kind: &'static str, name: &str, exists: bool| {
+            if !exists {
+                *missing.entry((kind, name.to_string())).or_default() += 1;
+            }
+        };
+
+        for sheet in book.sheets.iter() {
+            if let Some(style) = &sheet.style {
+                check(
+                    "table style",
+                    style.as_str(),
+                    book.tablestyles.contains_key(style.as_str()),
+                );
+            }
+            if let Some(style) = &sheet.default_cellstyle {
+                check(
+                    "cell style",
+                    style.as_str(),
+                    book.cellstyles.contains_key(style.as_str()),
+                );
+            }
+            for header in sheet.row_header.values() {
+                if let Some(style) = &header.style {
+                    check(
+                        "row style",
+                        style.as_str(),
+                        book.rowstyles.contains_key(style.as_str()),
+                    );
+                }
+                if let Some(style) = &header.cellstyle {
+                    check(
+                        "cell style",
+                        style.as_str(),
+                        book.cellstyles.contains_key(style.as_str()),
+                    );
+                }
+            }
+            for header in sheet.col_header.values() {
+                if let Some(style) = &header.style {
+                    check(
+                        "column style",
+                        style.as_str(),
+                        book.colstyles.contains_key(style.as_str()),
+                    );
+                }
+                if let Some(style) = &header.cellstyle {
+                    check(
+                        "cell style",
+                        style.as_str(),
+                        book.cellstyles.contains_key(style.as_str()),
+                    );
+                }
+            }
+            for cell in sheet.data.values() {
+                if let Some(style) = &cell.style {
+                    check(
+                        "cell style",
+                        style.as_str(),
+                        book.cellstyles.contains_key(style.as_str()),
+                    );
+                }
+                if let Some(name) = cell.extra.as_ref().and_then(|v| v.validation_name.as_ref()) {
+                    check(
+                        "validation",
+                        name,
+                        book.validations.contains_key(name.as_str()),
+                    );
+                }
+            }
+        }
+
+        for style in book.tablestyles.values() {
+            if let Some(name) = style.attrmap().attr("style:master-page-name") {
+                check(
+                    "master page",
+                    name,
+                    book.masterpages.contains_key(name.as_str()),
+                );
+            }
+        }
+        for page in book.masterpages.values() {
+            let name = page.pagestyle();
+            if !name.is_empty() {
+                check(
+                    "page style",
+                    name,
+                    book.pagestyles.contains_key(name.as_str()),
+                );
+            }
+        }
+        for style in book.cellstyles.values() {
+            if let Some(name) = style.value_format() {
+                let name = name.as_str();
+                check(
+                    "value format",
+                    name,
+                    book.formats_boolean.contains_key(name)
+                        || book.formats_number.contains_key(name)
+                        || book.formats_percentage.contains_key(name)
+                        || book.formats_currency.contains_key(name)
+                        || book.formats_text.contains_key(name)
+                        || book.formats_datetime.contains_key(name)
+                        || book.formats_timeduration.contains_key(name),
+                );
+            }
+        }
+
+        self.missing_styles = missing
+            .into_iter()
+            .map(|((kind, name), uses)| MissingStyle { kind, name, uses })
+            .collect();
+    }
+}
+
+/// Location of a cell for CoercedValue.
+pub(crate) fn cell_location(sheet: &str, row: u32, col: u32) -> String {
+    CellRef::remote(sheet, row, col).to_string()
+}
@@ -0,0 +1,118 @@
+//!
+//! Hooks for adding XML elements while writing, and observers for
+//! changes of cells.
+//!
+//! Elements that spreadsheet-ods doesn't know can be added at a few places
+//! of content.xml and styles.xml. The elements are written as they are,
+//! the namespaces of the element names must be declared in the root
+//! element.
+//!
+//! ```
+//! use spreadsheet_ods::hook::WriteHook;
+//! use spreadsheet_ods::xmltree::XmlTag;
+//! use spreadsheet_ods::{Sheet, WorkBook};
+//!
+//! struct Protection;
+//!
+//! impl WriteHook for Protection {
+//!     fn sheet(&self, _book: &WorkBook, sheet: &Sheet) -> Vec<XmlTag> {
+//!         vec![XmlTag::new("loext:table-protection")
+//!             .attr("loext:select-unprotected-cells", "true")
+//!             .attr("office:name", sheet.name().as_str())]
+//!     }
+//! }
+//!
+//! let mut wb = WorkBook::new_empty();
+//! wb.push_sheet(Sheet::new("1"));
+//! wb.add_write_hook(Protection);
+//! ```
+//!
+//! A CellObserver is registered on a Sheet and is told about the old and
+//! new values and formulas, e.g. to keep a dependency graph in sync.
+//!
+//! ```
+//! use spreadsheet_ods::hook::CellObserver;
+//! use spreadsheet_ods::{Sheet, Value};
+//! use std::sync::atomic::{AtomicU32, Ordering};
+//! use std::sync::Arc;
+//!
+//! struct Counter(Arc<AtomicU32>);
+//!
+//! impl CellObserver for Counter {
+//!     fn value_changed(&self, _sheet: &str, _row: u32, _col: u32, _old: &Value, _new: &Value) {
+//!         self.0.fetch_add(1, Ordering::Relaxed);
+//!     }
+//! }
+//!
+//! let changes = Arc::new(AtomicU32::new(0));
+//! let mut sheet = Sheet::new("1");
+//! sheet.add_observer(Counter(Arc::clone(&changes)));
+//! sheet.set_value(0, 0, 1);
+//! sheet.set_value(0, 0, 2);
+//! assert_eq!(changes.load(Ordering::Relaxed), 2);
+//! ```
+//!
+
+use crate::xmltree::XmlTag;
+use crate::{Sheet, Value, WorkBook};
+use std::panic::RefUnwindSafe;
+
+/// Adds elements while writing. All functions have a default
+/// implementation that adds nothing.
+///
+/// The hooks are called for each write, and are shared by all clones of
+/// the WorkBook.
+pub trait WriteHook: Send + Sync {
+    /// Elements at the end of table:table, after the rows and the named
+    /// ranges of the sheet.
+    fn sheet(&self, book: &WorkBook, sheet: &Sheet) -> Vec<XmlTag> {
+        let _ = (book, sheet);
+        Vec::new()
+    }
+
+    /// Elements at the end of office:spreadsheet, after the tables and
+    /// the named expressions.
+    fn spreadsheet(&self, book: &WorkBook) -> Vec<XmlTag> {
+        let _ = book;
+        Vec::new()
+    }
+
+    /// Elements at the end of office:styles in styles.xml.
+    fn styles(&self, book: &WorkBook) -> Vec<XmlTag> {
+        let _ = book;
+        Vec::new()
+    }
+}
+
+/// Gets notified when a cell is changed with Sheet::set_value(),
+/// set_styled_value(), set_formula(), set_formula_value() or
+/// clear_formula(), and the try_ variants. Both functions have a default
+/// implementation that does nothing.
+///
+/// The observer is called after the change, for every call, even if the
+/// new value is the same as the old one. add_cell() and remove_cell()
+/// report the formula and the value, a removed cell is Value::Empty.
+/// This includes the undo and rollback of a Transaction. Other changes,
+/// like inserting rows or reading a file, are not reported.
+///
+/// The observers are shared by all clones of the Sheet. The bound on
+/// RefUnwindSafe keeps the Sheet unwind safe, a Mutex or an atomic
+/// fulfills it.
+pub trait CellObserver: Send + Sync + RefUnwindSafe {
+    /// The value of the cell was set.
+    fn value_changed(&self, sheet: &str, row: u32, col: u32, old: &Value, new: &Value) {
+        let _ = (sheet, row, col, old, new);
+    }
+
+    /// The formula of the cell was set or removed.
+    fn formula_changed(
+        &self,
+        sheet: &str,
+        row: u32,
+        col: u32,
+        old: Option<&str>,
+        new: Option<&str>,
+    ) {
+        let _ = (sheet, row, col, old, new);
+    }
+}
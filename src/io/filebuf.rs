@@ -1,10 +1,12 @@
 use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
 
 /// Directory or file.
 #[derive(Clone)]
 pub(crate) enum FileBufEntry {
     Dir(String),
-    File(String, Vec<u8>),
+    // Shared between clones of the workbook.
+    File(String, Arc<Vec<u8>>),
 }
 
 impl Debug for FileBufEntry {
@@ -21,6 +23,14 @@ impl Debug for FileBufEntry {
     }
 }
 
+/// Entry of META-INF/manifest.xml.
+#[derive(Clone, Debug)]
+pub(crate) struct Manifest {
+    pub(crate) full_path: String,
+    pub(crate) version: Option<String>,
+    pub(crate) media_type: String,
+}
+
 /// Acts as a buffer for files and directories.
 #[derive(Clone, Debug)]
 pub(crate) struct FileBuf {
@@ -57,11 +67,49 @@ impl FileBuf {
         false
     }
 
+    /// Is there a file with this name, or a directory with any content.
+    pub(crate) fn contains_path<S: AsRef<str>>(&self, name: S) -> bool {
+        let name = name.as_ref();
+        if name.ends_with('/') {
+            self.buf.iter().any(|v| match v {
+                FileBufEntry::Dir(n) => n.starts_with(name),
+                FileBufEntry::File(n, _) => n.starts_with(name),
+            })
+        } else {
+            self.contains(name)
+        }
+    }
+
+    /// Removes a file. A name that ends with '/' removes the directory
+    /// and everything within.
+    pub(crate) fn remove<S: AsRef<str>>(&mut self, name: S) -> bool {
+        let name = name.as_ref();
+        let len = self.buf.len();
+        self.buf.retain(|v| {
+            let n = match v {
+                FileBufEntry::Dir(n) => n,
+                FileBufEntry::File(n, _) => n,
+            };
+            if name.ends_with('/') {
+                !n.starts_with(name)
+            } else {
+                n != name
+            }
+        });
+        len != self.buf.len()
+    }
+
     pub(crate) fn push_dir<S: Into<String>>(&mut self, dir: S) {
         self.buf.push(FileBufEntry::Dir(dir.into()));
     }
 
     pub(crate) fn push_file<S: Into<String>>(&mut self, file: S, data: Vec<u8>) {
+        self.buf
+            .push(FileBufEntry::File(file.into(), Arc::new(data)));
+    }
+
+    /// Adds a file without copying the data.
+    pub(crate) fn push_shared<S: Into<String>>(&mut self, file: S, data: Arc<Vec<u8>>) {
         self.buf.push(FileBufEntry::File(file.into(), data));
     }
 }
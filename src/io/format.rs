@@ -0,0 +1,501 @@
+//!
+//! Creates the text content of a cell from the value format of its cell-style.
+//!
+//! The text is what a reader sees, that doesn't evaluate the value
+//! attributes. Spreadsheet applications recalculate it anyway.
+//!
+//! The value formats are compiled once into a list of steps, rendering
+//! a value only runs these steps and doesn't look at the attributes
+//! again.
+//!
+
+use crate::format::{FormatPart, FormatPartType};
+use crate::health::FormatWarning;
+use crate::io::write::{format_iso_duration, format_number};
+use crate::locale::{currency_symbol_for_code, currency_symbol_for_country};
+use crate::style::CellStyleRef;
+use crate::{
+    Value, ValueFormatCurrency, ValueFormatDateTime, ValueFormatTrait, ValueType, WorkBook,
+};
+use chrono::{Datelike, NaiveDateTime, Timelike};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::rc::Rc;
+
+/// Renders the text of cell values with the value formats of a workbook.
+///
+/// Caches the compiled value format for each cell-style. The lookup goes
+/// via the cell-style and the value format and is the same for all cells
+/// with the same style. Create one for rendering many cells of the same
+/// workbook.
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use spreadsheet_ods::format::{create_date_dmy_format, RenderContext};
+/// use spreadsheet_ods::{CellStyle, Value, WorkBook};
+///
+/// let mut book = WorkBook::new_empty();
+/// let format = book.add_datetime_format(create_date_dmy_format("dmy"));
+/// let style = book.add_cellstyle(CellStyle::new("date", &format));
+///
+/// let mut ctx = RenderContext::new(&book);
+/// let value = Value::from(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+/// assert_eq!(ctx.formatted_value(&value, Some(&style)), "01.03.2024");
+/// ```
+#[derive(Debug)]
+pub struct RenderContext<'a> {
+    book: &'a WorkBook,
+    currency: HashMap<String, Option<CompiledCurrency<'a>>>,
+    datetime: HashMap<String, Option<CompiledDateTime<'a>>>,
+    warnings: Vec<FormatWarning>,
+}
+
+/// A currency format compiled into a list of steps.
+/// See [RenderContext::compile_currency].
+#[derive(Debug, Clone)]
+pub struct CompiledCurrency<'a>(Rc<[CurrencyOp<'a>]>);
+
+/// A datetime format compiled into a list of steps.
+/// See [RenderContext::compile_datetime].
+#[derive(Debug, Clone)]
+pub struct CompiledDateTime<'a>(Rc<[DateTimeOp<'a>]>);
+
+/// One step of a compiled currency format.
+#[derive(Debug, Clone)]
+enum CurrencyOp<'a> {
+    /// Fixed currency symbol.
+    Symbol(&'a str),
+    /// Symbol derived from the currency code of the value.
+    CodeSymbol,
+    Number,
+    Text(&'a str),
+}
+
+/// One step of a compiled datetime format.
+#[derive(Debug, Clone)]
+enum DateTimeOp<'a> {
+    Day { long: bool },
+    Month { long: bool },
+    MonthName { long: bool },
+    Year { long: bool, week_year: bool },
+    Era,
+    DayOfWeek { long: bool, numeric: bool },
+    WeekOfYear,
+    Quarter { long: bool },
+    Hours { long: bool, am_pm: bool },
+    Minutes { long: bool },
+    Seconds { long: bool, decimals: usize },
+    AmPm,
+    Text(&'a str),
+}
+
+/// Name of the value format for the cell-style.
+fn value_format<'a>(book: &'a WorkBook, style: &CellStyleRef) -> Option<&'a String> {
+    book.cellstyle(style.as_str())
+        .and_then(|v| v.value_format())
+}
+
+/// A value format that doesn't exist or is for another value type.
+/// The default format of the value type is used instead.
+fn missing_format(
+    book: &WorkBook,
+    style: &CellStyleRef,
+    format: &str,
+    value_type: ValueType,
+) -> FormatWarning {
+    let found = format_type(book, format);
+    trace_event!(
+        style = style.as_str(),
+        format = format,
+        found = ?found,
+        "value format not found, using the default"
+    );
+    FormatWarning {
+        style: style.as_str().to_string(),
+        format: format.to_string(),
+        value_type,
+        found,
+    }
+}
+
+/// Value type of the format with this name.
+fn format_type(book: &WorkBook, format: &str) -> Option<ValueType> {
+    if book.formats_boolean.contains_key(format) {
+        Some(ValueType::Boolean)
+    } else if book.formats_number.contains_key(format) {
+        Some(ValueType::Number)
+    } else if book.formats_percentage.contains_key(format) {
+        Some(ValueType::Percentage)
+    } else if book.formats_currency.contains_key(format) {
+        Some(ValueType::Currency)
+    } else if book.formats_text.contains_key(format) {
+        Some(ValueType::Text)
+    } else if book.formats_datetime.contains_key(format) {
+        Some(ValueType::DateTime)
+    } else if book.formats_timeduration.contains_key(format) {
+        Some(ValueType::TimeDuration)
+    } else {
+        None
+    }
+}
+
+impl<'a> RenderContext<'a> {
+    /// Render context for the workbook.
+    pub fn new(book: &'a WorkBook) -> Self {
+        Self {
+            book,
+            currency: Default::default(),
+            datetime: Default::default(),
+            warnings: Default::default(),
+        }
+    }
+
+    /// The cell-styles whose value format was missing or for another
+    /// value type, once for each cell-style. Their values were rendered
+    /// with the default format.
+    pub fn warnings(&self) -> &[FormatWarning] {
+        &self.warnings
+    }
+
+    pub(crate) fn take_warnings(&mut self) -> Vec<FormatWarning> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    /// The text of the value as it is written to the cell. Without a
+    /// cell-style the default style for the value type is used.
+    ///
+    /// Currencies and datetimes use the value format of the style, all
+    /// other values are written the same as their value attribute.
+    pub fn formatted_value(&mut self, value: &Value, style: Option<&CellStyleRef>) -> String {
+        let book = self.book;
+        let style = style.or_else(|| book.def_style(value.value_type()));
+        match value {
+            Value::Empty => String::new(),
+            Value::Text(s) => s.clone(),
+            Value::TextXml(t) => {
+                let mut buf = String::new();
+                for (i, tag) in t.iter().enumerate() {
+                    if i > 0 {
+                        buf.push('\n');
+                    }
+                    tag.extract_text(&mut buf);
+                }
+                buf
+            }
+            Value::DateTime(d) => self.format_datetime(style, d),
+            Value::TimeDuration(d) => format_iso_duration(d),
+            Value::Boolean(b) => b.to_string(),
+            Value::Currency(v, c) => {
+                self.format_currency(style, &format_number(*v, book.number_policy), c)
+            }
+            Value::Number(v) | Value::Percentage(v) => format_number(*v, book.number_policy),
+        }
+    }
+
+    /// Compiles a currency format. The value is used for the first number
+    /// part only. Without a number part it is appended.
+    pub fn compile_currency(format: &ValueFormatCurrency) -> CompiledCurrency<'_> {
+        CompiledCurrency(compile_currency(format))
+    }
+
+    /// Compiles a datetime format. If the format contains a week of the
+    /// year the year is the ISO 8601 week-year and the day of the week
+    /// is the ISO weekday 1 (Monday) to 7 (Sunday).
+    pub fn compile_datetime(format: &ValueFormatDateTime) -> CompiledDateTime<'_> {
+        CompiledDateTime(compile_datetime(format))
+    }
+
+    /// The compiled currency format of the cell-style. None if the style
+    /// has no currency format.
+    pub fn currency_format(
+        &mut self,
+        style: Option<&CellStyleRef>,
+    ) -> Option<CompiledCurrency<'a>> {
+        let style = style?;
+        if let Some(format) = self.currency.get(style.as_str()) {
+            return format.clone();
+        }
+        let book = self.book;
+        let format = value_format(book, style).and_then(|v| match book.currency_format(v) {
+            Some(format) => Some(Self::compile_currency(format)),
+            None => {
+                let warning = missing_format(book, style, v, ValueType::Currency);
+                self.warnings.push(warning);
+                None
+            }
+        });
+        self.currency
+            .insert(style.as_str().to_string(), format.clone());
+        format
+    }
+
+    /// The compiled datetime format of the cell-style. None if the style
+    /// has no datetime format.
+    pub fn datetime_format(
+        &mut self,
+        style: Option<&CellStyleRef>,
+    ) -> Option<CompiledDateTime<'a>> {
+        let style = style?;
+        if let Some(format) = self.datetime.get(style.as_str()) {
+            return format.clone();
+        }
+        let book = self.book;
+        let format = value_format(book, style).and_then(|v| match book.datetime_format(v) {
+            Some(format) => Some(Self::compile_datetime(format)),
+            None => {
+                let warning = missing_format(book, style, v, ValueType::DateTime);
+                self.warnings.push(warning);
+                None
+            }
+        });
+        self.datetime
+            .insert(style.as_str().to_string(), format.clone());
+        format
+    }
+
+    /// Text for a currency value. If the cell-style references a currency
+    /// format the currency symbol and its placement are taken from there,
+    /// otherwise the symbol for the currency code is put before the value.
+    pub(crate) fn format_currency(
+        &mut self,
+        style: Option<&CellStyleRef>,
+        value: &str,
+        currency: &[u8; 3],
+    ) -> String {
+        if let Some(format) = self.currency_format(style) {
+            format.render(value, currency)
+        } else {
+            let code = String::from_utf8_lossy(currency);
+            let symbol = currency_symbol_for_code(&code).unwrap_or(&code);
+            format!("{} {}", symbol, value)
+        }
+    }
+
+    /// Text for a datetime value. If the cell-style references a datetime
+    /// format the text is created from its parts, otherwise the ISO format
+    /// is used. A missing value format is reported with the feature
+    /// "tracing".
+    pub(crate) fn format_datetime(
+        &mut self,
+        style: Option<&CellStyleRef>,
+        value: &NaiveDateTime,
+    ) -> String {
+        if let Some(format) = self.datetime_format(style) {
+            format.render(value)
+        } else {
+            value.format("%Y-%m-%dT%H:%M:%S%.f").to_string()
+        }
+    }
+}
+
+impl CompiledCurrency<'_> {
+    /// Text for a currency value. The number is inserted as is. If the
+    /// format has no explicit symbol it is taken from the currency code.
+    pub fn render(&self, number: &str, currency: &[u8; 3]) -> String {
+        let code = String::from_utf8_lossy(currency);
+        let mut buf = String::new();
+        for op in self.0.iter() {
+            match op {
+                CurrencyOp::Symbol(symbol) => buf.push_str(symbol),
+                CurrencyOp::CodeSymbol => {
+                    buf.push_str(currency_symbol_for_code(&code).unwrap_or(&code))
+                }
+                CurrencyOp::Number => buf.push_str(number),
+                CurrencyOp::Text(text) => buf.push_str(text),
+            }
+        }
+        buf
+    }
+}
+
+impl CompiledDateTime<'_> {
+    /// Text for a datetime value.
+    pub fn render(&self, value: &NaiveDateTime) -> String {
+        let mut buf = String::new();
+        for op in self.0.iter() {
+            push_datetime_op(&mut buf, op, value);
+        }
+        buf
+    }
+}
+
+/// The value is used for the first number part only. Without a number
+/// part it is appended.
+fn compile_currency(format: &ValueFormatCurrency) -> Rc<[CurrencyOp<'_>]> {
+    let mut ops = Vec::new();
+    let mut has_number = false;
+    for part in format.parts() {
+        match part.part_type() {
+            FormatPartType::CurrencySymbol => {
+                ops.push(currency_symbol(part));
+            }
+            FormatPartType::Number if !has_number => {
+                ops.push(CurrencyOp::Number);
+                has_number = true;
+            }
+            FormatPartType::Text => {
+                if let Some(text) = part.content() {
+                    ops.push(CurrencyOp::Text(text));
+                }
+            }
+            _ => {}
+        }
+    }
+    if !has_number {
+        ops.push(CurrencyOp::Number);
+    }
+    ops.into()
+}
+
+/// Symbol for a currency-symbol part. Explicit symbol text wins, otherwise
+/// the symbol is derived from the country of the part or the currency code.
+fn currency_symbol(part: &FormatPart) -> CurrencyOp<'_> {
+    match part.content() {
+        Some(symbol) if !symbol.is_empty() => CurrencyOp::Symbol(symbol),
+        _ => match currency_symbol_for_country(part.attr_def("number:country", "")) {
+            Some(symbol) => CurrencyOp::Symbol(symbol),
+            None => CurrencyOp::CodeSymbol,
+        },
+    }
+}
+
+/// If the format contains a week of the year the year is the ISO 8601
+/// week-year, the year the week belongs to, and the day of the week is
+/// the ISO weekday 1 to 7.
+fn compile_datetime(format: &ValueFormatDateTime) -> Rc<[DateTimeOp<'_>]> {
+    let week_year = format
+        .parts()
+        .iter()
+        .any(|v| v.part_type() == FormatPartType::WeekOfYear);
+    let am_pm = format
+        .parts()
+        .iter()
+        .any(|v| v.part_type() == FormatPartType::AmPm);
+
+    let mut ops = Vec::new();
+    for part in format.parts() {
+        let long = part.attr_def("number:style", "short") == "long";
+        let op = match part.part_type() {
+            FormatPartType::Day => DateTimeOp::Day { long },
+            FormatPartType::Month => {
+                if part.attr_def("number:textual", "false") == "true" {
+                    DateTimeOp::MonthName { long }
+                } else {
+                    DateTimeOp::Month { long }
+                }
+            }
+            FormatPartType::Year => DateTimeOp::Year { long, week_year },
+            FormatPartType::Era => DateTimeOp::Era,
+            FormatPartType::DayOfWeek => DateTimeOp::DayOfWeek {
+                long,
+                numeric: week_year,
+            },
+            FormatPartType::WeekOfYear => DateTimeOp::WeekOfYear,
+            FormatPartType::Quarter => DateTimeOp::Quarter { long },
+            FormatPartType::Hours => DateTimeOp::Hours { long, am_pm },
+            FormatPartType::Minutes => DateTimeOp::Minutes { long },
+            FormatPartType::Seconds => DateTimeOp::Seconds {
+                long,
+                decimals: part
+                    .attr_def("number:decimal-places", "0")
+                    .parse::<usize>()
+                    .unwrap_or(0)
+                    .min(9),
+            },
+            FormatPartType::AmPm => DateTimeOp::AmPm,
+            FormatPartType::Text => match part.content() {
+                Some(text) => DateTimeOp::Text(text),
+                None => continue,
+            },
+            _ => continue,
+        };
+        ops.push(op);
+    }
+    ops.into()
+}
+
+fn push_datetime_op(buf: &mut String, op: &DateTimeOp<'_>, value: &NaiveDateTime) {
+    match *op {
+        DateTimeOp::Day { long } => {
+            push_datepart(buf, value.day(), long);
+        }
+        DateTimeOp::Month { long } => {
+            push_datepart(buf, value.month(), long);
+        }
+        DateTimeOp::MonthName { long } => {
+            let _ = write!(buf, "{}", value.format(if long { "%B" } else { "%b" }));
+        }
+        DateTimeOp::Year { long, week_year } => {
+            let year = if week_year {
+                value.iso_week().year()
+            } else {
+                value.year()
+            };
+            if long {
+                let _ = write!(buf, "{:04}", year);
+            } else {
+                let _ = write!(buf, "{:02}", year.rem_euclid(100));
+            }
+        }
+        DateTimeOp::Era => {
+            buf.push_str(if value.year() > 0 { "AD" } else { "BC" });
+        }
+        DateTimeOp::DayOfWeek { long, numeric } => {
+            if numeric {
+                let _ = write!(buf, "{}", value.weekday().number_from_monday());
+            } else {
+                let _ = write!(buf, "{}", value.format(if long { "%A" } else { "%a" }));
+            }
+        }
+        DateTimeOp::WeekOfYear => {
+            let _ = write!(buf, "{:02}", value.iso_week().week());
+        }
+        DateTimeOp::Quarter { long } => {
+            let quarter = value.month0() / 3 + 1;
+            if long {
+                let suffix = match quarter {
+                    1 => "st",
+                    2 => "nd",
+                    3 => "rd",
+                    _ => "th",
+                };
+                let _ = write!(buf, "{}{} quarter", quarter, suffix);
+            } else {
+                let _ = write!(buf, "Q{}", quarter);
+            }
+        }
+        DateTimeOp::Hours { long, am_pm } => {
+            let hour = if am_pm {
+                value.hour12().1
+            } else {
+                value.hour()
+            };
+            push_datepart(buf, hour, long);
+        }
+        DateTimeOp::Minutes { long } => {
+            push_datepart(buf, value.minute(), long);
+        }
+        DateTimeOp::Seconds { long, decimals } => {
+            push_datepart(buf, value.second(), long);
+            if decimals > 0 {
+                let nanos = format!("{:09}", value.nanosecond() % 1_000_000_000);
+                buf.push('.');
+                buf.push_str(&nanos[..decimals]);
+            }
+        }
+        DateTimeOp::AmPm => {
+            let _ = write!(buf, "{}", value.format("%p"));
+        }
+        DateTimeOp::Text(text) => {
+            buf.push_str(text);
+        }
+    }
+}
+
+fn push_datepart(buf: &mut String, value: u32, long: bool) {
+    if long {
+        let _ = write!(buf, "{:02}", value);
+    } else {
+        let _ = write!(buf, "{}", value);
+    }
+}
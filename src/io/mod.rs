@@ -1,4 +1,5 @@
 pub(crate) mod filebuf;
+pub(crate) mod format;
 pub(crate) mod parse;
 pub(crate) mod read;
 pub(crate) mod write;
@@ -9,3 +10,127 @@ mod zip_out;
 
 const DUMP_XML: bool = false;
 const DUMP_UNUSED: bool = false;
+
+/// The default cell style of a sheet is written as columns up to here.
+/// LibreOffice before 7.4 doesn't read more columns.
+const DEFAULT_CELLSTYLE_COLS: u32 = 1024;
+
+/// Namespaces declared in styles.xml.
+pub(crate) const STYLES_NAMESPACES: &[(&str, &str)] = &[
+    ("meta", "urn:oasis:names:tc:opendocument:xmlns:meta:1.0"),
+    ("office", "urn:oasis:names:tc:opendocument:xmlns:office:1.0"),
+    (
+        "fo",
+        "urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0",
+    ),
+    ("ooo", "http://openoffice.org/2004/office"),
+    ("xlink", "http://www.w3.org/1999/xlink"),
+    ("dc", "http://purl.org/dc/elements/1.1/"),
+    ("style", "urn:oasis:names:tc:opendocument:xmlns:style:1.0"),
+    ("text", "urn:oasis:names:tc:opendocument:xmlns:text:1.0"),
+    ("dr3d", "urn:oasis:names:tc:opendocument:xmlns:dr3d:1.0"),
+    (
+        "svg",
+        "urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0",
+    ),
+    ("chart", "urn:oasis:names:tc:opendocument:xmlns:chart:1.0"),
+    ("rpt", "http://openoffice.org/2005/report"),
+    ("table", "urn:oasis:names:tc:opendocument:xmlns:table:1.0"),
+    (
+        "number",
+        "urn:oasis:names:tc:opendocument:xmlns:datastyle:1.0",
+    ),
+    ("ooow", "http://openoffice.org/2004/writer"),
+    ("oooc", "http://openoffice.org/2004/calc"),
+    ("of", "urn:oasis:names:tc:opendocument:xmlns:of:1.2"),
+    ("tableooo", "http://openoffice.org/2009/table"),
+    (
+        "calcext",
+        "urn:org:documentfoundation:names:experimental:calc:xmlns:calcext:1.0",
+    ),
+    ("drawooo", "http://openoffice.org/2010/draw"),
+    ("draw", "urn:oasis:names:tc:opendocument:xmlns:drawing:1.0"),
+    (
+        "loext",
+        "urn:org:documentfoundation:names:experimental:office:xmlns:loext:1.0",
+    ),
+    (
+        "field",
+        "urn:openoffice:names:experimental:ooo-ms-interop:xmlns:field:1.0",
+    ),
+    ("math", "http://www.w3.org/1998/Math/MathML"),
+    ("form", "urn:oasis:names:tc:opendocument:xmlns:form:1.0"),
+    ("script", "urn:oasis:names:tc:opendocument:xmlns:script:1.0"),
+    ("dom", "http://www.w3.org/2001/xml-events"),
+    ("xhtml", "http://www.w3.org/1999/xhtml"),
+    ("grddl", "http://www.w3.org/2003/g/data-view#"),
+    ("css3t", "http://www.w3.org/TR/css3-text/"),
+    (
+        "presentation",
+        "urn:oasis:names:tc:opendocument:xmlns:presentation:1.0",
+    ),
+];
+
+/// Namespaces declared in content.xml.
+pub(crate) const CONTENT_NAMESPACES: &[(&str, &str)] = &[
+    ("meta", "urn:oasis:names:tc:opendocument:xmlns:meta:1.0"),
+    ("office", "urn:oasis:names:tc:opendocument:xmlns:office:1.0"),
+    (
+        "fo",
+        "urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0",
+    ),
+    ("ooo", "http://openoffice.org/2004/office"),
+    ("xlink", "http://www.w3.org/1999/xlink"),
+    ("dc", "http://purl.org/dc/elements/1.1/"),
+    ("style", "urn:oasis:names:tc:opendocument:xmlns:style:1.0"),
+    ("text", "urn:oasis:names:tc:opendocument:xmlns:text:1.0"),
+    ("draw", "urn:oasis:names:tc:opendocument:xmlns:drawing:1.0"),
+    ("dr3d", "urn:oasis:names:tc:opendocument:xmlns:dr3d:1.0"),
+    (
+        "svg",
+        "urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0",
+    ),
+    ("chart", "urn:oasis:names:tc:opendocument:xmlns:chart:1.0"),
+    ("rpt", "http://openoffice.org/2005/report"),
+    ("table", "urn:oasis:names:tc:opendocument:xmlns:table:1.0"),
+    (
+        "number",
+        "urn:oasis:names:tc:opendocument:xmlns:datastyle:1.0",
+    ),
+    ("ooow", "http://openoffice.org/2004/writer"),
+    ("oooc", "http://openoffice.org/2004/calc"),
+    ("of", "urn:oasis:names:tc:opendocument:xmlns:of:1.2"),
+    ("tableooo", "http://openoffice.org/2009/table"),
+    (
+        "calcext",
+        "urn:org:documentfoundation:names:experimental:calc:xmlns:calcext:1.0",
+    ),
+    ("drawooo", "http://openoffice.org/2010/draw"),
+    (
+        "loext",
+        "urn:org:documentfoundation:names:experimental:office:xmlns:loext:1.0",
+    ),
+    (
+        "field",
+        "urn:openoffice:names:experimental:ooo-ms-interop:xmlns:field:1.0",
+    ),
+    ("math", "http://www.w3.org/1998/Math/MathML"),
+    ("form", "urn:oasis:names:tc:opendocument:xmlns:form:1.0"),
+    ("script", "urn:oasis:names:tc:opendocument:xmlns:script:1.0"),
+    ("dom", "http://www.w3.org/2001/xml-events"),
+    ("xforms", "http://www.w3.org/2002/xforms"),
+    ("xsd", "http://www.w3.org/2001/XMLSchema"),
+    ("xsi", "http://www.w3.org/2001/XMLSchema-instance"),
+    (
+        "formx",
+        "urn:openoffice:names:experimental:ooxml-odf-interop:xmlns:form:1.0",
+    ),
+    ("xhtml", "http://www.w3.org/1999/xhtml"),
+    ("grddl", "http://www.w3.org/2003/g/data-view#"),
+    ("css3t", "http://www.w3.org/TR/css3-text/"),
+    (
+        "presentation",
+        "urn:oasis:names:tc:opendocument:xmlns:presentation:1.0",
+    ),
+    ("sods", "urn:spreadsheet-ods:xmlns:cellmeta:1.0"),
+];
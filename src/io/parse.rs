@@ -3,7 +3,7 @@
 //!
 //! For many cases this omits the transformation to a &str
 
-use crate::{OdsError, Visibility};
+use crate::{ContentType, OdsError, Visibility};
 use chrono::Duration;
 use chrono::NaiveDateTime;
 use nom::branch::alt;
@@ -29,6 +29,24 @@ pub(crate) fn parse_visibility(input: &[u8]) -> Result<Visibility, OdsError> {
     }
 }
 
+/// Parse as ContentType.
+pub(crate) fn parse_content_type(input: &[u8]) -> Result<ContentType, OdsError> {
+    match input {
+        b"float" => Ok(ContentType::Float),
+        b"percentage" => Ok(ContentType::Percentage),
+        b"currency" => Ok(ContentType::Currency),
+        b"date" => Ok(ContentType::Date),
+        b"time" => Ok(ContentType::Time),
+        b"boolean" => Ok(ContentType::Boolean),
+        b"string" => Ok(ContentType::String),
+        b"error" => Ok(ContentType::Error),
+        _ => Err(OdsError::Ods(format!(
+            "Unknown value for calcext:value-type {}",
+            from_utf8(input)?
+        ))),
+    }
+}
+
 /// Parse a attribute value as a currency.
 pub(crate) fn parse_currency(input: &[u8]) -> Result<[u8; 3], OdsError> {
     match input.len() {
@@ -80,6 +98,16 @@ pub(crate) fn parse_duration(input: &[u8]) -> Result<Duration, OdsError> {
     Ok(token_duration(input)?.1)
 }
 
+/// Parses a duration in the ISO 8601 format as used for time values,
+/// eg "PT12H30M5.25S" or "-P1DT2H".
+///
+/// Days count as 24 hours. Years and months have no fixed length and
+/// are only accepted if they are zero. The fraction of the seconds is
+/// kept up to nanoseconds.
+pub fn parse_iso_duration(input: &str) -> Result<Duration, OdsError> {
+    parse_duration(input.as_bytes())
+}
+
 fn token_bool(input: &[u8]) -> IResult<&[u8], bool> {
     let (input, result) = terminated(
         alt((map(tag(b"true"), |_| true), map(tag(b"false"), |_| false))),
@@ -256,31 +284,62 @@ fn token_datetime(input: &[u8]) -> IResult<&[u8], NaiveDateTime> {
 }
 
 fn token_duration(input: &[u8]) -> IResult<&[u8], Duration> {
-    let (input, result) = terminated(
+    let (rest, result) = terminated(
         tuple((
+            opt(byte(b'-')),
             byte(b'P'),
             opt(terminated(token_datepart, byte(b'Y'))),
             opt(terminated(token_datepart, byte(b'M'))),
             opt(terminated(token_datepart, byte(b'D'))),
-            byte(b'T'),
-            terminated(token_datepart, byte(b'H')),
-            terminated(token_datepart, byte(b'M')),
-            terminated(
-                pair(token_datepart, opt(preceded(byte(b'.'), token_nano))),
-                byte(b'S'),
-            ),
+            opt(preceded(
+                byte(b'T'),
+                tuple((
+                    opt(terminated(token_datepart, byte(b'H'))),
+                    opt(terminated(token_datepart, byte(b'M'))),
+                    opt(terminated(
+                        pair(token_datepart, opt(preceded(byte(b'.'), token_nano))),
+                        byte(b'S'),
+                    )),
+                )),
+            )),
         )),
         eof,
     )(input)?;
 
-    let result = if let Some(nanos) = result.7 .1 {
-        Duration::seconds(result.5 * 3600 + result.6 * 60 + result.7 .0)
-            + Duration::nanoseconds(nanos)
-    } else {
-        Duration::seconds(result.5 * 3600 + result.6 * 60 + result.7 .0)
+    let (sign, _, years, months, days, time) = result;
+    let (hours, minutes, seconds) = time.unwrap_or((None, None, None));
+    let (seconds, nanos) = seconds.unwrap_or((0, None));
+
+    // No fixed length for years and months.
+    if years.unwrap_or(0) != 0 || months.unwrap_or(0) != 0 {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            ErrorKind::Verify,
+        )));
+    }
+
+    // Must fit into a chrono::Duration.
+    let secs = days
+        .unwrap_or(0)
+        .checked_mul(86400)
+        .and_then(|v| v.checked_add(hours.unwrap_or(0).checked_mul(3600)?))
+        .and_then(|v| v.checked_add(minutes.unwrap_or(0).checked_mul(60)?))
+        .and_then(|v| v.checked_add(seconds))
+        .filter(|v| *v < i64::MAX / 1000);
+    let secs = match secs {
+        Some(v) => v,
+        None => {
+            return Err(nom::Err::Error(nom::error::Error::new(
+                input,
+                ErrorKind::Verify,
+            )))
+        }
     };
 
-    Ok((input, result))
+    let result = Duration::seconds(secs) + Duration::nanoseconds(nanos.unwrap_or(0));
+    let result = if sign.is_some() { -result } else { result };
+
+    Ok((rest, result))
 }
 
 pub(crate) fn byte(c: u8) -> impl Fn(&[u8]) -> IResult<&[u8], u8> {
@@ -362,6 +421,17 @@ mod tests {
             parse_duration(b"PT12H12M12.223S")?.num_milliseconds(),
             43932223
         );
+        assert_eq!(
+            parse_duration(b"PT0H0M0.000001234S")?.num_nanoseconds(),
+            Some(1234)
+        );
+        assert_eq!(parse_duration(b"P1DT1H")?.num_hours(), 25);
+        assert_eq!(parse_duration(b"P0Y0M1D")?.num_hours(), 24);
+        assert_eq!(parse_duration(b"-PT1M30S")?.num_seconds(), -90);
+        assert_eq!(parse_duration(b"PT90S")?.num_seconds(), 90);
+        parse_duration(b"P1M").unwrap_err();
+        parse_duration(b"PT99999999999999999H").unwrap_err();
+        parse_duration(b"PT1H ").unwrap_err();
         Ok(())
     }
 
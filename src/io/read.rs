@@ -1,11 +1,10 @@
 use std::convert::{TryFrom, TryInto};
 use std::fs::File;
-use std::io::{BufReader, Cursor, Read, Seek, Write};
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, Write};
 use std::path::Path;
 
 use chrono::{Duration, NaiveDateTime};
 use quick_xml::events::{BytesStart, Event};
-use zip::read::ZipFile;
 use zip::ZipArchive;
 
 use crate::attrmap2::AttrMap2;
@@ -15,17 +14,22 @@ use crate::ds::bufstack::BufStack;
 use crate::ds::detach::Detach;
 use crate::error::OdsError;
 use crate::format::{FormatPart, FormatPartType, ValueFormatTrait};
+use crate::health::{cell_location, DroppedElement, OdsHealthReport};
+use crate::io::filebuf::Manifest;
 use crate::io::parse::{
-    parse_bool, parse_currency, parse_datetime, parse_duration, parse_f64, parse_i16, parse_i32,
-    parse_i64, parse_u32, parse_visibility,
+    parse_bool, parse_content_type, parse_currency, parse_datetime, parse_duration, parse_f64,
+    parse_i16, parse_i32, parse_i64, parse_u32, parse_visibility,
 };
-use crate::io::{DUMP_UNUSED, DUMP_XML};
-use crate::refs::{parse_cellranges, parse_cellref};
+use crate::io::{
+    CONTENT_NAMESPACES, DEFAULT_CELLSTYLE_COLS, DUMP_UNUSED, DUMP_XML, STYLES_NAMESPACES,
+};
+use crate::meta;
+use crate::refs::{parse_cellref, CellRangeList};
 use crate::style::stylemap::StyleMap;
 use crate::style::tabstop::TabStop;
 use crate::style::{
-    ColStyle, FontFaceDecl, GraphicStyle, HeaderFooter, MasterPage, PageStyle, ParagraphStyle,
-    RowStyle, StyleOrigin, StyleUse, TableStyle, TextStyle,
+    CellStyleRef, ColStyle, ColStyleRef, FontFaceDecl, GraphicStyle, HeaderFooter, MasterPage,
+    PageStyle, ParagraphStyle, RowStyle, StyleOrigin, StyleUse, TableStyle, TextStyle,
 };
 use crate::text::{TextP, TextTag};
 use crate::validation::{MessageType, Validation, ValidationError, ValidationHelp};
@@ -37,60 +41,248 @@ use crate::{
 };
 use quick_xml::events::attributes::Attribute;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::mem;
 use std::str::from_utf8;
 
 /// Reads an ODS-file from a buffer
 pub fn read_ods_buf(buf: &[u8]) -> Result<WorkBook, OdsError> {
     let zip = ZipArchive::new(Cursor::new(buf))?;
-    read_ods_impl(zip)
+    read_ods_impl(&mut ReadCtx::new(false), zip)
 }
 
 /// Reads an ODS-file.
 pub fn read_ods<P: AsRef<Path>>(path: P) -> Result<WorkBook, OdsError> {
     let file = File::open(path.as_ref())?;
     let zip = ZipArchive::new(file)?;
-    read_ods_impl(zip)
+    read_ods_impl(&mut ReadCtx::new(false), zip)
+}
+
+/// Reads an ODS-file from a buffer. Invalid values of tables, rows and
+/// cells are replaced with a default and listed in the report.
+pub fn read_ods_buf_lenient(buf: &[u8]) -> Result<(WorkBook, OdsHealthReport), OdsError> {
+    let zip = ZipArchive::new(Cursor::new(buf))?;
+    read_lenient(|ctx| read_ods_impl(ctx, zip))
+}
+
+/// Reads an ODS-file. Invalid values of tables, rows and cells are
+/// replaced with a default and listed in the report.
+pub fn read_ods_lenient<P: AsRef<Path>>(path: P) -> Result<(WorkBook, OdsHealthReport), OdsError> {
+    let file = File::open(path.as_ref())?;
+    let zip = ZipArchive::new(file)?;
+    read_lenient(|ctx| read_ods_impl(ctx, zip))
+}
+
+fn read_lenient<F>(read: F) -> Result<(WorkBook, OdsHealthReport), OdsError>
+where
+    F: FnOnce(&mut ReadCtx) -> Result<WorkBook, OdsError>,
+{
+    let mut ctx = ReadCtx::new(true);
+    let book = read(&mut ctx)?;
+    let mut report = ctx.health.take().unwrap_or_default();
+    report.check_styles(&book);
+    Ok((book, report))
+}
+
+// Name, sheet index and cell of a skipped element.
+type DroppedKey = (String, Option<usize>, Option<(u32, u32)>);
+
+// State of one read.
+struct ReadCtx {
+    bs: BufStack,
+    // Report, if this is a lenient read.
+    health: Option<OdsHealthReport>,
+    // Skipped elements.
+    dropped: BTreeMap<DroppedKey, u32>,
+    // Index of the sheet that is read.
+    sheet: usize,
+}
+
+impl ReadCtx {
+    fn new(lenient: bool) -> Self {
+        Self {
+            bs: BufStack::new(),
+            health: lenient.then(OdsHealthReport::default),
+            dropped: BTreeMap::new(),
+            sheet: 0,
+        }
+    }
+
+    fn get_buf(&mut self) -> Vec<u8> {
+        self.bs.get_buf()
+    }
+
+    fn push(&mut self, buf: Vec<u8>) {
+        self.bs.push(buf)
+    }
+
+    // Adds to the report of a lenient read. Returns false if this is
+    // not a lenient read.
+    fn health<F: FnOnce(&mut OdsHealthReport)>(&mut self, f: F) -> bool {
+        match self.health.as_mut() {
+            Some(report) => {
+                f(report);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Returns the skipped elements with the names of the sheets.
+    fn take_dropped(&mut self, book: &WorkBook) -> Vec<DroppedElement> {
+        mem::take(&mut self.dropped)
+            .into_iter()
+            .map(|((name, sheet, cell), count)| DroppedElement {
+                name,
+                sheet: sheet.map(|v| book.sheet(v).name().clone()),
+                cell,
+                count,
+            })
+            .collect()
+    }
+}
+
+// Replaces an invalid value with the default in a lenient read.
+fn lenient<T, L: FnOnce() -> String>(
+    ctx: &mut ReadCtx,
+    location: L,
+    attr: &Attribute<'_>,
+    value: Result<T, OdsError>,
+    default: T,
+) -> Result<T, OdsError> {
+    match value {
+        Ok(v) => Ok(v),
+        Err(err) => {
+            let coerced = ctx.health(|h| {
+                h.add_coerced(
+                    location(),
+                    &String::from_utf8_lossy(attr.key.as_ref()),
+                    &String::from_utf8_lossy(attr.value.as_ref()),
+                )
+            });
+            if coerced {
+                Ok(default)
+            } else {
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Reads only the content.xml of an ODS-file.
+///
+/// Everything from styles.xml and settings.xml is missing, but the
+/// data can be read without the zip container. This is useful as
+/// an entry point for fuzzing the parser.
+pub fn read_ods_content(buf: &[u8]) -> Result<WorkBook, OdsError> {
+    let mut book = WorkBook::new_empty();
+    let mut ctx = ReadCtx::new(false);
+
+    read_content(&mut ctx, &mut book, buf)?;
+    book.config = default_settings();
+    calc_derived(&mut book)?;
+    book.clear_dirty();
+    book.dropped = ctx.take_dropped(&book);
+
+    Ok(book)
+}
+
+/// Reads only the styles.xml of an ODS-file into an empty workbook.
+///
+/// This is useful as an entry point for fuzzing the style parser.
+pub fn read_ods_styles(buf: &[u8]) -> Result<WorkBook, OdsError> {
+    let mut book = WorkBook::new_empty();
+    let mut ctx = ReadCtx::new(false);
+
+    read_styles(&mut ctx, &mut book, buf)?;
+    book.config = default_settings();
+    book.dropped = ctx.take_dropped(&book);
+
+    Ok(book)
+}
+
+/// Reads only the styles.xml of an ODS-file into an empty workbook.
+pub(crate) fn read_ods_styles_from<P: AsRef<Path>>(path: P) -> Result<WorkBook, OdsError> {
+    let file = File::open(path.as_ref())?;
+    let mut zip = ZipArchive::new(file)?;
+
+    let mut book = WorkBook::new_empty();
+    let mut ctx = ReadCtx::new(false);
+
+    read_styles(
+        &mut ctx,
+        &mut book,
+        BufReader::new(zip.by_name("styles.xml")?),
+    )?;
+
+    Ok(book)
 }
 
 /// Reads an ODS-file.
-fn read_ods_impl<R: Read + Seek>(mut zip: ZipArchive<R>) -> Result<WorkBook, OdsError> {
+fn read_ods_impl<R: Read + Seek>(
+    ctx: &mut ReadCtx,
+    mut zip: ZipArchive<R>,
+) -> Result<WorkBook, OdsError> {
+    trace_span!("read_ods", files = zip.len());
+
     let mut book = WorkBook::new_empty();
-    let mut bufstack = BufStack::new();
 
-    read_content(&mut bufstack, &mut book, &mut zip.by_name("content.xml")?)?;
-    read_styles(&mut bufstack, &mut book, &mut zip.by_name("styles.xml")?)?;
+    read_content(ctx, &mut book, BufReader::new(zip.by_name("content.xml")?))?;
+    read_styles(ctx, &mut book, BufReader::new(zip.by_name("styles.xml")?))?;
+    trace_event!(
+        cellstyles = book.cellstyles.len(),
+        validations = book.validations.len(),
+        "styles read"
+    );
     // may not exist.
     if let Ok(mut z) = zip.by_name("settings.xml") {
-        read_settings(&mut bufstack, &mut book, &mut z)?;
+        read_settings(ctx, &mut book, BufReader::new(&mut z))?;
     } else {
         book.config = default_settings();
     }
 
     // read all extras.
-    read_filebuf(&mut book, &mut zip)?;
+    read_filebuf(ctx, &mut book, &mut zip)?;
 
     // We do some data duplication here, to make everything easier to use.
     calc_derived(&mut book)?;
+    book.clear_dirty();
+    book.dropped = ctx.take_dropped(&book);
 
     Ok(book)
 }
 
 // Loads all unprocessed files as byte blobs into a buffer.
 fn read_filebuf<R: Read + Seek>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
     zip: &mut ZipArchive<R>,
 ) -> Result<(), OdsError> {
+    trace_span!("read_filebuf");
+
     for idx in 0..zip.len() {
         let mut ze = zip.by_index(idx)?;
 
         // These three are always interpreted and rewritten from scratch.
         // They have their own mechanism to cope with unknown data.
         if !matches!(ze.name(), "settings.xml" | "styles.xml" | "content.xml") {
-            if ze.is_dir() {
+            if ze.name() == "META-INF/manifest.xml" {
+                // The manifest is rewritten too, but the entries are kept.
+                book.manifest = read_manifest(ctx, BufReader::new(&mut ze))?;
+            } else if ze.is_dir() {
                 book.filebuf.push_dir(ze.name());
             } else if ze.is_file() {
                 let mut buf = Vec::new();
                 ze.read_to_end(&mut buf)?;
+                // meta.xml is rewritten with the user-defined fields.
+                // If it can't be parsed it is copied as is.
+                if ze.name() == "meta.xml" {
+                    if let Ok(mut meta) = read_meta(ctx, &buf) {
+                        book.user_meta = meta::take_user_meta(&mut meta);
+                        book.meta_xml = Some(meta);
+                        continue;
+                    }
+                }
                 book.filebuf.push_file(ze.name(), buf);
             }
         }
@@ -125,6 +317,36 @@ fn calc_derived(book: &mut WorkBook) -> Result<(), OdsError> {
     if let Some(ConfigValue::Boolean(n)) = v {
         book.config_mut().show_page_breaks = *n;
     }
+    let v = book
+        .config
+        .get_value(&["ooo:view-settings", "Views", "0", "ShowFormulas"]);
+    if let Some(ConfigValue::Boolean(n)) = v {
+        book.config_mut().show_formulas = *n;
+    }
+    let v = book
+        .config
+        .get_value(&["ooo:view-settings", "Views", "0", "ShowPageBreakPreview"]);
+    if let Some(ConfigValue::Boolean(n)) = v {
+        book.config_mut().show_page_break_preview = *n;
+    }
+    let v = book
+        .config
+        .get_value(&["ooo:view-settings", "Views", "0", "ShowZeroValues"]);
+    if let Some(ConfigValue::Boolean(n)) = v {
+        book.config_mut().show_zero_values = *n;
+    }
+    let v = book
+        .config
+        .get_value(&["ooo:view-settings", "Views", "0", "ShowNotes"]);
+    if let Some(ConfigValue::Boolean(n)) = v {
+        book.config_mut().show_notes = *n;
+    }
+    let v = book
+        .config
+        .get_value(&["ooo:view-settings", "Views", "0", "HasColumnRowHeaders"]);
+    if let Some(ConfigValue::Boolean(n)) = v {
+        book.config_mut().has_column_row_headers = *n;
+    }
 
     for i in 0..book.num_sheets() {
         let mut sheet = book.detach_sheet(i);
@@ -132,7 +354,7 @@ fn calc_derived(book: &mut WorkBook) -> Result<(), OdsError> {
         // Set the column widths.
         for ch in sheet.col_header.values_mut() {
             if let Some(style_name) = &ch.style {
-                if let Some(style) = book.colstyle(style_name) {
+                if let Some(style) = book.colstyle(style_name.as_str()) {
                     if style.use_optimal_col_width()? {
                         ch.set_width(Length::Default);
                     } else {
@@ -145,7 +367,7 @@ fn calc_derived(book: &mut WorkBook) -> Result<(), OdsError> {
         // Set the row heights
         for rh in sheet.row_header.values_mut() {
             if let Some(style_name) = &rh.style {
-                if let Some(style) = book.rowstyle(style_name) {
+                if let Some(style) = book.rowstyle(style_name.as_str()) {
                     if style.use_optimal_row_height()? {
                         rh.set_height(Length::Default);
                     } else {
@@ -203,19 +425,21 @@ fn calc_derived(book: &mut WorkBook) -> Result<(), OdsError> {
 }
 
 // Reads the content.xml
-fn read_content(
-    bs: &mut BufStack,
+fn read_content<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
-    zip_file: &mut ZipFile<'_>,
+    read: R,
 ) -> Result<(), OdsError> {
+    trace_span!("read_content");
+
     // xml parser
-    let mut xml = quick_xml::Reader::from_reader(BufReader::new(zip_file));
+    let mut xml = quick_xml::Reader::from_reader(read);
     // Do not trim text data. All text read contains significant whitespace.
     // The rest is ignored anyway.
     //
     // xml.trim_text(true);
 
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         let empty_tag = matches!(evt, Event::Empty(_));
@@ -242,8 +466,8 @@ fn read_content(
                         attr if attr.key.as_ref() == b"office:version" => {
                             book.set_version(attr.unescape_value()?.to_string());
                         }
-                        _ => {
-                            // noop
+                        attr => {
+                            read_namespace(book, CONTENT_NAMESPACES, &attr)?;
                         }
                     }
                 }
@@ -255,27 +479,32 @@ fn read_content(
 
             Event::Start(xml_tag)
             if xml_tag.name().as_ref() == b"office:font-face-decls" =>
-                read_fonts(bs, book, StyleOrigin::Content, &mut xml)?,
+                read_fonts(ctx, book, StyleOrigin::Content, &mut xml)?,
 
             Event::Start(xml_tag)
             if xml_tag.name().as_ref() == b"office:styles" =>
-                read_styles_tag(bs, book, StyleOrigin::Content, &mut xml)?,
+                read_styles_tag(ctx, book, StyleOrigin::Content, &mut xml)?,
 
             Event::Start(xml_tag)
             if xml_tag.name().as_ref() == b"office:automatic-styles" =>
-                read_auto_styles(bs, book, StyleOrigin::Content, &mut xml)?,
+                read_auto_styles(ctx, book, StyleOrigin::Content, &mut xml)?,
 
             Event::Start(xml_tag)
             if xml_tag.name().as_ref() == b"office:master-styles" =>
-                read_master_styles(bs, book, StyleOrigin::Content, &mut xml)?,
+                read_master_styles(ctx, book, StyleOrigin::Content, &mut xml)?,
 
             Event::Start(xml_tag)
             if xml_tag.name().as_ref() == b"table:content-validations" =>
-                read_validations(bs, book, &mut xml)?,
+                read_validations(ctx, book, &mut xml)?,
 
             Event::Start(xml_tag)
-            if xml_tag.name().as_ref() == b"table:table" =>
-                book.push_sheet(read_table(bs, &mut xml, xml_tag)?),
+            if xml_tag.name().as_ref() == b"table:table" => {
+                trace_span!("read_table");
+                ctx.sheet = book.num_sheets();
+                let sheet = read_table(ctx, &mut xml, xml_tag)?;
+                trace_event!(sheet = sheet.name().as_str(), cells = sheet.data.len(), "sheet read");
+                book.push_sheet(sheet);
+            }
 
             Event::Empty(xml_tag) |
             Event::Start(xml_tag)
@@ -294,7 +523,7 @@ fn read_content(
                 xml_tag.name().as_ref() == b"table:data-pilot-tables" ||
                 xml_tag.name().as_ref() == b"table:consolidation" ||
                 xml_tag.name().as_ref() == b"table:dde-links" => {
-                let v = read_xml(bs, xml_tag.name().as_ref(), &mut xml, &xml_tag, empty_tag)?;
+                let v = read_xml(ctx, xml_tag.name().as_ref(), &mut xml, &xml_tag, empty_tag)?;
                 book.extra.push(v);
             }
 
@@ -321,29 +550,31 @@ fn read_content(
                 break;
             }
             _ => {
-                dump_unused2("read_content", &evt)?;
+                dump_unused2(ctx, "read_content", &evt)?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok(())
 }
 
 // Reads the table.
-fn read_table(
-    bs: &mut BufStack,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+fn read_table<R: BufRead>(
+    ctx: &mut ReadCtx,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: BytesStart<'_>,
 ) -> Result<Sheet, OdsError> {
     let mut sheet = Sheet::new("");
 
-    read_table_attr(&mut sheet, xml_tag)?;
+    read_table_attr(ctx, &mut sheet, xml_tag)?;
 
     // Position within table-columns
     let mut table_col: u32 = 0;
+    // Last block of columns, if it reaches the end of the sheet.
+    let mut tail_cols: Option<TailCols> = None;
 
     // Cell position
     let mut row: u32 = 0;
@@ -358,7 +589,7 @@ fn read_table(
     let mut col_range_from = 0;
     let mut row_range_from = 0;
 
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         let empty_tag = matches!(evt, Event::Empty(_));
@@ -373,25 +604,31 @@ fn read_table(
 
             Event::Start(xml_tag) |
             Event::Empty(xml_tag)
-            if /* prelude */ xml_tag.name().as_ref() == b"table:title" ||
+            if /* prelude */ xml_tag.name().as_ref() == b"table:table-protection" ||
+                xml_tag.name().as_ref() == b"loext:table-protection" ||
+                xml_tag.name().as_ref() == b"table:title" ||
                 xml_tag.name().as_ref() == b"table:desc" ||
                 xml_tag.name().as_ref() == b"table:table-source" ||
                 xml_tag.name().as_ref() == b"office:dde-source" ||
                 xml_tag.name().as_ref() == b"table:scenario" ||
+                xml_tag.name().as_ref() == b"office:event-listeners" ||
                 xml_tag.name().as_ref() == b"office:forms" ||
                 xml_tag.name().as_ref() == b"table:shapes" ||
                 /* epilogue */
                 xml_tag.name().as_ref() == b"table:named-expressions" ||
                 xml_tag.name().as_ref() == b"calcext:conditional-formats" => {
-                sheet.extra.push(read_xml(bs, xml_tag.name().as_ref(), xml, &xml_tag, empty_tag)?);
+                sheet.extra.push(read_xml(ctx, xml_tag.name().as_ref(), xml, &xml_tag, empty_tag)?);
             }
 
             Event::End(xml_tag)
-            if /* prelude */ xml_tag.name().as_ref() == b"table:title" ||
+            if /* prelude */ xml_tag.name().as_ref() == b"table:table-protection" ||
+                xml_tag.name().as_ref() == b"loext:table-protection" ||
+                xml_tag.name().as_ref() == b"table:title" ||
                 xml_tag.name().as_ref() == b"table:desc" ||
                 xml_tag.name().as_ref() == b"table:table-source" ||
                 xml_tag.name().as_ref() == b"office:dde-source" ||
                 xml_tag.name().as_ref() == b"table:scenario" ||
+                xml_tag.name().as_ref() == b"office:event-listeners" ||
                 xml_tag.name().as_ref() == b"office:forms" ||
                 xml_tag.name().as_ref() == b"table:shapes" ||
                 /* epilogue */
@@ -410,7 +647,7 @@ fn read_table(
 
             Event::Empty(xml_tag)
             if xml_tag.name().as_ref() == b"table:table-column" => {
-                table_col = read_table_col_attr(&mut sheet, table_col,  &xml_tag)?;
+                table_col = read_table_col_attr(ctx, &mut sheet, table_col, &mut tail_cols, &xml_tag)?;
             }
 
             Event::Start(xml_tag)
@@ -425,7 +662,7 @@ fn read_table(
 
             Event::Start(xml_tag)
             if xml_tag.name().as_ref() == b"table:table-row" => {
-                let (repeat, style, cellstyle, visible) = read_table_row_attr( xml_tag)?;
+                let (repeat, style, cellstyle, visible) = read_table_row_attr(ctx, &sheet, row, xml_tag)?;
                 row_repeat = repeat;
                 rowstyle = style;
                 row_cellstyle = cellstyle;
@@ -457,27 +694,54 @@ fn read_table(
 
             Event::Empty(xml_tag)
             if xml_tag.name().as_ref() == b"table:table-cell" || xml_tag.name().as_ref() == b"table:covered-table-cell" => {
-                col = read_empty_table_cell(&mut sheet, row, col, xml_tag)?;
+                col = read_empty_table_cell(ctx, &mut sheet, row, col, xml_tag)?;
             }
 
             Event::Start(xml_tag)
             if xml_tag.name().as_ref() == b"table:table-cell" || xml_tag.name().as_ref() == b"table:covered-table-cell" => {
-                col = read_table_cell2(bs, &mut sheet, row, col, xml, xml_tag)?;
+                col = read_table_cell2(ctx, &mut sheet, row, col, xml, xml_tag)?;
+            }
+
+            Event::Eof => {
+                break;
             }
 
             _ => {
-                dump_unused2("read_table", &evt)?;
+                dump_unused_at(ctx, "read_table", &evt, None)?;
             }
         }
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
+
+    // The last block of columns gives the sheet default.
+    if let Some(mut tail) = tail_cols {
+        if let Some(cellstyle) = tail.cellstyle.take() {
+            sheet.default_cellstyle = Some(cellstyle.into());
+        }
+        if tail.style.is_some() || tail.visible != Visibility::Visible {
+            tail.apply(&mut sheet);
+        }
+    }
+
+    // Columns with the sheet default don't need their own.
+    if let Some(cellstyle) = &sheet.default_cellstyle {
+        for col_header in sheet.col_header.values_mut() {
+            if col_header.cellstyle() == Some(cellstyle) {
+                col_header.clear_cellstyle();
+            }
+        }
+    }
 
     Ok(sheet)
 }
 
 // Reads the table attributes.
-fn read_table_attr(sheet: &mut Sheet, xml_tag: BytesStart<'_>) -> Result<(), OdsError> {
+fn read_table_attr(
+    ctx: &mut ReadCtx,
+    sheet: &mut Sheet,
+    xml_tag: BytesStart<'_>,
+) -> Result<(), OdsError> {
     for attr in xml_tag.attributes().with_checks(false) {
         match attr? {
             attr if attr.key.as_ref() == b"table:name" => {
@@ -487,18 +751,30 @@ fn read_table_attr(sheet: &mut Sheet, xml_tag: BytesStart<'_>) -> Result<(), Ods
                 sheet.set_style(&attr.unescape_value()?.as_ref().into());
             }
             attr if attr.key.as_ref() == b"table:print" => {
-                sheet.set_print(parse_bool(&attr.value)?);
+                let loc = || format!("table {}", sheet.name());
+                sheet.set_print(lenient(ctx, loc, &attr, parse_bool(&attr.value), true)?);
             }
             attr if attr.key.as_ref() == b"table:display" => {
-                sheet.set_display(parse_bool(&attr.value)?);
+                let loc = || format!("table {}", sheet.name());
+                sheet.set_display(lenient(ctx, loc, &attr, parse_bool(&attr.value), true)?);
+            }
+            attr if attr.key.as_ref() == b"table:protected" => {
+                let loc = || format!("table {}", sheet.name());
+                sheet.set_protected(lenient(ctx, loc, &attr, parse_bool(&attr.value), false)?);
+            }
+            attr if attr.key.as_ref() == b"table:protection-key" => {
+                sheet.protection_key = Some(attr.unescape_value()?.to_string());
+            }
+            attr if attr.key.as_ref() == b"table:protection-key-digest-algorithm" => {
+                sheet.protection_key_digest = Some(attr.unescape_value()?.to_string());
             }
             attr if attr.key.as_ref() == b"table:print-ranges" => {
                 let v = attr.unescape_value()?;
-                let mut pos = 0usize;
-                sheet.print_ranges = parse_cellranges(v.as_ref(), &mut pos)?;
+                let v = CellRangeList::try_from(v.as_ref())?;
+                sheet.print_ranges = if v.is_empty() { None } else { Some(v.into()) };
             }
             attr => {
-                dump_unused("read_table_attr", xml_tag.name().as_ref(), &attr)?;
+                dump_unused(ctx, "read_table_attr", xml_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -508,6 +784,9 @@ fn read_table_attr(sheet: &mut Sheet, xml_tag: BytesStart<'_>) -> Result<(), Ods
 
 // Reads table-row attributes. Returns the repeat-count.
 fn read_table_row_attr(
+    ctx: &mut ReadCtx,
+    sheet: &Sheet,
+    row: u32,
     xml_tag: BytesStart<'_>,
 ) -> Result<(u32, Option<String>, Option<String>, Visibility), OdsError> {
     let mut row_repeat: u32 = 1;
@@ -519,7 +798,8 @@ fn read_table_row_attr(
         match attr? {
             // table:default-cell-style-name 19.615, table:visibility 19.749 and xml:id 19.914.
             attr if attr.key.as_ref() == b"table:number-rows-repeated" => {
-                row_repeat = parse_u32(&attr.value)?;
+                let loc = || format!("{} row {}", sheet.name(), row + 1);
+                row_repeat = lenient(ctx, loc, &attr, parse_u32(&attr.value), 1)?;
             }
             attr if attr.key.as_ref() == b"table:style-name" => {
                 rowstyle = Some(attr.unescape_value()?.to_string());
@@ -528,10 +808,17 @@ fn read_table_row_attr(
                 row_cellstyle = Some(attr.unescape_value()?.to_string());
             }
             attr if attr.key.as_ref() == b"table:visibility" => {
-                row_visible = parse_visibility(&attr.value)?;
+                let loc = || format!("{} row {}", sheet.name(), row + 1);
+                row_visible = lenient(
+                    ctx,
+                    loc,
+                    &attr,
+                    parse_visibility(&attr.value),
+                    Visibility::Visible,
+                )?;
             }
             attr => {
-                dump_unused("read_table_row_attr", xml_tag.name().as_ref(), &attr)?;
+                dump_unused(ctx, "read_table_row_attr", xml_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -541,19 +828,27 @@ fn read_table_row_attr(
 
 // Reads the table-column attributes. Creates as many copies as indicated.
 fn read_table_col_attr(
+    ctx: &mut ReadCtx,
     sheet: &mut Sheet,
-    mut table_col: u32,
+    table_col: u32,
+    tail_cols: &mut Option<TailCols>,
     xml_tag: &BytesStart<'_>,
 ) -> Result<u32, OdsError> {
+    // Not the last block after all.
+    if let Some(tail) = tail_cols.take() {
+        tail.apply(sheet);
+    }
+
     let mut style = None;
-    let mut cellstyle = None;
+    let mut cellstyle: Option<Cow<'_, str>> = None;
     let mut repeat: u32 = 1;
     let mut visible: Visibility = Default::default();
 
     for attr in xml_tag.attributes().with_checks(false) {
         match attr? {
             attr if attr.key.as_ref() == b"table:number-columns-repeated" => {
-                repeat = parse_u32(&attr.value)?;
+                let loc = || format!("{} column {}", sheet.name(), table_col + 1);
+                repeat = lenient(ctx, loc, &attr, parse_u32(&attr.value), 1)?;
             }
             attr if attr.key.as_ref() == b"table:style-name" => {
                 style = Some(attr.unescape_value()?);
@@ -562,27 +857,66 @@ fn read_table_col_attr(
                 cellstyle = Some(attr.unescape_value()?);
             }
             attr if attr.key.as_ref() == b"table:visibility" => {
-                visible = parse_visibility(&attr.value)?;
+                let loc = || format!("{} column {}", sheet.name(), table_col + 1);
+                visible = lenient(
+                    ctx,
+                    loc,
+                    &attr,
+                    parse_visibility(&attr.value),
+                    Visibility::Visible,
+                )?;
             }
             attr => {
-                dump_unused("read_table_col_attr", xml_tag.name().as_ref(), &attr)?;
+                dump_unused(ctx, "read_table_col_attr", xml_tag.name().as_ref(), &attr)?;
             }
         }
     }
 
-    while repeat > 0 {
-        if let Some(style) = &style {
-            sheet.set_colstyle(table_col, &style.as_ref().into());
-        }
-        if let Some(cellstyle) = &cellstyle {
-            sheet.set_col_cellstyle(table_col, &cellstyle.as_ref().into());
-        }
-        sheet.set_col_visible(table_col, visible);
-        table_col += 1;
-        repeat -= 1;
+    let cols = TailCols {
+        col: table_col,
+        repeat,
+        style: style.map(|v| v.to_string()),
+        cellstyle: cellstyle.map(|v| v.to_string()),
+        visible,
+    };
+    let next_col = table_col.saturating_add(repeat);
+
+    // A block up to the end of the sheet gives the sheet default, but only
+    // if it is the last one. That is known with the next block or the end
+    // of the table.
+    if repeat > 1 && next_col >= DEFAULT_CELLSTYLE_COLS {
+        *tail_cols = Some(cols);
+    } else {
+        cols.apply(sheet);
     }
 
-    Ok(table_col)
+    Ok(next_col)
+}
+
+// A block of repeated table-columns.
+struct TailCols {
+    col: u32,
+    repeat: u32,
+    style: Option<String>,
+    cellstyle: Option<String>,
+    visible: Visibility,
+}
+
+impl TailCols {
+    // Sets the attributes for each column.
+    fn apply(self, sheet: &mut Sheet) {
+        let style = self.style.map(ColStyleRef::from);
+        let cellstyle = self.cellstyle.map(CellStyleRef::from);
+        for col in self.col..self.col.saturating_add(self.repeat) {
+            if let Some(style) = &style {
+                sheet.set_colstyle(col, style);
+            }
+            if let Some(cellstyle) = &cellstyle {
+                sheet.set_col_cellstyle(col, cellstyle);
+            }
+            sheet.set_col_visible(col, self.visible);
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -607,12 +941,12 @@ struct ReadTableCell2 {
     content: TextContent2,
 }
 
-fn read_table_cell2(
-    bs: &mut BufStack,
+fn read_table_cell2<R: BufRead>(
+    ctx: &mut ReadCtx,
     sheet: &mut Sheet,
     row: u32,
     mut col: u32,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: BytesStart<'_>,
 ) -> Result<u32, OdsError> {
     // Current cell tag
@@ -624,8 +958,7 @@ fn read_table_cell2(
         value: Default::default(),
         formula: None,
         style: None,
-        validation_name: None,
-        span: Default::default(),
+        extra: None,
     };
 
     let mut tc = ReadTableCell2 {
@@ -639,81 +972,122 @@ fn read_table_cell2(
         content: TextContent2::Empty,
     };
 
+    let loc = || cell_location(sheet.name(), row, col);
     for attr in xml_tag.attributes().with_checks(false) {
         match attr? {
             attr if attr.key.as_ref() == b"table:number-columns-repeated" => {
-                cell_repeat = parse_u32(&attr.value)?;
+                cell_repeat = lenient(ctx, loc, &attr, parse_u32(&attr.value), 1)?;
             }
             attr if attr.key.as_ref() == b"table:number-rows-spanned" => {
-                cell.span.row_span = parse_u32(&attr.value)?;
+                cell.extra_mut().span.row_span =
+                    lenient(ctx, loc, &attr, parse_u32(&attr.value), 1)?;
             }
             attr if attr.key.as_ref() == b"table:number-columns-spanned" => {
-                cell.span.col_span = parse_u32(&attr.value)?;
+                cell.extra_mut().span.col_span =
+                    lenient(ctx, loc, &attr, parse_u32(&attr.value), 1)?;
             }
             attr if attr.key.as_ref() == b"table:content-validation-name" => {
-                cell.validation_name = Some(attr.unescape_value()?.to_string());
+                cell.extra_mut().validation_name = Some(attr.unescape_value()?.to_string());
+            }
+            attr if attr.key.as_ref().starts_with(b"sods:") => {
+                let key = from_utf8(&attr.key.as_ref()[5..])?;
+                let value = attr.unescape_value()?.to_string();
+                cell.extra_mut().metadata.insert(key.to_string(), value);
             }
             attr if attr.key.as_ref() == b"calcext:value-type" => {
-                // not used. office:value-type seems to be good enough.
+                // Only a hint, an unknown value is not worth failing for.
+                if let Ok(content_type) = parse_content_type(&attr.value) {
+                    cell.extra_mut().content_type = Some(content_type);
+                } else {
+                    ctx.health(|h| {
+                        h.add_coerced(
+                            loc(),
+                            "calcext:value-type",
+                            &String::from_utf8_lossy(attr.value.as_ref()),
+                        )
+                    });
+                }
             }
             attr if attr.key.as_ref() == b"office:value-type" => {
-                tc.val_type = match attr.value.as_ref() {
-                    b"string" => ValueType::Text,
-                    b"float" => ValueType::Number,
-                    b"percentage" => ValueType::Percentage,
-                    b"date" => ValueType::DateTime,
-                    b"time" => ValueType::TimeDuration,
-                    b"boolean" => ValueType::Boolean,
-                    b"currency" => ValueType::Currency,
-                    other => return Err(OdsError::Parse(format!("Unknown cell-type {:?}", other))),
-                }
+                let val_type = match attr.value.as_ref() {
+                    b"string" => Ok(ValueType::Text),
+                    b"float" => Ok(ValueType::Number),
+                    b"percentage" => Ok(ValueType::Percentage),
+                    b"date" => Ok(ValueType::DateTime),
+                    b"time" => Ok(ValueType::TimeDuration),
+                    b"boolean" => Ok(ValueType::Boolean),
+                    b"currency" => Ok(ValueType::Currency),
+                    other => Err(OdsError::Parse(format!("Unknown cell-type {:?}", other))),
+                };
+                tc.val_type = lenient(ctx, loc, &attr, val_type, ValueType::Text)?;
             }
             attr if attr.key.as_ref() == b"office:date-value" => {
-                tc.val_datetime = Some(parse_datetime(&attr.value)?);
+                tc.val_datetime =
+                    lenient(ctx, loc, &attr, parse_datetime(&attr.value).map(Some), None)?;
             }
             attr if attr.key.as_ref() == b"office:time-value" => {
-                tc.val_duration = Some(parse_duration(&attr.value)?);
+                tc.val_duration =
+                    lenient(ctx, loc, &attr, parse_duration(&attr.value).map(Some), None)?;
             }
             attr if attr.key.as_ref() == b"office:value" => {
-                tc.val_float = Some(parse_f64(&attr.value)?);
+                tc.val_float = lenient(ctx, loc, &attr, parse_f64(&attr.value).map(Some), None)?;
             }
             attr if attr.key.as_ref() == b"office:boolean-value" => {
-                tc.val_bool = Some(parse_bool(&attr.value)?);
+                tc.val_bool = lenient(ctx, loc, &attr, parse_bool(&attr.value).map(Some), None)?;
             }
             attr if attr.key.as_ref() == b"office:string-value" => {
                 tc.val_string = Some(attr.unescape_value()?.to_string());
             }
             attr if attr.key.as_ref() == b"office:currency" => {
-                tc.val_currency = Some(parse_currency(&attr.value)?);
+                tc.val_currency =
+                    lenient(ctx, loc, &attr, parse_currency(&attr.value).map(Some), None)?;
             }
             attr if attr.key.as_ref() == b"table:formula" => {
                 cell.formula = Some(attr.unescape_value()?.to_string());
             }
             attr if attr.key.as_ref() == b"table:style-name" => {
-                cell.style = Some(attr.unescape_value()?.to_string());
+                cell.style = Some(attr.unescape_value()?.as_ref().into());
             }
             attr => {
-                dump_unused("read_table_cell2", xml_tag.name().as_ref(), &attr)?;
+                dump_unused(ctx, "read_table_cell2", xml_tag.name().as_ref(), &attr)?;
             }
         }
     }
 
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
+        let empty_tag = matches!(evt, Event::Empty(_));
         if DUMP_XML {
             println!(" read_table_cell {:?}", evt);
         }
         match evt {
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"text:p" => {
-                let new_txt = read_text_or_tag(bs, b"text:p", xml, &xml_tag, false)?;
+                let new_txt = read_text_or_tag(ctx, b"text:p", xml, &xml_tag, false)?;
                 tc.content = append_text(new_txt, tc.content);
             }
             Event::Empty(xml_tag) if xml_tag.name().as_ref() == b"text:p" => {
                 // noop
             }
 
+            // Shapes anchored to the cell. Frames with images, objects,
+            // form controls ...
+            Event::Start(xml_tag) | Event::Empty(xml_tag)
+                if xml_tag.name().as_ref().starts_with(b"draw:") =>
+            {
+                let shape = read_xml(ctx, xml_tag.name().as_ref(), xml, &xml_tag, empty_tag)?;
+                cell.extra_mut().shapes.push(shape);
+            }
+
             Event::End(xml_tag) if xml_tag.name() == tag_name => {
+                if !has_value2(&tc)
+                    && ctx.health(|h| {
+                        h.add_coerced(loc(), "office:value-type", &format!("{:?}", tc.val_type))
+                    })
+                {
+                    // Use the text content.
+                    tc.val_type = ValueType::Text;
+                }
                 parse_value2(tc, &mut cell)?;
 
                 while cell_repeat > 1 {
@@ -732,13 +1106,13 @@ fn read_table_cell2(
             }
 
             _ => {
-                dump_unused2("read_table_cell", &evt)?;
+                dump_unused_at(ctx, "read_table_cell", &evt, Some((row, col)))?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok(col)
 }
@@ -806,6 +1180,18 @@ fn append_text(new_txt: TextContent2, mut content: TextContent2) -> TextContent2
     content
 }
 
+// Is there a value for the value-type?
+fn has_value2(tc: &ReadTableCell2) -> bool {
+    match tc.val_type {
+        ValueType::Empty | ValueType::Text | ValueType::TextXml => true,
+        ValueType::Boolean => tc.val_bool.is_some(),
+        ValueType::Number | ValueType::Percentage => tc.val_float.is_some(),
+        ValueType::Currency => tc.val_float.is_some() && tc.val_currency.is_some(),
+        ValueType::DateTime => tc.val_datetime.is_some(),
+        ValueType::TimeDuration => tc.val_duration.is_some(),
+    }
+}
+
 fn parse_value2(tc: ReadTableCell2, cell: &mut CellData) -> Result<(), OdsError> {
     match tc.val_type {
         ValueType::Empty => {
@@ -889,6 +1275,7 @@ fn parse_value2(tc: ReadTableCell2, cell: &mut CellData) -> Result<(), OdsError>
 /// There seems to be no data associated, but it can have a style and a formula.
 /// And first of all we need the repeat count for the correct placement.
 fn read_empty_table_cell(
+    ctx: &mut ReadCtx,
     sheet: &mut Sheet,
     row: u32,
     mut col: u32,
@@ -897,10 +1284,11 @@ fn read_empty_table_cell(
     let mut cell = None;
     // Default advance is one column.
     let mut cell_repeat = 1;
+    let loc = || cell_location(sheet.name(), row, col);
     for attr in xml_tag.attributes().with_checks(false) {
         match attr? {
             attr if attr.key.as_ref() == b"table:number-columns-repeated" => {
-                cell_repeat = parse_u32(&attr.value)?;
+                cell_repeat = lenient(ctx, loc, &attr, parse_u32(&attr.value), 1)?;
             }
             attr if attr.key.as_ref() == b"table:formula" => {
                 cell.get_or_insert_with(CellData::new).formula =
@@ -908,21 +1296,36 @@ fn read_empty_table_cell(
             }
             attr if attr.key.as_ref() == b"table:style-name" => {
                 cell.get_or_insert_with(CellData::new).style =
-                    Some(attr.unescape_value()?.to_string());
+                    Some(attr.unescape_value()?.as_ref().into());
             }
             attr if attr.key.as_ref() == b"table:number-rows-spanned" => {
-                cell.get_or_insert_with(CellData::new).span.row_span = parse_u32(&attr.value)?;
+                cell.get_or_insert_with(CellData::new)
+                    .extra_mut()
+                    .span
+                    .row_span = lenient(ctx, loc, &attr, parse_u32(&attr.value), 1)?;
             }
             attr if attr.key.as_ref() == b"table:number-columns-spanned" => {
-                cell.get_or_insert_with(CellData::new).span.col_span = parse_u32(&attr.value)?;
+                cell.get_or_insert_with(CellData::new)
+                    .extra_mut()
+                    .span
+                    .col_span = lenient(ctx, loc, &attr, parse_u32(&attr.value), 1)?;
             }
             attr if attr.key.as_ref() == b"table:content-validation-name" => {
-                cell.get_or_insert_with(CellData::new).validation_name =
-                    Some(attr.unescape_value()?.to_string());
+                cell.get_or_insert_with(CellData::new)
+                    .extra_mut()
+                    .validation_name = Some(attr.unescape_value()?.to_string());
+            }
+            attr if attr.key.as_ref().starts_with(b"sods:") => {
+                let key = from_utf8(&attr.key.as_ref()[5..])?;
+                let value = attr.unescape_value()?.to_string();
+                cell.get_or_insert_with(CellData::new)
+                    .extra_mut()
+                    .metadata
+                    .insert(key.to_string(), value);
             }
 
             attr => {
-                dump_unused("read_empty_table_cell", xml_tag.name().as_ref(), &attr)?;
+                dump_unused(ctx, "read_empty_table_cell", xml_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -943,17 +1346,17 @@ fn read_empty_table_cell(
 }
 
 // reads a font-face
-fn read_fonts(
-    bs: &mut BufStack,
+fn read_fonts<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
     origin: StyleOrigin,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     // no attributes
 ) -> Result<(), OdsError> {
     let mut font: FontFaceDecl = FontFaceDecl::new_empty();
     font.set_origin(origin);
 
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         if DUMP_XML {
@@ -972,7 +1375,7 @@ fn read_fonts(
                         font.set_origin(StyleOrigin::Content);
                     }
                     _ => {
-                        dump_unused2("read_fonts", &evt)?;
+                        dump_unused2(ctx, "read_fonts", &evt)?;
                     }
                 }
             }
@@ -985,22 +1388,22 @@ fn read_fonts(
                 break;
             }
             _ => {
-                dump_unused2("read_fonts", &evt)?;
+                dump_unused2(ctx, "read_fonts", &evt)?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok(())
 }
 
 // reads the page-layout tag
-fn read_page_style(
-    bs: &mut BufStack,
+fn read_page_style<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: &BytesStart<'_>,
 ) -> Result<(), OdsError> {
     let mut pl = PageStyle::new_empty();
@@ -1013,7 +1416,7 @@ fn read_page_style(
                 pl.master_page_usage = Some(attr.unescape_value()?.to_string());
             }
             attr => {
-                dump_unused("read_page_style", xml_tag.name().as_ref(), &attr)?;
+                dump_unused(ctx, "read_page_style", xml_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -1021,7 +1424,7 @@ fn read_page_style(
     let mut headerstyle = false;
     let mut footerstyle = false;
 
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         if DUMP_XML {
@@ -1045,7 +1448,7 @@ fn read_page_style(
                         // noop for now. sets the background transparent.
                     }
                     _ => {
-                        dump_unused2("read_page_layout", &evt)?;
+                        dump_unused2(ctx, "read_page_layout", &evt)?;
                     }
                 }
             }
@@ -1057,32 +1460,32 @@ fn read_page_style(
                 b"style:footer-style" => footerstyle = false,
                 b"style:header-footer-properties" => {}
                 _ => {
-                    dump_unused2("read_page_layout", &evt)?;
+                    dump_unused2(ctx, "read_page_layout", &evt)?;
                 }
             },
             Event::Eof => break,
             _ => {
-                dump_unused2("read_page_layout", &evt)?;
+                dump_unused2(ctx, "read_page_layout", &evt)?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     book.add_pagestyle(pl);
 
     Ok(())
 }
 
-fn read_validations(
-    bs: &mut BufStack,
+fn read_validations<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
 ) -> Result<(), OdsError> {
     let mut valid = Validation::new();
 
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         let empty_tag = matches!(evt, Event::Empty(_));
@@ -1117,6 +1520,7 @@ fn read_validations(
                                 }
                                 attr => {
                                     dump_unused(
+                                        ctx,
                                         "read_validations",
                                         xml_tag.name().as_ref(),
                                         &attr,
@@ -1157,6 +1561,7 @@ fn read_validations(
                                 }
                                 attr => {
                                     dump_unused(
+                                        ctx,
                                         "read_validations",
                                         xml_tag.name().as_ref(),
                                         &attr,
@@ -1165,7 +1570,7 @@ fn read_validations(
                             }
                         }
                         let txt =
-                            read_text_or_tag(bs, b"table:error-message", xml, xml_tag, empty_tag)?;
+                            read_text_or_tag(ctx, b"table:error-message", xml, xml_tag, empty_tag)?;
                         match txt {
                             TextContent2::Empty => {}
                             TextContent2::Xml(txt) => {
@@ -1193,6 +1598,7 @@ fn read_validations(
                                 }
                                 attr => {
                                     dump_unused(
+                                        ctx,
                                         "read_validations",
                                         xml_tag.name().as_ref(),
                                         &attr,
@@ -1201,7 +1607,7 @@ fn read_validations(
                             }
                         }
                         let txt =
-                            read_text_or_tag(bs, b"table:help-message", xml, xml_tag, empty_tag)?;
+                            read_text_or_tag(ctx, b"table:help-message", xml, xml_tag, empty_tag)?;
                         match txt {
                             TextContent2::Empty => {}
                             TextContent2::Xml(txt) => {
@@ -1220,7 +1626,7 @@ fn read_validations(
                     // b"office:event-listeners"
                     // b"table:error-macro"
                     _ => {
-                        dump_unused2("read_validations", &evt)?;
+                        dump_unused2(ctx, "read_validations", &evt)?;
                     }
                 }
             }
@@ -1240,24 +1646,24 @@ fn read_validations(
             Event::Text(_) => (),
             Event::Eof => break,
             _ => {
-                dump_unused2("read_validations", &evt)?;
+                dump_unused2(ctx, "read_validations", &evt)?;
             }
         }
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok(())
 }
 
 // read the master-styles tag
-fn read_master_styles(
-    bs: &mut BufStack,
+fn read_master_styles<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
     origin: StyleOrigin,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     // no attributes
 ) -> Result<(), OdsError> {
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         if DUMP_XML {
@@ -1267,10 +1673,10 @@ fn read_master_styles(
             Event::Start(ref xml_tag) | Event::Empty(ref xml_tag) => {
                 match xml_tag.name().as_ref() {
                     b"style:master-page" => {
-                        read_master_page(bs, book, origin, xml, xml_tag)?;
+                        read_master_page(ctx, book, origin, xml, xml_tag)?;
                     }
                     _ => {
-                        dump_unused2("read_master_styles", &evt)?;
+                        dump_unused2(ctx, "read_master_styles", &evt)?;
                     }
                 }
             }
@@ -1282,23 +1688,23 @@ fn read_master_styles(
             }
             Event::Eof => break,
             _ => {
-                dump_unused2("read_master_styles", &evt)?;
+                dump_unused2(ctx, "read_master_styles", &evt)?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok(())
 }
 
 // read the master-page tag
-fn read_master_page(
-    bs: &mut BufStack,
+fn read_master_page<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
     _origin: StyleOrigin,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: &BytesStart<'_>,
 ) -> Result<(), OdsError> {
     let mut masterpage = MasterPage::new_empty();
@@ -1312,12 +1718,12 @@ fn read_master_page(
                 masterpage.set_pagestyle(&attr.unescape_value()?.as_ref().into());
             }
             attr => {
-                dump_unused("read_master_page", xml_tag.name().as_ref(), &attr)?;
+                dump_unused(ctx, "read_master_page", xml_tag.name().as_ref(), &attr)?;
             }
         }
     }
 
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         if DUMP_XML {
@@ -1326,11 +1732,11 @@ fn read_master_page(
         match evt {
             Event::Start(ref xml_tag) => match xml_tag.name().as_ref() {
                 b"style:header" => {
-                    masterpage.set_header(read_headerfooter(bs, b"style:header", xml, xml_tag)?);
+                    masterpage.set_header(read_headerfooter(ctx, b"style:header", xml, xml_tag)?);
                 }
                 b"style:header-first" => {
                     masterpage.set_header_first(read_headerfooter(
-                        bs,
+                        ctx,
                         b"style:header-first",
                         xml,
                         xml_tag,
@@ -1338,18 +1744,18 @@ fn read_master_page(
                 }
                 b"style:header-left" => {
                     masterpage.set_header_left(read_headerfooter(
-                        bs,
+                        ctx,
                         b"style:header-left",
                         xml,
                         xml_tag,
                     )?);
                 }
                 b"style:footer" => {
-                    masterpage.set_footer(read_headerfooter(bs, b"style:footer", xml, xml_tag)?);
+                    masterpage.set_footer(read_headerfooter(ctx, b"style:footer", xml, xml_tag)?);
                 }
                 b"style:footer-first" => {
                     masterpage.set_footer_first(read_headerfooter(
-                        bs,
+                        ctx,
                         b"style:footer-first",
                         xml,
                         xml_tag,
@@ -1357,14 +1763,14 @@ fn read_master_page(
                 }
                 b"style:footer-left" => {
                     masterpage.set_footer_left(read_headerfooter(
-                        bs,
+                        ctx,
                         b"style:footer-left",
                         xml,
                         xml_tag,
                     )?);
                 }
                 _ => {
-                    dump_unused2("read_master_page", &evt)?;
+                    dump_unused2(ctx, "read_master_page", &evt)?;
                 }
             },
 
@@ -1380,13 +1786,13 @@ fn read_master_page(
             }
             Event::Eof => break,
             _ => {
-                dump_unused2("read_master_page", &evt)?;
+                dump_unused2(ctx, "read_master_page", &evt)?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     book.add_masterpage(masterpage);
 
@@ -1394,10 +1800,10 @@ fn read_master_page(
 }
 
 // reads any header or footer tags
-fn read_headerfooter(
-    bs: &mut BufStack,
+fn read_headerfooter<R: BufRead>(
+    ctx: &mut ReadCtx,
     end_tag: &[u8],
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: &BytesStart<'_>,
 ) -> Result<HeaderFooter, OdsError> {
     let mut hf = HeaderFooter::new();
@@ -1409,12 +1815,12 @@ fn read_headerfooter(
                 hf.set_display(parse_bool(&attr.value)?);
             }
             attr => {
-                dump_unused("read_headerfooter", xml_tag.name().as_ref(), &attr)?;
+                dump_unused(ctx, "read_headerfooter", xml_tag.name().as_ref(), &attr)?;
             }
         }
     }
 
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         let empty_tag = matches!(evt, Event::Empty(_));
@@ -1425,28 +1831,28 @@ fn read_headerfooter(
             Event::Start(ref xml_tag) | Event::Empty(ref xml_tag) => {
                 match xml_tag.name().as_ref() {
                     b"style:region-left" => {
-                        let reg = read_xml(bs, b"style:region-left", xml, xml_tag, empty_tag)?;
+                        let reg = read_xml(ctx, b"style:region-left", xml, xml_tag, empty_tag)?;
                         hf.set_left(reg.into_vec()?);
                     }
                     b"style:region-center" => {
-                        let reg = read_xml(bs, b"style:region-center", xml, xml_tag, empty_tag)?;
+                        let reg = read_xml(ctx, b"style:region-center", xml, xml_tag, empty_tag)?;
                         hf.set_center(reg.into_vec()?);
                     }
                     b"style:region-right" => {
-                        let reg = read_xml(bs, b"style:region-right", xml, xml_tag, empty_tag)?;
+                        let reg = read_xml(ctx, b"style:region-right", xml, xml_tag, empty_tag)?;
                         hf.set_right(reg.into_vec()?);
                     }
                     b"text:p" => {
-                        let new_txt = read_text_or_tag(bs, b"text:p", xml, xml_tag, empty_tag)?;
+                        let new_txt = read_text_or_tag(ctx, b"text:p", xml, xml_tag, empty_tag)?;
                         content = append_text(new_txt, content);
                     }
                     b"text:h" => {
-                        let new_txt = read_text_or_tag(bs, b"text:p", xml, xml_tag, empty_tag)?;
+                        let new_txt = read_text_or_tag(ctx, b"text:p", xml, xml_tag, empty_tag)?;
                         content = append_text(new_txt, content);
                     }
                     // no other tags supported for now. they have never been seen in the wild.
                     _ => {
-                        dump_unused2("read_headerfooter", &evt)?;
+                        dump_unused2(ctx, "read_headerfooter", &evt)?;
                     }
                 }
             }
@@ -1464,26 +1870,26 @@ fn read_headerfooter(
             }
             Event::Eof => break,
             _ => {
-                dump_unused2("read_headerfooter", &evt)?;
+                dump_unused2(ctx, "read_headerfooter", &evt)?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok(hf)
 }
 
 // reads the office-styles tag
-fn read_styles_tag(
-    bs: &mut BufStack,
+fn read_styles_tag<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
     origin: StyleOrigin,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     // not attributes
 ) -> Result<(), OdsError> {
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         let empty_tag = matches!(evt, Event::Empty(_));
@@ -1495,7 +1901,7 @@ fn read_styles_tag(
                 match xml_tag.name().as_ref() {
                     b"style:style" => {
                         read_style_style(
-                            bs,
+                            ctx,
                             book,
                             origin,
                             StyleUse::Named,
@@ -1507,7 +1913,7 @@ fn read_styles_tag(
                     }
                     b"style:default-style" => {
                         read_style_style(
-                            bs,
+                            ctx,
                             book,
                             origin,
                             StyleUse::Default,
@@ -1524,11 +1930,11 @@ fn read_styles_tag(
                     | b"number:currency-style"
                     | b"number:percentage-style"
                     | b"number:text-style" => {
-                        read_value_format(bs, book, origin, StyleUse::Named, xml, xml_tag)?;
+                        read_value_format(ctx, book, origin, StyleUse::Named, xml, xml_tag)?;
                     }
                     // style:default-page-layout
                     _ => {
-                        dump_unused2("read_styles_tag", &evt)?;
+                        dump_unused2(ctx, "read_styles_tag", &evt)?;
                     }
                 }
             }
@@ -1540,26 +1946,26 @@ fn read_styles_tag(
             }
             Event::Eof => break,
             _ => {
-                dump_unused2("read_styles_tag", &evt)?;
+                dump_unused2(ctx, "read_styles_tag", &evt)?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok(())
 }
 
 // read the automatic-styles tag
-fn read_auto_styles(
-    bs: &mut BufStack,
+fn read_auto_styles<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
     origin: StyleOrigin,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     // no attributes
 ) -> Result<(), OdsError> {
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         let empty_tag = matches!(evt, Event::Empty(_));
@@ -1571,7 +1977,7 @@ fn read_auto_styles(
                 match xml_tag.name().as_ref() {
                     b"style:style" => {
                         read_style_style(
-                            bs,
+                            ctx,
                             book,
                             origin,
                             StyleUse::Automatic,
@@ -1588,14 +1994,14 @@ fn read_auto_styles(
                     | b"number:currency-style"
                     | b"number:percentage-style"
                     | b"number:text-style" => {
-                        read_value_format(bs, book, origin, StyleUse::Automatic, xml, xml_tag)?;
+                        read_value_format(ctx, book, origin, StyleUse::Automatic, xml, xml_tag)?;
                     }
                     // style:default-page-layout
                     b"style:page-layout" => {
-                        read_page_style(bs, book, xml, xml_tag)?;
+                        read_page_style(ctx, book, xml, xml_tag)?;
                     }
                     _ => {
-                        dump_unused2("read_auto_styles", &evt)?;
+                        dump_unused2(ctx, "read_auto_styles", &evt)?;
                     }
                 }
             }
@@ -1607,60 +2013,60 @@ fn read_auto_styles(
             }
             Event::Eof => break,
             _ => {
-                dump_unused2("read_auto_styles", &evt)?;
+                dump_unused2(ctx, "read_auto_styles", &evt)?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok(())
 }
 
 // Reads any of the number:xxx tags
-fn read_value_format(
-    bs: &mut BufStack,
+fn read_value_format<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
     origin: StyleOrigin,
     styleuse: StyleUse,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: &BytesStart<'_>,
 ) -> Result<(), OdsError> {
     match xml_tag.name().as_ref() {
         b"number:boolean-style" => {
             let mut valuestyle = ValueFormatBoolean::new_empty();
-            read_value_format_parts(bs, origin, styleuse, &mut valuestyle, xml, xml_tag)?;
+            read_value_format_parts(ctx, origin, styleuse, &mut valuestyle, xml, xml_tag)?;
             book.add_boolean_format(valuestyle);
         }
         b"number:date-style" => {
             let mut valuestyle = ValueFormatDateTime::new_empty();
-            read_value_format_parts(bs, origin, styleuse, &mut valuestyle, xml, xml_tag)?;
+            read_value_format_parts(ctx, origin, styleuse, &mut valuestyle, xml, xml_tag)?;
             book.add_datetime_format(valuestyle);
         }
         b"number:time-style" => {
             let mut valuestyle = ValueFormatTimeDuration::new_empty();
-            read_value_format_parts(bs, origin, styleuse, &mut valuestyle, xml, xml_tag)?;
+            read_value_format_parts(ctx, origin, styleuse, &mut valuestyle, xml, xml_tag)?;
             book.add_timeduration_format(valuestyle);
         }
         b"number:number-style" => {
             let mut valuestyle = ValueFormatNumber::new_empty();
-            read_value_format_parts(bs, origin, styleuse, &mut valuestyle, xml, xml_tag)?;
+            read_value_format_parts(ctx, origin, styleuse, &mut valuestyle, xml, xml_tag)?;
             book.add_number_format(valuestyle);
         }
         b"number:currency-style" => {
             let mut valuestyle = ValueFormatCurrency::new_empty();
-            read_value_format_parts(bs, origin, styleuse, &mut valuestyle, xml, xml_tag)?;
+            read_value_format_parts(ctx, origin, styleuse, &mut valuestyle, xml, xml_tag)?;
             book.add_currency_format(valuestyle);
         }
         b"number:percentage-style" => {
             let mut valuestyle = ValueFormatPercentage::new_empty();
-            read_value_format_parts(bs, origin, styleuse, &mut valuestyle, xml, xml_tag)?;
+            read_value_format_parts(ctx, origin, styleuse, &mut valuestyle, xml, xml_tag)?;
             book.add_percentage_format(valuestyle);
         }
         b"number:text-style" => {
             let mut valuestyle = ValueFormatText::new_empty();
-            read_value_format_parts(bs, origin, styleuse, &mut valuestyle, xml, xml_tag)?;
+            read_value_format_parts(ctx, origin, styleuse, &mut valuestyle, xml, xml_tag)?;
             book.add_text_format(valuestyle);
         }
         _ => {
@@ -1676,12 +2082,12 @@ fn read_value_format(
 }
 
 // Reads any of the number:xxx tags
-fn read_value_format_parts<T: ValueFormatTrait>(
-    bs: &mut BufStack,
+fn read_value_format_parts<T: ValueFormatTrait, R: BufRead>(
+    ctx: &mut ReadCtx,
     origin: StyleOrigin,
     styleuse: StyleUse,
     valuestyle: &mut T,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: &BytesStart<'_>,
 ) -> Result<(), OdsError> {
     valuestyle.set_origin(origin);
@@ -1689,128 +2095,163 @@ fn read_value_format_parts<T: ValueFormatTrait>(
     let name = proc_style_attr(valuestyle.attrmap_mut(), xml_tag)?;
     valuestyle.set_name(name.as_str());
 
-    // Styles with content information are stored before completion.
-    let mut valuestyle_part = None;
-
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         if DUMP_XML {
             println!(" read_value_format {:?}", evt);
         }
+        let empty_tag = matches!(evt, Event::Empty(_));
         match evt {
             Event::Start(ref xml_tag) | Event::Empty(ref xml_tag) => {
                 match xml_tag.name().as_ref() {
-                    b"number:boolean" => {
-                        valuestyle.push_part(read_part(bs, xml, xml_tag, FormatPartType::Boolean)?)
-                    }
-                    b"number:number" => {
-                        valuestyle.push_part(read_part(bs, xml, xml_tag, FormatPartType::Number)?)
-                    }
+                    b"number:boolean" => valuestyle.push_part(read_part(
+                        ctx,
+                        xml,
+                        xml_tag,
+                        empty_tag,
+                        FormatPartType::Boolean,
+                    )?),
+                    b"number:number" => valuestyle.push_part(read_part(
+                        ctx,
+                        xml,
+                        xml_tag,
+                        empty_tag,
+                        FormatPartType::Number,
+                    )?),
                     b"number:scientific-number" => valuestyle.push_part(read_part(
-                        bs,
+                        ctx,
                         xml,
                         xml_tag,
+                        empty_tag,
                         FormatPartType::ScientificNumber,
                     )?),
-                    b"number:day" => {
-                        valuestyle.push_part(read_part(bs, xml, xml_tag, FormatPartType::Day)?)
-                    }
-                    b"number:month" => {
-                        valuestyle.push_part(read_part(bs, xml, xml_tag, FormatPartType::Month)?)
-                    }
-                    b"number:year" => {
-                        valuestyle.push_part(read_part(bs, xml, xml_tag, FormatPartType::Year)?)
-                    }
-                    b"number:era" => {
-                        valuestyle.push_part(read_part(bs, xml, xml_tag, FormatPartType::Era)?)
-                    }
+                    b"number:day" => valuestyle.push_part(read_part(
+                        ctx,
+                        xml,
+                        xml_tag,
+                        empty_tag,
+                        FormatPartType::Day,
+                    )?),
+                    b"number:month" => valuestyle.push_part(read_part(
+                        ctx,
+                        xml,
+                        xml_tag,
+                        empty_tag,
+                        FormatPartType::Month,
+                    )?),
+                    b"number:year" => valuestyle.push_part(read_part(
+                        ctx,
+                        xml,
+                        xml_tag,
+                        empty_tag,
+                        FormatPartType::Year,
+                    )?),
+                    b"number:era" => valuestyle.push_part(read_part(
+                        ctx,
+                        xml,
+                        xml_tag,
+                        empty_tag,
+                        FormatPartType::Era,
+                    )?),
                     b"number:day-of-week" => valuestyle.push_part(read_part(
-                        bs,
+                        ctx,
                         xml,
                         xml_tag,
+                        empty_tag,
                         FormatPartType::DayOfWeek,
                     )?),
                     b"number:week-of-year" => valuestyle.push_part(read_part(
-                        bs,
+                        ctx,
                         xml,
                         xml_tag,
+                        empty_tag,
                         FormatPartType::WeekOfYear,
                     )?),
-                    b"number:quarter" => {
-                        valuestyle.push_part(read_part(bs, xml, xml_tag, FormatPartType::Quarter)?)
-                    }
-                    b"number:hours" => {
-                        valuestyle.push_part(read_part(bs, xml, xml_tag, FormatPartType::Hours)?)
-                    }
-                    b"number:minutes" => {
-                        valuestyle.push_part(read_part(bs, xml, xml_tag, FormatPartType::Minutes)?)
-                    }
-                    b"number:seconds" => {
-                        valuestyle.push_part(read_part(bs, xml, xml_tag, FormatPartType::Seconds)?)
-                    }
-                    b"number:fraction" => {
-                        valuestyle.push_part(read_part(bs, xml, xml_tag, FormatPartType::Fraction)?)
-                    }
-                    b"number:am-pm" => {
-                        valuestyle.push_part(read_part(bs, xml, xml_tag, FormatPartType::AmPm)?)
-                    }
+                    b"number:quarter" => valuestyle.push_part(read_part(
+                        ctx,
+                        xml,
+                        xml_tag,
+                        empty_tag,
+                        FormatPartType::Quarter,
+                    )?),
+                    b"number:hours" => valuestyle.push_part(read_part(
+                        ctx,
+                        xml,
+                        xml_tag,
+                        empty_tag,
+                        FormatPartType::Hours,
+                    )?),
+                    b"number:minutes" => valuestyle.push_part(read_part(
+                        ctx,
+                        xml,
+                        xml_tag,
+                        empty_tag,
+                        FormatPartType::Minutes,
+                    )?),
+                    b"number:seconds" => valuestyle.push_part(read_part(
+                        ctx,
+                        xml,
+                        xml_tag,
+                        empty_tag,
+                        FormatPartType::Seconds,
+                    )?),
+                    b"number:fraction" => valuestyle.push_part(read_part(
+                        ctx,
+                        xml,
+                        xml_tag,
+                        empty_tag,
+                        FormatPartType::Fraction,
+                    )?),
+                    b"number:am-pm" => valuestyle.push_part(read_part(
+                        ctx,
+                        xml,
+                        xml_tag,
+                        empty_tag,
+                        FormatPartType::AmPm,
+                    )?),
                     b"number:text-content" => valuestyle.push_part(read_part(
-                        bs,
+                        ctx,
                         xml,
                         xml_tag,
+                        empty_tag,
                         FormatPartType::TextContent,
                     )?),
-                    b"style:text" => {
-                        valuestyle.push_part(read_part(bs, xml, xml_tag, FormatPartType::Day)?)
-                    }
-                    b"style:map" => valuestyle.push_stylemap(read_stylemap(xml_tag)?),
-                    b"number:fill-character" => {
-                        valuestyle_part =
-                            Some(read_part(bs, xml, xml_tag, FormatPartType::FillCharacter)?);
-
-                        // Empty-Tag. Finish here.
-                        if let Event::Empty(_) = evt {
-                            if let Some(part) = valuestyle_part {
-                                valuestyle.push_part(part);
-                            }
-                            valuestyle_part = None;
-                        }
-                    }
-                    b"number:currency-symbol" => {
-                        valuestyle_part =
-                            Some(read_part(bs, xml, xml_tag, FormatPartType::CurrencySymbol)?);
-
-                        // Empty-Tag. Finish here.
-                        if let Event::Empty(_) = evt {
-                            if let Some(part) = valuestyle_part {
-                                valuestyle.push_part(part);
-                            }
-                            valuestyle_part = None;
-                        }
-                    }
-                    b"number:text" => {
-                        valuestyle_part = Some(read_part(bs, xml, xml_tag, FormatPartType::Text)?);
-
-                        // Empty-Tag. Finish here.
-                        if let Event::Empty(_) = evt {
-                            if let Some(part) = valuestyle_part {
-                                valuestyle.push_part(part);
-                            }
-                            valuestyle_part = None;
-                        }
-                    }
+                    b"style:text" => valuestyle.push_part(read_part(
+                        ctx,
+                        xml,
+                        xml_tag,
+                        empty_tag,
+                        FormatPartType::Day,
+                    )?),
+                    b"style:map" => valuestyle.push_stylemap(read_stylemap(ctx, xml_tag)?),
+                    b"number:fill-character" => valuestyle.push_part(read_part(
+                        ctx,
+                        xml,
+                        xml_tag,
+                        empty_tag,
+                        FormatPartType::FillCharacter,
+                    )?),
+                    b"number:currency-symbol" => valuestyle.push_part(read_part(
+                        ctx,
+                        xml,
+                        xml_tag,
+                        empty_tag,
+                        FormatPartType::CurrencySymbol,
+                    )?),
+                    b"number:text" => valuestyle.push_part(read_part(
+                        ctx,
+                        xml,
+                        xml_tag,
+                        empty_tag,
+                        FormatPartType::Text,
+                    )?),
                     b"style:text-properties" => copy_attr2(valuestyle.textstyle_mut(), xml_tag)?,
                     _ => {
-                        dump_unused2("read_value_format", &evt)?;
+                        dump_unused2(ctx, "read_value_format", &evt)?;
                     }
                 }
             }
-            Event::Text(ref e) => {
-                if let Some(part) = &mut valuestyle_part {
-                    part.set_content(xml.decoder().decode(e.unescape()?.as_bytes())?);
-                }
-            }
             Event::End(ref e) => match e.name().as_ref() {
                 b"number:boolean-style"
                 | b"number:date-style"
@@ -1821,40 +2262,39 @@ fn read_value_format_parts<T: ValueFormatTrait>(
                 | b"number:text-style" => {
                     break;
                 }
-                b"number:currency-symbol" | b"number:text" | b"number:fill-character" => {
-                    if let Some(part) = valuestyle_part {
-                        valuestyle.push_part(part);
-                    }
-                    valuestyle_part = None;
-                }
                 _ => {
-                    dump_unused2("read_value_format", &evt)?;
+                    dump_unused2(ctx, "read_value_format", &evt)?;
                 }
             },
             Event::Eof => break,
             _ => {
-                dump_unused2("read_value_format", &evt)?;
+                dump_unused2(ctx, "read_value_format", &evt)?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok(())
 }
 
-fn read_part(
-    bs: &mut BufStack,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+fn read_part<R: BufRead>(
+    ctx: &mut ReadCtx,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: &BytesStart<'_>,
+    empty_tag: bool,
     part_type: FormatPartType,
 ) -> Result<FormatPart, OdsError> {
     let mut part = FormatPart::new(part_type);
     copy_attr2(part.attrmap_mut(), xml_tag)?;
 
+    if empty_tag {
+        return Ok(part);
+    }
+
     // There is one relevant subtag embedded-text.
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         if DUMP_XML {
@@ -1879,39 +2319,37 @@ fn read_part(
                             }
                         }
                     }
-                    _ => dump_unused2("read_value_format", &evt)?,
+                    _ => dump_unused2(ctx, "read_value_format", &evt)?,
                 }
             }
             Event::Text(ref e) => {
                 part.set_content(xml.decoder().decode(e.unescape()?.as_bytes())?);
             }
-            Event::End(ref e) => match e.name().as_ref() {
-                b"number:embedded-text" => {
+            Event::End(ref e) => {
+                if e.name() == xml_tag.name() {
                     break;
                 }
-                _ => {
-                    dump_unused2("read_value_format", &evt)?;
-                }
-            },
+            }
             Event::Eof => break,
             _ => {}
         }
+        buf.clear();
     }
 
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok(part)
 }
 
 #[allow(clippy::too_many_arguments)]
 // style:style tag
-fn read_style_style(
-    bs: &mut BufStack,
+fn read_style_style<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
     origin: StyleOrigin,
     styleuse: StyleUse,
     end_tag: &[u8],
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: &BytesStart<'_>,
     empty_tag: bool,
 ) -> Result<(), OdsError> {
@@ -1920,25 +2358,25 @@ fn read_style_style(
             attr if attr.key.as_ref() == b"style:family" => {
                 match attr.value.as_ref() {
                     b"table" => read_tablestyle(
-                        bs, book, origin, styleuse, end_tag, xml, xml_tag, empty_tag,
+                        ctx, book, origin, styleuse, end_tag, xml, xml_tag, empty_tag,
+                    )?,
+                    b"table-column" => read_colstyle(
+                        ctx, book, origin, styleuse, end_tag, xml, xml_tag, empty_tag,
+                    )?,
+                    b"table-row" => read_rowstyle(
+                        ctx, book, origin, styleuse, end_tag, xml, xml_tag, empty_tag,
                     )?,
-                    b"table-column" => {
-                        read_colstyle(bs, book, origin, styleuse, end_tag, xml, xml_tag, empty_tag)?
-                    }
-                    b"table-row" => {
-                        read_rowstyle(bs, book, origin, styleuse, end_tag, xml, xml_tag, empty_tag)?
-                    }
                     b"table-cell" => read_cellstyle(
-                        bs, book, origin, styleuse, end_tag, xml, xml_tag, empty_tag,
+                        ctx, book, origin, styleuse, end_tag, xml, xml_tag, empty_tag,
                     )?,
                     b"graphic" => read_graphicstyle(
-                        bs, book, origin, styleuse, end_tag, xml, xml_tag, empty_tag,
+                        ctx, book, origin, styleuse, end_tag, xml, xml_tag, empty_tag,
                     )?,
                     b"paragraph" => read_paragraphstyle(
-                        bs, book, origin, styleuse, end_tag, xml, xml_tag, empty_tag,
+                        ctx, book, origin, styleuse, end_tag, xml, xml_tag, empty_tag,
                     )?,
                     b"text" => read_textstyle(
-                        bs, book, origin, styleuse, end_tag, xml, xml_tag, empty_tag,
+                        ctx, book, origin, styleuse, end_tag, xml, xml_tag, empty_tag,
                     )?,
                     value => {
                         return Err(OdsError::Ods(format!(
@@ -1949,7 +2387,7 @@ fn read_style_style(
                 };
             }
             attr => {
-                dump_unused("read_style_style", xml_tag.name().as_ref(), &attr)?;
+                dump_unused(ctx, "read_style_style", xml_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -1960,13 +2398,13 @@ fn read_style_style(
 // style:style tag
 #[allow(clippy::collapsible_else_if)]
 #[allow(clippy::too_many_arguments)]
-fn read_tablestyle(
-    bs: &mut BufStack,
+fn read_tablestyle<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
     origin: StyleOrigin,
     styleuse: StyleUse,
     end_tag: &[u8],
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: &BytesStart<'_>,
     empty_tag: bool,
 ) -> Result<(), OdsError> {
@@ -1980,7 +2418,7 @@ fn read_tablestyle(
     if empty_tag {
         book.add_tablestyle(style);
     } else {
-        let mut buf = bs.get_buf();
+        let mut buf = ctx.get_buf();
         loop {
             let evt = xml.read_event_into(&mut buf)?;
             if DUMP_XML {
@@ -1991,7 +2429,7 @@ fn read_tablestyle(
                     match xml_tag.name().as_ref() {
                         b"style:table-properties" => copy_attr2(style.tablestyle_mut(), xml_tag)?,
                         _ => {
-                            dump_unused2("read_table_style", &evt)?;
+                            dump_unused2(ctx, "read_table_style", &evt)?;
                         }
                     }
                 }
@@ -2001,17 +2439,17 @@ fn read_tablestyle(
                         book.add_tablestyle(style);
                         break;
                     } else {
-                        dump_unused2("read_table_style", &evt)?;
+                        dump_unused2(ctx, "read_table_style", &evt)?;
                     }
                 }
                 Event::Eof => break,
                 _ => {
-                    dump_unused2("read_table_style", &evt)?;
+                    dump_unused2(ctx, "read_table_style", &evt)?;
                 }
             }
         }
 
-        bs.push(buf);
+        ctx.push(buf);
     }
 
     Ok(())
@@ -2020,13 +2458,13 @@ fn read_tablestyle(
 // style:style tag
 #[allow(clippy::collapsible_else_if)]
 #[allow(clippy::too_many_arguments)]
-fn read_rowstyle(
-    bs: &mut BufStack,
+fn read_rowstyle<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
     origin: StyleOrigin,
     styleuse: StyleUse,
     end_tag: &[u8],
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: &BytesStart<'_>,
     empty_tag: bool,
 ) -> Result<(), OdsError> {
@@ -2040,7 +2478,7 @@ fn read_rowstyle(
     if empty_tag {
         book.add_rowstyle(style);
     } else {
-        let mut buf = bs.get_buf();
+        let mut buf = ctx.get_buf();
         loop {
             let evt = xml.read_event_into(&mut buf)?;
             if DUMP_XML {
@@ -2051,7 +2489,7 @@ fn read_rowstyle(
                     match xml_tag.name().as_ref() {
                         b"style:table-row-properties" => copy_attr2(style.rowstyle_mut(), xml_tag)?,
                         _ => {
-                            dump_unused2("read_rowstyle", &evt)?;
+                            dump_unused2(ctx, "read_rowstyle", &evt)?;
                         }
                     }
                 }
@@ -2061,16 +2499,16 @@ fn read_rowstyle(
                         book.add_rowstyle(style);
                         break;
                     } else {
-                        dump_unused2("read_rowstyle", &evt)?;
+                        dump_unused2(ctx, "read_rowstyle", &evt)?;
                     }
                 }
                 Event::Eof => break,
                 _ => {
-                    dump_unused2("read_rowstyle", &evt)?;
+                    dump_unused2(ctx, "read_rowstyle", &evt)?;
                 }
             }
         }
-        bs.push(buf);
+        ctx.push(buf);
     }
 
     Ok(())
@@ -2079,13 +2517,13 @@ fn read_rowstyle(
 // style:style tag
 #[allow(clippy::collapsible_else_if)]
 #[allow(clippy::too_many_arguments)]
-fn read_colstyle(
-    bs: &mut BufStack,
+fn read_colstyle<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
     origin: StyleOrigin,
     styleuse: StyleUse,
     end_tag: &[u8],
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: &BytesStart<'_>,
     empty_tag: bool,
 ) -> Result<(), OdsError> {
@@ -2099,7 +2537,7 @@ fn read_colstyle(
     if empty_tag {
         book.add_colstyle(style);
     } else {
-        let mut buf = bs.get_buf();
+        let mut buf = ctx.get_buf();
         loop {
             let evt = xml.read_event_into(&mut buf)?;
             if DUMP_XML {
@@ -2112,7 +2550,7 @@ fn read_colstyle(
                 {
                     b"style:table-column-properties" => copy_attr2(style.colstyle_mut(), xml_tag)?,
                     _ => {
-                        dump_unused2("read_colstyle", &evt)?;
+                        dump_unused2(ctx, "read_colstyle", &evt)?;
                     }
                 },
                 Event::Text(_) => (),
@@ -2121,17 +2559,17 @@ fn read_colstyle(
                         book.add_colstyle(style);
                         break;
                     } else {
-                        dump_unused2("read_colstyle", &evt)?;
+                        dump_unused2(ctx, "read_colstyle", &evt)?;
                     }
                 }
                 Event::Eof => break,
                 _ => {
-                    dump_unused2("read_colstyle", &evt)?;
+                    dump_unused2(ctx, "read_colstyle", &evt)?;
                 }
             }
         }
 
-        bs.push(buf);
+        ctx.push(buf);
     }
     Ok(())
 }
@@ -2139,13 +2577,13 @@ fn read_colstyle(
 // style:style tag
 #[allow(clippy::collapsible_else_if)]
 #[allow(clippy::too_many_arguments)]
-fn read_cellstyle(
-    bs: &mut BufStack,
+fn read_cellstyle<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
     origin: StyleOrigin,
     styleuse: StyleUse,
     end_tag: &[u8],
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: &BytesStart<'_>,
     empty_tag: bool,
 ) -> Result<(), OdsError> {
@@ -2159,7 +2597,7 @@ fn read_cellstyle(
     if empty_tag {
         book.add_cellstyle(style);
     } else {
-        let mut buf = bs.get_buf();
+        let mut buf = ctx.get_buf();
         loop {
             let evt = xml.read_event_into(&mut buf)?;
             if DUMP_XML {
@@ -2176,7 +2614,7 @@ fn read_cellstyle(
                         copy_attr2(style.paragraphstyle_mut(), xml_tag)?
                     }
                     // b"style:graphic-properties" => copy_attr(style.graphic_mut(), xml, xml_tag)?,
-                    b"style:map" => style.push_stylemap(read_stylemap(xml_tag)?),
+                    b"style:map" => style.push_stylemap(read_stylemap(ctx, xml_tag)?),
 
                     // b"style:tab-stops" => (),
                     // b"style:tab-stop" => {
@@ -2185,7 +2623,7 @@ fn read_cellstyle(
                     //     style.paragraph_mut().add_tabstop(ts);
                     // }
                     _ => {
-                        dump_unused2("read_cellstyle", &evt)?;
+                        dump_unused2(ctx, "read_cellstyle", &evt)?;
                     }
                 },
                 Event::Text(_) => (),
@@ -2196,16 +2634,16 @@ fn read_cellstyle(
                     } else if e.name().as_ref() == b"style:paragraph-properties" {
                         // noop
                     } else {
-                        dump_unused2("read_cellstyle", &evt)?;
+                        dump_unused2(ctx, "read_cellstyle", &evt)?;
                     }
                 }
                 Event::Eof => break,
                 _ => {
-                    dump_unused2("read_cellstyle", &evt)?;
+                    dump_unused2(ctx, "read_cellstyle", &evt)?;
                 }
             }
         }
-        bs.push(buf);
+        ctx.push(buf);
     }
 
     Ok(())
@@ -2214,13 +2652,13 @@ fn read_cellstyle(
 // style:style tag
 #[allow(clippy::collapsible_else_if)]
 #[allow(clippy::too_many_arguments)]
-fn read_paragraphstyle(
-    bs: &mut BufStack,
+fn read_paragraphstyle<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
     origin: StyleOrigin,
     styleuse: StyleUse,
     end_tag: &[u8],
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: &BytesStart<'_>,
     empty_tag: bool,
 ) -> Result<(), OdsError> {
@@ -2234,7 +2672,7 @@ fn read_paragraphstyle(
     if empty_tag {
         book.add_paragraphstyle(style);
     } else {
-        let mut buf = bs.get_buf();
+        let mut buf = ctx.get_buf();
         loop {
             let evt = xml.read_event_into(&mut buf)?;
             if DUMP_XML {
@@ -2248,7 +2686,7 @@ fn read_paragraphstyle(
                             copy_attr2(style.paragraphstyle_mut(), xml_tag)?
                         }
                         // b"style:graphic-properties" => copy_attr(style.graphic_mut(), xml, xml_tag)?,
-                        // b"style:map" => style.push_stylemap(read_stylemap(xml, xml_tag)?),
+                        // b"style:map" => style.push_stylemap(read_stylemap(ctx, xml, xml_tag)?),
                         b"style:tab-stops" => (),
                         b"style:tab-stop" => {
                             let mut ts = TabStop::new();
@@ -2256,7 +2694,7 @@ fn read_paragraphstyle(
                             style.add_tabstop(ts);
                         }
                         _ => {
-                            dump_unused2("read_paragraphstyle", &evt)?;
+                            dump_unused2(ctx, "read_paragraphstyle", &evt)?;
                         }
                     }
                 }
@@ -2270,16 +2708,16 @@ fn read_paragraphstyle(
                     {
                         // noop
                     } else {
-                        dump_unused2("read_paragraphstyle", &evt)?;
+                        dump_unused2(ctx, "read_paragraphstyle", &evt)?;
                     }
                 }
                 Event::Eof => break,
                 _ => {
-                    dump_unused2("read_paragraphstyle", &evt)?;
+                    dump_unused2(ctx, "read_paragraphstyle", &evt)?;
                 }
             }
         }
-        bs.push(buf);
+        ctx.push(buf);
     }
 
     Ok(())
@@ -2288,13 +2726,13 @@ fn read_paragraphstyle(
 // style:style tag
 #[allow(clippy::collapsible_else_if)]
 #[allow(clippy::too_many_arguments)]
-fn read_textstyle(
-    bs: &mut BufStack,
+fn read_textstyle<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
     origin: StyleOrigin,
     styleuse: StyleUse,
     end_tag: &[u8],
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: &BytesStart<'_>,
     empty_tag: bool,
 ) -> Result<(), OdsError> {
@@ -2308,7 +2746,7 @@ fn read_textstyle(
     if empty_tag {
         book.add_textstyle(style);
     } else {
-        let mut buf = bs.get_buf();
+        let mut buf = ctx.get_buf();
         loop {
             let evt = xml.read_event_into(&mut buf)?;
             if DUMP_XML {
@@ -2319,7 +2757,7 @@ fn read_textstyle(
                     match xml_tag.name().as_ref() {
                         b"style:text-properties" => copy_attr2(style.textstyle_mut(), xml_tag)?,
                         _ => {
-                            dump_unused2("read_textstyle", &evt)?;
+                            dump_unused2(ctx, "read_textstyle", &evt)?;
                         }
                     }
                 }
@@ -2329,16 +2767,16 @@ fn read_textstyle(
                         book.add_textstyle(style);
                         break;
                     } else {
-                        dump_unused2("read_textstyle", &evt)?;
+                        dump_unused2(ctx, "read_textstyle", &evt)?;
                     }
                 }
                 Event::Eof => break,
                 _ => {
-                    dump_unused2("read_textstyle", &evt)?;
+                    dump_unused2(ctx, "read_textstyle", &evt)?;
                 }
             }
         }
-        bs.push(buf);
+        ctx.push(buf);
     }
 
     Ok(())
@@ -2347,13 +2785,13 @@ fn read_textstyle(
 // style:style tag
 #[allow(clippy::collapsible_else_if)]
 #[allow(clippy::too_many_arguments)]
-fn read_graphicstyle(
-    bs: &mut BufStack,
+fn read_graphicstyle<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
     origin: StyleOrigin,
     styleuse: StyleUse,
     end_tag: &[u8],
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: &BytesStart<'_>,
     empty_tag: bool,
 ) -> Result<(), OdsError> {
@@ -2367,7 +2805,7 @@ fn read_graphicstyle(
     if empty_tag {
         book.add_graphicstyle(style);
     } else {
-        let mut buf = bs.get_buf();
+        let mut buf = ctx.get_buf();
         loop {
             let evt = xml.read_event_into(&mut buf)?;
             if DUMP_XML {
@@ -2379,8 +2817,12 @@ fn read_graphicstyle(
                     .as_ref()
                 {
                     b"style:graphic-properties" => copy_attr2(style.graphicstyle_mut(), xml_tag)?,
+                    b"style:paragraph-properties" => {
+                        copy_attr2(style.paragraphstyle_mut(), xml_tag)?
+                    }
+                    b"style:text-properties" => copy_attr2(style.textstyle_mut(), xml_tag)?,
                     _ => {
-                        dump_unused2("read_graphicstyle", &evt)?;
+                        dump_unused2(ctx, "read_graphicstyle", &evt)?;
                     }
                 },
                 Event::Text(_) => (),
@@ -2389,22 +2831,22 @@ fn read_graphicstyle(
                         book.add_graphicstyle(style);
                         break;
                     } else {
-                        dump_unused2("read_graphicstyle", &evt)?;
+                        dump_unused2(ctx, "read_graphicstyle", &evt)?;
                     }
                 }
                 Event::Eof => break,
                 _ => {
-                    dump_unused2("read_graphicstyle", &evt)?;
+                    dump_unused2(ctx, "read_graphicstyle", &evt)?;
                 }
             }
         }
-        bs.push(buf);
+        ctx.push(buf);
     }
 
     Ok(())
 }
 
-fn read_stylemap(xml_tag: &BytesStart<'_>) -> Result<StyleMap, OdsError> {
+fn read_stylemap(ctx: &mut ReadCtx, xml_tag: &BytesStart<'_>) -> Result<StyleMap, OdsError> {
     let mut sm = StyleMap::default();
     for attr in xml_tag.attributes().with_checks(false) {
         match attr? {
@@ -2421,7 +2863,7 @@ fn read_stylemap(xml_tag: &BytesStart<'_>) -> Result<StyleMap, OdsError> {
                 sm.set_base_cell(parse_cellref(v.as_ref(), &mut pos)?);
             }
             attr => {
-                dump_unused("read_stylemap", xml_tag.name().as_ref(), &attr)?;
+                dump_unused(ctx, "read_stylemap", xml_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -2465,18 +2907,20 @@ fn copy_attr2(attrmap: &mut AttrMap2, xml_tag: &BytesStart<'_>) -> Result<(), Od
     Ok(())
 }
 
-fn read_styles(
-    bs: &mut BufStack,
+fn read_styles<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
-    zip_file: &mut ZipFile<'_>,
+    read: R,
 ) -> Result<(), OdsError> {
-    let mut xml = quick_xml::Reader::from_reader(BufReader::new(zip_file));
+    trace_span!("read_styles");
+
+    let mut xml = quick_xml::Reader::from_reader(read);
     // Do not trim text data. All text read contains significant whitespace.
     // The rest is ignored anyway.
     //
     // xml.trim_text(true);
 
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         if DUMP_XML {
@@ -2486,39 +2930,41 @@ fn read_styles(
             Event::Decl(_) => {}
 
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:document-styles" => {
-                // noop
+                for attr in xml_tag.attributes().with_checks(false) {
+                    read_namespace(book, STYLES_NAMESPACES, &attr?)?;
+                }
             }
             Event::End(xml_tag) if xml_tag.name().as_ref() == b"office:document-styles" => {
                 // noop
             }
 
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:font-face-decls" => {
-                read_fonts(bs, book, StyleOrigin::Styles, &mut xml)?
+                read_fonts(ctx, book, StyleOrigin::Styles, &mut xml)?
             }
 
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:styles" => {
-                read_styles_tag(bs, book, StyleOrigin::Styles, &mut xml)?
+                read_styles_tag(ctx, book, StyleOrigin::Styles, &mut xml)?
             }
 
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:automatic-styles" => {
-                read_auto_styles(bs, book, StyleOrigin::Styles, &mut xml)?
+                read_auto_styles(ctx, book, StyleOrigin::Styles, &mut xml)?
             }
 
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:master-styles" => {
-                read_master_styles(bs, book, StyleOrigin::Styles, &mut xml)?
+                read_master_styles(ctx, book, StyleOrigin::Styles, &mut xml)?
             }
 
             Event::Eof => {
                 break;
             }
             _ => {
-                dump_unused2("read_styles", &evt)?;
+                dump_unused2(ctx, "read_styles", &evt)?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok(())
 }
@@ -2614,18 +3060,43 @@ pub(crate) fn default_settings() -> Detach<Config> {
     dc
 }
 
-fn read_settings(
-    bs: &mut BufStack,
+// Reads meta.xml as a xml tree.
+fn read_meta(ctx: &mut ReadCtx, buf: &[u8]) -> Result<XmlTag, OdsError> {
+    let mut xml = quick_xml::Reader::from_reader(buf);
+
+    let mut buf = ctx.get_buf();
+    loop {
+        let evt = xml.read_event_into(&mut buf)?;
+        match evt {
+            Event::Start(ref xml_tag) if xml_tag.name().as_ref() == b"office:document-meta" => {
+                return read_xml(ctx, b"office:document-meta", &mut xml, xml_tag, false);
+            }
+            Event::Empty(ref xml_tag) if xml_tag.name().as_ref() == b"office:document-meta" => {
+                return read_xml(ctx, b"office:document-meta", &mut xml, xml_tag, true);
+            }
+            Event::Eof => {
+                return Err(OdsError::Ods("meta.xml without document-meta".to_string()));
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+}
+
+fn read_settings<R: BufRead>(
+    ctx: &mut ReadCtx,
     book: &mut WorkBook,
-    zip_file: &mut ZipFile<'_>,
+    read: R,
 ) -> Result<(), OdsError> {
-    let mut xml = quick_xml::Reader::from_reader(BufReader::new(zip_file));
+    trace_span!("read_settings");
+
+    let mut xml = quick_xml::Reader::from_reader(read);
     // Do not trim text data. All text read contains significant whitespace.
     // The rest is ignored anyway.
     //
     // xml.trim_text(true);
 
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         if DUMP_XML {
@@ -2643,33 +3114,87 @@ fn read_settings(
             }
 
             Event::Start(xml_tag) if xml_tag.name().as_ref() == b"office:settings" => {
-                book.config = Detach::new(read_office_settings(bs, &mut xml)?);
+                book.config = Detach::new(read_office_settings(ctx, &mut xml)?);
             }
 
             Event::Eof => {
                 break;
             }
             _ => {
-                dump_unused2("read_settings", &evt)?;
+                dump_unused2(ctx, "read_settings", &evt)?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok(())
 }
 
+// Reads the file entries of the manifest.
+fn read_manifest<R: BufRead>(ctx: &mut ReadCtx, read: R) -> Result<Vec<Manifest>, OdsError> {
+    trace_span!("read_manifest");
+
+    let mut manifest = Vec::new();
+
+    let mut xml = quick_xml::Reader::from_reader(read);
+    let mut buf = Vec::new();
+    loop {
+        let evt = xml.read_event_into(&mut buf)?;
+        if DUMP_XML {
+            println!(" read_manifest {:?}", evt);
+        }
+        match evt {
+            Event::Start(ref xml_tag) | Event::Empty(ref xml_tag)
+                if xml_tag.name().as_ref() == b"manifest:file-entry" =>
+            {
+                let mut entry = Manifest {
+                    full_path: Default::default(),
+                    version: None,
+                    media_type: Default::default(),
+                };
+                for attr in xml_tag.attributes().with_checks(false) {
+                    match attr? {
+                        attr if attr.key.as_ref() == b"manifest:full-path" => {
+                            entry.full_path = attr.unescape_value()?.to_string();
+                        }
+                        attr if attr.key.as_ref() == b"manifest:version" => {
+                            entry.version = Some(attr.unescape_value()?.to_string());
+                        }
+                        attr if attr.key.as_ref() == b"manifest:media-type" => {
+                            entry.media_type = attr.unescape_value()?.to_string();
+                        }
+                        attr => {
+                            dump_unused(ctx, "read_manifest", xml_tag.name().as_ref(), &attr)?;
+                        }
+                    }
+                }
+                manifest.push(entry);
+            }
+            Event::Start(ref xml_tag) if xml_tag.name().as_ref() == b"manifest:manifest" => {}
+            Event::Eof => {
+                break;
+            }
+            _ => {
+                dump_unused2(ctx, "read_manifest", &evt)?;
+            }
+        }
+        buf.clear();
+    }
+
+    Ok(manifest)
+}
+
 // read the automatic-styles tag
-fn read_office_settings(
-    bs: &mut BufStack,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+fn read_office_settings<R: BufRead>(
+    ctx: &mut ReadCtx,
+    xml: &mut quick_xml::Reader<R>,
     // no attributes
 ) -> Result<Config, OdsError> {
     let mut config = Config::new();
 
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         if DUMP_XML {
@@ -2677,7 +3202,7 @@ fn read_office_settings(
         }
         match evt {
             Event::Start(ref xml_tag) if xml_tag.name().as_ref() == b"config:config-item-set" => {
-                let (name, set) = read_config_item_set(bs, xml_tag, xml)?;
+                let (name, set) = read_config_item_set(ctx, xml_tag, xml)?;
                 config.insert(name, set);
             }
             Event::End(ref e) if e.name().as_ref() == b"office:settings" => {
@@ -2685,22 +3210,22 @@ fn read_office_settings(
             }
             Event::Eof => break,
             _ => {
-                dump_unused2("read_office_settings", &evt)?;
+                dump_unused2(ctx, "read_office_settings", &evt)?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok(config)
 }
 
 // read the automatic-styles tag
-fn read_config_item_set(
-    bs: &mut BufStack,
+fn read_config_item_set<R: BufRead>(
+    ctx: &mut ReadCtx,
     xml_tag: &BytesStart<'_>,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     // no attributes
 ) -> Result<(String, ConfigItem), OdsError> {
     let mut name = None;
@@ -2712,7 +3237,7 @@ fn read_config_item_set(
                 name = Some(attr.unescape_value()?.to_string());
             }
             attr => {
-                dump_unused("read_config_item_set", xml_tag.name().as_ref(), &attr)?;
+                dump_unused(ctx, "read_config_item_set", xml_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -2723,7 +3248,7 @@ fn read_config_item_set(
         return Err(OdsError::Ods("config-item-set without name".to_string()));
     };
 
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         if DUMP_XML {
@@ -2731,23 +3256,23 @@ fn read_config_item_set(
         }
         match evt {
             Event::Start(ref xml_tag) if xml_tag.name().as_ref() == b"config:config-item" => {
-                let (name, val) = read_config_item(bs, xml_tag, xml)?;
+                let (name, val) = read_config_item(ctx, xml_tag, xml)?;
                 config_set.insert(name, val);
             }
             Event::Start(ref xml_tag) if xml_tag.name().as_ref() == b"config:config-item-set" => {
-                let (name, val) = read_config_item_set(bs, xml_tag, xml)?;
+                let (name, val) = read_config_item_set(ctx, xml_tag, xml)?;
                 config_set.insert(name, val);
             }
             Event::Start(ref xml_tag)
                 if xml_tag.name().as_ref() == b"config:config-item-map-indexed" =>
             {
-                let (name, val) = read_config_item_map_indexed(bs, xml_tag, xml)?;
+                let (name, val) = read_config_item_map_indexed(ctx, xml_tag, xml)?;
                 config_set.insert(name, val);
             }
             Event::Start(ref xml_tag)
                 if xml_tag.name().as_ref() == b"config:config-item-map-named" =>
             {
-                let (name, val) = read_config_item_map_named(bs, xml_tag, xml)?;
+                let (name, val) = read_config_item_map_named(ctx, xml_tag, xml)?;
                 config_set.insert(name, val);
             }
             Event::End(ref e) if e.name().as_ref() == b"config:config-item-set" => {
@@ -2755,22 +3280,22 @@ fn read_config_item_set(
             }
             Event::Eof => break,
             _ => {
-                dump_unused2("read_config_item_set", &evt)?;
+                dump_unused2(ctx, "read_config_item_set", &evt)?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok((name, config_set))
 }
 
 // read the automatic-styles tag
-fn read_config_item_map_indexed(
-    bs: &mut BufStack,
+fn read_config_item_map_indexed<R: BufRead>(
+    ctx: &mut ReadCtx,
     xml_tag: &BytesStart<'_>,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     // no attributes
 ) -> Result<(String, ConfigItem), OdsError> {
     let mut name = None;
@@ -2783,6 +3308,7 @@ fn read_config_item_map_indexed(
             }
             attr => {
                 dump_unused(
+                    ctx,
                     "read_config_item_map_indexed",
                     xml_tag.name().as_ref(),
                     &attr,
@@ -2801,7 +3327,7 @@ fn read_config_item_map_indexed(
 
     let mut index = 0;
 
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         if DUMP_XML {
@@ -2811,7 +3337,7 @@ fn read_config_item_map_indexed(
             Event::Start(ref xml_tag)
                 if xml_tag.name().as_ref() == b"config:config-item-map-entry" =>
             {
-                let (_, entry) = read_config_item_map_entry(bs, xml_tag, xml)?;
+                let (_, entry) = read_config_item_map_entry(ctx, xml_tag, xml)?;
                 config_vec.insert(index.to_string(), entry);
                 index += 1;
             }
@@ -2820,22 +3346,22 @@ fn read_config_item_map_indexed(
             }
             Event::Eof => break,
             _ => {
-                dump_unused2("read_config_item_map_indexed", &evt)?;
+                dump_unused2(ctx, "read_config_item_map_indexed", &evt)?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok((name, config_vec))
 }
 
 // read the automatic-styles tag
-fn read_config_item_map_named(
-    bs: &mut BufStack,
+fn read_config_item_map_named<R: BufRead>(
+    ctx: &mut ReadCtx,
     xml_tag: &BytesStart<'_>,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     // no attributes
 ) -> Result<(String, ConfigItem), OdsError> {
     let mut name = None;
@@ -2847,7 +3373,12 @@ fn read_config_item_map_named(
                 name = Some(attr.unescape_value()?.to_string());
             }
             attr => {
-                dump_unused("read_config_item_map_named", xml_tag.name().as_ref(), &attr)?;
+                dump_unused(
+                    ctx,
+                    "read_config_item_map_named",
+                    xml_tag.name().as_ref(),
+                    &attr,
+                )?;
             }
         }
     }
@@ -2860,7 +3391,7 @@ fn read_config_item_map_named(
         ));
     };
 
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         if DUMP_XML {
@@ -2870,7 +3401,7 @@ fn read_config_item_map_named(
             Event::Start(ref xml_tag)
                 if xml_tag.name().as_ref() == b"config:config-item-map-entry" =>
             {
-                let (name, entry) = read_config_item_map_entry(bs, xml_tag, xml)?;
+                let (name, entry) = read_config_item_map_entry(ctx, xml_tag, xml)?;
 
                 let name = if let Some(name) = name {
                     name
@@ -2887,22 +3418,22 @@ fn read_config_item_map_named(
             }
             Event::Eof => break,
             _ => {
-                dump_unused2("read_config_item_map_named", &evt)?;
+                dump_unused2(ctx, "read_config_item_map_named", &evt)?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok((name, config_map))
 }
 
 // read the automatic-styles tag
-fn read_config_item_map_entry(
-    bs: &mut BufStack,
+fn read_config_item_map_entry<R: BufRead>(
+    ctx: &mut ReadCtx,
     xml_tag: &BytesStart<'_>,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     // no attributes
 ) -> Result<(Option<String>, ConfigItem), OdsError> {
     let mut name = None;
@@ -2914,12 +3445,17 @@ fn read_config_item_map_entry(
                 name = Some(attr.unescape_value()?.to_string());
             }
             attr => {
-                dump_unused("read_config_item_map_entry", xml_tag.name().as_ref(), &attr)?;
+                dump_unused(
+                    ctx,
+                    "read_config_item_map_entry",
+                    xml_tag.name().as_ref(),
+                    &attr,
+                )?;
             }
         }
     }
 
-    let mut buf = bs.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         if DUMP_XML {
@@ -2927,23 +3463,23 @@ fn read_config_item_map_entry(
         }
         match evt {
             Event::Start(ref xml_tag) if xml_tag.name().as_ref() == b"config:config-item" => {
-                let (name, val) = read_config_item(bs, xml_tag, xml)?;
+                let (name, val) = read_config_item(ctx, xml_tag, xml)?;
                 config_set.insert(name, ConfigItem::from(val));
             }
             Event::Start(ref xml_tag) if xml_tag.name().as_ref() == b"config:config-item-set" => {
-                let (name, val) = read_config_item_set(bs, xml_tag, xml)?;
+                let (name, val) = read_config_item_set(ctx, xml_tag, xml)?;
                 config_set.insert(name, val);
             }
             Event::Start(ref xml_tag)
                 if xml_tag.name().as_ref() == b"config:config-item-map-indexed" =>
             {
-                let (name, val) = read_config_item_map_indexed(bs, xml_tag, xml)?;
+                let (name, val) = read_config_item_map_indexed(ctx, xml_tag, xml)?;
                 config_set.insert(name, val);
             }
             Event::Start(ref xml_tag)
                 if xml_tag.name().as_ref() == b"config:config-item-map-named" =>
             {
-                let (name, val) = read_config_item_map_named(bs, xml_tag, xml)?;
+                let (name, val) = read_config_item_map_named(ctx, xml_tag, xml)?;
                 config_set.insert(name, val);
             }
             Event::End(ref e) if e.name().as_ref() == b"config:config-item-map-entry" => {
@@ -2951,22 +3487,22 @@ fn read_config_item_map_entry(
             }
             Event::Eof => break,
             _ => {
-                dump_unused2("read_config_item_map_entry", &evt)?;
+                dump_unused2(ctx, "read_config_item_map_entry", &evt)?;
             }
         }
 
         buf.clear();
     }
-    bs.push(buf);
+    ctx.push(buf);
 
     Ok((name, config_set))
 }
 
 // read the automatic-styles tag
-fn read_config_item(
-    bs: &mut BufStack,
+fn read_config_item<R: BufRead>(
+    ctx: &mut ReadCtx,
     xml_tag: &BytesStart<'_>,
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     // no attributes
 ) -> Result<(String, ConfigValue), OdsError> {
     #[derive(PartialEq)]
@@ -3010,7 +3546,7 @@ fn read_config_item(
                 };
             }
             attr => {
-                dump_unused("read_config_item", xml_tag.name().as_ref(), &attr)?;
+                dump_unused(ctx, "read_config_item", xml_tag.name().as_ref(), &attr)?;
             }
         }
     }
@@ -3029,8 +3565,8 @@ fn read_config_item(
         ));
     };
 
-    let mut value = bs.get_buf();
-    let mut buf = bs.get_buf();
+    let mut value = ctx.get_buf();
+    let mut buf = ctx.get_buf();
     loop {
         let evt = xml.read_event_into(&mut buf)?;
         match evt {
@@ -3074,7 +3610,7 @@ fn read_config_item(
                 break;
             }
             _ => {
-                dump_unused2("read_config_item", &evt)?;
+                dump_unused2(ctx, "read_config_item", &evt)?;
             }
         }
 
@@ -3083,8 +3619,8 @@ fn read_config_item(
         }
         buf.clear();
     }
-    bs.push(buf);
-    bs.push(value);
+    ctx.push(buf);
+    ctx.push(value);
 
     let config_val = if let Some(config_val) = config_val {
         config_val
@@ -3096,10 +3632,10 @@ fn read_config_item(
 }
 
 // Reads a part of the XML as XmlTag's.
-fn read_xml(
-    bs: &mut BufStack,
+fn read_xml<R: BufRead>(
+    ctx: &mut ReadCtx,
     end_tag: &[u8],
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: &BytesStart<'_>,
     empty_tag: bool,
 ) -> Result<XmlTag, OdsError> {
@@ -3110,7 +3646,7 @@ fn read_xml(
     stack.push(tag);
 
     if !empty_tag {
-        let mut buf = bs.get_buf();
+        let mut buf = ctx.get_buf();
         loop {
             let evt = xml.read_event_into(&mut buf)?;
             if DUMP_XML {
@@ -3124,7 +3660,8 @@ fn read_xml(
                 }
 
                 Event::End(xmlbytes) => {
-                    if xmlbytes.name().as_ref() == end_tag {
+                    // Nested tags can have the same name.
+                    if xmlbytes.name().as_ref() == end_tag && stack.len() == 1 {
                         break;
                     } else {
                         let tag = stack.pop().unwrap();
@@ -3164,23 +3701,23 @@ fn read_xml(
                 }
 
                 _ => {
-                    dump_unused2("read_xml", &evt)?;
+                    dump_unused2(ctx, "read_xml", &evt)?;
                 }
             }
             buf.clear();
         }
 
-        bs.push(buf);
+        ctx.push(buf);
     }
 
     assert_eq!(stack.len(), 1);
     Ok(stack.pop().unwrap())
 }
 
-fn read_text_or_tag(
-    bs: &mut BufStack,
+fn read_text_or_tag<R: BufRead>(
+    ctx: &mut ReadCtx,
     end_tag: &[u8],
-    xml: &mut quick_xml::Reader<BufReader<&mut ZipFile<'_>>>,
+    xml: &mut quick_xml::Reader<R>,
     xml_tag: &BytesStart<'_>,
     empty_tag: bool,
 ) -> Result<TextContent2, OdsError> {
@@ -3201,7 +3738,7 @@ fn read_text_or_tag(
     };
 
     if !empty_tag {
-        let mut buf = bs.get_buf();
+        let mut buf = ctx.get_buf();
         loop {
             let evt = xml.read_event_into(&mut buf)?;
             if DUMP_XML {
@@ -3318,17 +3855,43 @@ fn read_text_or_tag(
                 }
 
                 _ => {
-                    dump_unused2("read_text_or_tag", &evt)?;
+                    dump_unused2(ctx, "read_text_or_tag", &evt)?;
                 }
             }
         }
-        bs.push(buf);
+        ctx.push(buf);
     }
 
     Ok(cellcontent)
 }
 
-fn dump_unused(func: &str, tag: &[u8], attr: &Attribute<'_>) -> Result<(), OdsError> {
+// Keeps the namespace declarations that are not written anyway.
+fn read_namespace(
+    book: &mut WorkBook,
+    namespaces: &[(&str, &str)],
+    attr: &Attribute<'_>,
+) -> Result<(), OdsError> {
+    if let Some(prefix) = attr.key.as_ref().strip_prefix(b"xmlns:") {
+        let prefix = from_utf8(prefix)?;
+        if !namespaces.iter().any(|(v, _)| *v == prefix) {
+            book.add_namespace(prefix, attr.unescape_value()?.to_string());
+        }
+    }
+    Ok(())
+}
+
+fn dump_unused(
+    ctx: &mut ReadCtx,
+    func: &str,
+    tag: &[u8],
+    attr: &Attribute<'_>,
+) -> Result<(), OdsError> {
+    ctx.health(|h| {
+        h.add_unknown_attr(
+            &String::from_utf8_lossy(tag),
+            &String::from_utf8_lossy(attr.key.as_ref()),
+        )
+    });
     if DUMP_UNUSED {
         let tag = from_utf8(tag)?;
         let key = from_utf8(attr.key.as_ref())?;
@@ -3338,7 +3901,36 @@ fn dump_unused(func: &str, tag: &[u8], attr: &Attribute<'_>) -> Result<(), OdsEr
     Ok(())
 }
 
-fn dump_unused2(func: &str, evt: &Event<'_>) -> Result<(), OdsError> {
+fn dump_unused2(ctx: &mut ReadCtx, func: &str, evt: &Event<'_>) -> Result<(), OdsError> {
+    dump_unused_evt(ctx, func, evt, None)
+}
+
+// Records the skipped element for the sheet that is read.
+fn dump_unused_at(
+    ctx: &mut ReadCtx,
+    func: &str,
+    evt: &Event<'_>,
+    cell: Option<(u32, u32)>,
+) -> Result<(), OdsError> {
+    let sheet = ctx.sheet;
+    dump_unused_evt(ctx, func, evt, Some((sheet, cell)))
+}
+
+fn dump_unused_evt(
+    ctx: &mut ReadCtx,
+    func: &str,
+    evt: &Event<'_>,
+    at: Option<(usize, Option<(u32, u32)>)>,
+) -> Result<(), OdsError> {
+    if let Event::Start(xml_tag) | Event::Empty(xml_tag) = evt {
+        let name = String::from_utf8_lossy(xml_tag.name().as_ref()).to_string();
+        ctx.health(|h| h.add_unknown_element(&name));
+        let key = match at {
+            Some((sheet, cell)) => (name, Some(sheet), cell),
+            None => (name, None, None),
+        };
+        *ctx.dropped.entry(key).or_default() += 1;
+    }
     if DUMP_UNUSED {
         println!("unused attr: {} ({:?})", func, evt);
     }
@@ -4,15 +4,20 @@ use std::io;
 use std::io::{Cursor, Seek, Write};
 use std::path::Path;
 
-use chrono::NaiveDateTime;
+use chrono::{Duration, NaiveDateTime};
 use zip::write::FileOptions;
 
+use crate::compat::{excel_skips, WriteProfile};
 use crate::config::{ConfigItem, ConfigItemType, ConfigValue};
 use crate::error::OdsError;
 use crate::format::FormatPartType;
+use crate::health::FormatWarning;
 use crate::io::filebuf::FileBufEntry;
+use crate::io::format::RenderContext;
 use crate::io::xmlwriter::XmlWriter;
 use crate::io::zip_out::{ZipOut, ZipWrite};
+use crate::io::{CONTENT_NAMESPACES, DEFAULT_CELLSTYLE_COLS, STYLES_NAMESPACES};
+use crate::meta;
 use crate::refs::{cellranges_string, CellRange};
 use crate::style::{
     CellStyle, ColStyle, FontFaceDecl, GraphicStyle, HeaderFooter, MasterPage, PageStyle,
@@ -21,7 +26,8 @@ use crate::style::{
 use crate::validation::ValidationDisplay;
 use crate::xmltree::{XmlContent, XmlTag};
 use crate::{
-    CellContentRef, Length, Sheet, Value, ValueFormatTrait, ValueType, Visibility, WorkBook,
+    CellContentRef, Length, NumberPolicy, Sheet, Value, ValueFormatTrait, ValueType, Visibility,
+    WorkBook,
 };
 
 type OdsWriter<W> = ZipOut<W>;
@@ -57,12 +63,23 @@ fn write_ods_impl<W: Write + Seek>(
     book: &mut WorkBook,
     mut zip_writer: OdsWriter<W>,
 ) -> Result<W, OdsError> {
+    trace_span!("write_ods", sheets = book.num_sheets());
+
     sanity_checks(book)?;
 
     store_derived(book)?;
 
+    if book.meta_stamp {
+        let hash = book.content_hash();
+        book.set_user_meta(meta::CONTENT_HASH, hash);
+        book.set_user_meta(meta::GENERATOR, meta::generator());
+    }
+
     // copy all buffered data from the original.
+    #[cfg(not(feature = "rayon"))]
     copy_workbook(book, &mut zip_writer)?;
+    #[cfg(feature = "rayon")]
+    par_copy_workbook(book, &mut zip_writer)?;
     // write the rest, if necessary.
     write_mimetype(book, &mut zip_writer)?;
     write_manifest(book, &mut zip_writer)?;
@@ -70,13 +87,61 @@ fn write_ods_impl<W: Write + Seek>(
     write_meta(book, &mut zip_writer)?;
     // not in use any more, just ignore
     // write_configurations(&mut zip_writer, &mut file_set)?;
-    write_settings(book, &mut zip_writer)?;
-    write_ods_styles(book, &mut zip_writer)?;
-    write_ods_content(book, &mut zip_writer)?;
+    #[cfg(not(feature = "rayon"))]
+    {
+        write_settings(book, &mut zip_writer)?;
+        write_ods_styles(book, &mut zip_writer)?;
+        book.format_warnings = write_ods_content(book, &mut zip_writer)?;
+    }
+    #[cfg(feature = "rayon")]
+    {
+        book.format_warnings = par_write_parts(book, &mut zip_writer)?;
+    }
 
     Ok(zip_writer.zip()?)
 }
 
+/// Writes settings.xml, styles.xml and content.xml in parallel, each into
+/// its own in-memory archive. They are appended in the usual order.
+#[cfg(feature = "rayon")]
+fn par_write_parts<W: Write + Seek>(
+    book: &WorkBook,
+    zip_writer: &mut OdsWriter<W>,
+) -> Result<Vec<FormatWarning>, OdsError> {
+    trace_span!("par_write_parts");
+
+    let mut settings = zip_writer.part();
+    let mut styles = zip_writer.part();
+    let mut content = zip_writer.part();
+
+    let (r_settings, (r_styles, r_content)) = rayon::join(
+        || write_settings(book, &mut settings),
+        || {
+            rayon::join(
+                || write_ods_styles(book, &mut styles),
+                || write_ods_content(book, &mut content),
+            )
+        },
+    );
+    r_settings?;
+    r_styles?;
+    let warnings = r_content?;
+
+    zip_writer.append(settings)?;
+    zip_writer.append(styles)?;
+    zip_writer.append(content)?;
+
+    Ok(warnings)
+}
+
+// The office:version that is written.
+fn office_version(book: &WorkBook) -> &str {
+    match book.write_profile {
+        WriteProfile::Default => book.version(),
+        WriteProfile::Excel => "1.2",
+    }
+}
+
 fn sanity_checks(book: &mut WorkBook) -> Result<(), OdsError> {
     if book.sheets.is_empty() {
         return Err(OdsError::Ods("Workbook contains no sheets.".to_string()));
@@ -101,53 +166,120 @@ fn store_derived(book: &mut WorkBook) -> Result<(), OdsError> {
     bc.insert("HasSheetTabs", book.config().has_sheet_tabs);
     bc.insert("ShowGrid", book.config().show_grid);
     bc.insert("ShowPageBreaks", book.config().show_page_breaks);
-
+    bc.insert("ShowFormulas", book.config().show_formulas);
+    bc.insert(
+        "ShowPageBreakPreview",
+        book.config().show_page_break_preview,
+    );
+    bc.insert("ShowZeroValues", book.config().show_zero_values);
+    bc.insert("ShowNotes", book.config().show_notes);
+    bc.insert("HasColumnRowHeaders", book.config().has_column_row_headers);
+
+    // The sheets and styles are only changed if necessary, otherwise
+    // a sheet that is shared with a clone of the workbook would be copied.
     for i in 0..book.num_sheets() {
-        let mut sheet = book.detach_sheet(i);
-
-        // Set the column widths.
-        for ch in sheet.col_header.values_mut() {
-            // Any non default values?
-            if ch.width() != Length::Default {
-                if ch.style().is_none() {
-                    let colstyle = book.add_colstyle(ColStyle::new_empty());
-                    ch.set_style(&colstyle);
-                }
+        // Column widths and row heights need a style.
+        let cols: Vec<u32> = book
+            .sheet(i)
+            .col_header
+            .iter()
+            .filter(|(_, ch)| ch.width() != Length::Default && ch.style().is_none())
+            .map(|(col, _)| *col)
+            .collect();
+        for col in cols {
+            let colstyle = book.add_colstyle(ColStyle::new_empty());
+            if let Some(ch) = book.sheet_mut(i).col_header.get_mut(&col) {
+                ch.set_style(&colstyle);
             }
+        }
+        let rows: Vec<u32> = book
+            .sheet(i)
+            .row_header
+            .iter()
+            .filter(|(_, rh)| rh.height() != Length::Default && rh.style().is_none())
+            .map(|(row, _)| *row)
+            .collect();
+        for row in rows {
+            let rowstyle = book.add_rowstyle(RowStyle::new_empty());
+            if let Some(rh) = book.sheet_mut(i).row_header.get_mut(&row) {
+                rh.set_style(&rowstyle);
+            }
+        }
 
-            // Write back to the style.
-            if let Some(style_name) = ch.style() {
-                if let Some(style) = book.colstyle_mut(style_name) {
-                    if ch.width() == Length::Default {
+        // Write back to the style.
+        let widths: Vec<(String, Length)> = book
+            .sheet(i)
+            .col_header
+            .values()
+            .filter_map(|ch| ch.style().map(|v| (v.to_string(), ch.width())))
+            .collect();
+        for (style_name, width) in widths {
+            let (optimal, width_attr) = if width == Length::Default {
+                (Some("true"), None)
+            } else {
+                (None, Some(width.to_string()))
+            };
+            let changed = match book.colstyle(&style_name) {
+                Some(style) => {
+                    let attr = style.colstyle();
+                    attr.attr("style:column-width") != width_attr.as_ref()
+                        || optimal.is_some()
+                            && attr
+                                .attr("style:use-optimal-column-width")
+                                .map(|v| v.as_str())
+                                != optimal
+                }
+                None => false,
+            };
+            if changed {
+                if let Some(style) = book.colstyle_mut(&style_name) {
+                    if width == Length::Default {
                         style.set_use_optimal_col_width(true);
                         style.set_col_width(Length::Default);
                     } else {
-                        style.set_col_width(ch.width());
+                        style.set_col_width(width);
                     }
                 }
             }
         }
 
-        for rh in sheet.row_header.values_mut() {
-            if rh.height() != Length::Default {
-                if rh.style().is_none() {
-                    let rowstyle = book.add_rowstyle(RowStyle::new_empty());
-                    rh.set_style(&rowstyle);
+        let heights: Vec<(String, Length)> = book
+            .sheet(i)
+            .row_header
+            .values()
+            .filter_map(|rh| rh.style().map(|v| (v.to_string(), rh.height())))
+            .collect();
+        for (style_name, height) in heights {
+            let optimal = if height == Length::Default {
+                "true"
+            } else {
+                "false"
+            };
+            let changed = match book.rowstyle(&style_name) {
+                Some(style) => {
+                    let attr = style.rowstyle();
+                    attr.attr("style:row-height") != Some(&height.to_string())
+                        || attr
+                            .attr("style:use-optimal-row-height")
+                            .map(|v| v.as_str())
+                            != Some(optimal)
                 }
-            }
-
-            if let Some(style_name) = rh.style() {
-                if let Some(style) = book.rowstyle_mut(style_name) {
-                    if rh.height() == Length::Default {
+                None => false,
+            };
+            if changed {
+                if let Some(style) = book.rowstyle_mut(&style_name) {
+                    if height == Length::Default {
                         style.set_use_optimal_row_height(true);
                         style.set_row_height(Length::Default);
                     } else {
-                        style.set_row_height(rh.height());
+                        style.set_use_optimal_row_height(false);
+                        style.set_row_height(height);
                     }
                 }
             }
         }
 
+        let sheet = book.sheet(i);
         let bc = config.create_path(&[
             ("ooo:view-settings", ConfigItemType::Set),
             ("Views", ConfigItemType::Vec),
@@ -178,8 +310,6 @@ fn store_derived(book: &mut WorkBook) -> Result<(), OdsError> {
             (sheet.name().as_str(), ConfigItemType::Entry),
         ]);
         bc.insert("CodeName", sheet.name().as_str().to_string());
-
-        book.attach_sheet(sheet);
     }
 
     book.config.attach(config);
@@ -187,10 +317,13 @@ fn store_derived(book: &mut WorkBook) -> Result<(), OdsError> {
     Ok(())
 }
 
+#[cfg(not(feature = "rayon"))]
 fn copy_workbook<W: Write + Seek>(
     book: &WorkBook,
     zip_writer: &mut OdsWriter<W>,
 ) -> Result<(), OdsError> {
+    trace_span!("copy_workbook");
+
     for filebuf in book.filebuf.iter() {
         match filebuf {
             FileBufEntry::Dir(name) => {
@@ -206,6 +339,45 @@ fn copy_workbook<W: Write + Seek>(
     Ok(())
 }
 
+/// Same as copy_workbook, but compresses the files in parallel.
+#[cfg(feature = "rayon")]
+fn par_copy_workbook<W: Write + Seek>(
+    book: &WorkBook,
+    zip_writer: &mut OdsWriter<W>,
+) -> Result<(), OdsError> {
+    use rayon::prelude::*;
+
+    trace_span!("par_copy_workbook");
+
+    let parts = book
+        .filebuf
+        .iter()
+        .map(|v| (v, zip_writer.part()))
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|(filebuf, mut part)| {
+            if let FileBufEntry::File(name, buf) = filebuf {
+                let mut wr = part.start_file(name, FileOptions::default())?;
+                wr.write_all(buf.as_slice())?;
+            }
+            Ok(part)
+        })
+        .collect::<Result<Vec<_>, OdsError>>()?;
+
+    for (filebuf, part) in book.filebuf.iter().zip(parts) {
+        match filebuf {
+            FileBufEntry::Dir(name) => {
+                zip_writer.add_directory(name, FileOptions::default())?;
+            }
+            FileBufEntry::File(_, _) => {
+                zip_writer.append(part)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn write_mimetype<W: Write + Seek>(
     book: &WorkBook,
     zip_out: &mut OdsWriter<W>,
@@ -228,7 +400,9 @@ fn write_manifest<W: Write + Seek>(
     zip_out: &mut OdsWriter<W>,
 ) -> Result<(), OdsError> {
     if !book.filebuf.contains("META-INF/manifest.xml") {
-        zip_out.add_directory("META-INF", FileOptions::default())?;
+        if !book.filebuf.contains("META-INF/") {
+            zip_out.add_directory("META-INF", FileOptions::default())?;
+        }
         let w = zip_out.start_file("META-INF/manifest.xml", FileOptions::default())?;
 
         let mut xml_out = XmlWriter::new(w);
@@ -240,11 +414,11 @@ fn write_manifest<W: Write + Seek>(
             "xmlns:manifest",
             "urn:oasis:names:tc:opendocument:xmlns:manifest:1.0",
         )?;
-        xml_out.attr("manifest:version", book.version())?;
+        xml_out.attr("manifest:version", office_version(book))?;
 
         xml_out.empty("manifest:file-entry")?;
         xml_out.attr("manifest:full-path", "/")?;
-        xml_out.attr("manifest:version", book.version())?;
+        xml_out.attr("manifest:version", office_version(book))?;
         xml_out.attr(
             "manifest:media-type",
             "application/vnd.oasis.opendocument.spreadsheet",
@@ -275,6 +449,23 @@ fn write_manifest<W: Write + Seek>(
         xml_out.attr("manifest:full-path", "settings.xml")?;
         xml_out.attr("manifest:media-type", "text/xml")?;
 
+        // Entries of the original manifest for files that still exist.
+        for entry in &book.manifest {
+            if matches!(
+                entry.full_path.as_str(),
+                "/" | "manifest.rdf" | "styles.xml" | "meta.xml" | "content.xml" | "settings.xml"
+            ) || !book.filebuf.contains_path(&entry.full_path)
+            {
+                continue;
+            }
+            xml_out.empty("manifest:file-entry")?;
+            xml_out.attr_esc("manifest:full-path", &entry.full_path)?;
+            if let Some(version) = &entry.version {
+                xml_out.attr_esc("manifest:version", version)?;
+            }
+            xml_out.attr_esc("manifest:media-type", &entry.media_type)?;
+        }
+
         xml_out.end_elem("manifest:manifest")?;
 
         xml_out.close()?;
@@ -343,7 +534,17 @@ fn write_meta<W: Write + Seek>(
     book: &WorkBook,
     zip_out: &mut OdsWriter<W>,
 ) -> Result<(), OdsError> {
-    if !book.filebuf.contains("meta.xml") {
+    if let Some(meta_xml) = &book.meta_xml {
+        let w = zip_out.start_file("meta.xml", FileOptions::default())?;
+        let mut xml_out = XmlWriter::new(w);
+        xml_out.dtd("UTF-8")?;
+
+        let mut meta_xml = meta_xml.clone();
+        meta::put_user_meta(&mut meta_xml, &book.user_meta);
+        write_xmltag(&meta_xml, &mut xml_out)?;
+
+        xml_out.close()?;
+    } else if !book.filebuf.contains("meta.xml") {
         let w = zip_out.start_file("meta.xml", FileOptions::default())?;
 
         let mut xml_out = XmlWriter::new(w);
@@ -359,7 +560,7 @@ fn write_meta<W: Write + Seek>(
             "xmlns:office",
             "urn:oasis:names:tc:opendocument:xmlns:office:1.0",
         )?;
-        xml_out.attr("office:version", book.version())?;
+        xml_out.attr("office:version", office_version(book))?;
 
         xml_out.elem("office:meta")?;
 
@@ -376,6 +577,10 @@ fn write_meta<W: Write + Seek>(
 
         // TODO: allow to set this data.
 
+        for tag in meta::user_meta_tags(&book.user_meta) {
+            write_xmltag(&tag, &mut xml_out)?;
+        }
+
         xml_out.end_elem("office:meta")?;
 
         xml_out.end_elem("office:document-meta")?;
@@ -390,6 +595,8 @@ fn write_settings<W: Write + Seek>(
     book: &WorkBook,
     zip_out: &mut OdsWriter<W>,
 ) -> Result<(), OdsError> {
+    trace_span!("write_settings");
+
     let w = zip_out.start_file("settings.xml", FileOptions::default())?;
 
     let mut xml_out = XmlWriter::new(w);
@@ -406,7 +613,7 @@ fn write_settings<W: Write + Seek>(
         "xmlns:config",
         "urn:oasis:names:tc:opendocument:xmlns:config:1.0",
     )?;
-    xml_out.attr("office:version", book.version())?;
+    xml_out.attr("office:version", office_version(book))?;
     xml_out.elem("office:settings")?;
 
     for (name, item) in book.config.iter() {
@@ -648,96 +855,20 @@ fn write_ods_styles<W: Write + Seek>(
     book: &WorkBook,
     zip_out: &mut OdsWriter<W>,
 ) -> Result<(), OdsError> {
+    trace_span!("write_ods_styles");
+
     let w = zip_out.start_file("styles.xml", FileOptions::default())?;
 
     let mut xml_out = XmlWriter::new(w);
+    if book.write_profile == WriteProfile::Excel {
+        xml_out.set_skip(excel_skips);
+    }
 
     xml_out.dtd("UTF-8")?;
 
     xml_out.elem("office:document-styles")?;
-    xml_out.attr(
-        "xmlns:meta",
-        "urn:oasis:names:tc:opendocument:xmlns:meta:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:office",
-        "urn:oasis:names:tc:opendocument:xmlns:office:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:fo",
-        "urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0",
-    )?;
-    xml_out.attr("xmlns:ooo", "http://openoffice.org/2004/office")?;
-    xml_out.attr("xmlns:xlink", "http://www.w3.org/1999/xlink")?;
-    xml_out.attr("xmlns:dc", "http://purl.org/dc/elements/1.1/")?;
-    xml_out.attr(
-        "xmlns:style",
-        "urn:oasis:names:tc:opendocument:xmlns:style:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:text",
-        "urn:oasis:names:tc:opendocument:xmlns:text:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:dr3d",
-        "urn:oasis:names:tc:opendocument:xmlns:dr3d:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:svg",
-        "urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:chart",
-        "urn:oasis:names:tc:opendocument:xmlns:chart:1.0",
-    )?;
-    xml_out.attr("xmlns:rpt", "http://openoffice.org/2005/report")?;
-    xml_out.attr(
-        "xmlns:table",
-        "urn:oasis:names:tc:opendocument:xmlns:table:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:number",
-        "urn:oasis:names:tc:opendocument:xmlns:datastyle:1.0",
-    )?;
-    xml_out.attr("xmlns:ooow", "http://openoffice.org/2004/writer")?;
-    xml_out.attr("xmlns:oooc", "http://openoffice.org/2004/calc")?;
-    xml_out.attr("xmlns:of", "urn:oasis:names:tc:opendocument:xmlns:of:1.2")?;
-    xml_out.attr("xmlns:tableooo", "http://openoffice.org/2009/table")?;
-    xml_out.attr(
-        "xmlns:calcext",
-        "urn:org:documentfoundation:names:experimental:calc:xmlns:calcext:1.0",
-    )?;
-    xml_out.attr("xmlns:drawooo", "http://openoffice.org/2010/draw")?;
-    xml_out.attr(
-        "xmlns:draw",
-        "urn:oasis:names:tc:opendocument:xmlns:drawing:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:loext",
-        "urn:org:documentfoundation:names:experimental:office:xmlns:loext:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:field",
-        "urn:openoffice:names:experimental:ooo-ms-interop:xmlns:field:1.0",
-    )?;
-    xml_out.attr("xmlns:math", "http://www.w3.org/1998/Math/MathML")?;
-    xml_out.attr(
-        "xmlns:form",
-        "urn:oasis:names:tc:opendocument:xmlns:form:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:script",
-        "urn:oasis:names:tc:opendocument:xmlns:script:1.0",
-    )?;
-    xml_out.attr("xmlns:dom", "http://www.w3.org/2001/xml-events")?;
-    xml_out.attr("xmlns:xhtml", "http://www.w3.org/1999/xhtml")?;
-    xml_out.attr("xmlns:grddl", "http://www.w3.org/2003/g/data-view#")?;
-    xml_out.attr("xmlns:css3t", "http://www.w3.org/TR/css3-text/")?;
-    xml_out.attr(
-        "xmlns:presentation",
-        "urn:oasis:names:tc:opendocument:xmlns:presentation:1.0",
-    )?;
-    xml_out.attr("office:version", book.version())?;
+    write_namespaces(book, STYLES_NAMESPACES, &mut xml_out)?;
+    xml_out.attr("office:version", office_version(book))?;
 
     xml_out.elem("office:font-face-decls")?;
     write_font_decl(&book.fonts, StyleOrigin::Styles, &mut xml_out)?;
@@ -831,6 +962,11 @@ fn write_ods_styles<W: Write + Seek>(
         StyleUse::Default,
         &mut xml_out,
     )?;
+    for hook in &book.write_hooks {
+        for tag in hook.styles(book) {
+            write_xmltag(&tag, &mut xml_out)?;
+        }
+    }
     xml_out.end_elem("office:styles")?;
 
     xml_out.elem("office:automatic-styles")?;
@@ -891,109 +1027,49 @@ fn write_ods_styles<W: Write + Seek>(
     Ok(())
 }
 
+// The namespaces of the file and the additional namespaces of the
+// workbook. Prefixes of the file are not declared twice.
+fn write_namespaces<W: Write + Seek>(
+    book: &WorkBook,
+    namespaces: &[(&str, &str)],
+    xml_out: &mut XmlOdsWriter<'_, W>,
+) -> Result<(), OdsError> {
+    for (prefix, uri) in namespaces {
+        xml_out.attr(format!("xmlns:{}", prefix).as_str(), *uri)?;
+    }
+    for (prefix, uri) in &book.namespaces {
+        if !namespaces.iter().any(|(v, _)| v == prefix) {
+            xml_out.attr_esc(format!("xmlns:{}", prefix).as_str(), uri.as_str())?;
+        }
+    }
+    Ok(())
+}
+
+// Returns the value formats that couldn't be used for the cell text.
 fn write_ods_content<W: Write + Seek>(
     book: &WorkBook,
     zip_out: &mut OdsWriter<W>,
-) -> Result<(), OdsError> {
+) -> Result<Vec<FormatWarning>, OdsError> {
+    trace_span!("write_ods_content");
+
     let w = zip_out.start_file("content.xml", FileOptions::default())?;
     let mut xml_out = XmlWriter::new(w);
+    if book.write_profile == WriteProfile::Excel {
+        xml_out.set_skip(excel_skips);
+    }
 
     xml_out.dtd("UTF-8")?;
 
     xml_out.elem("office:document-content")?;
-    xml_out.attr(
-        "xmlns:meta",
-        "urn:oasis:names:tc:opendocument:xmlns:meta:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:office",
-        "urn:oasis:names:tc:opendocument:xmlns:office:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:fo",
-        "urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0",
-    )?;
-    xml_out.attr("xmlns:ooo", "http://openoffice.org/2004/office")?;
-    xml_out.attr("xmlns:xlink", "http://www.w3.org/1999/xlink")?;
-    xml_out.attr("xmlns:dc", "http://purl.org/dc/elements/1.1/")?;
-    xml_out.attr(
-        "xmlns:style",
-        "urn:oasis:names:tc:opendocument:xmlns:style:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:text",
-        "urn:oasis:names:tc:opendocument:xmlns:text:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:draw",
-        "urn:oasis:names:tc:opendocument:xmlns:drawing:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:dr3d",
-        "urn:oasis:names:tc:opendocument:xmlns:dr3d:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:svg",
-        "urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:chart",
-        "urn:oasis:names:tc:opendocument:xmlns:chart:1.0",
-    )?;
-    xml_out.attr("xmlns:rpt", "http://openoffice.org/2005/report")?;
-    xml_out.attr(
-        "xmlns:table",
-        "urn:oasis:names:tc:opendocument:xmlns:table:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:number",
-        "urn:oasis:names:tc:opendocument:xmlns:datastyle:1.0",
-    )?;
-    xml_out.attr("xmlns:ooow", "http://openoffice.org/2004/writer")?;
-    xml_out.attr("xmlns:oooc", "http://openoffice.org/2004/calc")?;
-    xml_out.attr("xmlns:of", "urn:oasis:names:tc:opendocument:xmlns:of:1.2")?;
-    xml_out.attr("xmlns:tableooo", "http://openoffice.org/2009/table")?;
-    xml_out.attr(
-        "xmlns:calcext",
-        "urn:org:documentfoundation:names:experimental:calc:xmlns:calcext:1.0",
-    )?;
-    xml_out.attr("xmlns:drawooo", "http://openoffice.org/2010/draw")?;
-    xml_out.attr(
-        "xmlns:loext",
-        "urn:org:documentfoundation:names:experimental:office:xmlns:loext:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:field",
-        "urn:openoffice:names:experimental:ooo-ms-interop:xmlns:field:1.0",
-    )?;
-    xml_out.attr("xmlns:math", "http://www.w3.org/1998/Math/MathML")?;
-    xml_out.attr(
-        "xmlns:form",
-        "urn:oasis:names:tc:opendocument:xmlns:form:1.0",
-    )?;
-    xml_out.attr(
-        "xmlns:script",
-        "urn:oasis:names:tc:opendocument:xmlns:script:1.0",
-    )?;
-    xml_out.attr("xmlns:dom", "http://www.w3.org/2001/xml-events")?;
-    xml_out.attr("xmlns:xforms", "http://www.w3.org/2002/xforms")?;
-    xml_out.attr("xmlns:xsd", "http://www.w3.org/2001/XMLSchema")?;
-    xml_out.attr("xmlns:xsi", "http://www.w3.org/2001/XMLSchema-instance")?;
-    xml_out.attr(
-        "xmlns:formx",
-        "urn:openoffice:names:experimental:ooxml-odf-interop:xmlns:form:1.0",
-    )?;
-    xml_out.attr("xmlns:xhtml", "http://www.w3.org/1999/xhtml")?;
-    xml_out.attr("xmlns:grddl", "http://www.w3.org/2003/g/data-view#")?;
-    xml_out.attr("xmlns:css3t", "http://www.w3.org/TR/css3-text/")?;
-    xml_out.attr(
-        "xmlns:presentation",
-        "urn:oasis:names:tc:opendocument:xmlns:presentation:1.0",
-    )?;
+    write_namespaces(book, CONTENT_NAMESPACES, &mut xml_out)?;
 
-    xml_out.attr("office:version", book.version())?;
+    xml_out.attr("office:version", office_version(book))?;
 
-    xml_out.empty("office:scripts")?;
+    if let Some(scripts) = book.extra.iter().find(|v| v.name() == "office:scripts") {
+        write_xmltag(scripts, &mut xml_out)?;
+    } else {
+        xml_out.empty("office:scripts")?;
+    }
 
     xml_out.elem("office:font-face-decls")?;
     write_font_decl(&book.fonts, StyleOrigin::Content, &mut xml_out)?;
@@ -1055,8 +1131,7 @@ fn write_ods_content<W: Write + Seek>(
 
     // extra tags. pass through only
     for tag in &book.extra {
-        if tag.name() == "office:scripts" ||
-            tag.name() == "table:tracked-changes" ||
+        if tag.name() == "table:tracked-changes" ||
             tag.name() == "text:variable-decls" ||
             tag.name() == "text:sequence-decls" ||
             tag.name() == "text:user-field-decls" ||
@@ -1071,8 +1146,9 @@ fn write_ods_content<W: Write + Seek>(
 
     write_content_validations(book, &mut xml_out)?;
 
+    let mut formats = RenderContext::new(book);
     for sheet in &book.sheets {
-        write_sheet(book, sheet, &mut xml_out)?;
+        write_sheet(book, sheet, &mut formats, &mut xml_out)?;
     }
 
     // extra tags. pass through only
@@ -1087,13 +1163,19 @@ fn write_ods_content<W: Write + Seek>(
         }
     }
 
+    for hook in &book.write_hooks {
+        for tag in hook.spreadsheet(book) {
+            write_xmltag(&tag, &mut xml_out)?;
+        }
+    }
+
     xml_out.end_elem("office:spreadsheet")?;
     xml_out.end_elem("office:body")?;
     xml_out.end_elem("office:document-content")?;
 
     xml_out.close()?;
 
-    Ok(())
+    Ok(formats.take_warnings())
 }
 
 fn write_content_validations<W: Write + Seek>(
@@ -1187,8 +1269,15 @@ pub(crate) fn remove_outlooped(ranges: &mut Vec<CellRange>, row: u32, col: u32)
 fn write_sheet<W: Write + Seek>(
     book: &WorkBook,
     sheet: &Sheet,
+    formats: &mut RenderContext<'_>,
     xml_out: &mut XmlOdsWriter<'_, W>,
 ) -> Result<(), OdsError> {
+    trace_span!(
+        "write_sheet",
+        sheet = sheet.name().as_str(),
+        cells = sheet.data.len()
+    );
+
     xml_out.elem("table:table")?;
     xml_out.attr_esc("table:name", &*sheet.name)?;
     if let Some(style) = &sheet.style {
@@ -1203,15 +1292,27 @@ fn write_sheet<W: Write + Seek>(
     if !sheet.display() {
         xml_out.attr("table:display", "false")?;
     }
+    if sheet.protected() {
+        xml_out.attr("table:protected", "true")?;
+    }
+    if let Some(key) = &sheet.protection_key {
+        xml_out.attr_esc("table:protection-key", key)?;
+    }
+    if let Some(digest) = &sheet.protection_key_digest {
+        xml_out.attr_esc("table:protection-key-digest-algorithm", digest)?;
+    }
 
     let max_cell = sheet.used_grid_size();
 
     for tag in &sheet.extra {
-        if tag.name() == "table:title"
+        if tag.name() == "table:table-protection"
+            || tag.name() == "loext:table-protection"
+            || tag.name() == "table:title"
             || tag.name() == "table:desc"
             || tag.name() == "table:table-source"
             || tag.name() == "office:dde-source"
             || tag.name() == "table:scenario"
+            || tag.name() == "office:event-listeners"
             || tag.name() == "office:forms"
             || tag.name() == "table:shapes"
         {
@@ -1299,7 +1400,7 @@ fn write_sheet<W: Write + Seek>(
         let (is_hidden, hidden_cols) = check_hidden(&spans, cur_row, cur_col);
 
         // And now to something completely different ...
-        write_cell(book, &cell, is_hidden, xml_out)?;
+        write_cell(book, &cell, is_hidden, formats, xml_out)?;
 
         // There may be some blank cells until the next one, but only one less the forward.
         if forward_dc > 1 {
@@ -1330,14 +1431,20 @@ fn write_sheet<W: Write + Seek>(
         last_c = cur_col;
     }
 
-    xml_out.end_elem("table:table")?;
-
     for tag in &sheet.extra {
         if tag.name() == "table:named-expressions" || tag.name() == "calcext:conditional-formats" {
             write_xmltag(tag, xml_out)?;
         }
     }
 
+    for hook in &book.write_hooks {
+        for tag in hook.sheet(book, sheet) {
+            write_xmltag(&tag, xml_out)?;
+        }
+    }
+
+    xml_out.end_elem("table:table")?;
+
     Ok(())
 }
 
@@ -1549,13 +1656,16 @@ fn write_xmltag<W: Write + Seek>(
     x: &XmlTag,
     xml_out: &mut XmlOdsWriter<'_, W>,
 ) -> Result<(), OdsError> {
+    if xml_out.skips(x.name()) {
+        return Ok(());
+    }
     if x.is_empty() {
         xml_out.empty(x.name())?;
     } else {
         xml_out.elem(x.name())?;
     }
     for (k, v) in x.attrmap().iter() {
-        xml_out.attr_esc(k.as_ref(), v.as_str())?;
+        xml_out.attr_esc(k, v.as_str())?;
     }
 
     for c in x.content() {
@@ -1591,13 +1701,17 @@ fn write_table_columns<W: Write + Seek>(
         }
 
         xml_out.empty("table:table-column")?;
-        if let Some(col_header) = sheet.col_header.get(&c) {
-            if let Some(style) = col_header.style() {
-                xml_out.attr_esc("table:style-name", style.as_str())?;
-            }
-            if let Some(cellstyle) = col_header.cellstyle() {
-                xml_out.attr_esc("table:default-cell-style-name", cellstyle.as_str())?;
-            }
+        let col_header = sheet.col_header.get(&c);
+        if let Some(style) = col_header.and_then(|v| v.style()) {
+            xml_out.attr_esc("table:style-name", style.as_str())?;
+        }
+        if let Some(cellstyle) = col_header
+            .and_then(|v| v.cellstyle())
+            .or(sheet.default_cellstyle.as_ref())
+        {
+            xml_out.attr_esc("table:default-cell-style-name", cellstyle.as_str())?;
+        }
+        if let Some(col_header) = col_header {
             if col_header.visible() != Visibility::Visible {
                 xml_out.attr_esc(
                     "table:visibility",
@@ -1614,14 +1728,128 @@ fn write_table_columns<W: Write + Seek>(
         }
     }
 
+    // The sheet default fills the columns up to the end.
+    if let Some(cellstyle) = &sheet.default_cellstyle {
+        if max_cell.1 < DEFAULT_CELLSTYLE_COLS {
+            let repeat = (DEFAULT_CELLSTYLE_COLS - max_cell.1).to_string();
+            xml_out.empty("table:table-column")?;
+            xml_out.attr("table:number-columns-repeated", repeat.as_str())?;
+            xml_out.attr_esc("table:default-cell-style-name", cellstyle.as_str())?;
+        }
+    }
+
     Ok(())
 }
 
+/// Formats a float value for office:value according to the policy.
+pub(crate) fn format_number(v: f64, policy: NumberPolicy) -> String {
+    let (digits, even) = match policy {
+        NumberPolicy::Exact => return v.to_string(),
+        NumberPolicy::Significant(n) => (usize::from(n.max(1)), false),
+        NumberPolicy::SignificantEven(n) => (usize::from(n.max(1)), true),
+    };
+    if !v.is_finite() {
+        return v.to_string();
+    }
+
+    // Shortest representation, eg "-1.2345e-3".
+    let s = format!("{:e}", v);
+    let (mantissa, exp) = match s.split_once('e') {
+        Some(v) => v,
+        None => return v.to_string(),
+    };
+    let mut exp: i32 = exp.parse().unwrap_or_default();
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(m) => ("-", m),
+        None => ("", mantissa),
+    };
+    let mut buf: Vec<u8> = mantissa.bytes().filter(|c| *c != b'.').collect();
+    if buf.len() <= digits {
+        return v.to_string();
+    }
+
+    let rest = buf.split_off(digits);
+    let round_up = match rest[0] {
+        b'6'..=b'9' => true,
+        b'5' => !even || rest[1..].iter().any(|c| *c != b'0') || buf[digits - 1] % 2 == 1,
+        _ => false,
+    };
+    if round_up {
+        let mut carry = true;
+        for c in buf.iter_mut().rev() {
+            if *c == b'9' {
+                *c = b'0';
+            } else {
+                *c += 1;
+                carry = false;
+                break;
+            }
+        }
+        if carry {
+            buf.insert(0, b'1');
+            buf.pop();
+            exp += 1;
+        }
+    }
+
+    let fract = if buf.len() > 1 {
+        String::from_utf8_lossy(&buf[1..]).to_string()
+    } else {
+        "0".to_string()
+    };
+    let rounded = format!("{}{}.{}e{}", sign, buf[0] as char, fract, exp);
+    match rounded.parse::<f64>() {
+        Ok(v) => v.to_string(),
+        Err(_) => v.to_string(),
+    }
+}
+
+/// Formats a duration in the ISO 8601 format as used for time values,
+/// eg "PT12H30M5.25S". The fraction of the seconds is kept up to nanoseconds.
+pub fn format_iso_duration(d: &Duration) -> String {
+    let mut buf = String::new();
+
+    let d = if *d < Duration::zero() {
+        buf.push('-');
+        -*d
+    } else {
+        *d
+    };
+
+    buf.push_str("PT");
+    buf.push_str(&d.num_hours().to_string());
+    buf.push('H');
+    buf.push_str(&(d.num_minutes() % 60).to_string());
+    buf.push('M');
+    buf.push_str(&(d.num_seconds() % 60).to_string());
+    let nanos = (d - Duration::seconds(d.num_seconds()))
+        .num_nanoseconds()
+        .unwrap_or(0);
+    if nanos > 0 {
+        buf.push('.');
+        buf.push_str(format!("{:09}", nanos).trim_end_matches('0'));
+    }
+    buf.push('S');
+
+    buf
+}
+
+// Valid as XML attribute name without prefix.
+fn is_metadata_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    match chars.next() {
+        Some(c) if c.is_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
+
 #[allow(clippy::single_char_add_str)]
 fn write_cell<W: Write + Seek>(
     book: &WorkBook,
     cell: &CellContentRef<'_>,
     is_hidden: bool,
+    formats: &mut RenderContext<'_>,
     xml_out: &mut XmlOdsWriter<'_, W>,
 ) -> Result<(), OdsError> {
     let tag = if is_hidden {
@@ -1630,13 +1858,19 @@ fn write_cell<W: Write + Seek>(
         "table:table-cell"
     };
 
-    match cell.value {
-        None | Some(Value::Empty) => xml_out.empty(tag)?,
-        _ => xml_out.elem(tag)?,
+    let is_empty = matches!(cell.value, None | Some(Value::Empty)) && cell.shapes.is_none();
+    if is_empty {
+        xml_out.empty(tag)?;
+    } else {
+        xml_out.elem(tag)?;
     }
 
     if let Some(formula) = cell.formula {
-        xml_out.attr_esc("table:formula", formula.as_str())?;
+        if book.write_profile == WriteProfile::Excel && formula.starts_with('=') {
+            xml_out.attr_esc("table:formula", format!("of:{}", formula))?;
+        } else {
+            xml_out.attr_esc("table:formula", formula.as_str())?;
+        }
     }
 
     // Direct style oder value based default style.
@@ -1669,6 +1903,23 @@ fn write_cell<W: Write + Seek>(
         }
     }
 
+    // Extended value type, if it still fits the value.
+    if let (Some(content_type), Some(value)) = (cell.content_type, cell.value) {
+        if content_type.matches(value.value_type()) {
+            xml_out.attr("calcext:value-type", content_type.to_string())?;
+        }
+    }
+
+    // Custom metadata
+    if let Some(metadata) = cell.metadata {
+        for (key, value) in metadata {
+            if !is_metadata_key(key) {
+                return Err(OdsError::Ods(format!("Invalid metadata key {:?}", key)));
+            }
+            xml_out.attr_esc(format!("sods:{}", key).as_str(), value)?;
+        }
+    }
+
     // This finds the correct ValueFormat, but there is no way to use it.
     // Falls back to: Output the same string as needed for the value-attribute
     // and hope for the best. Seems to work well enough.
@@ -1679,6 +1930,8 @@ fn write_cell<W: Write + Seek>(
     //     None
     // };
 
+    let number_policy = cell.number_policy.unwrap_or(book.number_policy);
+
     match cell.value {
         None | Some(Value::Empty) => {}
         Some(Value::Text(s)) => {
@@ -1700,22 +1953,13 @@ fn write_cell<W: Write + Seek>(
             let value = d.format("%Y-%m-%dT%H:%M:%S%.f").to_string();
             xml_out.attr("office:date-value", value.as_str())?;
             xml_out.elem("text:p")?;
-            xml_out.text_esc(value)?;
+            let style = cell.style.or_else(|| book.def_style(ValueType::DateTime));
+            xml_out.text_esc(formats.format_datetime(style, d))?;
             xml_out.end_elem("text:p")?;
         }
         Some(Value::TimeDuration(d)) => {
             xml_out.attr("office:value-type", "time")?;
-
-            let mut value = String::from("PT");
-            value.push_str(&d.num_hours().to_string());
-            value.push_str("H");
-            value.push_str(&(d.num_minutes() % 60).to_string());
-            value.push_str("M");
-            value.push_str(&(d.num_seconds() % 60).to_string());
-            value.push_str(".");
-            value.push_str(&(d.num_milliseconds() % 1000).to_string());
-            value.push_str("S");
-
+            let value = format_iso_duration(d);
             xml_out.attr("office:time-value", value.as_str())?;
 
             xml_out.elem("text:p")?;
@@ -1732,17 +1976,16 @@ fn write_cell<W: Write + Seek>(
         Some(Value::Currency(v, c)) => {
             xml_out.attr("office:value-type", "currency")?;
             xml_out.attr_esc("office:currency", String::from_utf8_lossy(c))?;
-            let value = v.to_string();
+            let value = format_number(*v, number_policy);
             xml_out.attr("office:value", value.as_str())?;
             xml_out.elem("text:p")?;
-            xml_out.text(String::from_utf8_lossy(c))?;
-            xml_out.text(" ")?;
-            xml_out.text(value)?;
+            let style = cell.style.or_else(|| book.def_style(ValueType::Currency));
+            xml_out.text_esc(formats.format_currency(style, &value, c))?;
             xml_out.end_elem("text:p")?;
         }
         Some(Value::Number(v)) => {
             xml_out.attr("office:value-type", "float")?;
-            let value = v.to_string();
+            let value = format_number(*v, number_policy);
             xml_out.attr("office:value", value.as_str())?;
             xml_out.elem("text:p")?;
             xml_out.text(value)?;
@@ -1750,7 +1993,7 @@ fn write_cell<W: Write + Seek>(
         }
         Some(Value::Percentage(v)) => {
             xml_out.attr("office:value-type", "percentage")?;
-            let value = v.to_string();
+            let value = format_number(*v, number_policy);
             xml_out.attr("office:value", value.as_str())?;
             xml_out.elem("text:p")?;
             xml_out.text(value)?;
@@ -1758,9 +2001,14 @@ fn write_cell<W: Write + Seek>(
         }
     }
 
-    match cell.value {
-        None | Some(Value::Empty) => {}
-        _ => xml_out.end_elem(tag)?,
+    if let Some(shapes) = cell.shapes {
+        for shape in shapes {
+            write_xmltag(shape, xml_out)?;
+        }
+    }
+
+    if !is_empty {
+        xml_out.end_elem(tag)?;
     }
 
     Ok(())
@@ -1775,7 +2023,7 @@ fn write_font_decl<W: Write + Seek>(
         xml_out.empty("style:font-face")?;
         xml_out.attr_esc("style:name", font.name().as_str())?;
         for (a, v) in font.attrmap().iter() {
-            xml_out.attr_esc(a.as_ref(), v.as_str())?;
+            xml_out.attr_esc(a, v.as_str())?;
         }
     }
     Ok(())
@@ -1847,11 +2095,11 @@ fn write_tablestyle<W: Write + Seek>(
     }
     xml_out.attr("style:family", "table")?;
     for (a, v) in style.attrmap().iter() {
-        match a.as_ref() {
+        match a {
             "style:name" => {}
             "style:family" => {}
             _ => {
-                xml_out.attr_esc(a.as_ref(), v.as_str())?;
+                xml_out.attr_esc(a, v.as_str())?;
             }
         }
     }
@@ -1859,7 +2107,7 @@ fn write_tablestyle<W: Write + Seek>(
     if !style.tablestyle().is_empty() {
         xml_out.empty("style:table-properties")?;
         for (a, v) in style.tablestyle().iter() {
-            xml_out.attr_esc(a.as_ref(), v.as_str())?;
+            xml_out.attr_esc(a, v.as_str())?;
         }
     }
     if style.styleuse() == StyleUse::Default {
@@ -1883,11 +2131,11 @@ fn write_rowstyle<W: Write + Seek>(
     }
     xml_out.attr("style:family", "table-row")?;
     for (a, v) in style.attrmap().iter() {
-        match a.as_ref() {
+        match a {
             "style:name" => {}
             "style:family" => {}
             _ => {
-                xml_out.attr_esc(a.as_ref(), v.as_str())?;
+                xml_out.attr_esc(a, v.as_str())?;
             }
         }
     }
@@ -1895,7 +2143,7 @@ fn write_rowstyle<W: Write + Seek>(
     if !style.rowstyle().is_empty() {
         xml_out.empty("style:table-row-properties")?;
         for (a, v) in style.rowstyle().iter() {
-            xml_out.attr_esc(a.as_ref(), v.as_str())?;
+            xml_out.attr_esc(a, v.as_str())?;
         }
     }
     if style.styleuse() == StyleUse::Default {
@@ -1919,11 +2167,11 @@ fn write_colstyle<W: Write + Seek>(
     }
     xml_out.attr("style:family", "table-column")?;
     for (a, v) in style.attrmap().iter() {
-        match a.as_ref() {
+        match a {
             "style:name" => {}
             "style:family" => {}
             _ => {
-                xml_out.attr_esc(a.as_ref(), v.as_str())?;
+                xml_out.attr_esc(a, v.as_str())?;
             }
         }
     }
@@ -1931,7 +2179,7 @@ fn write_colstyle<W: Write + Seek>(
     if !style.colstyle().is_empty() {
         xml_out.empty("style:table-column-properties")?;
         for (a, v) in style.colstyle().iter() {
-            xml_out.attr_esc(a.as_ref(), v.as_str())?;
+            xml_out.attr_esc(a, v.as_str())?;
         }
     }
     if style.styleuse() == StyleUse::Default {
@@ -1955,11 +2203,11 @@ fn write_cellstyle<W: Write + Seek>(
     }
     xml_out.attr("style:family", "table-cell")?;
     for (a, v) in style.attrmap().iter() {
-        match a.as_ref() {
+        match a {
             "style:name" => {}
             "style:family" => {}
             _ => {
-                xml_out.attr_esc(a.as_ref(), v.as_str())?;
+                xml_out.attr_esc(a, v.as_str())?;
             }
         }
     }
@@ -1967,19 +2215,19 @@ fn write_cellstyle<W: Write + Seek>(
     if !style.cellstyle().is_empty() {
         xml_out.empty("style:table-cell-properties")?;
         for (a, v) in style.cellstyle().iter() {
-            xml_out.attr_esc(a.as_ref(), v.as_str())?;
+            xml_out.attr_esc(a, v.as_str())?;
         }
     }
     if !&style.paragraphstyle().is_empty() {
         xml_out.empty("style:paragraph-properties")?;
         for (a, v) in style.paragraphstyle().iter() {
-            xml_out.attr_esc(a.as_ref(), v.as_str())?;
+            xml_out.attr_esc(a, v.as_str())?;
         }
     }
     if !style.textstyle().is_empty() {
         xml_out.empty("style:text-properties")?;
         for (a, v) in style.textstyle().iter() {
-            xml_out.attr_esc(a.as_ref(), v.as_str())?;
+            xml_out.attr_esc(a, v.as_str())?;
         }
     }
     if let Some(stylemaps) = style.stylemaps() {
@@ -2011,11 +2259,11 @@ fn write_paragraphstyle<W: Write + Seek>(
     }
     xml_out.attr("style:family", "paragraph")?;
     for (a, v) in style.attrmap().iter() {
-        match a.as_ref() {
+        match a {
             "style:name" => {}
             "style:family" => {}
             _ => {
-                xml_out.attr_esc(a.as_ref(), v.as_str())?;
+                xml_out.attr_esc(a, v.as_str())?;
             }
         }
     }
@@ -2024,19 +2272,19 @@ fn write_paragraphstyle<W: Write + Seek>(
         if style.tabstops().is_none() {
             xml_out.empty("style:paragraph-properties")?;
             for (a, v) in style.paragraphstyle().iter() {
-                xml_out.attr_esc(a.as_ref(), v.as_str())?;
+                xml_out.attr_esc(a, v.as_str())?;
             }
         } else {
             xml_out.elem("style:paragraph-properties")?;
             for (a, v) in style.paragraphstyle().iter() {
-                xml_out.attr_esc(a.as_ref(), v.as_str())?;
+                xml_out.attr_esc(a, v.as_str())?;
             }
             xml_out.elem("style:tab-stops")?;
             if let Some(tabstops) = style.tabstops() {
                 for ts in tabstops {
                     xml_out.empty("style:tab-stop")?;
                     for (a, v) in ts.attrmap().iter() {
-                        xml_out.attr_esc(a.as_ref(), v.as_str())?;
+                        xml_out.attr_esc(a, v.as_str())?;
                     }
                 }
             }
@@ -2047,7 +2295,7 @@ fn write_paragraphstyle<W: Write + Seek>(
     if !style.textstyle().is_empty() {
         xml_out.empty("style:text-properties")?;
         for (a, v) in style.textstyle().iter() {
-            xml_out.attr_esc(a.as_ref(), v.as_str())?;
+            xml_out.attr_esc(a, v.as_str())?;
         }
     }
     if style.styleuse() == StyleUse::Default {
@@ -2071,11 +2319,11 @@ fn write_textstyle<W: Write + Seek>(
     }
     xml_out.attr("style:family", "text")?;
     for (a, v) in style.attrmap().iter() {
-        match a.as_ref() {
+        match a {
             "style:name" => {}
             "style:family" => {}
             _ => {
-                xml_out.attr_esc(a.as_ref(), v.as_str())?;
+                xml_out.attr_esc(a, v.as_str())?;
             }
         }
     }
@@ -2083,7 +2331,7 @@ fn write_textstyle<W: Write + Seek>(
     if !style.textstyle().is_empty() {
         xml_out.empty("style:text-properties")?;
         for (a, v) in style.textstyle().iter() {
-            xml_out.attr_esc(a.as_ref(), v.as_str())?;
+            xml_out.attr_esc(a, v.as_str())?;
         }
     }
     if style.styleuse() == StyleUse::Default {
@@ -2107,11 +2355,11 @@ fn write_graphicstyle<W: Write + Seek>(
     }
     xml_out.attr("style:family", "graphic")?;
     for (a, v) in style.attrmap().iter() {
-        match a.as_ref() {
+        match a {
             "style:name" => {}
             "style:family" => {}
             _ => {
-                xml_out.attr_esc(a.as_ref(), v.as_str())?;
+                xml_out.attr_esc(a, v.as_str())?;
             }
         }
     }
@@ -2119,7 +2367,19 @@ fn write_graphicstyle<W: Write + Seek>(
     if !style.graphicstyle().is_empty() {
         xml_out.empty("style:graphic-properties")?;
         for (a, v) in style.graphicstyle().iter() {
-            xml_out.attr_esc(a.as_ref(), v.as_str())?;
+            xml_out.attr_esc(a, v.as_str())?;
+        }
+    }
+    if !style.paragraphstyle().is_empty() {
+        xml_out.empty("style:paragraph-properties")?;
+        for (a, v) in style.paragraphstyle().iter() {
+            xml_out.attr_esc(a, v.as_str())?;
+        }
+    }
+    if !style.textstyle().is_empty() {
+        xml_out.empty("style:text-properties")?;
+        for (a, v) in style.textstyle().iter() {
+            xml_out.attr_esc(a, v.as_str())?;
         }
     }
 
@@ -2157,13 +2417,13 @@ fn write_valuestyles<W: Write + Seek, T: ValueFormatTrait>(
         xml_out.elem(tag)?;
         xml_out.attr_esc("style:name", value_format.name().as_str())?;
         for (a, v) in value_format.attrmap().iter() {
-            xml_out.attr_esc(a.as_ref(), v.as_str())?;
+            xml_out.attr_esc(a, v.as_str())?;
         }
 
         if !value_format.textstyle().is_empty() {
             xml_out.empty("style:text-properties")?;
             for (a, v) in value_format.textstyle().iter() {
-                xml_out.attr_esc(a.as_ref(), v.as_str())?;
+                xml_out.attr_esc(a, v.as_str())?;
             }
         }
 
@@ -2196,7 +2456,7 @@ fn write_valuestyles<W: Write + Seek, T: ValueFormatTrait>(
             {
                 xml_out.elem(part_tag)?;
                 for (a, v) in part.attrmap().iter() {
-                    xml_out.attr_esc(a.as_ref(), v.as_str())?;
+                    xml_out.attr_esc(a, v.as_str())?;
                 }
                 if let Some(content) = part.content() {
                     xml_out.text_esc(content)?;
@@ -2206,7 +2466,7 @@ fn write_valuestyles<W: Write + Seek, T: ValueFormatTrait>(
                 if let Some(embedded_text) = part.content() {
                     xml_out.elem(part_tag)?;
                     for (a, v) in part.attrmap().iter() {
-                        xml_out.attr_esc(a.as_ref(), v.as_str())?;
+                        xml_out.attr_esc(a, v.as_str())?;
                     }
 
                     // embedded text
@@ -2219,13 +2479,13 @@ fn write_valuestyles<W: Write + Seek, T: ValueFormatTrait>(
                 } else {
                     xml_out.empty(part_tag)?;
                     for (a, v) in part.attrmap().iter() {
-                        xml_out.attr_esc(a.as_ref(), v.as_str())?;
+                        xml_out.attr_esc(a, v.as_str())?;
                     }
                 }
             } else {
                 xml_out.empty(part_tag)?;
                 for (a, v) in part.attrmap().iter() {
-                    xml_out.attr_esc(a.as_ref(), v.as_str())?;
+                    xml_out.attr_esc(a, v.as_str())?;
                 }
             }
         }
@@ -2259,7 +2519,7 @@ fn write_pagestyles<W: Write + Seek>(
         if !style.style().is_empty() {
             xml_out.empty("style:page-layout-properties")?;
             for (k, v) in style.style().iter() {
-                xml_out.attr(k.as_ref(), v.as_str())?;
+                xml_out.attr_esc(k, v.as_str())?;
             }
         }
 
@@ -2267,7 +2527,7 @@ fn write_pagestyles<W: Write + Seek>(
         xml_out.empty("style:header-footer-properties")?;
         if !style.headerstyle().style().is_empty() {
             for (k, v) in style.headerstyle().style().iter() {
-                xml_out.attr(k.as_ref(), v.as_str())?;
+                xml_out.attr_esc(k, v.as_str())?;
             }
         }
         xml_out.end_elem("style:header-style")?;
@@ -2276,7 +2536,7 @@ fn write_pagestyles<W: Write + Seek>(
         xml_out.empty("style:header-footer-properties")?;
         if !style.footerstyle().style().is_empty() {
             for (k, v) in style.footerstyle().style().iter() {
-                xml_out.attr(k.as_ref(), v.as_str())?;
+                xml_out.attr_esc(k, v.as_str())?;
             }
         }
         xml_out.end_elem("style:footer-style")?;
@@ -2373,3 +2633,26 @@ fn write_regions<W: Write + Seek>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{write_ods_buf, Length, Sheet, WorkBook};
+
+    #[test]
+    fn test_write_shared() {
+        let mut wb = WorkBook::new_empty();
+        let mut sh = Sheet::new("1");
+        sh.set_value(0, 0, "template");
+        sh.set_col_width(0, cm!(3));
+        sh.set_row_height(0, cm!(1));
+        wb.push_sheet(sh);
+        write_ods_buf(&mut wb, Vec::new()).expect("write");
+
+        // Writing a fork doesn't copy the sheets or the styles.
+        let mut fork = wb.clone();
+        write_ods_buf(&mut fork, Vec::new()).expect("write");
+        assert!(wb.sheets[0].ptr_eq(&fork.sheets[0]));
+        assert!(wb.colstyles.ptr_eq(&fork.colstyles));
+        assert!(wb.rowstyles.ptr_eq(&fork.rowstyles));
+    }
+}
@@ -74,6 +74,8 @@ pub(crate) struct XmlWriter<W: Write> {
     buf: String,
     stack: Stack,
     open: Open,
+    // Attributes and elements for which this returns true are not written.
+    skip: Option<fn(&str) -> bool>,
 }
 
 impl<W: Write> fmt::Debug for XmlWriter<W> {
@@ -94,9 +96,21 @@ impl<W: Write> XmlWriter<W> {
             buf: String::new(),
             writer: Box::new(writer),
             open: Open::None,
+            skip: None,
         }
     }
 
+    /// Leaves out all attributes for which the function returns true.
+    /// Elements must be checked with `skips`.
+    pub(crate) fn set_skip(&mut self, skip: fn(&str) -> bool) {
+        self.skip = Some(skip);
+    }
+
+    /// Is an attribute or element with this name left out?
+    pub(crate) fn skips(&self, name: &str) -> bool {
+        matches!(self.skip, Some(skip) if skip(name))
+    }
+
     /// Write the DTD. You have to take care of the encoding
     /// on the underlying Write yourself.
     pub(crate) fn dtd(&mut self, encoding: &str) -> io::Result<()> {
@@ -186,6 +200,9 @@ impl<W: Write> XmlWriter<W> {
     /// Write an attr, make sure name and value contain only allowed chars.
     /// For an escaping version use `attr_esc`
     pub(crate) fn attr<S: AsRef<str>>(&mut self, name: &str, value: S) -> io::Result<()> {
+        if self.skips(name) {
+            return Ok(());
+        }
         if cfg!(feature = "check_xml") && self.open == Open::None {
             panic!(
                 "Attempted to write attr to elem, when no elem was opened, stack {:?}",
@@ -203,6 +220,9 @@ impl<W: Write> XmlWriter<W> {
 
     /// Write an attr, make sure name contains only allowed chars
     pub(crate) fn attr_esc<S: AsRef<str>>(&mut self, name: &str, value: S) -> io::Result<()> {
+        if self.skips(name) {
+            return Ok(());
+        }
         if cfg!(feature = "check_xml") && self.open == Open::None {
             panic!(
                 "Attempted to write attr to elem, when no elem was opened, stack {:?}",
@@ -4,6 +4,8 @@ use std::path::Path;
 
 use zip::result::ZipError;
 use zip::write::FileOptions;
+#[cfg(feature = "rayon")]
+use zip::ZipArchive;
 use zip::{CompressionMethod, ZipWriter};
 
 /// Reduced Interface for ZipWriter.
@@ -66,6 +68,26 @@ impl<W: Write + Seek> ZipOut<W> {
     pub(crate) fn zip(mut self) -> Result<W, ZipError> {
         self.zip.finish()
     }
+
+    /// New in-memory archive with the same compression. It can be filled
+    /// on another thread and appended later.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn part(&self) -> ZipOut<Cursor<Vec<u8>>> {
+        ZipOut {
+            zip: ZipWriter::new(Cursor::new(Vec::new())),
+            compression: self.compression,
+        }
+    }
+
+    /// Copies the already compressed files of the part.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn append(&mut self, part: ZipOut<Cursor<Vec<u8>>>) -> Result<(), ZipError> {
+        let mut part = ZipArchive::new(part.zip()?)?;
+        for idx in 0..part.len() {
+            self.zip.raw_copy_file(part.by_index_raw(idx)?)?;
+        }
+        Ok(())
+    }
 }
 
 impl<W: Write + Seek> Write for ZipWrite<'_, W> {
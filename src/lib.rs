@@ -142,9 +142,9 @@
 //!
 //! When storing a previously read ODS file, all the contained files
 //! are copied to the new file, except settings.xml, styles.xml and content.xml.
-//! For a new ODS file mimetype, manifest, manifest.rdf, meta.xml
-//! are filled with minimal defaults. There is no way to set these
-//! for now.
+//! meta.xml is copied too, but with the current user-defined fields, see
+//! the meta module. For a new ODS file mimetype, manifest, manifest.rdf,
+//! meta.xml are filled with minimal defaults.
 //!
 
 #![doc(html_root_url = "https://docs.rs/spreadsheet-ods/0.4.0")]
@@ -184,27 +184,46 @@ pub use crate::format::{
     ValueFormatBoolean, ValueFormatCurrency, ValueFormatDateTime, ValueFormatNumber,
     ValueFormatPercentage, ValueFormatRef, ValueFormatText, ValueFormatTimeDuration,
 };
-pub use crate::io::read::{read_ods, read_ods_buf};
-pub use crate::io::write::{write_ods, write_ods_buf, write_ods_buf_uncompressed};
-pub use crate::refs::{CellRange, CellRef, ColRange, RowRange};
+pub use crate::io::parse::parse_iso_duration;
+pub use crate::io::read::{
+    read_ods, read_ods_buf, read_ods_buf_lenient, read_ods_content, read_ods_lenient,
+    read_ods_styles,
+};
+pub use crate::io::write::{
+    format_iso_duration, write_ods, write_ods_buf, write_ods_buf_uncompressed,
+};
+pub use crate::refs::{CellRange, CellRangeList, CellRef, ColRange, RowRange};
 pub use crate::style::units::{Angle, Length};
 pub use crate::style::{CellStyle, CellStyleRef};
+pub use crate::tableref::{DanglingRef, DanglingRefs};
 
+use crate::compat::{CompatReport, WriteProfile};
 use crate::config::Config;
+use crate::dde::DdeSource;
 use crate::defaultstyles::{DefaultFormat, DefaultStyle};
 use crate::ds::detach::Detach;
 use crate::ds::detach::Detached;
-use crate::format::ValueFormatTrait;
-use crate::io::filebuf::FileBuf;
-use crate::io::read::default_settings;
+use crate::event::EventListener;
+use crate::form::FormControl;
+use crate::format::{FormatPart, FormatSections, ValueFormatTrait};
+use crate::health::{DroppedElement, FormatWarning};
+use crate::hook::{CellObserver, WriteHook};
+use crate::io::filebuf::{FileBuf, FileBufEntry, Manifest};
+use crate::io::read::{default_settings, read_ods_styles_from};
+use crate::names::NamedRange;
+use crate::pagination::PageBreaks;
+use crate::security::SecurityPolicy;
+use crate::style::units::CellProtect;
 use crate::style::{
     ColStyle, ColStyleRef, FontFaceDecl, GraphicStyle, GraphicStyleRef, MasterPage, MasterPageRef,
     PageStyle, PageStyleRef, ParagraphStyle, ParagraphStyleRef, RowStyle, RowStyleRef, TableStyle,
     TableStyleRef, TextStyle, TextStyleRef,
 };
 use crate::text::TextTag;
+use crate::theme::Theme;
 use crate::validation::{Validation, ValidationRef};
-use crate::xmltree::XmlTag;
+use crate::xmltree::{XmlContent, XmlTag};
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
 use chrono::{Duration, NaiveTime};
 use chrono::{NaiveDate, NaiveDateTime};
 use icu_locid::Locale;
@@ -213,13 +232,17 @@ use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 #[cfg(feature = "use_decimal")]
 use rust_decimal::Decimal;
 use std::borrow::Cow;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::iter::FusedIterator;
+use std::mem;
+use std::mem::size_of;
 use std::ops::RangeBounds;
+use std::path::Path;
 use std::str::from_utf8;
+use std::sync::Arc;
 
 #[macro_use]
 mod attr_macro;
@@ -231,26 +254,66 @@ mod format_macro;
 mod style_macro;
 #[macro_use]
 mod text_macro;
+#[macro_use]
+mod trace_macro;
 
 mod attrmap2;
 mod config;
 mod ds;
 mod io;
 mod locale;
+mod merge;
+mod tableref;
 
+pub mod builder;
+pub mod compat;
 pub mod condition;
+pub mod dde;
 pub mod defaultstyles;
 pub mod error;
+pub mod event;
+pub mod fill;
+pub mod form;
 pub mod format;
 pub mod formula;
+pub mod health;
+pub mod hook;
+pub mod meta;
+pub mod names;
+pub mod pagination;
 pub mod refs;
+pub mod security;
 pub mod style;
+pub mod table;
+#[cfg(feature = "test_util")]
+pub mod test_util;
 pub mod text;
+pub mod theme;
+pub mod transaction;
 pub mod validation;
 pub mod xmltree;
 
+// A &WorkBook is shared between threads, see WorkBook. This fails to
+// compile if that is no longer possible.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<WorkBook>();
+    assert_send_sync::<Sheet>();
+};
+
 /// Book is the main structure for the Spreadsheet.
-#[derive(Clone, Default)]
+///
+/// Cloning a WorkBook is cheap for the sheets and the files copied from
+/// the original ODS. They are shared between the clones and a sheet is
+/// only copied when it is modified. This allows to read a template once
+/// and fork it for each use. The same goes for the styles and value
+/// formats, each kind is copied as a whole on the first modification.
+///
+/// WorkBook is Send and Sync and has no interior mutability, a &WorkBook
+/// can be shared between threads for reading. Writing needs a &mut
+/// WorkBook, to write the same template from several threads each one
+/// uses a clone.
+#[derive(Clone)]
 pub struct WorkBook {
     /// The data.
     sheets: Vec<Detach<Sheet>>,
@@ -259,37 +322,37 @@ pub struct WorkBook {
     version: String,
 
     /// FontDecl hold the style:font-face elements
-    fonts: HashMap<String, FontFaceDecl>,
+    fonts: Detach<HashMap<String, FontFaceDecl>>,
 
     /// Auto-Styles. Maps the prefix to a number.
     autonum: HashMap<String, u32>,
 
     /// Styles hold the style:style elements.
-    tablestyles: HashMap<String, TableStyle>,
-    rowstyles: HashMap<String, RowStyle>,
-    colstyles: HashMap<String, ColStyle>,
-    cellstyles: HashMap<String, CellStyle>,
-    paragraphstyles: HashMap<String, ParagraphStyle>,
-    textstyles: HashMap<String, TextStyle>,
-    graphicstyles: HashMap<String, GraphicStyle>,
+    tablestyles: Detach<HashMap<String, TableStyle>>,
+    rowstyles: Detach<HashMap<String, RowStyle>>,
+    colstyles: Detach<HashMap<String, ColStyle>>,
+    cellstyles: Detach<HashMap<String, CellStyle>>,
+    paragraphstyles: Detach<HashMap<String, ParagraphStyle>>,
+    textstyles: Detach<HashMap<String, TextStyle>>,
+    graphicstyles: Detach<HashMap<String, GraphicStyle>>,
 
     /// Value-styles are actual formatting instructions for various datatypes.
     /// Represents the various number:xxx-style elements.
-    formats_boolean: HashMap<String, ValueFormatBoolean>,
-    formats_number: HashMap<String, ValueFormatNumber>,
-    formats_percentage: HashMap<String, ValueFormatPercentage>,
-    formats_currency: HashMap<String, ValueFormatCurrency>,
-    formats_text: HashMap<String, ValueFormatText>,
-    formats_datetime: HashMap<String, ValueFormatDateTime>,
-    formats_timeduration: HashMap<String, ValueFormatTimeDuration>,
+    formats_boolean: Detach<HashMap<String, ValueFormatBoolean>>,
+    formats_number: Detach<HashMap<String, ValueFormatNumber>>,
+    formats_percentage: Detach<HashMap<String, ValueFormatPercentage>>,
+    formats_currency: Detach<HashMap<String, ValueFormatCurrency>>,
+    formats_text: Detach<HashMap<String, ValueFormatText>>,
+    formats_datetime: Detach<HashMap<String, ValueFormatDateTime>>,
+    formats_timeduration: Detach<HashMap<String, ValueFormatTimeDuration>>,
 
     /// Default-styles per Type.
     /// This is only used when writing the ods file.
-    def_styles: HashMap<ValueType, String>,
+    def_styles: HashMap<ValueType, CellStyleRef>,
 
     /// Page-layout data.
-    pagestyles: HashMap<String, PageStyle>,
-    masterpages: HashMap<String, MasterPage>,
+    pagestyles: Detach<HashMap<String, PageStyle>>,
+    masterpages: Detach<HashMap<String, MasterPage>>,
 
     /// Validations.
     validations: HashMap<String, Validation>,
@@ -302,9 +365,38 @@ pub struct WorkBook {
 
     /// All extra files from the original ODS are copied here.
     filebuf: FileBuf,
+    /// Manifest entries of the original ODS.
+    manifest: Vec<Manifest>,
 
     /// other stuff ...
     extra: Vec<XmlTag>,
+
+    /// Additional namespaces. Prefix and URI.
+    namespaces: Vec<(String, String)>,
+    /// Hooks for writing.
+    write_hooks: Vec<Arc<dyn WriteHook>>,
+    /// Adapts the output for other applications.
+    write_profile: WriteProfile,
+    /// How float values are written.
+    number_policy: NumberPolicy,
+
+    /// meta.xml of the original ODS without the user-defined fields.
+    meta_xml: Option<XmlTag>,
+    /// User-defined fields of meta.xml.
+    user_meta: BTreeMap<String, String>,
+    /// Adds the content hash and the generator to the user-defined fields.
+    meta_stamp: bool,
+
+    /// Elements that were skipped when reading.
+    dropped: Vec<DroppedElement>,
+    /// Value formats that couldn't be used by the last write.
+    format_warnings: Vec<FormatWarning>,
+}
+
+impl Default for WorkBook {
+    fn default() -> Self {
+        WorkBook::new_empty()
+    }
 }
 
 impl fmt::Debug for WorkBook {
@@ -375,6 +467,40 @@ impl fmt::Debug for WorkBook {
     }
 }
 
+/// An embedded file of the document, e.g. a picture.
+#[derive(Debug, Clone, Copy)]
+pub struct MediaRef<'a> {
+    /// Path within the ODS file.
+    pub path: &'a str,
+    /// Media type from the manifest, or guessed from the file extension.
+    pub media_type: &'a str,
+    /// File content.
+    pub data: &'a [u8],
+}
+
+// Pictures, media and embedded objects.
+pub(crate) fn is_media_path(path: &str) -> bool {
+    let dir = path.split_once('/').map_or("", |v| v.0);
+    matches!(dir, "Pictures" | "Media" | "ObjectReplacements") || dir.starts_with("Object ")
+}
+
+// Media type for files that are missing in the manifest.
+fn guess_media_type(path: &str) -> &'static str {
+    let ext = path.rsplit_once('.').map_or("", |v| v.1);
+    match ext.to_ascii_lowercase().as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "bmp" => "image/bmp",
+        "tif" | "tiff" => "image/tiff",
+        "wmf" => "image/x-wmf",
+        "emf" => "image/x-emf",
+        "xml" => "text/xml",
+        _ => "application/octet-stream",
+    }
+}
+
 /// Autogenerate a stylename. Runs a counter with the prefix and
 /// checks for existence.
 fn auto_style_name<T>(
@@ -409,30 +535,40 @@ impl WorkBook {
         WorkBook {
             sheets: Default::default(),
             version: "1.3".to_string(),
-            fonts: Default::default(),
+            fonts: Detach::new(HashMap::new()),
             autonum: Default::default(),
-            tablestyles: Default::default(),
-            rowstyles: Default::default(),
-            colstyles: Default::default(),
-            cellstyles: Default::default(),
-            paragraphstyles: Default::default(),
-            textstyles: Default::default(),
-            graphicstyles: Default::default(),
-            formats_boolean: Default::default(),
-            formats_number: Default::default(),
-            formats_percentage: Default::default(),
-            formats_currency: Default::default(),
-            formats_text: Default::default(),
-            formats_datetime: Default::default(),
-            formats_timeduration: Default::default(),
+            tablestyles: Detach::new(HashMap::new()),
+            rowstyles: Detach::new(HashMap::new()),
+            colstyles: Detach::new(HashMap::new()),
+            cellstyles: Detach::new(HashMap::new()),
+            paragraphstyles: Detach::new(HashMap::new()),
+            textstyles: Detach::new(HashMap::new()),
+            graphicstyles: Detach::new(HashMap::new()),
+            formats_boolean: Detach::new(HashMap::new()),
+            formats_number: Detach::new(HashMap::new()),
+            formats_percentage: Detach::new(HashMap::new()),
+            formats_currency: Detach::new(HashMap::new()),
+            formats_text: Detach::new(HashMap::new()),
+            formats_datetime: Detach::new(HashMap::new()),
+            formats_timeduration: Detach::new(HashMap::new()),
             def_styles: Default::default(),
-            pagestyles: Default::default(),
-            masterpages: Default::default(),
+            pagestyles: Detach::new(HashMap::new()),
+            masterpages: Detach::new(HashMap::new()),
             validations: Default::default(),
             config: default_settings(),
             workbook_config: Default::default(),
             extra: vec![],
             filebuf: Default::default(),
+            manifest: Default::default(),
+            namespaces: Default::default(),
+            write_hooks: Default::default(),
+            write_profile: Default::default(),
+            number_policy: Default::default(),
+            meta_xml: None,
+            user_meta: Default::default(),
+            meta_stamp: false,
+            dropped: Default::default(),
+            format_warnings: Default::default(),
         }
     }
 
@@ -519,6 +655,47 @@ impl WorkBook {
         self.version = version;
     }
 
+    /// Lists everything that relies on extensions of LibreOffice or
+    /// needs ODF 1.3. See the compat module.
+    pub fn compat_report(&self) -> CompatReport {
+        compat::compat_report(self)
+    }
+
+    /// Adds a named range for the whole workbook. Replaces a name that
+    /// already exists. The range should contain the table name.
+    pub fn add_named_range<S: Into<String>>(&mut self, name: S, range: CellRange) {
+        names::add_named_range(&mut self.extra, NamedRange::new(name, range));
+    }
+
+    /// Removes a named range of the workbook. Returns false if there
+    /// was none.
+    pub fn remove_named_range(&mut self, name: &str) -> bool {
+        names::remove_named_range(&mut self.extra, name)
+    }
+
+    /// Named range of the workbook.
+    pub fn named_range(&self, name: &str) -> Option<NamedRange> {
+        names::named_range(&self.extra, name)
+    }
+
+    /// All named ranges of the workbook. The sheets can have their
+    /// own, see Sheet::named_ranges().
+    pub fn named_ranges(&self) -> Vec<NamedRange> {
+        names::named_ranges(&self.extra)
+    }
+
+    /// Finds the named range as seen from a formula on the n-th sheet.
+    /// The names of the sheet come first, then the names of the workbook.
+    ///
+    /// Panics
+    ///
+    /// Panics if n does not exist.
+    pub fn resolve_name(&self, n: usize, name: &str) -> Option<NamedRange> {
+        self.sheet(n)
+            .named_range(name)
+            .or_else(|| self.named_range(name))
+    }
+
     /// Configuration flags.
     pub fn config(&self) -> &WorkBookConfig {
         &self.workbook_config
@@ -606,17 +783,700 @@ impl WorkBook {
         self.sheets.remove(n).take()
     }
 
+    /// Removes a sheet and looks for references to it in the formulas,
+    /// named expressions and ranges, print ranges, validations and
+    /// style-maps of the rest of the workbook. Returns the sheet and
+    /// everything that referenced it.
+    ///
+    /// With DanglingRefs::Error the sheet is not removed if there are any
+    /// references.
+    ///
+    /// Panics
+    ///
+    /// Panics if n does not exist or any sheet is detached.
+    pub fn remove_sheet_checked(
+        &mut self,
+        n: usize,
+        dangling: DanglingRefs,
+    ) -> Result<(Sheet, Vec<DanglingRef>), OdsError> {
+        tableref::remove_sheet(self, n, dangling)
+    }
+
+    /// Renames a sheet. All references to the sheet in formulas, named
+    /// expressions and ranges, print ranges, validations, style-maps and
+    /// the settings are changed to the new name. Names that are not plain
+    /// identifiers are quoted like 'Sheet 1'.
+    ///
+    /// Fails if another sheet has the same name. Sheet names are compared
+    /// without regard to case, as LibreOffice does.
+    ///
+    /// Panics
+    ///
+    /// Panics if n does not exist or any sheet is detached.
+    pub fn rename_sheet<S: Into<String>>(&mut self, n: usize, name: S) -> Result<(), OdsError> {
+        tableref::rename_sheet(self, n, name.into())
+    }
+
+    /// Indices of the sheets that have been changed since reading.
+    /// New sheets are included, removed sheets obviously not.
+    /// See Sheet::is_dirty().
+    ///
+    /// Panics
+    ///
+    /// Panics if a sheet was detached.
+    pub fn dirty_sheets(&self) -> Vec<usize> {
+        (0..self.sheets.len())
+            .filter(|n| self.sheet(*n).is_dirty())
+            .collect()
+    }
+
+    /// Marks all sheets as unchanged.
+    ///
+    /// Panics
+    ///
+    /// Panics if a sheet was detached.
+    pub fn clear_dirty(&mut self) {
+        for n in 0..self.sheets.len() {
+            // Don't copy a shared sheet for nothing.
+            if self.sheet(n).is_dirty() {
+                self.sheet_mut(n).clear_dirty();
+            }
+        }
+    }
+
+    /// Splits a sheet into multiple sheets with at most chunk_size data rows.
+    ///
+    /// The new sheets replace the original sheet and are named
+    /// "name_1", "name_2", ... If such a name is used by another sheet
+    /// "_2", "_3", ... is appended. Everything up to the end of the header rows
+    /// (see Sheet::set_header_rows()) is copied to every new sheet. The column
+    /// data, the sheet style and the print settings are copied too.
+    /// Formulas are not changed.
+    ///
+    /// Returns the number of new sheets.
+    ///
+    /// Panics
+    ///
+    /// Panics if n is out of bounds, if chunk_size is 0 or if a sheet
+    /// is detached.
+    pub fn split_sheet_by_rows(&mut self, n: usize, chunk_size: u32) -> usize {
+        assert!(chunk_size > 0, "chunk_size must not be 0");
+
+        let mut sheet = self.remove_sheet(n);
+        let data_row = sheet.header_rows.as_ref().map_or(0, |v| v.to_row() + 1);
+
+        let data = mem::take(&mut sheet.data);
+        let row_header = mem::take(&mut sheet.row_header);
+
+        let mut template = sheet.clone_no_data();
+        template.sheet_config = sheet.sheet_config.clone();
+        template.data = data
+            .range(..(data_row, 0))
+            .map(|(k, v)| (*k, v.clone()))
+            .collect();
+        template.row_header = row_header
+            .range(..data_row)
+            .map(|(k, v)| (*k, v.clone()))
+            .collect();
+
+        // chunk and the row in the chunk.
+        let chunk_row = |row: u32| {
+            let chunk = ((row - data_row) / chunk_size) as usize;
+            (chunk, data_row + (row - data_row) % chunk_size)
+        };
+        let mut chunks = vec![template.clone()];
+        let grow = |chunks: &mut Vec<Sheet>, idx: usize| {
+            while chunks.len() <= idx {
+                chunks.push(template.clone());
+            }
+        };
+
+        for ((r, c), mut cell) in data.into_iter().filter(|((r, _), _)| *r >= data_row) {
+            let (idx, row) = chunk_row(r);
+            let span = cell.span();
+            if row + span.row_span > data_row + chunk_size {
+                cell.extra_mut().span.row_span = data_row + chunk_size - row;
+            }
+            grow(&mut chunks, idx);
+            chunks[idx].data.insert((row, c), cell);
+        }
+        for (r, header) in row_header.into_iter().filter(|(r, _)| *r >= data_row) {
+            let (idx, row) = chunk_row(r);
+            grow(&mut chunks, idx);
+            chunks[idx].row_header.insert(row, header);
+        }
+
+        // Sheet names are compared without regard to case.
+        let mut used: HashSet<String> = self.sheets.iter().map(|v| v.name.to_lowercase()).collect();
+        let n_chunks = chunks.len();
+        for (idx, mut chunk) in chunks.into_iter().enumerate() {
+            let mut name = format!("{}_{}", sheet.name, idx + 1);
+            if used.contains(&name.to_lowercase()) {
+                name = merge::unique_name(&name, |v| used.contains(&v.to_lowercase()));
+            }
+            used.insert(name.to_lowercase());
+            chunk.set_name(name);
+            self.insert_sheet(n + idx, chunk);
+        }
+        n_chunks
+    }
+
+    /// Appends all sheets of the other workbook.
+    ///
+    /// Sheets with the same name as an existing sheet are renamed to
+    /// "name_2", "name_3", ... and the references to them in the formulas,
+    /// names and validations of the other workbook are changed. All styles,
+    /// fonts, value formats, page-styles, master-pages and validations are
+    /// moved too, and are renamed the same way if the name exists already.
+    /// All references in the appended sheets and styles are changed
+    /// accordingly.
+    ///
+    /// The named ranges and named expressions of the workbook are moved
+    /// as well. A name that exists with another definition is renamed,
+    /// and the formulas of the appended sheets are changed, except where
+    /// a name of the sheet itself hides it. Database ranges, pilot tables,
+    /// DDE links and label ranges are added to the existing ones. The
+    /// pictures, media and embedded objects are copied, files that exist
+    /// already are renamed and the links of the shapes are changed.
+    ///
+    /// Panics
+    ///
+    /// Panics if a sheet of the other workbook is detached.
+    pub fn append(&mut self, other: WorkBook) {
+        merge::append(self, other);
+    }
+
+    /// Imports the styles of another ODS file. Only styles.xml is read,
+    /// the content of the file is ignored.
+    ///
+    /// This imports the named cell-styles and the default styles, value
+    /// formats, fonts, page-styles and master-pages. Existing styles with
+    /// the same name are replaced, so the styles can be maintained in
+    /// a template file and the data is filled in afterwards. Automatic
+    /// styles like page layouts or the text styles of headers are not
+    /// replaced but renamed to "name_2", ... if the name exists already.
+    pub fn import_styles_from<P: AsRef<Path>>(&mut self, path: P) -> Result<(), OdsError> {
+        let other = read_ods_styles_from(path)?;
+        merge::import_styles(self, other);
+        Ok(())
+    }
+
+    /// Computes the automatic page breaks of the n-th sheet when printed.
+    ///
+    /// The page style is found via the table style and its master page.
+    /// If there is none, A4 with 2cm margins is assumed.
+    /// See the pagination module for details.
+    pub fn page_breaks(&self, n: usize) -> Result<PageBreaks, OdsError> {
+        let sheet = self.sheet(n);
+        let page = sheet
+            .style
+            .as_ref()
+            .and_then(|v| self.tablestyle(v.as_str()))
+            .and_then(|v| v.attrmap().attr("style:master-page-name"))
+            .and_then(|v| self.masterpage(v))
+            .and_then(|v| self.pagestyle(v.pagestyle()));
+        pagination::page_breaks(sheet, page)
+    }
+
+    /// Lists the DDE sources of the document and all sheets.
+    /// See the dde module.
+    pub fn dde_sources(&self) -> Vec<DdeSource<'_>> {
+        let mut sources = Vec::new();
+        for tag in self
+            .extra
+            .iter()
+            .filter(|v| v.name() == "table:dde-links" || v.name() == "text:dde-connection-decls")
+        {
+            dde::dde_sources(tag, &mut sources);
+        }
+        for sheet in self.sheets.iter() {
+            sources.extend(sheet.dde_source());
+        }
+        sources
+    }
+
+    /// Lists the event listeners of the document. See the event module.
+    pub fn event_listeners(&self) -> Vec<EventListener<'_>> {
+        let mut listeners = Vec::new();
+        for scripts in self.extra.iter().filter(|v| v.name() == "office:scripts") {
+            for tag in scripts.content() {
+                if let XmlContent::Tag(tag) = tag {
+                    if tag.name() == "office:event-listeners" {
+                        event::event_listeners(tag, &mut listeners);
+                    }
+                }
+            }
+        }
+        listeners
+    }
+
+    /// Removes the event listeners of the document for this event.
+    /// Returns the number of removed listeners.
+    pub fn remove_event_listener(&mut self, event_name: &str) -> usize {
+        let mut n = 0;
+        for scripts in self
+            .extra
+            .iter_mut()
+            .filter(|v| v.name() == "office:scripts")
+        {
+            for tag in scripts.content_mut() {
+                if let XmlContent::Tag(tag) = tag {
+                    if tag.name() == "office:event-listeners" {
+                        n += event::remove_event_listeners(tag, event_name);
+                    }
+                }
+            }
+        }
+        n
+    }
+
+    /// Removes all event listeners of the document.
+    pub fn clear_event_listeners(&mut self) {
+        for scripts in self
+            .extra
+            .iter_mut()
+            .filter(|v| v.name() == "office:scripts")
+        {
+            scripts.content_mut().retain(
+                |v| !matches!(v, XmlContent::Tag(tag) if tag.name() == "office:event-listeners"),
+            );
+        }
+    }
+
+    /// Allows editing the cells of the range when the n-th sheet is
+    /// protected. See Sheet::set_protected().
+    ///
+    /// This sets a cell-style with CellProtect::None. It is derived from
+    /// the cell-style the cell has now and is named "stylename_editable".
+    /// A cell without a cell-style uses "Default_editable". For whole
+    /// columns or rows the default cell-style of the column or row is
+    /// changed instead, and only the existing cells get their own style.
+    /// A range that covers the whole sheet is cut down to the used
+    /// columns.
+    pub fn set_editable_range(&mut self, n: usize, range: &CellRange) {
+        let sheet = self.sheet(n);
+        let cell_base = |row: u32, col: u32| {
+            sheet
+                .cellstyle(row, col)
+                .or_else(|| match sheet.value(row, col) {
+                    Value::Empty => None,
+                    v => self.def_style(v.value_type()),
+                })
+                .or_else(|| sheet.row_cellstyle(row))
+                .or_else(|| sheet.col_cellstyle(col))
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "Default".to_string())
+        };
+        let default_base = |style: Option<&CellStyleRef>| {
+            style
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "Default".to_string())
+        };
+
+        let mut cells = BTreeMap::new();
+        let mut rows = Vec::new();
+        let mut cols = Vec::new();
+        if range.is_whole_cols() {
+            let to_col = if range.is_whole_rows() {
+                sheet.used_grid_size().1.saturating_sub(1)
+            } else {
+                range.to_col()
+            };
+            for col in range.col()..=to_col {
+                cols.push((col, default_base(sheet.col_cellstyle(col))));
+            }
+            // The default of the row goes before the one of the column.
+            for (row, header) in sheet.row_header.iter() {
+                if header.cellstyle().is_some() {
+                    for col in range.col()..=to_col {
+                        cells.insert((*row, col), cell_base(*row, col));
+                    }
+                }
+            }
+            for (row, col) in sheet.data.keys() {
+                if (range.col()..=to_col).contains(col) {
+                    cells.insert((*row, *col), cell_base(*row, *col));
+                }
+            }
+        } else if range.is_whole_rows() {
+            for row in range.row()..=range.to_row() {
+                rows.push((row, default_base(sheet.row_cellstyle(row))));
+            }
+            for (row, col) in sheet.data.keys() {
+                if (range.row()..=range.to_row()).contains(row) {
+                    cells.insert((*row, *col), cell_base(*row, *col));
+                }
+            }
+        } else {
+            for row in range.row()..=range.to_row() {
+                for col in range.col()..=range.to_col() {
+                    cells.insert((row, col), cell_base(row, col));
+                }
+            }
+        }
+
+        let mut editable = HashMap::new();
+        for base in cells
+            .values()
+            .chain(rows.iter().map(|(_, v)| v))
+            .chain(cols.iter().map(|(_, v)| v))
+        {
+            if !editable.contains_key(base) {
+                editable.insert(base.clone(), self.editable_cellstyle(base));
+            }
+        }
+
+        let sheet = self.sheet_mut(n);
+        for ((row, col), base) in cells {
+            sheet.set_cellstyle(row, col, &editable[&base]);
+        }
+        for (row, base) in rows {
+            sheet.set_row_cellstyle(row, &editable[&base]);
+        }
+        for (col, base) in cols {
+            sheet.set_col_cellstyle(col, &editable[&base]);
+        }
+    }
+
+    // Finds or creates a cell-style with CellProtect::None derived from base.
+    fn editable_cellstyle(&mut self, base: &str) -> CellStyleRef {
+        let is_editable = |style: &CellStyle| {
+            style.cellstyle().attr("style:cell-protect") == Some(&CellProtect::None.to_string())
+        };
+        if let Some(style) = self.cellstyles.get(base) {
+            if is_editable(style) {
+                return style.style_ref();
+            }
+        }
+
+        let mut name = format!("{}_editable", base);
+        let mut idx = 1;
+        while let Some(style) = self.cellstyles.get(&name) {
+            if is_editable(style) {
+                return style.style_ref();
+            }
+            idx += 1;
+            name = format!("{}_editable_{}", base, idx);
+        }
+
+        let mut editable = match self.cellstyles.get(base) {
+            Some(style) if base != "Default" => style.clone(),
+            _ => {
+                let mut style = CellStyle::new_empty();
+                if base == "Default" {
+                    style.set_parent_style(&CellStyleRef::from("Default"));
+                }
+                style
+            }
+        };
+        editable.set_name(name);
+        editable.set_cell_protect(CellProtect::None);
+        self.add_cellstyle(editable)
+    }
+
+    /// Removes macros, event listeners, external links and DDE as
+    /// given by the policy. See the security module.
+    pub fn sanitize(&mut self, policy: &SecurityPolicy) {
+        security::sanitize(self, policy);
+    }
+
+    /// Declares an additional XML namespace on the root elements of
+    /// content.xml and styles.xml. A namespace with the same prefix is
+    /// replaced. The namespaces that are always declared can't be changed,
+    /// for these prefixes the call is ignored when writing.
+    ///
+    /// Unknown namespaces of a file that is read are added here too.
+    pub fn add_namespace<S: Into<String>, T: Into<String>>(&mut self, prefix: S, uri: T) {
+        let prefix = prefix.into();
+        let uri = uri.into();
+        if let Some(ns) = self.namespaces.iter_mut().find(|(v, _)| *v == prefix) {
+            ns.1 = uri;
+        } else {
+            self.namespaces.push((prefix, uri));
+        }
+    }
+
+    /// Removes an additional namespace.
+    pub fn remove_namespace(&mut self, prefix: &str) {
+        self.namespaces.retain(|(v, _)| v != prefix);
+    }
+
+    /// Additional namespaces as prefix and URI.
+    pub fn namespaces(&self) -> &[(String, String)] {
+        &self.namespaces
+    }
+
+    /// Adds a hook that adds XML elements while writing. See the hook
+    /// module.
+    pub fn add_write_hook<H: WriteHook + 'static>(&mut self, hook: H) {
+        self.write_hooks.push(Arc::new(hook));
+    }
+
+    /// Removes all write hooks.
+    pub fn clear_write_hooks(&mut self) {
+        self.write_hooks.clear();
+    }
+
+    /// Adapts the output for other applications. The workbook itself is
+    /// not changed. See WriteProfile.
+    pub fn set_write_profile(&mut self, profile: WriteProfile) {
+        self.write_profile = profile;
+    }
+
+    /// Write profile.
+    pub fn write_profile(&self) -> WriteProfile {
+        self.write_profile
+    }
+
+    /// How float, percentage and currency values are written. Can be
+    /// overridden per cell with Sheet::set_number_policy().
+    /// The values in the workbook are not changed.
+    pub fn set_number_policy(&mut self, policy: NumberPolicy) {
+        self.number_policy = policy;
+    }
+
+    /// Number policy.
+    pub fn number_policy(&self) -> NumberPolicy {
+        self.number_policy
+    }
+
+    /// Sets a user-defined field of meta.xml. See the meta module.
+    pub fn set_user_meta<S: Into<String>, T: Into<String>>(&mut self, name: S, value: T) {
+        self.user_meta.insert(name.into(), value.into());
+    }
+
+    /// Returns a user-defined field of meta.xml.
+    pub fn user_meta(&self, name: &str) -> Option<&str> {
+        self.user_meta.get(name).map(|v| v.as_str())
+    }
+
+    /// Removes a user-defined field of meta.xml.
+    pub fn remove_user_meta(&mut self, name: &str) -> Option<String> {
+        self.user_meta.remove(name)
+    }
+
+    /// All user-defined fields of meta.xml.
+    pub fn user_meta_fields(&self) -> &BTreeMap<String, String> {
+        &self.user_meta
+    }
+
+    /// Writes the content hash and the version of spreadsheet-ods as
+    /// the user-defined fields meta::CONTENT_HASH and meta::GENERATOR.
+    pub fn set_meta_stamp(&mut self, stamp: bool) {
+        self.meta_stamp = stamp;
+    }
+
+    /// Writes the content hash and the version of spreadsheet-ods.
+    pub fn meta_stamp(&self) -> bool {
+        self.meta_stamp
+    }
+
+    /// Hash of the sheet names and the values, formulas and style names
+    /// of all cells. Styles, settings and media are not included.
+    pub fn content_hash(&self) -> String {
+        meta::content_hash(self)
+    }
+
+    /// The content hash stored in meta.xml is the hash of the current
+    /// content.
+    pub fn is_stamp_current(&self) -> bool {
+        self.user_meta(meta::CONTENT_HASH) == Some(self.content_hash().as_str())
+    }
+
+    /// Elements that were skipped when the workbook was read, with their
+    /// position and count. They will be missing when the workbook is
+    /// written. Empty for a new workbook. See the health module.
+    pub fn dropped_elements(&self) -> &[DroppedElement] {
+        &self.dropped
+    }
+
+    /// The cell-styles whose value format was missing or for another
+    /// value type when the workbook was written last. The text of these
+    /// cells was created with the default format. See
+    /// RenderContext::warnings().
+    pub fn format_warnings(&self) -> &[FormatWarning] {
+        &self.format_warnings
+    }
+
+    /// Installs the fonts, formats and styles of the theme. See the theme
+    /// module.
+    pub fn install_theme(&mut self, theme: &Theme) {
+        theme::install(self, theme);
+    }
+
+    /// Lists the embedded pictures, media files and objects.
+    ///
+    /// These are the files in the directories Pictures/, Media/,
+    /// ObjectReplacements/ and "Object n/" of the original ODS.
+    /// An embedded object consists of several files, each is listed.
+    pub fn media(&self) -> Vec<MediaRef<'_>> {
+        let mut media = Vec::new();
+        for entry in self.filebuf.iter() {
+            if let FileBufEntry::File(path, buf) = entry {
+                if is_media_path(path) {
+                    let media_type = self
+                        .manifest
+                        .iter()
+                        .find(|v| &v.full_path == path)
+                        .map_or_else(|| guess_media_type(path), |v| v.media_type.as_str());
+                    media.push(MediaRef {
+                        path: path.as_str(),
+                        media_type,
+                        data: buf.as_slice(),
+                    });
+                }
+            }
+        }
+        media
+    }
+
+    /// Removes an embedded file and its manifest entry. A path that ends
+    /// with '/' removes a complete directory, e.g. an embedded object
+    /// "Object 1/".
+    ///
+    /// References to the file in the content are not changed.
+    ///
+    /// Returns true if anything was removed.
+    pub fn remove_media(&mut self, path: &str) -> bool {
+        self.manifest.retain(|v| {
+            if path.ends_with('/') {
+                !v.full_path.starts_with(path)
+            } else {
+                v.full_path != path
+            }
+        });
+        self.filebuf.remove(path)
+    }
+
     /// Adds a default-style for all new values.
     /// This information is only used when writing the data to the ODS file.
     pub fn add_def_style(&mut self, value_type: ValueType, style: &CellStyleRef) {
-        self.def_styles.insert(value_type, style.to_string());
+        self.def_styles.insert(value_type, style.clone());
     }
 
     /// Returns the default style name.
-    pub fn def_style(&self, value_type: ValueType) -> Option<&String> {
+    pub fn def_style(&self, value_type: ValueType) -> Option<&CellStyleRef> {
         self.def_styles.get(&value_type)
     }
 
+    /// Counts the cells that use each cell-style, over all sheets.
+    ///
+    /// Styles that are not used by any cell are not contained in the result.
+    /// The default cell-styles of rows and columns are not counted.
+    ///
+    /// Panics
+    ///
+    /// Panics if a sheet is detached.
+    pub fn cellstyle_refcount(&self) -> HashMap<CellStyleRef, usize> {
+        let mut count = HashMap::new();
+        for sheet in &self.sheets {
+            for cell in sheet.as_ref().data.values() {
+                if let Some(style) = &cell.style {
+                    *count.entry(style.clone()).or_insert(0) += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Counts the cell-styles that use each value format.
+    ///
+    /// Formats that are not used by any cell-style are not contained in the result.
+    pub fn format_refcount(&self) -> HashMap<ValueFormatRef, usize> {
+        let mut count = HashMap::new();
+        for style in self.cellstyles.values() {
+            if let Some(format) = style.value_format() {
+                *count.entry(ValueFormatRef::from(format)).or_insert(0) += 1;
+            }
+        }
+        count
+    }
+
+    /// Estimates the memory used by the workbook.
+    ///
+    /// This is an estimate. It counts the data held by the sheets, the
+    /// styles and the extra files of the ODS, but not the overhead of
+    /// the allocator and the collections. A detached sheet counts as 0.
+    pub fn memory_footprint(&self) -> MemoryFootprint {
+        fn map_size<T>(map: &HashMap<String, T>, f: impl Fn(&T) -> usize) -> usize {
+            map.capacity() * (size_of::<String>() + size_of::<T>())
+                + map.iter().map(|(k, v)| k.capacity() + f(v)).sum::<usize>()
+        }
+        fn format_size<T: ValueFormatTrait>(map: &HashMap<String, T>) -> usize {
+            map_size(map, |v| {
+                v.attrmap().heap_size()
+                    + v.textstyle().heap_size()
+                    + v.parts().capacity() * size_of::<FormatPart>()
+                    + v.parts()
+                        .iter()
+                        .map(|p| {
+                            p.attrmap().heap_size() + p.content().map(|c| c.capacity()).unwrap_or(0)
+                        })
+                        .sum::<usize>()
+            })
+        }
+
+        let mut footprint = MemoryFootprint::default();
+
+        for sheet in &self.sheets {
+            if sheet.is_detached() {
+                footprint.sheets.push(0);
+            } else {
+                let (size, strings) = sheet.memory_footprint();
+                footprint.sheets.push(size);
+                footprint.strings += strings;
+            }
+        }
+
+        footprint.styles = map_size(&self.fonts, |v| v.attrmap().heap_size())
+            + map_size(&self.tablestyles, |v| {
+                v.attrmap().heap_size() + v.tablestyle().heap_size()
+            })
+            + map_size(&self.rowstyles, |v| {
+                v.attrmap().heap_size() + v.rowstyle().heap_size()
+            })
+            + map_size(&self.colstyles, |v| {
+                v.attrmap().heap_size() + v.colstyle().heap_size()
+            })
+            + map_size(&self.cellstyles, |v| {
+                v.attrmap().heap_size()
+                    + v.cellstyle().heap_size()
+                    + v.paragraphstyle().heap_size()
+                    + v.textstyle().heap_size()
+            })
+            + map_size(&self.paragraphstyles, |v| {
+                v.attrmap().heap_size() + v.paragraphstyle().heap_size() + v.textstyle().heap_size()
+            })
+            + map_size(&self.textstyles, |v| {
+                v.attrmap().heap_size() + v.textstyle().heap_size()
+            })
+            + map_size(&self.graphicstyles, |v| {
+                v.attrmap().heap_size() + v.graphicstyle().heap_size()
+            })
+            + format_size(&self.formats_boolean)
+            + format_size(&self.formats_number)
+            + format_size(&self.formats_percentage)
+            + format_size(&self.formats_currency)
+            + format_size(&self.formats_text)
+            + format_size(&self.formats_datetime)
+            + format_size(&self.formats_timeduration)
+            + map_size(&self.pagestyles, |v| v.style().heap_size())
+            + map_size(&self.masterpages, |_| 0)
+            + map_size(&self.validations, |_| 0);
+
+        for entry in self.filebuf.iter() {
+            footprint.files += match entry {
+                FileBufEntry::Dir(name) => name.capacity(),
+                FileBufEntry::File(name, buf) => name.capacity() + buf.capacity(),
+            };
+        }
+        for entry in &self.manifest {
+            footprint.files += entry.full_path.capacity() + entry.media_type.capacity();
+        }
+
+        footprint
+    }
+
     /// Adds a font.
     pub fn add_font(&mut self, font: FontFaceDecl) {
         self.fonts.insert(font.name().to_string(), font);
@@ -663,6 +1523,11 @@ impl WorkBook {
         self.tablestyles.get_mut(name)
     }
 
+    /// Iterates all table styles.
+    pub fn iter_tablestyles(&self) -> impl Iterator<Item = &TableStyle> {
+        self.tablestyles.values()
+    }
+
     /// Adds a style.
     /// Unnamed styles will be assigned an automatic name.
     pub fn add_rowstyle(&mut self, mut style: RowStyle) -> RowStyleRef {
@@ -689,6 +1554,11 @@ impl WorkBook {
         self.rowstyles.get_mut(name)
     }
 
+    /// Iterates all row styles.
+    pub fn iter_rowstyles(&self) -> impl Iterator<Item = &RowStyle> {
+        self.rowstyles.values()
+    }
+
     /// Adds a style.
     /// Unnamed styles will be assigned an automatic name.
     pub fn add_colstyle(&mut self, mut style: ColStyle) -> ColStyleRef {
@@ -715,6 +1585,11 @@ impl WorkBook {
         self.colstyles.get_mut(name)
     }
 
+    /// Iterates all column styles.
+    pub fn iter_colstyles(&self) -> impl Iterator<Item = &ColStyle> {
+        self.colstyles.values()
+    }
+
     /// Adds a style.
     /// Unnamed styles will be assigned an automatic name.
     pub fn add_cellstyle(&mut self, mut style: CellStyle) -> CellStyleRef {
@@ -741,6 +1616,11 @@ impl WorkBook {
         self.cellstyles.get_mut(name)
     }
 
+    /// Iterates all cell styles.
+    pub fn iter_cellstyles(&self) -> impl Iterator<Item = &CellStyle> {
+        self.cellstyles.values()
+    }
+
     /// Adds a style.
     /// Unnamed styles will be assigned an automatic name.
     pub fn add_paragraphstyle(&mut self, mut style: ParagraphStyle) -> ParagraphStyleRef {
@@ -771,6 +1651,11 @@ impl WorkBook {
         self.paragraphstyles.get_mut(name)
     }
 
+    /// Iterates all paragraph styles.
+    pub fn iter_paragraphstyles(&self) -> impl Iterator<Item = &ParagraphStyle> {
+        self.paragraphstyles.values()
+    }
+
     /// Adds a style.
     /// Unnamed styles will be assigned an automatic name.
     pub fn add_textstyle(&mut self, mut style: TextStyle) -> TextStyleRef {
@@ -797,6 +1682,11 @@ impl WorkBook {
         self.textstyles.get_mut(name)
     }
 
+    /// Iterates all text styles.
+    pub fn iter_textstyles(&self) -> impl Iterator<Item = &TextStyle> {
+        self.textstyles.values()
+    }
+
     /// Adds a style.
     /// Unnamed styles will be assigned an automatic name.
     pub fn add_graphicstyle(&mut self, mut style: GraphicStyle) -> GraphicStyleRef {
@@ -827,6 +1717,11 @@ impl WorkBook {
         self.graphicstyles.get_mut(name)
     }
 
+    /// Iterates all graphic styles.
+    pub fn iter_graphicstyles(&self) -> impl Iterator<Item = &GraphicStyle> {
+        self.graphicstyles.values()
+    }
+
     /// Adds a value format.
     /// Unnamed formats will be assigned an automatic name.
     pub fn add_boolean_format(&mut self, mut vstyle: ValueFormatBoolean) -> ValueFormatRef {
@@ -856,6 +1751,11 @@ impl WorkBook {
         self.formats_boolean.get_mut(name)
     }
 
+    /// Iterates all boolean formats.
+    pub fn iter_boolean_formats(&self) -> impl Iterator<Item = &ValueFormatBoolean> {
+        self.formats_boolean.values()
+    }
+
     /// Adds a value format.
     /// Unnamed formats will be assigned an automatic name.
     pub fn add_number_format(&mut self, mut vstyle: ValueFormatNumber) -> ValueFormatRef {
@@ -870,19 +1770,42 @@ impl WorkBook {
         sref
     }
 
+    /// Adds a value format with sections. The sections are added as
+    /// number formats, the text section as text format. Returns the
+    /// reference to the main format.
+    pub fn add_number_sections(
+        &mut self,
+        sections: FormatSections<ValueFormatNumber>,
+    ) -> ValueFormatRef {
+        let name = sections.name().to_string();
+        let (formats, text) = sections.link();
+        for v in formats {
+            self.add_number_format(v);
+        }
+        if let Some(text) = text {
+            self.add_text_format(text);
+        }
+        ValueFormatRef::from(name)
+    }
+
     /// Removes the format.
     pub fn remove_number_format(&mut self, name: &str) -> Option<ValueFormatNumber> {
         self.formats_number.remove(name)
     }
 
     /// Returns the format.
-    pub fn number_format(&self, name: &str) -> Option<&ValueFormatBoolean> {
-        self.formats_boolean.get(name)
+    pub fn number_format(&self, name: &str) -> Option<&ValueFormatNumber> {
+        self.formats_number.get(name)
     }
 
     /// Returns the mutable format.
-    pub fn number_format_mut(&mut self, name: &str) -> Option<&mut ValueFormatBoolean> {
-        self.formats_boolean.get_mut(name)
+    pub fn number_format_mut(&mut self, name: &str) -> Option<&mut ValueFormatNumber> {
+        self.formats_number.get_mut(name)
+    }
+
+    /// Iterates all number formats.
+    pub fn iter_number_formats(&self) -> impl Iterator<Item = &ValueFormatNumber> {
+        self.formats_number.values()
     }
 
     /// Adds a value format.
@@ -904,6 +1827,24 @@ impl WorkBook {
         sref
     }
 
+    /// Adds a value format with sections. The sections are added as
+    /// percentage formats, the text section as text format. Returns the
+    /// reference to the main format.
+    pub fn add_percentage_sections(
+        &mut self,
+        sections: FormatSections<ValueFormatPercentage>,
+    ) -> ValueFormatRef {
+        let name = sections.name().to_string();
+        let (formats, text) = sections.link();
+        for v in formats {
+            self.add_percentage_format(v);
+        }
+        if let Some(text) = text {
+            self.add_text_format(text);
+        }
+        ValueFormatRef::from(name)
+    }
+
     /// Removes the format.
     pub fn remove_percentage_format(&mut self, name: &str) -> Option<ValueFormatPercentage> {
         self.formats_percentage.remove(name)
@@ -919,6 +1860,11 @@ impl WorkBook {
         self.formats_percentage.get_mut(name)
     }
 
+    /// Iterates all percentage formats.
+    pub fn iter_percentage_formats(&self) -> impl Iterator<Item = &ValueFormatPercentage> {
+        self.formats_percentage.values()
+    }
+
     /// Adds a value format.
     /// Unnamed formats will be assigned an automatic name.
     pub fn add_currency_format(&mut self, mut vstyle: ValueFormatCurrency) -> ValueFormatRef {
@@ -933,6 +1879,24 @@ impl WorkBook {
         sref
     }
 
+    /// Adds a value format with sections. The sections are added as
+    /// currency formats, the text section as text format. Returns the
+    /// reference to the main format.
+    pub fn add_currency_sections(
+        &mut self,
+        sections: FormatSections<ValueFormatCurrency>,
+    ) -> ValueFormatRef {
+        let name = sections.name().to_string();
+        let (formats, text) = sections.link();
+        for v in formats {
+            self.add_currency_format(v);
+        }
+        if let Some(text) = text {
+            self.add_text_format(text);
+        }
+        ValueFormatRef::from(name)
+    }
+
     /// Removes the format.
     pub fn remove_currency_format(&mut self, name: &str) -> Option<ValueFormatCurrency> {
         self.formats_currency.remove(name)
@@ -948,6 +1912,11 @@ impl WorkBook {
         self.formats_currency.get_mut(name)
     }
 
+    /// Iterates all currency formats.
+    pub fn iter_currency_formats(&self) -> impl Iterator<Item = &ValueFormatCurrency> {
+        self.formats_currency.values()
+    }
+
     /// Adds a value format.
     /// Unnamed formats will be assigned an automatic name.
     pub fn add_text_format(&mut self, mut vstyle: ValueFormatText) -> ValueFormatRef {
@@ -976,6 +1945,11 @@ impl WorkBook {
         self.formats_text.get_mut(name)
     }
 
+    /// Iterates all text formats.
+    pub fn iter_text_formats(&self) -> impl Iterator<Item = &ValueFormatText> {
+        self.formats_text.values()
+    }
+
     /// Adds a value format.
     /// Unnamed formats will be assigned an automatic name.
     pub fn add_datetime_format(&mut self, mut vstyle: ValueFormatDateTime) -> ValueFormatRef {
@@ -1005,6 +1979,11 @@ impl WorkBook {
         self.formats_datetime.get_mut(name)
     }
 
+    /// Iterates all datetime formats.
+    pub fn iter_datetime_formats(&self) -> impl Iterator<Item = &ValueFormatDateTime> {
+        self.formats_datetime.values()
+    }
+
     /// Adds a value format.
     /// Unnamed formats will be assigned an automatic name.
     pub fn add_timeduration_format(
@@ -1042,6 +2021,11 @@ impl WorkBook {
         self.formats_timeduration.get_mut(name)
     }
 
+    /// Iterates all time-duration formats.
+    pub fn iter_timeduration_formats(&self) -> impl Iterator<Item = &ValueFormatTimeDuration> {
+        self.formats_timeduration.values()
+    }
+
     /// Adds a value PageStyle.
     /// Unnamed formats will be assigned an automatic name.
     pub fn add_pagestyle(&mut self, mut pstyle: PageStyle) -> PageStyleRef {
@@ -1068,6 +2052,11 @@ impl WorkBook {
         self.pagestyles.get_mut(name)
     }
 
+    /// Iterates all page styles.
+    pub fn iter_pagestyles(&self) -> impl Iterator<Item = &PageStyle> {
+        self.pagestyles.values()
+    }
+
     /// Adds a value MasterPage.
     /// Unnamed formats will be assigned an automatic name.
     pub fn add_masterpage(&mut self, mut mpage: MasterPage) -> MasterPageRef {
@@ -1094,6 +2083,11 @@ impl WorkBook {
         self.masterpages.get_mut(name)
     }
 
+    /// Iterates all master pages.
+    pub fn iter_masterpages(&self) -> impl Iterator<Item = &MasterPage> {
+        self.masterpages.values()
+    }
+
     /// Adds a Validation.
     /// Nameless validations will be assigned a name.
     pub fn add_validation(&mut self, mut valid: Validation) -> ValidationRef {
@@ -1121,6 +2115,27 @@ impl WorkBook {
     }
 }
 
+/// Estimated memory usage of a workbook in bytes.
+/// See WorkBook::memory_footprint().
+#[derive(Clone, Debug, Default)]
+pub struct MemoryFootprint {
+    /// Per sheet, including the strings. 0 for a detached sheet.
+    pub sheets: Vec<usize>,
+    /// Cell texts and formulas of all sheets.
+    pub strings: usize,
+    /// Styles, value formats, page layouts and validations.
+    pub styles: usize,
+    /// Extra files copied from the original ODS.
+    pub files: usize,
+}
+
+impl MemoryFootprint {
+    /// Sum of everything.
+    pub fn total(&self) -> usize {
+        self.sheets.iter().sum::<usize>() + self.styles + self.files
+    }
+}
+
 /// Subset of the Workbook wide configurations.
 #[derive(Clone, Debug)]
 pub struct WorkBookConfig {
@@ -1132,6 +2147,16 @@ pub struct WorkBookConfig {
     pub show_page_breaks: bool,
     /// Are the sheet-tabs shown or not.
     pub has_sheet_tabs: bool,
+    /// Show the formulas instead of their results.
+    pub show_formulas: bool,
+    /// Show the page break preview instead of the normal view.
+    pub show_page_break_preview: bool,
+    /// Show zero values or leave the cells empty.
+    pub show_zero_values: bool,
+    /// Show the markers of annotations.
+    pub show_notes: bool,
+    /// Show the column and row headers.
+    pub has_column_row_headers: bool,
 }
 
 impl Default for WorkBookConfig {
@@ -1141,6 +2166,50 @@ impl Default for WorkBookConfig {
             show_grid: true,
             show_page_breaks: false,
             has_sheet_tabs: true,
+            show_formulas: false,
+            show_page_break_preview: false,
+            show_zero_values: true,
+            show_notes: true,
+            has_column_row_headers: true,
+        }
+    }
+}
+
+impl WorkBookConfig {
+    /// Opens with the page break preview, which shows the printed pages.
+    /// All other settings are the defaults.
+    ///
+    /// ```
+    /// use spreadsheet_ods::{WorkBook, WorkBookConfig};
+    ///
+    /// let mut wb = WorkBook::new_empty();
+    /// *wb.config_mut() = WorkBookConfig::print_preview();
+    /// ```
+    pub fn print_preview() -> Self {
+        Self {
+            show_page_break_preview: true,
+            show_page_breaks: true,
+            ..Default::default()
+        }
+    }
+
+    /// Shows the formulas instead of their results. All other settings
+    /// are the defaults.
+    pub fn formula_view() -> Self {
+        Self {
+            show_formulas: true,
+            ..Default::default()
+        }
+    }
+
+    /// Only the cells. No grid, headers or sheet tabs. All other
+    /// settings are the defaults.
+    pub fn clean_view() -> Self {
+        Self {
+            show_grid: false,
+            has_sheet_tabs: false,
+            has_column_row_headers: false,
+            ..Default::default()
         }
     }
 }
@@ -1173,8 +2242,8 @@ impl Display for Visibility {
 /// Row data
 #[derive(Debug, Clone, Default)]
 struct RowHeader {
-    style: Option<String>,
-    cellstyle: Option<String>,
+    style: Option<RowStyleRef>,
+    cellstyle: Option<CellStyleRef>,
     visible: Visibility,
     repeat: u32,
     height: Length,
@@ -1192,26 +2261,26 @@ impl RowHeader {
     }
 
     pub(crate) fn set_style(&mut self, style: &RowStyleRef) {
-        self.style = Some(style.to_string());
+        self.style = Some(style.clone());
     }
 
     pub(crate) fn clear_style(&mut self) {
         self.style = None;
     }
 
-    pub(crate) fn style(&self) -> Option<&String> {
+    pub(crate) fn style(&self) -> Option<&RowStyleRef> {
         self.style.as_ref()
     }
 
     pub(crate) fn set_cellstyle(&mut self, style: &CellStyleRef) {
-        self.cellstyle = Some(style.to_string());
+        self.cellstyle = Some(style.clone());
     }
 
     pub(crate) fn clear_cellstyle(&mut self) {
         self.cellstyle = None;
     }
 
-    pub(crate) fn cellstyle(&self) -> Option<&String> {
+    pub(crate) fn cellstyle(&self) -> Option<&CellStyleRef> {
         self.cellstyle.as_ref()
     }
 
@@ -1239,13 +2308,21 @@ impl RowHeader {
     pub(crate) fn height(&self) -> Length {
         self.height
     }
+
+    /// Everything but the repeat is the same.
+    pub(crate) fn same_format(&self, other: &RowHeader) -> bool {
+        self.style == other.style
+            && self.cellstyle == other.cellstyle
+            && self.visible == other.visible
+            && self.height == other.height
+    }
 }
 
 /// Column data
 #[derive(Debug, Clone, Default)]
 struct ColHeader {
-    style: Option<String>,
-    cellstyle: Option<String>,
+    style: Option<ColStyleRef>,
+    cellstyle: Option<CellStyleRef>,
     visible: Visibility,
     width: Length,
 }
@@ -1261,26 +2338,26 @@ impl ColHeader {
     }
 
     pub(crate) fn set_style(&mut self, style: &ColStyleRef) {
-        self.style = Some(style.to_string());
+        self.style = Some(style.clone());
     }
 
     pub(crate) fn clear_style(&mut self) {
         self.style = None;
     }
 
-    pub(crate) fn style(&self) -> Option<&String> {
+    pub(crate) fn style(&self) -> Option<&ColStyleRef> {
         self.style.as_ref()
     }
 
     pub(crate) fn set_cellstyle(&mut self, style: &CellStyleRef) {
-        self.cellstyle = Some(style.to_string());
+        self.cellstyle = Some(style.clone());
     }
 
     pub(crate) fn clear_cellstyle(&mut self) {
         self.cellstyle = None;
     }
 
-    pub(crate) fn cellstyle(&self) -> Option<&String> {
+    pub(crate) fn cellstyle(&self) -> Option<&CellStyleRef> {
         self.cellstyle.as_ref()
     }
 
@@ -1309,7 +2386,8 @@ impl ColHeader {
 #[derive(Clone, Default)]
 pub struct Sheet {
     name: String,
-    style: Option<String>,
+    style: Option<TableStyleRef>,
+    default_cellstyle: Option<CellStyleRef>,
 
     data: BTreeMap<(u32, u32), CellData>,
 
@@ -1319,13 +2397,26 @@ pub struct Sheet {
     display: bool,
     print: bool,
 
+    protected: bool,
+    protection_key: Option<String>,
+    protection_key_digest: Option<String>,
+
     header_rows: Option<RowRange>,
     header_cols: Option<ColRange>,
     print_ranges: Option<Vec<CellRange>>,
+    // Cells that can't be changed by the api. Not written.
+    guarded: Vec<CellRange>,
+    // Notified of changed values and formulas.
+    observers: Vec<Arc<dyn CellObserver>>,
 
     sheet_config: SheetConfig,
 
     extra: Vec<XmlTag>,
+
+    // Changes since reading the sheet.
+    dirty: bool,
+    dirty_all: bool,
+    dirty_cells: BTreeSet<(u32, u32)>,
 }
 
 impl<'a> IntoIterator for &'a Sheet {
@@ -1473,14 +2564,23 @@ impl Sheet {
             data: BTreeMap::new(),
             col_header: Default::default(),
             style: None,
+            default_cellstyle: None,
             header_rows: None,
             header_cols: None,
             print_ranges: None,
+            guarded: Default::default(),
+            observers: Default::default(),
             sheet_config: Default::default(),
             extra: vec![],
             row_header: Default::default(),
             display: true,
             print: true,
+            protected: false,
+            protection_key: None,
+            protection_key_digest: None,
+            dirty: true,
+            dirty_all: true,
+            dirty_cells: Default::default(),
         }
     }
 
@@ -1489,16 +2589,25 @@ impl Sheet {
         Self {
             name: self.name.clone(),
             style: self.style.clone(),
+            default_cellstyle: self.default_cellstyle.clone(),
             data: Default::default(),
             col_header: self.col_header.clone(),
             row_header: self.row_header.clone(),
             display: self.display,
             print: self.print,
+            protected: self.protected,
+            protection_key: self.protection_key.clone(),
+            protection_key_digest: self.protection_key_digest.clone(),
             header_rows: self.header_rows.clone(),
             header_cols: self.header_cols.clone(),
             print_ranges: self.print_ranges.clone(),
+            guarded: self.guarded.clone(),
+            observers: self.observers.clone(),
             sheet_config: Default::default(),
             extra: self.extra.clone(),
+            dirty: true,
+            dirty_all: true,
+            dirty_cells: Default::default(),
         }
     }
 
@@ -1517,8 +2626,20 @@ impl Sheet {
         }
     }
 
+    /// Iterate all cells in parallel. Needs the feature "rayon".
+    ///
+    /// The cells are not visited in order.
+    #[cfg(feature = "rayon")]
+    pub fn par_iter_cells(
+        &self,
+    ) -> impl rayon::iter::ParallelIterator<Item = ((u32, u32), CellContentRef<'_>)> {
+        use rayon::prelude::*;
+        self.data.par_iter().map(|(k, v)| (*k, v.into()))
+    }
+
     /// Sheet name.
     pub fn set_name<V: Into<String>>(&mut self, name: V) {
+        self.mark_dirty();
         self.name = name.into();
     }
 
@@ -1534,21 +2655,44 @@ impl Sheet {
 
     /// Configuration for the sheet.
     pub fn config_mut(&mut self) -> &mut SheetConfig {
+        self.mark_dirty();
         &mut self.sheet_config
     }
 
     /// Sets the table-style
     pub fn set_style(&mut self, style: &TableStyleRef) {
-        self.style = Some(style.to_string());
+        self.mark_dirty();
+        self.style = Some(style.clone());
     }
 
     /// Returns the table-style.
-    pub fn style(&self) -> Option<&String> {
+    pub fn style(&self) -> Option<&TableStyleRef> {
         self.style.as_ref()
     }
 
+    /// Default cell style for the whole sheet. It is used for all columns
+    /// without a column cell style, including the empty area after the
+    /// last used column. A background color of the cell style fills the
+    /// sheet, on screen and when printed.
+    pub fn set_default_cellstyle(&mut self, style: &CellStyleRef) {
+        self.mark_dirty();
+        self.default_cellstyle = Some(style.clone());
+    }
+
+    /// Removes the default cell style.
+    pub fn clear_default_cellstyle(&mut self) {
+        self.mark_dirty();
+        self.default_cellstyle = None;
+    }
+
+    /// Returns the default cell style for the whole sheet.
+    pub fn default_cellstyle(&self) -> Option<&CellStyleRef> {
+        self.default_cellstyle.as_ref()
+    }
+
     /// Column style.
     pub fn set_colstyle(&mut self, col: u32, style: &ColStyleRef) {
+        self.mark_dirty();
         self.col_header
             .entry(col)
             .or_insert_with(ColHeader::new)
@@ -1557,6 +2701,7 @@ impl Sheet {
 
     /// Remove the style.
     pub fn clear_colstyle(&mut self, col: u32) {
+        self.mark_dirty();
         self.col_header
             .entry(col)
             .or_insert_with(ColHeader::new)
@@ -1564,7 +2709,7 @@ impl Sheet {
     }
 
     /// Returns the column style.
-    pub fn colstyle(&self, col: u32) -> Option<&String> {
+    pub fn colstyle(&self, col: u32) -> Option<&ColStyleRef> {
         if let Some(col_header) = self.col_header.get(&col) {
             col_header.style()
         } else {
@@ -1574,6 +2719,7 @@ impl Sheet {
 
     /// Default cell style for this column.
     pub fn set_col_cellstyle(&mut self, col: u32, style: &CellStyleRef) {
+        self.mark_dirty();
         self.col_header
             .entry(col)
             .or_insert_with(ColHeader::new)
@@ -1582,6 +2728,7 @@ impl Sheet {
 
     /// Remove the style.
     pub fn clear_col_cellstyle(&mut self, col: u32) {
+        self.mark_dirty();
         self.col_header
             .entry(col)
             .or_insert_with(ColHeader::new)
@@ -1589,7 +2736,7 @@ impl Sheet {
     }
 
     /// Returns the default cell style for this column.
-    pub fn col_cellstyle(&self, col: u32) -> Option<&String> {
+    pub fn col_cellstyle(&self, col: u32) -> Option<&CellStyleRef> {
         if let Some(col_header) = self.col_header.get(&col) {
             col_header.cellstyle()
         } else {
@@ -1599,6 +2746,7 @@ impl Sheet {
 
     /// Visibility of the column
     pub fn set_col_visible(&mut self, col: u32, visible: Visibility) {
+        self.mark_dirty();
         self.col_header
             .entry(col)
             .or_insert_with(ColHeader::new)
@@ -1616,6 +2764,7 @@ impl Sheet {
 
     /// Sets the column width for this column.
     pub fn set_col_width(&mut self, col: u32, width: Length) {
+        self.mark_dirty();
         self.col_header
             .entry(col)
             .or_insert_with(ColHeader::new)
@@ -1633,6 +2782,7 @@ impl Sheet {
 
     /// Row style.
     pub fn set_rowstyle(&mut self, row: u32, style: &RowStyleRef) {
+        self.mark_dirty();
         self.row_header
             .entry(row)
             .or_insert_with(RowHeader::new)
@@ -1641,6 +2791,7 @@ impl Sheet {
 
     /// Remove the style.
     pub fn clear_rowstyle(&mut self, row: u32) {
+        self.mark_dirty();
         self.row_header
             .entry(row)
             .or_insert_with(RowHeader::new)
@@ -1648,7 +2799,7 @@ impl Sheet {
     }
 
     /// Returns the row style.
-    pub fn rowstyle(&self, row: u32) -> Option<&String> {
+    pub fn rowstyle(&self, row: u32) -> Option<&RowStyleRef> {
         if let Some(row_header) = self.row_header.get(&row) {
             row_header.style()
         } else {
@@ -1658,6 +2809,7 @@ impl Sheet {
 
     /// Default cell style for this row.
     pub fn set_row_cellstyle(&mut self, row: u32, style: &CellStyleRef) {
+        self.mark_dirty();
         self.row_header
             .entry(row)
             .or_insert_with(RowHeader::new)
@@ -1666,6 +2818,7 @@ impl Sheet {
 
     /// Remove the style.
     pub fn clear_row_cellstyle(&mut self, row: u32) {
+        self.mark_dirty();
         self.row_header
             .entry(row)
             .or_insert_with(RowHeader::new)
@@ -1673,7 +2826,7 @@ impl Sheet {
     }
 
     /// Returns the default cell style for this row.
-    pub fn row_cellstyle(&self, row: u32) -> Option<&String> {
+    pub fn row_cellstyle(&self, row: u32) -> Option<&CellStyleRef> {
         if let Some(row_header) = self.row_header.get(&row) {
             row_header.cellstyle()
         } else {
@@ -1683,6 +2836,7 @@ impl Sheet {
 
     /// Visibility of the row
     pub fn set_row_visible(&mut self, row: u32, visible: Visibility) {
+        self.mark_dirty();
         self.row_header
             .entry(row)
             .or_insert_with(RowHeader::new)
@@ -1707,6 +2861,7 @@ impl Sheet {
     ///
     /// Panics if the repeat is 0.
     pub fn set_row_repeat(&mut self, row: u32, repeat: u32) {
+        self.mark_dirty();
         self.row_header
             .entry(row)
             .or_insert_with(RowHeader::new)
@@ -1724,6 +2879,7 @@ impl Sheet {
 
     /// Sets the row-height.
     pub fn set_row_height(&mut self, row: u32, height: Length) {
+        self.mark_dirty();
         self.row_header
             .entry(row)
             .or_insert_with(RowHeader::new)
@@ -1739,6 +2895,151 @@ impl Sheet {
         }
     }
 
+    /// Cell styles referenced by the sheet. These are the styles of the
+    /// cells, the default cell styles of rows and columns and the default
+    /// cell style of the sheet.
+    ///
+    /// The sheet only knows the names, WorkBook::cellstyle() tells whether
+    /// a style is automatic.
+    pub fn used_cellstyles(&self) -> BTreeSet<CellStyleRef> {
+        let mut styles = BTreeSet::new();
+        styles.extend(self.default_cellstyle.iter().cloned());
+        styles.extend(self.row_header.values().filter_map(|v| v.cellstyle.clone()));
+        styles.extend(self.col_header.values().filter_map(|v| v.cellstyle.clone()));
+        styles.extend(self.data.values().filter_map(|v| v.style.clone()));
+        styles
+    }
+
+    /// Column styles referenced by the sheet.
+    pub fn used_colstyles(&self) -> BTreeSet<ColStyleRef> {
+        self.col_header
+            .values()
+            .filter_map(|v| v.style.clone())
+            .collect()
+    }
+
+    /// Row styles referenced by the sheet.
+    pub fn used_rowstyles(&self) -> BTreeSet<RowStyleRef> {
+        self.row_header
+            .values()
+            .filter_map(|v| v.style.clone())
+            .collect()
+    }
+
+    /// Re-points the cell styles of the sheet. The function is called for
+    /// every reference and returns the new style or None to keep the old
+    /// one. All references are replaced in one pass, so two styles can be
+    /// swapped. Covers the same references as used_cellstyles().
+    /// Returns the number of replaced references.
+    ///
+    /// ```
+    /// use spreadsheet_ods::{CellStyleRef, Sheet};
+    ///
+    /// let light = CellStyleRef::from("light");
+    /// let dark = CellStyleRef::from("dark");
+    ///
+    /// let mut sheet = Sheet::new("1");
+    /// sheet.set_styled_value(0, 0, 1, &light);
+    /// sheet.set_styled_value(1, 0, 2, &dark);
+    ///
+    /// sheet.remap_cellstyles(|v| match v.as_str() {
+    ///     "light" => Some(dark.clone()),
+    ///     "dark" => Some(light.clone()),
+    ///     _ => None,
+    /// });
+    /// assert_eq!(sheet.cellstyle(0, 0), Some(&dark));
+    /// assert_eq!(sheet.cellstyle(1, 0), Some(&light));
+    /// ```
+    pub fn remap_cellstyles<F>(&mut self, mut f: F) -> u32
+    where
+        F: FnMut(&CellStyleRef) -> Option<CellStyleRef>,
+    {
+        let mut count = 0;
+        let mut remap = |style: &mut Option<CellStyleRef>| {
+            if let Some(new_style) = style.as_ref().and_then(&mut f) {
+                *style = Some(new_style);
+                count += 1;
+                true
+            } else {
+                false
+            }
+        };
+
+        let mut changed = remap(&mut self.default_cellstyle);
+        for header in self.row_header.values_mut() {
+            changed |= remap(&mut header.cellstyle);
+        }
+        for header in self.col_header.values_mut() {
+            changed |= remap(&mut header.cellstyle);
+        }
+        let mut dirty_cells = Vec::new();
+        for (pos, cell) in self.data.iter_mut() {
+            if remap(&mut cell.style) {
+                dirty_cells.push(*pos);
+            }
+        }
+
+        if changed {
+            self.mark_dirty();
+        }
+        for (row, col) in dirty_cells {
+            self.mark_cell_dirty(row, col);
+        }
+        count
+    }
+
+    /// Re-points the column styles of the sheet. See remap_cellstyles().
+    pub fn remap_colstyles<F>(&mut self, mut f: F) -> u32
+    where
+        F: FnMut(&ColStyleRef) -> Option<ColStyleRef>,
+    {
+        let mut count = 0;
+        for header in self.col_header.values_mut() {
+            if let Some(new_style) = header.style.as_ref().and_then(&mut f) {
+                header.style = Some(new_style);
+                count += 1;
+            }
+        }
+        if count > 0 {
+            self.mark_dirty();
+        }
+        count
+    }
+
+    /// Re-points the row styles of the sheet. See remap_cellstyles().
+    pub fn remap_rowstyles<F>(&mut self, mut f: F) -> u32
+    where
+        F: FnMut(&RowStyleRef) -> Option<RowStyleRef>,
+    {
+        let mut count = 0;
+        for header in self.row_header.values_mut() {
+            if let Some(new_style) = header.style.as_ref().and_then(&mut f) {
+                header.style = Some(new_style);
+                count += 1;
+            }
+        }
+        if count > 0 {
+            self.mark_dirty();
+        }
+        count
+    }
+
+    /// Replaces one cell style with another everywhere in the sheet.
+    /// Returns the number of replaced references.
+    pub fn replace_cellstyle(&mut self, from: &CellStyleRef, to: &CellStyleRef) -> u32 {
+        self.remap_cellstyles(|v| (v == from).then(|| to.clone()))
+    }
+
+    /// Replaces one column style with another. See replace_cellstyle().
+    pub fn replace_colstyle(&mut self, from: &ColStyleRef, to: &ColStyleRef) -> u32 {
+        self.remap_colstyles(|v| (v == from).then(|| to.clone()))
+    }
+
+    /// Replaces one row style with another. See replace_cellstyle().
+    pub fn replace_rowstyle(&mut self, from: &RowStyleRef, to: &RowStyleRef) -> u32 {
+        self.remap_rowstyles(|v| (v == from).then(|| to.clone()))
+    }
+
     /// Returns the maximum used column +1 in the column header
     pub fn used_cols(&self) -> u32 {
         *self.col_header.keys().max().unwrap_or(&0) + 1
@@ -1760,8 +3061,27 @@ impl Sheet {
         (max.0 + 1, max.1 + 1)
     }
 
+    // Cuts whole columns and rows down to the used grid.
+    pub(crate) fn clip_whole(&self, range: &CellRange) -> CellRange {
+        let mut range = range.clone();
+        if range.is_whole_cols() || range.is_whole_rows() {
+            let (row, col) = self
+                .data
+                .keys()
+                .fold((0, 0), |(row, col), (r, c)| (row.max(*r), col.max(*c)));
+            if range.is_whole_cols() {
+                range.set_to_row(row);
+            }
+            if range.is_whole_rows() {
+                range.set_to_col(col);
+            }
+        }
+        range
+    }
+
     /// Is the sheet displayed?
     pub fn set_display(&mut self, display: bool) {
+        self.mark_dirty();
         self.display = display;
     }
 
@@ -1772,6 +3092,7 @@ impl Sheet {
 
     /// Is the sheet printed?
     pub fn set_print(&mut self, print: bool) {
+        self.mark_dirty();
         self.print = print;
     }
 
@@ -1780,6 +3101,264 @@ impl Sheet {
         self.print
     }
 
+    /// Protects the sheet. Which cells can be edited is defined by the
+    /// cell-style, see CellStyle::set_cell_protect() and
+    /// WorkBook::set_editable_range().
+    pub fn set_protected(&mut self, protected: bool) {
+        self.mark_dirty();
+        self.protected = protected;
+    }
+
+    /// Is the sheet protected?
+    pub fn protected(&self) -> bool {
+        self.protected
+    }
+
+    /// Password for the sheet protection. This is the base64 encoded
+    /// digest of the password and the algorithm used, e.g.
+    /// "http://www.w3.org/2000/09/xmldsig#sha256".
+    pub fn set_protection_key<S: Into<String>, T: Into<String>>(
+        &mut self,
+        key: S,
+        digest_algorithm: T,
+    ) {
+        self.mark_dirty();
+        self.protection_key = Some(key.into());
+        self.protection_key_digest = Some(digest_algorithm.into());
+    }
+
+    /// Removes the password.
+    pub fn clear_protection_key(&mut self) {
+        self.mark_dirty();
+        self.protection_key = None;
+        self.protection_key_digest = None;
+    }
+
+    /// Password digest for the sheet protection.
+    pub fn protection_key(&self) -> Option<&String> {
+        self.protection_key.as_ref()
+    }
+
+    /// Digest algorithm for the protection key.
+    pub fn protection_key_digest(&self) -> Option<&String> {
+        self.protection_key_digest.as_ref()
+    }
+
+    /// Guards the cells of the range against changes of the value or
+    /// formula. This is a check for the code that fills the sheet,
+    /// independent of the protection in the ODS file, and it's not
+    /// written.
+    ///
+    /// The guard is checked by the try_ variants of the edits, they
+    /// return an error for a guarded cell: try_set_value(),
+    /// try_set_styled_value(), try_set_formula(), try_set_formula_value(),
+    /// try_clear_formula(), try_add_cell() and try_remove_cell(). The
+    /// edits of many cells fail if a guarded cell would be moved, removed
+    /// or overwritten: try_insert_rows(), try_delete_rows(),
+    /// try_move_rows(), the same for columns, try_retain_rows() and its
+    /// relatives, try_fill_down(), try_fill_series() and try_copy_range().
+    /// The variants without try_ don't check the guard. The range is not
+    /// adjusted when rows or columns are inserted or deleted.
+    ///
+    /// ```
+    /// use spreadsheet_ods::{CellRange, Sheet};
+    ///
+    /// let mut sheet = Sheet::new("1");
+    /// sheet.set_formula(5, 0, "of:=SUM([.A1:.A5])");
+    /// sheet.guard_range(CellRange::local(5, 0, 5, 9));
+    ///
+    /// assert!(sheet.try_set_value(4, 0, 1).is_ok());
+    /// assert!(sheet.try_set_value(5, 0, 1).is_err());
+    /// ```
+    pub fn guard_range(&mut self, range: CellRange) {
+        self.guarded.push(range);
+    }
+
+    /// Removes the guard for the range. Only removes a range that is
+    /// equal to the given one.
+    pub fn unguard_range(&mut self, range: &CellRange) -> bool {
+        let len = self.guarded.len();
+        self.guarded.retain(|v| v != range);
+        len != self.guarded.len()
+    }
+
+    /// Removes all guards.
+    pub fn clear_guarded_ranges(&mut self) {
+        self.guarded.clear();
+    }
+
+    /// Guarded ranges.
+    pub fn guarded_ranges(&self) -> &[CellRange] {
+        &self.guarded
+    }
+
+    /// Is the cell guarded? See guard_range().
+    pub fn is_guarded(&self, row: u32, col: u32) -> bool {
+        self.guarded.iter().any(|v| v.contains(row, col))
+    }
+
+    fn check_guard(&self, row: u32, col: u32) -> Result<(), OdsError> {
+        if !self.guarded.is_empty() && self.is_guarded(row, col) {
+            Err(OdsError::Ods(format!(
+                "cell {} is guarded",
+                CellRef::remote(self.name.as_str(), row, col)
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    // Same as check_guard() for all cells of the area.
+    fn check_guard_area(
+        &self,
+        row: u32,
+        col: u32,
+        to_row: u32,
+        to_col: u32,
+    ) -> Result<(), OdsError> {
+        match self.guarded.iter().find(|v| {
+            v.row() <= to_row && row <= v.to_row() && v.col() <= to_col && col <= v.to_col()
+        }) {
+            Some(guarded) => self.check_guard(guarded.row().max(row), guarded.col().max(col)),
+            None => Ok(()),
+        }
+    }
+
+    /// Adds an observer for changed values and formulas. See CellObserver.
+    pub fn add_observer<O: CellObserver + 'static>(&mut self, observer: O) {
+        self.observers.push(Arc::new(observer));
+    }
+
+    /// Removes all observers.
+    pub fn clear_observers(&mut self) {
+        self.observers.clear();
+    }
+
+    // A removed cell is reported as Value::Empty.
+    fn notify_value(&self, row: u32, col: u32, old: &Value) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let empty = Value::Empty;
+        let new = self.data.get(&(row, col)).map_or(&empty, |v| &v.value);
+        for observer in &self.observers {
+            observer.value_changed(&self.name, row, col, old, new);
+        }
+    }
+
+    fn notify_formula(&self, row: u32, col: u32, old: Option<&str>) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let new = self
+            .data
+            .get(&(row, col))
+            .and_then(|v| v.formula.as_deref());
+        for observer in &self.observers {
+            observer.formula_changed(&self.name, row, col, old, new);
+        }
+    }
+
+    /// Has the sheet been changed since it was read? A sheet that was
+    /// created with new() or clone_no_data() starts out dirty.
+    ///
+    /// Changes to the workbook itself, e.g. the styles, are not tracked.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Rows or columns have been inserted, deleted or moved, or the sheet
+    /// is new. In this case dirty_cells() doesn't cover all the changes
+    /// and every cell must be treated as changed.
+    pub fn all_cells_dirty(&self) -> bool {
+        self.dirty_all
+    }
+
+    /// Has the cell been changed since the sheet was read? This includes
+    /// cells that were removed.
+    pub fn is_cell_dirty(&self, row: u32, col: u32) -> bool {
+        self.dirty_all || self.dirty_cells.contains(&(row, col))
+    }
+
+    /// All cells that have been changed since the sheet was read. This
+    /// includes cells that were removed. Check all_cells_dirty() too.
+    pub fn dirty_cells(&self) -> &BTreeSet<(u32, u32)> {
+        &self.dirty_cells
+    }
+
+    /// Marks the sheet as unchanged. This is done after reading, can be
+    /// used after the changes have been synced somewhere else.
+    pub fn clear_dirty(&mut self) {
+        self.dirty = false;
+        self.dirty_all = false;
+        self.dirty_cells.clear();
+    }
+
+    pub(crate) fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub(crate) fn mark_cell_dirty(&mut self, row: u32, col: u32) {
+        self.dirty = true;
+        if !self.dirty_all {
+            self.dirty_cells.insert((row, col));
+        }
+    }
+
+    pub(crate) fn mark_all_dirty(&mut self) {
+        self.dirty = true;
+        self.dirty_all = true;
+        self.dirty_cells.clear();
+    }
+
+    // Estimated memory usage in bytes. Returns the total and the part
+    // used by strings.
+    fn memory_footprint(&self) -> (usize, usize) {
+        let mut strings = 0;
+        let mut size = size_of::<Sheet>()
+            + self.name.capacity()
+            + self.row_header.len() * size_of::<(u32, RowHeader)>()
+            + self.col_header.len() * size_of::<(u32, ColHeader)>()
+            + self.dirty_cells.len() * size_of::<(u32, u32)>()
+            + self.extra.iter().map(|v| v.heap_size()).sum::<usize>();
+
+        for cell in self.data.values() {
+            size += size_of::<((u32, u32), CellData)>();
+            if cell.extra.is_some() {
+                size += size_of::<CellExtras>();
+            }
+            if let Some(style) = &cell.style {
+                size += style.as_str().len();
+            }
+            strings += match &cell.value {
+                Value::Text(v) => v.capacity(),
+                Value::TextXml(v) => {
+                    v.capacity() * size_of::<TextTag>()
+                        + v.iter().map(|v| v.heap_size()).sum::<usize>()
+                }
+                _ => 0,
+            };
+            if let Some(formula) = &cell.formula {
+                strings += formula.capacity();
+            }
+            if let Some(name) = cell.validation_name() {
+                size += name.capacity();
+            }
+            if let Some(metadata) = cell.metadata() {
+                strings += metadata
+                    .iter()
+                    .map(|(k, v)| size_of::<(String, String)>() + k.capacity() + v.capacity())
+                    .sum::<usize>();
+            }
+            if let Some(shapes) = cell.shapes() {
+                size += shapes.capacity() * size_of::<XmlTag>()
+                    + shapes.iter().map(|v| v.heap_size()).sum::<usize>();
+            }
+        }
+
+        (size + strings, strings)
+    }
+
     /// Returns true if there is no SCell at the given position.
     pub fn is_empty(&self, row: u32, col: u32) -> bool {
         self.data.get(&(row, col)).is_none()
@@ -1801,43 +3380,90 @@ impl Sheet {
             value: value.value.clone(),
             style: value.style.clone(),
             formula: value.formula.clone(),
-            validation_name: value.validation_name.clone(),
-            span: value.span,
+            validation_name: value.validation_name().cloned(),
+            span: value.span(),
+            metadata: value.metadata().cloned().unwrap_or_default(),
+            shapes: value.shapes().cloned().unwrap_or_default(),
+            content_type: value.content_type(),
+            number_policy: value.number_policy(),
         })
     }
 
     /// Consumes the CellContent and sets the values.
     pub fn add_cell(&mut self, row: u32, col: u32, cell: CellContent) {
-        self.add_cell_data(
-            row,
-            col,
-            CellData {
-                value: cell.value,
-                formula: cell.formula,
-                style: cell.style,
-                validation_name: cell.validation_name,
-                span: cell.span,
-            },
-        );
+        self.mark_cell_dirty(row, col);
+        let mut data = CellData {
+            value: cell.value,
+            formula: cell.formula,
+            style: cell.style,
+            extra: None,
+        };
+        if cell.validation_name.is_some() {
+            data.extra_mut().validation_name = cell.validation_name;
+        }
+        if cell.span != CellSpan::default() {
+            data.extra_mut().span = cell.span;
+        }
+        if !cell.metadata.is_empty() {
+            data.extra_mut().metadata = cell.metadata;
+        }
+        if !cell.shapes.is_empty() {
+            data.extra_mut().shapes = cell.shapes;
+        }
+        if cell.content_type.is_some() {
+            data.extra_mut().content_type = cell.content_type;
+        }
+        if cell.number_policy.is_some() {
+            data.extra_mut().number_policy = cell.number_policy;
+        }
+        let (old_value, old_formula) = match self.data.insert((row, col), data) {
+            Some(old) => (old.value, old.formula),
+            None => (Value::Empty, None),
+        };
+        self.notify_formula(row, col, old_formula.as_deref());
+        self.notify_value(row, col, &old_value);
+    }
+
+    /// Consumes the CellContent and sets the values. Fails if the cell is
+    /// guarded, see guard_range().
+    pub fn try_add_cell(&mut self, row: u32, col: u32, cell: CellContent) -> Result<(), OdsError> {
+        self.check_guard(row, col)?;
+        self.add_cell(row, col, cell);
+        Ok(())
     }
 
     /// Removes the cell and returns the values as CellContent.
     pub fn remove_cell(&mut self, row: u32, col: u32) -> Option<CellContent> {
+        self.mark_cell_dirty(row, col);
         let value = self.data.remove(&(row, col));
 
         if let Some(value) = value {
+            self.notify_formula(row, col, value.formula.as_deref());
+            self.notify_value(row, col, &value.value);
+            let extra = value.extra.map(|v| *v).unwrap_or_default();
             Some(CellContent {
                 value: value.value,
                 style: value.style,
                 formula: value.formula,
-                validation_name: value.validation_name,
-                span: value.span,
+                validation_name: extra.validation_name,
+                span: extra.span,
+                metadata: extra.metadata,
+                shapes: extra.shapes,
+                content_type: extra.content_type,
+                number_policy: extra.number_policy,
             })
         } else {
             None
         }
     }
 
+    /// Removes the cell and returns the values as CellContent. Fails if
+    /// the cell is guarded, see guard_range().
+    pub fn try_remove_cell(&mut self, row: u32, col: u32) -> Result<Option<CellContent>, OdsError> {
+        self.check_guard(row, col)?;
+        Ok(self.remove_cell(row, col))
+    }
+
     /// Add a new cell. Main use is for reading the spreadsheet.
     pub(crate) fn add_cell_data(&mut self, row: u32, col: u32, cell: CellData) {
         self.data.insert((row, col), cell);
@@ -1851,15 +3477,46 @@ impl Sheet {
         value: V,
         style: &CellStyleRef,
     ) {
+        self.mark_cell_dirty(row, col);
         let mut cell = self.data.entry((row, col)).or_insert_with(CellData::new);
-        cell.value = value.into();
-        cell.style = Some(style.to_string());
+        let old = mem::replace(&mut cell.value, value.into());
+        cell.style = Some(style.clone());
+        self.notify_value(row, col, &old);
     }
 
     /// Sets a value for the specified cell. Creates a new cell if necessary.
     pub fn set_value<V: Into<Value>>(&mut self, row: u32, col: u32, value: V) {
+        self.mark_cell_dirty(row, col);
         let mut cell = self.data.entry((row, col)).or_insert_with(CellData::new);
-        cell.value = value.into();
+        let old = mem::replace(&mut cell.value, value.into());
+        self.notify_value(row, col, &old);
+    }
+
+    /// Sets a value for the specified cell. Fails if the cell is guarded,
+    /// see guard_range().
+    pub fn try_set_value<V: Into<Value>>(
+        &mut self,
+        row: u32,
+        col: u32,
+        value: V,
+    ) -> Result<(), OdsError> {
+        self.check_guard(row, col)?;
+        self.set_value(row, col, value);
+        Ok(())
+    }
+
+    /// Sets a value for the specified cell. Fails if the cell is guarded,
+    /// see guard_range().
+    pub fn try_set_styled_value<V: Into<Value>>(
+        &mut self,
+        row: u32,
+        col: u32,
+        value: V,
+        style: &CellStyleRef,
+    ) -> Result<(), OdsError> {
+        self.check_guard(row, col)?;
+        self.set_styled_value(row, col, value, style);
+        Ok(())
     }
 
     /// Returns a value
@@ -1871,19 +3528,93 @@ impl Sheet {
         }
     }
 
+    /// Extracts the values of a column, one entry per row of the range.
+    /// The range ends with the last used row of the sheet.
+    ///
+    /// Empty cells give Ok(None), a value that can't be converted gives
+    /// an error with the cell reference. The values are converted in
+    /// place, nothing is cloned except for strings.
+    ///
+    /// ```
+    /// use spreadsheet_ods::{RowRange, Sheet};
+    ///
+    /// let mut sheet = Sheet::new("1");
+    /// sheet.set_value(0, 0, "amount");
+    /// sheet.set_value(1, 0, 1.5);
+    /// sheet.set_value(3, 0, 2.5);
+    ///
+    /// let values = sheet.col_values_as::<f64>(0, &RowRange::new(1, u32::MAX));
+    /// assert_eq!(values.len(), 3);
+    /// assert_eq!(values[0].as_ref().ok(), Some(&Some(1.5)));
+    /// assert_eq!(values[1].as_ref().ok(), Some(&None));
+    /// ```
+    pub fn col_values_as<T: FromValue>(
+        &self,
+        col: u32,
+        rows: &RowRange,
+    ) -> Vec<Result<Option<T>, OdsError>> {
+        let last_row = match self.data.keys().next_back() {
+            Some((r, _)) => *r,
+            None => return Vec::new(),
+        };
+        if rows.row() > last_row {
+            return Vec::new();
+        }
+
+        (rows.row()..=rows.to_row().min(last_row))
+            .map(|row| match self.data.get(&(row, col)) {
+                None => Ok(None),
+                Some(cell) if matches!(cell.value, Value::Empty) => Ok(None),
+                Some(cell) => match T::from_value(&cell.value) {
+                    Some(v) => Ok(Some(v)),
+                    None => Err(OdsError::Parse(format!(
+                        "{}: {:?} is not a {}",
+                        CellRef::local(row, col),
+                        cell.value.value_type(),
+                        std::any::type_name::<T>()
+                    ))),
+                },
+            })
+            .collect()
+    }
+
     /// Sets a formula for the specified cell. Creates a new cell if necessary.
     pub fn set_formula<V: Into<String>>(&mut self, row: u32, col: u32, formula: V) {
+        self.mark_cell_dirty(row, col);
         let mut cell = self.data.entry((row, col)).or_insert_with(CellData::new);
-        cell.formula = Some(formula.into());
+        let old = cell.formula.replace(formula.into());
+        self.notify_formula(row, col, old.as_deref());
+    }
+
+    /// Sets a formula for the specified cell. Fails if the cell is guarded,
+    /// see guard_range().
+    pub fn try_set_formula<V: Into<String>>(
+        &mut self,
+        row: u32,
+        col: u32,
+        formula: V,
+    ) -> Result<(), OdsError> {
+        self.check_guard(row, col)?;
+        self.set_formula(row, col, formula);
+        Ok(())
     }
 
     /// Removes the formula.
     pub fn clear_formula(&mut self, row: u32, col: u32) {
+        self.mark_cell_dirty(row, col);
         if let Some(cell) = self.data.get_mut(&(row, col)) {
-            cell.formula = None;
+            let old = cell.formula.take();
+            self.notify_formula(row, col, old.as_deref());
         }
     }
 
+    /// Removes the formula. Fails if the cell is guarded, see guard_range().
+    pub fn try_clear_formula(&mut self, row: u32, col: u32) -> Result<(), OdsError> {
+        self.check_guard(row, col)?;
+        self.clear_formula(row, col);
+        Ok(())
+    }
+
     /// Returns a value
     pub fn formula(&self, row: u32, col: u32) -> Option<&String> {
         if let Some(c) = self.data.get(&(row, col)) {
@@ -1893,21 +3624,68 @@ impl Sheet {
         }
     }
 
+    /// Sets the formula and its result. The result is written as the
+    /// cached value of the formula, other applications show it without
+    /// recalculating. Creates a new cell if necessary.
+    pub fn set_formula_value<F: Into<String>, V: Into<Value>>(
+        &mut self,
+        row: u32,
+        col: u32,
+        formula: F,
+        value: V,
+    ) {
+        self.mark_cell_dirty(row, col);
+        let cell = self.data.entry((row, col)).or_insert_with(CellData::new);
+        let old_formula = cell.formula.replace(formula.into());
+        let old_value = mem::replace(&mut cell.value, value.into());
+        self.notify_formula(row, col, old_formula.as_deref());
+        self.notify_value(row, col, &old_value);
+    }
+
+    /// Sets the formula and its result. Fails if the cell is guarded,
+    /// see guard_range().
+    pub fn try_set_formula_value<F: Into<String>, V: Into<Value>>(
+        &mut self,
+        row: u32,
+        col: u32,
+        formula: F,
+        value: V,
+    ) -> Result<(), OdsError> {
+        self.check_guard(row, col)?;
+        self.set_formula_value(row, col, formula, value);
+        Ok(())
+    }
+
+    /// Returns the cached result of the formula, as it was calculated by
+    /// the application that wrote the file. spreadsheet-ods doesn't
+    /// calculate formulas itself.
+    ///
+    /// None if the cell has no formula. The cached result is the same as
+    /// value().
+    pub fn cached_value(&self, row: u32, col: u32) -> Option<&Value> {
+        match self.data.get(&(row, col)) {
+            Some(c) if c.formula.is_some() => Some(&c.value),
+            _ => None,
+        }
+    }
+
     /// Sets the cell-style for the specified cell. Creates a new cell if necessary.
     pub fn set_cellstyle(&mut self, row: u32, col: u32, style: &CellStyleRef) {
+        self.mark_cell_dirty(row, col);
         let mut cell = self.data.entry((row, col)).or_insert_with(CellData::new);
-        cell.style = Some(style.to_string());
+        cell.style = Some(style.clone());
     }
 
     /// Removes the cell-style.
     pub fn clear_cellstyle(&mut self, row: u32, col: u32) {
+        self.mark_cell_dirty(row, col);
         if let Some(cell) = self.data.get_mut(&(row, col)) {
             cell.style = None;
         }
     }
 
     /// Returns a value
-    pub fn cellstyle(&self, row: u32, col: u32) -> Option<&String> {
+    pub fn cellstyle(&self, row: u32, col: u32) -> Option<&CellStyleRef> {
         if let Some(c) = self.data.get(&(row, col)) {
             c.style.as_ref()
         } else {
@@ -1917,64 +3695,938 @@ impl Sheet {
 
     /// Sets a content-validation for this cell.
     pub fn set_validation(&mut self, row: u32, col: u32, validation: &ValidationRef) {
+        self.mark_cell_dirty(row, col);
+        let mut cell = self.data.entry((row, col)).or_insert_with(CellData::new);
+        cell.extra_mut().validation_name = Some(validation.to_string());
+    }
+
+    /// Removes the cell-style.
+    pub fn clear_validation(&mut self, row: u32, col: u32) {
+        self.mark_cell_dirty(row, col);
+        if let Some(cell) = self.data.get_mut(&(row, col)) {
+            if let Some(extra) = &mut cell.extra {
+                extra.validation_name = None;
+            }
+        }
+    }
+
+    /// Returns a content-validation name for this cell.
+    pub fn validation(&self, row: u32, col: u32) -> Option<&String> {
+        if let Some(c) = self.data.get(&(row, col)) {
+            c.validation_name()
+        } else {
+            None
+        }
+    }
+
+    /// Attaches custom metadata to the cell.
+    ///
+    /// The metadata is written as attribute "sods:key" of the cell, with a
+    /// namespace of its own. The key must be a valid XML name without a
+    /// namespace prefix, otherwise writing fails. It survives reading and
+    /// writing with this crate, but other applications may drop it.
+    pub fn set_metadata<K: Into<String>, V: Into<String>>(
+        &mut self,
+        row: u32,
+        col: u32,
+        key: K,
+        value: V,
+    ) {
+        self.mark_cell_dirty(row, col);
+        let cell = self.data.entry((row, col)).or_insert_with(CellData::new);
+        cell.extra_mut().metadata.insert(key.into(), value.into());
+    }
+
+    /// Removes custom metadata from the cell.
+    pub fn clear_metadata(&mut self, row: u32, col: u32, key: &str) -> Option<String> {
+        self.mark_cell_dirty(row, col);
+        if let Some(cell) = self.data.get_mut(&(row, col)) {
+            if let Some(extra) = &mut cell.extra {
+                return extra.metadata.remove(key);
+            }
+        }
+        None
+    }
+
+    /// Returns the custom metadata of the cell.
+    pub fn metadata(&self, row: u32, col: u32, key: &str) -> Option<&String> {
+        self.data
+            .get(&(row, col))
+            .and_then(|c| c.metadata())
+            .and_then(|v| v.get(key))
+    }
+
+    /// Sets the extended value type of the cell. See ContentType.
+    ///
+    /// It's only written if it matches the value of the cell, so a
+    /// changed value doesn't keep an outdated content type.
+    pub fn set_content_type(&mut self, row: u32, col: u32, content_type: ContentType) {
+        self.mark_cell_dirty(row, col);
+        let cell = self.data.entry((row, col)).or_insert_with(CellData::new);
+        cell.extra_mut().content_type = Some(content_type);
+    }
+
+    /// Removes the extended value type.
+    pub fn clear_content_type(&mut self, row: u32, col: u32) {
+        self.mark_cell_dirty(row, col);
+        if let Some(cell) = self.data.get_mut(&(row, col)) {
+            if let Some(extra) = &mut cell.extra {
+                extra.content_type = None;
+            }
+        }
+    }
+
+    /// Returns the extended value type of the cell.
+    pub fn content_type(&self, row: u32, col: u32) -> Option<ContentType> {
+        self.data.get(&(row, col)).and_then(|c| c.content_type())
+    }
+
+    /// Overrides the number policy of the workbook for this cell.
+    /// See WorkBook::set_number_policy(). This is not stored in the file.
+    pub fn set_number_policy(&mut self, row: u32, col: u32, policy: NumberPolicy) {
+        self.mark_cell_dirty(row, col);
+        let cell = self.data.entry((row, col)).or_insert_with(CellData::new);
+        cell.extra_mut().number_policy = Some(policy);
+    }
+
+    /// Removes the number policy of the cell.
+    pub fn clear_number_policy(&mut self, row: u32, col: u32) {
+        self.mark_cell_dirty(row, col);
+        if let Some(cell) = self.data.get_mut(&(row, col)) {
+            if let Some(extra) = &mut cell.extra {
+                extra.number_policy = None;
+            }
+        }
+    }
+
+    /// Returns the number policy of the cell.
+    pub fn number_policy(&self, row: u32, col: u32) -> Option<NumberPolicy> {
+        self.data.get(&(row, col)).and_then(|c| c.number_policy())
+    }
+
+    /// The DDE source if the sheet is linked via DDE.
+    pub fn dde_source(&self) -> Option<DdeSource<'_>> {
+        let mut sources = Vec::new();
+        for tag in self
+            .extra
+            .iter()
+            .filter(|v| v.name() == "office:dde-source")
+        {
+            dde::dde_sources(tag, &mut sources);
+        }
+        sources.pop()
+    }
+
+    /// Lists the event listeners of the sheet. See the event module.
+    pub fn event_listeners(&self) -> Vec<EventListener<'_>> {
+        let mut listeners = Vec::new();
+        for tag in self
+            .extra
+            .iter()
+            .filter(|v| v.name() == "office:event-listeners")
+        {
+            event::event_listeners(tag, &mut listeners);
+        }
+        listeners
+    }
+
+    /// Removes the event listeners of the sheet for this event.
+    /// Returns the number of removed listeners.
+    pub fn remove_event_listener(&mut self, event_name: &str) -> usize {
+        let mut n = 0;
+        for tag in self
+            .extra
+            .iter_mut()
+            .filter(|v| v.name() == "office:event-listeners")
+        {
+            n += event::remove_event_listeners(tag, event_name);
+        }
+        if n > 0 {
+            self.mark_dirty();
+        }
+        n
+    }
+
+    /// Removes all event listeners of the sheet.
+    pub fn clear_event_listeners(&mut self) {
+        let len = self.extra.len();
+        self.extra.retain(|v| v.name() != "office:event-listeners");
+        if self.extra.len() != len {
+            self.mark_dirty();
+        }
+    }
+
+    /// Lists the form controls of the sheet. See the form module.
+    pub fn form_controls(&self) -> Vec<FormControl<'_>> {
+        let mut controls = Vec::new();
+        for tag in self.extra.iter().filter(|v| v.name() == "office:forms") {
+            form::form_controls(tag, &mut controls);
+        }
+        controls
+    }
+
+    /// Returns the shapes anchored to the cell. These are the draw:frame,
+    /// draw:control ... elements as read from the original ODS.
+    pub fn shapes(&self, row: u32, col: u32) -> Option<&Vec<XmlTag>> {
+        self.data.get(&(row, col)).and_then(|c| c.shapes())
+    }
+
+    /// Sets the rowspan of the cell. Must be greater than 0.
+    pub fn set_row_span(&mut self, row: u32, col: u32, span: u32) {
+        self.mark_cell_dirty(row, col);
         let mut cell = self.data.entry((row, col)).or_insert_with(CellData::new);
-        cell.validation_name = Some(validation.to_string());
+        cell.extra_mut().span.row_span = span;
+    }
+
+    /// Rowspan of the cell.
+    pub fn row_span(&self, row: u32, col: u32) -> u32 {
+        if let Some(c) = self.data.get(&(row, col)) {
+            c.span().row_span
+        } else {
+            1
+        }
+    }
+
+    /// Sets the colspan of the cell. Must be greater than 0.
+    pub fn set_col_span(&mut self, row: u32, col: u32, span: u32) {
+        self.mark_cell_dirty(row, col);
+        assert!(span > 0);
+        let mut cell = self.data.entry((row, col)).or_insert_with(CellData::new);
+        cell.extra_mut().span.col_span = span;
+    }
+
+    /// Colspan of the cell.
+    pub fn col_span(&self, row: u32, col: u32) -> u32 {
+        if let Some(c) = self.data.get(&(row, col)) {
+            c.span().col_span
+        } else {
+            1
+        }
+    }
+
+    /// Repeats the rows of the range in the n rows below it, like dragging
+    /// the fill handle in a spreadsheet.
+    ///
+    /// Values, styles and the other cell data are copied. The relative
+    /// parts of references in formulas move along with the row, the
+    /// absolute parts stay. An empty cell in the range clears the target
+    /// cell. Only the columns of the range are filled. Whole columns or
+    /// rows are cut down to the used area of the sheet, rows that would
+    /// be beyond u32::MAX are not filled.
+    ///
+    /// ```
+    /// use spreadsheet_ods::{CellRange, Sheet};
+    ///
+    /// let mut sheet = Sheet::new("1");
+    /// sheet.set_value(0, 0, 1);
+    /// sheet.set_formula(0, 1, "of:=[.A1]*[.$C$1]");
+    /// sheet.fill_down(&CellRange::local(0, 0, 0, 1), 2);
+    ///
+    /// assert_eq!(sheet.formula(2, 1).unwrap(), "of:=[.A3]*[.$C$1]");
+    /// ```
+    pub fn fill_down(&mut self, src: &CellRange, n: u32) {
+        let src = self.clip_whole(src);
+        let height = src.to_row() - src.row() + 1;
+        for i in 0..n {
+            let row = match src.to_row().checked_add(1 + i) {
+                Some(v) => v,
+                None => break,
+            };
+            let src_row = src.row() + i % height;
+            let delta = i64::from(row) - i64::from(src_row);
+            for col in src.col()..=src.to_col() {
+                self.mark_cell_dirty(row, col);
+                match self.data.get(&(src_row, col)).cloned() {
+                    Some(mut cell) => {
+                        if let Some(formula) = &cell.formula {
+                            cell.formula = Some(formula::shift_refs(formula, delta, 0));
+                        }
+                        self.data.insert((row, col), cell);
+                    }
+                    None => {
+                        self.data.remove(&(row, col));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Same as fill_down(), but fails if a target cell is guarded, see
+    /// guard_range().
+    pub fn try_fill_down(&mut self, src: &CellRange, n: u32) -> Result<(), OdsError> {
+        if n > 0 {
+            let clipped = self.clip_whole(src);
+            if let Some(row) = clipped.to_row().checked_add(1) {
+                let to_row = clipped.to_row().saturating_add(n);
+                self.check_guard_area(row, clipped.col(), to_row, clipped.to_col())?;
+            }
+        }
+        self.fill_down(src, n);
+        Ok(())
+    }
+
+    /// Writes n values of the series downwards, starting with the given
+    /// cell. The cell style of the starting cell is used for all cells.
+    /// Stops early if a date leaves the range of NaiveDate.
+    pub fn fill_series(&mut self, row: u32, col: u32, n: u32, series: &fill::Series) {
+        let style = self.cellstyle(row, col).cloned();
+        for i in 0..n {
+            let value = match series.nth(i) {
+                Some(v) => v,
+                None => break,
+            };
+            match &style {
+                Some(style) => self.set_styled_value(row + i, col, value, style),
+                None => self.set_value(row + i, col, value),
+            }
+        }
+    }
+
+    /// Same as fill_series(), but fails if a target cell is guarded, see
+    /// guard_range().
+    pub fn try_fill_series(
+        &mut self,
+        row: u32,
+        col: u32,
+        n: u32,
+        series: &fill::Series,
+    ) -> Result<(), OdsError> {
+        if n > 0 {
+            self.check_guard_area(row, col, row.saturating_add(n - 1), col)?;
+        }
+        self.fill_series(row, col, n, series);
+        Ok(())
+    }
+
+    /// Inserts n empty rows before the given row.
+    ///
+    /// The cells below move down together with everything attached to them.
+    /// Row-styles and other row data move too. Cells whose row span
+    /// crosses the inserted rows are extended, a repeated row that
+    /// crosses them is split. Cells that would move past the last
+    /// possible row are dropped. Formulas are not changed.
+    pub fn insert_rows(&mut self, row: u32, n: u32) {
+        self.mark_all_dirty();
+        self.data = mem::take(&mut self.data)
+            .into_iter()
+            .filter_map(|((r, c), mut cell)| {
+                if r >= row {
+                    Some(((r.checked_add(n)?, c), cell))
+                } else {
+                    let span = cell.span();
+                    if r.saturating_add(span.row_span) > row {
+                        cell.extra_mut().span.row_span = span.row_span.saturating_add(n);
+                    }
+                    Some(((r, c), cell))
+                }
+            })
+            .collect();
+        let mut row_header = BTreeMap::new();
+        for (r, mut h) in mem::take(&mut self.row_header) {
+            if r >= row {
+                if let Some(r) = r.checked_add(n) {
+                    row_header.insert(r, h);
+                }
+            } else {
+                let end = r.saturating_add(h.repeat());
+                if end > row {
+                    if let Some(tail_row) = row.checked_add(n) {
+                        let mut tail = h.clone();
+                        tail.set_repeat(end - row);
+                        row_header.insert(tail_row, tail);
+                    }
+                    h.set_repeat(row - r);
+                }
+                row_header.insert(r, h);
+            }
+        }
+        self.row_header = row_header;
+    }
+
+    /// Same as insert_rows(), but fails if a cell that moves is guarded,
+    /// see guard_range().
+    pub fn try_insert_rows(&mut self, row: u32, n: u32) -> Result<(), OdsError> {
+        if n > 0 {
+            self.check_guard_area(row, 0, u32::MAX, u32::MAX)?;
+        }
+        self.insert_rows(row, n);
+        Ok(())
+    }
+
+    /// Deletes n rows starting with the given row.
+    ///
+    /// The cells below move up together with everything attached to them.
+    /// Row-styles and other row data move too. Cells whose row span
+    /// reaches into the deleted rows are shortened, and so is a repeated
+    /// row that crosses them. A repeated row that was split by
+    /// insert_rows() is joined again. Formulas are not changed.
+    pub fn delete_rows(&mut self, row: u32, n: u32) {
+        self.mark_all_dirty();
+        let end = row.saturating_add(n);
+        self.data = mem::take(&mut self.data)
+            .into_iter()
+            .filter_map(|((r, c), mut cell)| {
+                if r >= end {
+                    Some(((r - n, c), cell))
+                } else if r >= row {
+                    None
+                } else {
+                    let span = cell.span();
+                    let span_end = r.saturating_add(span.row_span);
+                    if span_end > row {
+                        cell.extra_mut().span.row_span = span.row_span - (span_end.min(end) - row);
+                    }
+                    Some(((r, c), cell))
+                }
+            })
+            .collect();
+        let mut row_header = BTreeMap::new();
+        for (r, mut h) in mem::take(&mut self.row_header) {
+            let h_end = r.saturating_add(h.repeat());
+            if r >= end {
+                row_header.insert(r - n, h);
+            } else if h_end <= row {
+                row_header.insert(r, h);
+            } else {
+                // Only the rows before and after the deleted ones remain.
+                let repeat = row.saturating_sub(r) + h_end.saturating_sub(end);
+                if repeat > 0 {
+                    h.set_repeat(repeat);
+                    row_header.insert(r.min(row), h);
+                }
+            }
+        }
+        if let Some(next) = row_header.get(&row).cloned() {
+            if let Some((_, prev)) = row_header
+                .range_mut(..row)
+                .next_back()
+                .filter(|(r, h)| r.saturating_add(h.repeat()) == row && h.same_format(&next))
+            {
+                prev.set_repeat(prev.repeat().saturating_add(next.repeat()));
+                row_header.remove(&row);
+            }
+        }
+        self.row_header = row_header;
+    }
+
+    /// Same as delete_rows(), but fails if a cell that is deleted or moves
+    /// is guarded, see guard_range().
+    pub fn try_delete_rows(&mut self, row: u32, n: u32) -> Result<(), OdsError> {
+        if n > 0 {
+            self.check_guard_area(row, 0, u32::MAX, u32::MAX)?;
+        }
+        self.delete_rows(row, n);
+        Ok(())
+    }
+
+    /// Keeps only the rows for which the predicate returns true and
+    /// deletes all others.
+    ///
+    /// The predicate is called for every row up to the last used row,
+    /// with the cells of the row. The remaining rows move up as with
+    /// delete_rows(). Formulas are not changed.
+    pub fn retain_rows<F>(&mut self, f: F)
+    where
+        F: FnMut(u32, Range<'_>) -> bool,
+    {
+        let deleted = self.rejected_rows(f);
+        self.delete_row_list(&deleted, false);
+    }
+
+    /// Same as retain_rows(), but fails if a cell that is deleted or moves
+    /// is guarded, see guard_range(). Nothing is changed in that case.
+    pub fn try_retain_rows<F>(&mut self, f: F) -> Result<(), OdsError>
+    where
+        F: FnMut(u32, Range<'_>) -> bool,
+    {
+        let deleted = self.rejected_rows(f);
+        self.check_guard_rows(&deleted)?;
+        self.delete_row_list(&deleted, false);
+        Ok(())
+    }
+
+    /// Same as retain_rows(), but the references to this sheet in the
+    /// formulas of the remaining cells are adjusted too.
+    ///
+    /// A reference to a deleted cell is replaced with #REF!. A range
+    /// shrinks to the remaining rows. Formulas in other sheets are not
+    /// changed.
+    pub fn retain_rows_fix_formulas<F>(&mut self, f: F)
+    where
+        F: FnMut(u32, Range<'_>) -> bool,
+    {
+        let deleted = self.rejected_rows(f);
+        self.delete_row_list(&deleted, true);
+    }
+
+    /// Same as retain_rows_fix_formulas(), but fails if a cell that is
+    /// deleted or moves is guarded, see guard_range().
+    pub fn try_retain_rows_fix_formulas<F>(&mut self, f: F) -> Result<(), OdsError>
+    where
+        F: FnMut(u32, Range<'_>) -> bool,
+    {
+        let deleted = self.rejected_rows(f);
+        self.check_guard_rows(&deleted)?;
+        self.delete_row_list(&deleted, true);
+        Ok(())
+    }
+
+    /// Deletes the rows in the range that have the same values in the
+    /// key columns as a row before them. The first row is kept.
+    ///
+    /// Values are compared by type and content, the styles are not
+    /// compared. Rows without any cells are never deleted. The remaining rows move up as with delete_rows().
+    /// Formulas are not changed. Returns the number of deleted rows.
+    pub fn dedup_rows(&mut self, range: &RowRange, key_cols: &[u32]) -> u32 {
+        let deleted = self.duplicate_rows(range, key_cols);
+        self.delete_row_list(&deleted, false);
+        deleted.len() as u32
+    }
+
+    /// Same as dedup_rows(), but fails if a cell that is deleted or moves
+    /// is guarded, see guard_range().
+    pub fn try_dedup_rows(&mut self, range: &RowRange, key_cols: &[u32]) -> Result<u32, OdsError> {
+        let deleted = self.duplicate_rows(range, key_cols);
+        self.check_guard_rows(&deleted)?;
+        self.delete_row_list(&deleted, false);
+        Ok(deleted.len() as u32)
+    }
+
+    // Rows for dedup_rows().
+    fn duplicate_rows(&self, range: &RowRange, key_cols: &[u32]) -> Vec<u32> {
+        let mut seen = HashSet::new();
+        self.rejected_rows(|row, cells| {
+            if row < range.row() || row > range.to_row() {
+                return true;
+            }
+            let cells: Vec<_> = cells.collect();
+            if cells.is_empty() {
+                return true;
+            }
+            let key: Vec<_> = key_cols
+                .iter()
+                .map(|col| match cells.iter().find(|((_, c), _)| c == col) {
+                    Some((_, cell)) => dedup_key(cell.value()),
+                    None => dedup_key(&Value::Empty),
+                })
+                .collect();
+            seen.insert(key)
+        })
+    }
+
+    /// Deletes the rows in the range that contain no values and no
+    /// formulas. Rows with a styled cell or a shape anchored to a cell
+    /// are kept.
+    ///
+    /// The remaining rows move up as with delete_rows(). Formulas are not
+    /// changed. Returns the number of deleted rows.
+    pub fn remove_empty_rows(&mut self, range: &RowRange) -> u32 {
+        let deleted = self.empty_rows(range);
+        self.delete_row_list(&deleted, false);
+        deleted.len() as u32
+    }
+
+    /// Same as remove_empty_rows(), but fails if a cell that is deleted or
+    /// moves is guarded, see guard_range().
+    pub fn try_remove_empty_rows(&mut self, range: &RowRange) -> Result<u32, OdsError> {
+        let deleted = self.empty_rows(range);
+        self.check_guard_rows(&deleted)?;
+        self.delete_row_list(&deleted, false);
+        Ok(deleted.len() as u32)
+    }
+
+    // Rows for remove_empty_rows().
+    fn empty_rows(&self, range: &RowRange) -> Vec<u32> {
+        self.rejected_rows(|row, mut cells| {
+            if row < range.row() || row > range.to_row() {
+                return true;
+            }
+            !cells.all(|(_, cell)| {
+                matches!(cell.value(), Value::Empty)
+                    && cell.formula().is_none()
+                    && cell.style().is_none()
+                    && cell.shapes.is_none_or(|v| v.is_empty())
+            })
+        })
+    }
+
+    // The rows up to the last used row for which the predicate returns false.
+    fn rejected_rows<F>(&self, mut f: F) -> Vec<u32>
+    where
+        F: FnMut(u32, Range<'_>) -> bool,
+    {
+        let last_row = self
+            .data
+            .keys()
+            .next_back()
+            .map(|(r, _)| *r)
+            .max(self.row_header.keys().next_back().copied());
+        let last_row = match last_row {
+            Some(v) => v,
+            None => return Vec::new(),
+        };
+
+        (0..=last_row)
+            .filter(|row| !f(*row, self.range((*row, 0)..=(*row, u32::MAX))))
+            .collect()
+    }
+
+    // Is any cell guarded that moves when the rows are deleted.
+    fn check_guard_rows(&self, deleted: &[u32]) -> Result<(), OdsError> {
+        match deleted.first() {
+            Some(row) => self.check_guard_area(*row, 0, u32::MAX, u32::MAX),
+            None => Ok(()),
+        }
+    }
+
+    // Deletes the rows, they must be sorted.
+    fn delete_row_list(&mut self, deleted: &[u32], fix_formulas: bool) {
+        if deleted.is_empty() {
+            return;
+        }
+        self.mark_all_dirty();
+
+        // number of deleted rows before the given row.
+        let deleted_before = |row: u32| deleted.partition_point(|v| *v < row) as u32;
+        let is_deleted = |row: u32| deleted.binary_search(&row).is_ok();
+
+        let name = self.name.clone();
+        self.data = mem::take(&mut self.data)
+            .into_iter()
+            .filter(|((r, _), _)| !is_deleted(*r))
+            .map(|((r, c), mut cell)| {
+                let span = cell.span();
+                if span.row_span > 1 {
+                    let span_deleted =
+                        deleted_before(r.saturating_add(span.row_span)) - deleted_before(r);
+                    if span_deleted > 0 {
+                        cell.extra_mut().span.row_span = span.row_span - span_deleted;
+                    }
+                }
+                if fix_formulas {
+                    if let Some(formula) = &cell.formula {
+                        cell.formula =
+                            Some(formula::map_row_refs(formula, &name, |row, to_row| {
+                                let row = row - deleted_before(row);
+                                let to_row = to_row.saturating_add(1);
+                                let to_row = to_row - deleted_before(to_row);
+                                if row < to_row {
+                                    Some((row, to_row - 1))
+                                } else {
+                                    None
+                                }
+                            }));
+                    }
+                }
+                ((r - deleted_before(r), c), cell)
+            })
+            .collect();
+        let mut row_header: BTreeMap<u32, RowHeader> = BTreeMap::new();
+        for (r, mut h) in mem::take(&mut self.row_header) {
+            // Only the remaining rows of a repeat are kept.
+            let h_end = r.saturating_add(h.repeat());
+            let repeat = h.repeat() - (deleted_before(h_end) - deleted_before(r));
+            if repeat == 0 {
+                continue;
+            }
+            h.set_repeat(repeat);
+            let r = r - deleted_before(r);
+            if let Some((_, prev)) = row_header
+                .range_mut(..r)
+                .next_back()
+                .filter(|(pr, ph)| pr.saturating_add(ph.repeat()) == r && ph.same_format(&h))
+            {
+                prev.set_repeat(prev.repeat().saturating_add(repeat));
+            } else {
+                row_header.insert(r, h);
+            }
+        }
+        self.row_header = row_header;
+    }
+
+    /// Inserts n empty columns before the given column.
+    ///
+    /// The cells to the right move together with everything attached to them.
+    /// Column-styles and other column data move too. Cells whose column span
+    /// crosses the inserted columns are extended. Cells that would move past
+    /// the last possible column are dropped. Formulas are not changed.
+    pub fn insert_cols(&mut self, col: u32, n: u32) {
+        self.mark_all_dirty();
+        self.data = mem::take(&mut self.data)
+            .into_iter()
+            .filter_map(|((r, c), mut cell)| {
+                if c >= col {
+                    Some(((r, c.checked_add(n)?), cell))
+                } else {
+                    let span = cell.span();
+                    if c.saturating_add(span.col_span) > col {
+                        cell.extra_mut().span.col_span = span.col_span.saturating_add(n);
+                    }
+                    Some(((r, c), cell))
+                }
+            })
+            .collect();
+        self.col_header = mem::take(&mut self.col_header)
+            .into_iter()
+            .filter_map(|(c, h)| {
+                if c >= col {
+                    Some((c.checked_add(n)?, h))
+                } else {
+                    Some((c, h))
+                }
+            })
+            .collect();
+    }
+
+    /// Same as insert_cols(), but fails if a cell that moves is guarded,
+    /// see guard_range().
+    pub fn try_insert_cols(&mut self, col: u32, n: u32) -> Result<(), OdsError> {
+        if n > 0 {
+            self.check_guard_area(0, col, u32::MAX, u32::MAX)?;
+        }
+        self.insert_cols(col, n);
+        Ok(())
+    }
+
+    /// Deletes n columns starting with the given column.
+    ///
+    /// The cells to the right move left together with everything attached
+    /// to them. Column-styles and other column data move too. Cells whose
+    /// column span reaches into the deleted columns are shortened.
+    /// Formulas are not changed.
+    pub fn delete_cols(&mut self, col: u32, n: u32) {
+        self.mark_all_dirty();
+        let end = col.saturating_add(n);
+        self.data = mem::take(&mut self.data)
+            .into_iter()
+            .filter_map(|((r, c), mut cell)| {
+                if c >= end {
+                    Some(((r, c - n), cell))
+                } else if c >= col {
+                    None
+                } else {
+                    let span = cell.span();
+                    let span_end = c + span.col_span;
+                    if span_end > col {
+                        cell.extra_mut().span.col_span = span.col_span - (span_end.min(end) - col);
+                    }
+                    Some(((r, c), cell))
+                }
+            })
+            .collect();
+        self.col_header = mem::take(&mut self.col_header)
+            .into_iter()
+            .filter_map(|(c, h)| {
+                if c >= end {
+                    Some((c - n, h))
+                } else if c >= col {
+                    None
+                } else {
+                    Some((c, h))
+                }
+            })
+            .collect();
     }
 
-    /// Removes the cell-style.
-    pub fn clear_validation(&mut self, row: u32, col: u32) {
-        if let Some(cell) = self.data.get_mut(&(row, col)) {
-            cell.validation_name = None;
+    /// Same as delete_cols(), but fails if a cell that is deleted or moves
+    /// is guarded, see guard_range().
+    pub fn try_delete_cols(&mut self, col: u32, n: u32) -> Result<(), OdsError> {
+        if n > 0 {
+            self.check_guard_area(0, col, u32::MAX, u32::MAX)?;
         }
+        self.delete_cols(col, n);
+        Ok(())
     }
 
-    /// Returns a content-validation name for this cell.
-    pub fn validation(&self, row: u32, col: u32) -> Option<&String> {
-        if let Some(c) = self.data.get(&(row, col)) {
-            c.validation_name.as_ref()
-        } else {
-            None
+    /// Moves the rows of the range before the given row.
+    ///
+    /// The rows in between move up or down to fill the gap. The cells move
+    /// together with everything attached to them, the row-styles and other
+    /// row data move too. A repeated row that crosses the edges of the
+    /// moved rows is split. The references to this sheet in the formulas of
+    /// this sheet are adjusted. For a range reference the first and last
+    /// row are moved, the rows in between are not checked. A cell that
+    /// spans rows which don't stay together is cut down.
+    ///
+    /// Only the formulas of this sheet are changed. References in other
+    /// sheets, named ranges, print ranges and validations keep pointing
+    /// at the old row numbers.
+    pub fn move_rows(&mut self, range: &RowRange, to: u32) {
+        let from = range.row().min(range.to_row());
+        let to_row = range.row().max(range.to_row());
+        self.mark_all_dirty();
+        self.split_row_repeat(from);
+        self.split_row_repeat(to_row.saturating_add(1));
+        self.split_row_repeat(to);
+        let moved = |r: u32| moved_pos(r, from, to_row, to);
+
+        let name = self.name.clone();
+        self.data = mem::take(&mut self.data)
+            .into_iter()
+            .map(|((r, c), mut cell)| {
+                if let Some(formula) = &cell.formula {
+                    cell.formula = Some(formula::map_row_refs(formula, &name, |r0, r1| {
+                        let (r0, r1) = (moved(r0), moved(r1));
+                        Some((r0.min(r1), r0.max(r1)))
+                    }));
+                }
+                let span = cell.span();
+                if span.row_span > 1 {
+                    cell.extra_mut().span.row_span = moved_len(r, span.row_span, from, to_row, to);
+                }
+                ((moved(r), c), cell)
+            })
+            .collect();
+        self.row_header = mem::take(&mut self.row_header)
+            .into_iter()
+            .map(|(r, h)| (moved(r), h))
+            .collect();
+    }
+
+    // Splits a repeated row that crosses the given row, the second part
+    // starts there.
+    fn split_row_repeat(&mut self, row: u32) {
+        if let Some((&r, h)) = self.row_header.range_mut(..row).next_back() {
+            let end = r.saturating_add(h.repeat());
+            if end > row {
+                let mut tail = h.clone();
+                tail.set_repeat(end - row);
+                h.set_repeat(row - r);
+                self.row_header.insert(row, tail);
+            }
         }
     }
 
-    /// Sets the rowspan of the cell. Must be greater than 0.
-    pub fn set_row_span(&mut self, row: u32, col: u32, span: u32) {
-        let mut cell = self.data.entry((row, col)).or_insert_with(CellData::new);
-        cell.span.row_span = span;
+    /// Same as move_rows(), but fails if a cell that moves is guarded,
+    /// see guard_range().
+    pub fn try_move_rows(&mut self, range: &RowRange, to: u32) -> Result<(), OdsError> {
+        let from = range.row().min(range.to_row());
+        let to_row = range.row().max(range.to_row());
+        if let Some((first, last)) = moved_span(from, to_row, to) {
+            self.check_guard_area(first, 0, last, u32::MAX)?;
+        }
+        self.move_rows(range, to);
+        Ok(())
     }
 
-    /// Rowspan of the cell.
-    pub fn row_span(&self, row: u32, col: u32) -> u32 {
-        if let Some(c) = self.data.get(&(row, col)) {
-            c.span.row_span
-        } else {
-            1
+    /// Moves the columns of the range before the given column.
+    ///
+    /// The columns in between move left or right to fill the gap. The cells
+    /// move together with everything attached to them, the column-styles and
+    /// other column data move too. The references to this sheet in the
+    /// formulas of this sheet are adjusted. For a range reference the first
+    /// and last column are moved, the columns in between are not checked.
+    /// A cell that spans columns which don't stay together is cut down.
+    ///
+    /// Only the formulas of this sheet are changed. References in other
+    /// sheets, named ranges, print ranges and validations keep pointing
+    /// at the old column numbers.
+    pub fn move_cols(&mut self, range: &ColRange, to: u32) {
+        let from = range.col().min(range.to_col());
+        let to_col = range.col().max(range.to_col());
+        self.mark_all_dirty();
+        let moved = |c: u32| moved_pos(c, from, to_col, to);
+
+        let name = self.name.clone();
+        self.data = mem::take(&mut self.data)
+            .into_iter()
+            .map(|((r, c), mut cell)| {
+                if let Some(formula) = &cell.formula {
+                    cell.formula = Some(formula::map_col_refs(formula, &name, |c0, c1| {
+                        let (c0, c1) = (moved(c0), moved(c1));
+                        Some((c0.min(c1), c0.max(c1)))
+                    }));
+                }
+                let span = cell.span();
+                if span.col_span > 1 {
+                    cell.extra_mut().span.col_span = moved_len(c, span.col_span, from, to_col, to);
+                }
+                ((r, moved(c)), cell)
+            })
+            .collect();
+        self.col_header = mem::take(&mut self.col_header)
+            .into_iter()
+            .map(|(c, h)| (moved(c), h))
+            .collect();
+    }
+
+    /// Same as move_cols(), but fails if a cell that moves is guarded,
+    /// see guard_range().
+    pub fn try_move_cols(&mut self, range: &ColRange, to: u32) -> Result<(), OdsError> {
+        let from = range.col().min(range.to_col());
+        let to_col = range.col().max(range.to_col());
+        if let Some((first, last)) = moved_span(from, to_col, to) {
+            self.check_guard_area(0, first, u32::MAX, last)?;
         }
+        self.move_cols(range, to);
+        Ok(())
     }
 
-    /// Sets the colspan of the cell. Must be greater than 0.
-    pub fn set_col_span(&mut self, row: u32, col: u32, span: u32) {
-        assert!(span > 0);
-        let mut cell = self.data.entry((row, col)).or_insert_with(CellData::new);
-        cell.span.col_span = span;
-    }
+    /// Copies the cells of the range to the given position.
+    ///
+    /// The target area is cleared first, everything attached to the
+    /// cells is copied. Formulas are not changed. Whole columns or rows
+    /// are cut down to the used area of the sheet, cells that would be
+    /// beyond u32::MAX are not copied.
+    pub fn copy_range(&mut self, range: &CellRange, row: u32, col: u32) {
+        let range = self.clip_whole(range);
+        let to_row = row.saturating_add(range.to_row() - range.row());
+        let to_col = col.saturating_add(range.to_col() - range.col());
+        let copy: Vec<_> = self
+            .data
+            .range((range.row(), 0)..=(range.to_row(), u32::MAX))
+            .filter(|((_, c), _)| *c >= range.col() && *c <= range.to_col())
+            .filter_map(|((r, c), cell)| {
+                let r = (r - range.row()).checked_add(row)?;
+                let c = (c - range.col()).checked_add(col)?;
+                Some(((r, c), cell.clone()))
+            })
+            .collect();
 
-    /// Colspan of the cell.
-    pub fn col_span(&self, row: u32, col: u32) -> u32 {
-        if let Some(c) = self.data.get(&(row, col)) {
-            c.span.col_span
-        } else {
-            1
+        let mut cleared = Vec::new();
+        self.data.retain(|(r, c), _| {
+            if *r >= row && *r <= to_row && *c >= col && *c <= to_col {
+                cleared.push((*r, *c));
+                false
+            } else {
+                true
+            }
+        });
+
+        for (r, c) in cleared {
+            self.mark_cell_dirty(r, c);
         }
+        for ((r, c), _) in &copy {
+            self.mark_cell_dirty(*r, *c);
+        }
+        self.data.extend(copy);
+    }
+
+    /// Same as copy_range(), but fails if a cell of the target area is
+    /// guarded, see guard_range().
+    pub fn try_copy_range(
+        &mut self,
+        range: &CellRange,
+        row: u32,
+        col: u32,
+    ) -> Result<(), OdsError> {
+        let clipped = self.clip_whole(range);
+        let to_row = row.saturating_add(clipped.to_row() - clipped.row());
+        let to_col = col.saturating_add(clipped.to_col() - clipped.col());
+        self.check_guard_area(row, col, to_row, to_col)?;
+        self.copy_range(range, row, col);
+        Ok(())
     }
 
     /// Defines a range of rows as header rows.
     pub fn set_header_rows(&mut self, row_start: u32, row_end: u32) {
+        self.mark_dirty();
         self.header_rows = Some(RowRange::new(row_start, row_end));
     }
 
     /// Clears the header-rows definition.
     pub fn clear_header_rows(&mut self) {
+        self.mark_dirty();
         self.header_rows = None;
     }
 
@@ -1985,11 +4637,13 @@ impl Sheet {
 
     /// Defines a range of columns as header columns.
     pub fn set_header_cols(&mut self, col_start: u32, col_end: u32) {
+        self.mark_dirty();
         self.header_cols = Some(ColRange::new(col_start, col_end));
     }
 
     /// Clears the header-columns definition.
     pub fn clear_header_cols(&mut self) {
+        self.mark_dirty();
         self.header_cols = None;
     }
 
@@ -2000,11 +4654,13 @@ impl Sheet {
 
     /// Print ranges.
     pub fn add_print_range(&mut self, range: CellRange) {
+        self.mark_dirty();
         self.print_ranges.get_or_insert_with(Vec::new).push(range);
     }
 
     /// Remove print ranges.
     pub fn clear_print_ranges(&mut self) {
+        self.mark_dirty();
         self.print_ranges = None;
     }
 
@@ -2013,6 +4669,40 @@ impl Sheet {
         self.print_ranges.as_ref()
     }
 
+    /// Adds a named range that is only visible on this sheet. Replaces
+    /// a name that already exists. A range without table name gets the
+    /// name of this sheet.
+    pub fn add_named_range<S: Into<String>>(&mut self, name: S, mut range: CellRange) {
+        if range.table().is_none() {
+            range.set_table(self.name.as_str());
+        }
+        let named_range = NamedRange::new(name, range);
+        if names::named_range(&self.extra, named_range.name()).as_ref() != Some(&named_range) {
+            self.mark_dirty();
+            names::add_named_range(&mut self.extra, named_range);
+        }
+    }
+
+    /// Removes a named range of this sheet. Returns false if there
+    /// was none.
+    pub fn remove_named_range(&mut self, name: &str) -> bool {
+        let found = names::remove_named_range(&mut self.extra, name);
+        if found {
+            self.mark_dirty();
+        }
+        found
+    }
+
+    /// Named range of this sheet.
+    pub fn named_range(&self, name: &str) -> Option<NamedRange> {
+        names::named_range(&self.extra, name)
+    }
+
+    /// All named ranges of this sheet.
+    pub fn named_ranges(&self) -> Vec<NamedRange> {
+        names::named_ranges(&self.extra)
+    }
+
     /// Split horizontally on a cell boundary. The splitting is fixed in
     /// position.
     pub fn split_col_header(&mut self, col: u32) {
@@ -2031,6 +4721,18 @@ impl Sheet {
         self.config_mut().cursor_y = row;
     }
 
+    /// Freezes the first row, so it stays visible when scrolling.
+    /// Same as split_row_header(1).
+    pub fn freeze_header_row(&mut self) {
+        self.split_row_header(1);
+    }
+
+    /// Freezes the first column, so it stays visible when scrolling.
+    /// Same as split_col_header(1).
+    pub fn freeze_first_col(&mut self) {
+        self.split_col_header(1);
+    }
+
     /// Split horizontally with a pixel width. The split can be moved around.
     /// For more control look at SheetConfig.
     pub fn split_horizontal(&mut self, col: u32) {
@@ -2046,6 +4748,77 @@ impl Sheet {
     }
 }
 
+/// Comparable key for a value. Text and markup text are the same.
+fn dedup_key(value: &Value) -> (ValueType, String) {
+    match value {
+        Value::Empty => (ValueType::Empty, String::new()),
+        Value::Boolean(v) => (ValueType::Boolean, v.to_string()),
+        Value::Number(v) => (ValueType::Number, v.to_string()),
+        Value::Percentage(v) => (ValueType::Percentage, v.to_string()),
+        Value::Currency(v, c) => (
+            ValueType::Currency,
+            format!("{} {}", v, String::from_utf8_lossy(c)),
+        ),
+        Value::Text(_) | Value::TextXml(_) => {
+            (ValueType::Text, value.as_cow_str_or("").into_owned())
+        }
+        Value::DateTime(v) => (ValueType::DateTime, v.to_string()),
+        Value::TimeDuration(v) => (ValueType::TimeDuration, v.to_string()),
+    }
+}
+
+// First and last row or column that changes its position, when the
+// block from..=to_pos is moved before dest.
+fn moved_span(from: u32, to_pos: u32, dest: u32) -> Option<(u32, u32)> {
+    if dest < from {
+        Some((dest, to_pos))
+    } else if dest > to_pos.saturating_add(1) {
+        Some((from, dest - 1))
+    } else {
+        None
+    }
+}
+
+// New position of pos, when the block from..=to_pos is moved before dest.
+// The block is moved only if dest is outside of it, so the block is
+// never the whole u32 range when n is needed.
+fn moved_pos(pos: u32, from: u32, to_pos: u32, dest: u32) -> u32 {
+    let n = || to_pos - from + 1;
+    if pos >= from && pos <= to_pos {
+        if dest < from {
+            dest + (pos - from)
+        } else if dest > to_pos.saturating_add(1) {
+            dest - n() + (pos - from)
+        } else {
+            pos
+        }
+    } else if dest < from && pos >= dest && pos < from {
+        pos + n()
+    } else if dest > to_pos.saturating_add(1) && pos > to_pos && pos < dest {
+        pos - n()
+    } else {
+        pos
+    }
+}
+
+// Length of the part of the span pos..pos+len that stays together after
+// the move. The positions move in blocks, so only the block starts need
+// to be checked.
+fn moved_len(pos: u32, len: u32, from: u32, to_pos: u32, dest: u32) -> u32 {
+    let start = moved_pos(pos, from, to_pos, dest);
+    let mut breaks = [Some(from), to_pos.checked_add(1), Some(dest)];
+    breaks.sort();
+    for b in breaks.into_iter().flatten() {
+        if b > pos && b - pos < len {
+            let b_pos = moved_pos(b, from, to_pos, dest);
+            if start.checked_add(b - pos) != Some(b_pos) {
+                return b - pos;
+            }
+        }
+    }
+    len
+}
+
 /// There are two ways a sheet can be split. There are fixed column/row header
 /// like splits, and there is a moveable split.
 ///
@@ -2145,8 +4918,30 @@ impl Default for SheetConfig {
     }
 }
 
+impl SheetConfig {
+    /// Zoom in percent, for the normal view and the page break preview.
+    /// All other settings are the defaults.
+    pub fn zoom(percent: i32) -> Self {
+        Self {
+            zoom_value: percent,
+            page_view_zoom_value: percent,
+            ..Default::default()
+        }
+    }
+
+    /// Settings for the page break preview. Full size and no grid.
+    /// All other settings are the defaults.
+    pub fn print_preview() -> Self {
+        Self {
+            page_view_zoom_value: 100,
+            show_grid: false,
+            ..Default::default()
+        }
+    }
+}
+
 /// A cell can span multiple rows/columns.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CellSpan {
     row_span: u32,
     col_span: u32,
@@ -2211,11 +5006,30 @@ struct CellData {
     // Unparsed formula string.
     formula: Option<String>,
     // Cell style name.
-    style: Option<String>,
+    style: Option<CellStyleRef>,
+    // Everything else that is attached to the cell.
+    extra: Option<Box<CellExtras>>,
+}
+
+/// Less common data attached to a cell. Kept in a box to keep
+/// CellData small.
+///
+/// Anything that is stored here moves with the cell when rows or
+/// columns are inserted, deleted or copied.
+#[derive(Debug, Clone, Default)]
+struct CellExtras {
     // Content validation name.
     validation_name: Option<String>,
     // Row/Column span.
     span: CellSpan,
+    // Custom metadata.
+    metadata: BTreeMap<String, String>,
+    // Shapes anchored to the cell.
+    shapes: Vec<XmlTag>,
+    // Extended value type.
+    content_type: Option<ContentType>,
+    // Number policy for writing.
+    number_policy: Option<NumberPolicy>,
 }
 
 impl CellData {
@@ -2225,10 +5039,55 @@ impl CellData {
             value: Value::Empty,
             formula: None,
             style: None,
-            validation_name: None,
-            span: Default::default(),
+            extra: None,
         }
     }
+
+    /// Extra data. Created if necessary.
+    pub(crate) fn extra_mut(&mut self) -> &mut CellExtras {
+        self.extra.get_or_insert_with(Box::default)
+    }
+
+    /// Content validation name.
+    pub(crate) fn validation_name(&self) -> Option<&String> {
+        self.extra
+            .as_ref()
+            .and_then(|extra| extra.validation_name.as_ref())
+    }
+
+    /// Custom metadata.
+    pub(crate) fn metadata(&self) -> Option<&BTreeMap<String, String>> {
+        self.extra
+            .as_ref()
+            .map(|extra| &extra.metadata)
+            .filter(|v| !v.is_empty())
+    }
+
+    /// Shapes anchored to the cell.
+    pub(crate) fn shapes(&self) -> Option<&Vec<XmlTag>> {
+        self.extra
+            .as_ref()
+            .map(|extra| &extra.shapes)
+            .filter(|v| !v.is_empty())
+    }
+
+    /// Extended value type.
+    pub(crate) fn content_type(&self) -> Option<ContentType> {
+        self.extra.as_ref().and_then(|extra| extra.content_type)
+    }
+
+    /// Number policy for writing.
+    pub(crate) fn number_policy(&self) -> Option<NumberPolicy> {
+        self.extra.as_ref().and_then(|extra| extra.number_policy)
+    }
+
+    /// Row/Column span.
+    pub(crate) fn span(&self) -> CellSpan {
+        self.extra
+            .as_ref()
+            .map(|extra| extra.span)
+            .unwrap_or_default()
+    }
 }
 
 /// Holds references to the combined content of a cell.
@@ -2238,13 +5097,21 @@ pub struct CellContentRef<'a> {
     /// Reference to the cell value.
     pub value: Option<&'a Value>,
     /// Reference to the stylename.
-    pub style: Option<&'a String>,
+    pub style: Option<&'a CellStyleRef>,
     /// Reference to the cell formula.
     pub formula: Option<&'a String>,
     /// Reference to a cell validation.
     pub validation_name: Option<&'a String>,
     /// Reference to the cellspan.
     pub span: Option<&'a CellSpan>,
+    /// Reference to the custom metadata.
+    pub metadata: Option<&'a BTreeMap<String, String>>,
+    /// Reference to the shapes anchored to the cell.
+    pub shapes: Option<&'a Vec<XmlTag>>,
+    /// Extended value type.
+    pub content_type: Option<ContentType>,
+    /// Number policy for writing.
+    pub number_policy: Option<NumberPolicy>,
 }
 
 impl<'a> From<&'a CellData> for CellContentRef<'a> {
@@ -2253,8 +5120,12 @@ impl<'a> From<&'a CellData> for CellContentRef<'a> {
             value: Some(&cd.value),
             style: cd.style.as_ref(),
             formula: cd.formula.as_ref(),
-            validation_name: cd.validation_name.as_ref(),
-            span: Some(&cd.span),
+            validation_name: cd.validation_name(),
+            span: cd.extra.as_ref().map(|extra| &extra.span),
+            metadata: cd.metadata(),
+            shapes: cd.shapes(),
+            content_type: cd.content_type(),
+            number_policy: cd.number_policy(),
         }
     }
 }
@@ -2274,8 +5145,14 @@ impl<'a> CellContentRef<'a> {
         self.formula
     }
 
+    /// Returns the cached result of the formula. None if there is no
+    /// formula.
+    pub fn cached_value(&self) -> Option<&'a Value> {
+        self.formula.map(|_| self.value())
+    }
+
     /// Returns the cell style.
-    pub fn style(&self) -> Option<&'a String> {
+    pub fn style(&self) -> Option<&'a CellStyleRef> {
         self.style
     }
 
@@ -2284,6 +5161,21 @@ impl<'a> CellContentRef<'a> {
         self.validation_name
     }
 
+    /// Returns the custom metadata for the key.
+    pub fn metadata(&self, key: &str) -> Option<&'a String> {
+        self.metadata.and_then(|v| v.get(key))
+    }
+
+    /// Returns the extended value type.
+    pub fn content_type(&self) -> Option<ContentType> {
+        self.content_type
+    }
+
+    /// Returns the number policy.
+    pub fn number_policy(&self) -> Option<NumberPolicy> {
+        self.number_policy
+    }
+
     /// Returns the row span.
     pub fn row_span(&self) -> u32 {
         if let Some(span) = self.span {
@@ -2309,13 +5201,22 @@ pub struct CellContent {
     /// Cell value.
     pub value: Value,
     /// Cell stylename.
-    pub style: Option<String>,
+    pub style: Option<CellStyleRef>,
     /// Cell formula.
     pub formula: Option<String>,
     /// Reference to a validation rule.
     pub validation_name: Option<String>,
     /// Cellspan.
     pub span: CellSpan,
+    /// Custom metadata.
+    pub metadata: BTreeMap<String, String>,
+    /// Shapes anchored to the cell. Images, embedded objects, form
+    /// controls etc. are kept as xml.
+    pub shapes: Vec<XmlTag>,
+    /// Extended value type.
+    pub content_type: Option<ContentType>,
+    /// Number policy for writing.
+    pub number_policy: Option<NumberPolicy>,
 }
 
 impl CellContent {
@@ -2327,6 +5228,10 @@ impl CellContent {
             formula: None,
             validation_name: None,
             span: Default::default(),
+            metadata: Default::default(),
+            shapes: Default::default(),
+            content_type: None,
+            number_policy: None,
         }
     }
 
@@ -2355,14 +5260,26 @@ impl CellContent {
         self.formula = None;
     }
 
+    /// Returns the cached result of the formula. None if there is no
+    /// formula.
+    pub fn cached_value(&self) -> Option<&Value> {
+        self.formula.as_ref().map(|_| &self.value)
+    }
+
+    /// Sets the formula and its cached result.
+    pub fn set_formula_value<F: Into<String>, V: Into<Value>>(&mut self, formula: F, value: V) {
+        self.formula = Some(formula.into());
+        self.value = value.into();
+    }
+
     /// Returns the cell style.
-    pub fn style(&self) -> Option<&String> {
+    pub fn style(&self) -> Option<&CellStyleRef> {
         self.style.as_ref()
     }
 
     /// Sets the cell style.
     pub fn set_style(&mut self, style: &CellStyleRef) {
-        self.style = Some(style.to_string());
+        self.style = Some(style.clone());
     }
 
     /// Removes the style.
@@ -2385,6 +5302,56 @@ impl CellContent {
         self.validation_name = None;
     }
 
+    /// Returns the custom metadata for the key.
+    pub fn metadata(&self, key: &str) -> Option<&String> {
+        self.metadata.get(key)
+    }
+
+    /// Returns the shapes anchored to the cell.
+    pub fn shapes(&self) -> &Vec<XmlTag> {
+        &self.shapes
+    }
+
+    /// Returns the extended value type.
+    pub fn content_type(&self) -> Option<ContentType> {
+        self.content_type
+    }
+
+    /// Sets the extended value type.
+    pub fn set_content_type(&mut self, content_type: ContentType) {
+        self.content_type = Some(content_type);
+    }
+
+    /// Removes the extended value type.
+    pub fn clear_content_type(&mut self) {
+        self.content_type = None;
+    }
+
+    /// Returns the number policy.
+    pub fn number_policy(&self) -> Option<NumberPolicy> {
+        self.number_policy
+    }
+
+    /// Sets the number policy. See Sheet::set_number_policy().
+    pub fn set_number_policy(&mut self, policy: NumberPolicy) {
+        self.number_policy = Some(policy);
+    }
+
+    /// Removes the number policy.
+    pub fn clear_number_policy(&mut self) {
+        self.number_policy = None;
+    }
+
+    /// Sets custom metadata. See Sheet::set_metadata().
+    pub fn set_metadata<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) {
+        self.metadata.insert(key.into(), value.into());
+    }
+
+    /// Removes the custom metadata for the key.
+    pub fn clear_metadata(&mut self, key: &str) -> Option<String> {
+        self.metadata.remove(key)
+    }
+
     /// Sets the row span of this cell.
     /// Cells below with values will be lost when writing.
     pub fn set_row_span(&mut self, rows: u32) {
@@ -2410,6 +5377,58 @@ impl CellContent {
     }
 }
 
+/// Extended value type of LibreOffice, written as calcext:value-type.
+///
+/// It is a hint in addition to the type of the value. LibreOffice uses it
+/// to mark the results of formulas that are errors, and to keep text
+/// that looks like a number ("007", phone numbers) as text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum ContentType {
+    Float,
+    Percentage,
+    Currency,
+    Date,
+    Time,
+    Boolean,
+    String,
+    Error,
+}
+
+impl ContentType {
+    /// Can this be the content type of a value of this type?
+    pub fn matches(&self, value_type: ValueType) -> bool {
+        matches!(
+            (self, value_type),
+            (ContentType::Float, ValueType::Number)
+                | (ContentType::Percentage, ValueType::Percentage)
+                | (ContentType::Currency, ValueType::Currency)
+                | (ContentType::Date, ValueType::DateTime)
+                | (ContentType::Time, ValueType::TimeDuration)
+                | (ContentType::Boolean, ValueType::Boolean)
+                | (
+                    ContentType::String | ContentType::Error,
+                    ValueType::Text | ValueType::TextXml
+                )
+        )
+    }
+}
+
+impl Display for ContentType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            ContentType::Float => write!(f, "float"),
+            ContentType::Percentage => write!(f, "percentage"),
+            ContentType::Currency => write!(f, "currency"),
+            ContentType::Date => write!(f, "date"),
+            ContentType::Time => write!(f, "time"),
+            ContentType::Boolean => write!(f, "boolean"),
+            ContentType::String => write!(f, "string"),
+            ContentType::Error => write!(f, "error"),
+        }
+    }
+}
+
 /// Datatypes for the values. Only the discriminants of the Value enum.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[allow(missing_docs)]
@@ -2674,9 +5693,130 @@ impl Value {
     }
 
     /// Create a percentage value.
+    ///
+    /// The value is stored as a fraction, as in the ODS file:
+    /// 0.15 is displayed as 15%. See also percent_from_fraction() and
+    /// percent_from_percent().
     pub fn new_percentage(value: f64) -> Self {
         Value::Percentage(value)
     }
+
+    /// Create a percentage value from a fraction. 0.15 is 15%.
+    pub fn percent_from_fraction(fraction: f64) -> Self {
+        Value::Percentage(fraction)
+    }
+
+    /// Create a percentage value from a percent number. 15.0 is 15%.
+    pub fn percent_from_percent(percent: f64) -> Self {
+        Value::Percentage(percent / 100.0)
+    }
+
+    /// Returns a percentage value as fraction. 15% is 0.15.
+    /// None if the value is not a percentage.
+    pub fn as_fraction(&self) -> Option<f64> {
+        match self {
+            Value::Percentage(p) => Some(*p),
+            _ => None,
+        }
+    }
+
+    /// Returns a percentage value as percent number. 15% is 15.0.
+    /// None if the value is not a percentage.
+    pub fn as_percent(&self) -> Option<f64> {
+        match self {
+            Value::Percentage(p) => Some(*p * 100.0),
+            _ => None,
+        }
+    }
+
+    /// Create a datetime value from a timezone aware datetime.
+    /// The spreadsheet has no concept of timezones, the policy decides
+    /// which date and time are stored.
+    pub fn new_datetime_tz<Tz: TimeZone>(dt: &DateTime<Tz>, policy: TzPolicy) -> Self {
+        match policy {
+            TzPolicy::Utc => Value::DateTime(dt.naive_utc()),
+            TzPolicy::Local => Value::DateTime(dt.naive_local()),
+        }
+    }
+}
+
+/// Conversion of a cell value to a rust type. Used by
+/// Sheet::col_values_as().
+pub trait FromValue: Sized {
+    /// Converts the value. Returns None if the value has the wrong type.
+    fn from_value(value: &Value) -> Option<Self>;
+}
+
+macro_rules! from_value {
+    ($t:ty, $f:ident) => {
+        impl FromValue for $t {
+            fn from_value(value: &Value) -> Option<Self> {
+                value.$f()
+            }
+        }
+    };
+}
+
+from_value!(i32, as_i32_opt);
+from_value!(u32, as_u32_opt);
+from_value!(f64, as_f64_opt);
+from_value!(NaiveDateTime, as_datetime_opt);
+from_value!(NaiveDate, as_date_opt);
+from_value!(Duration, as_timeduration_opt);
+#[cfg(feature = "use_decimal")]
+from_value!(Decimal, as_decimal_opt);
+
+impl FromValue for bool {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Boolean(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for String {
+    /// Text and markup text. The markup is removed.
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Text(_) | Value::TextXml(_) => Some(value.as_cow_str_or("").into_owned()),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for Value {
+    fn from_value(value: &Value) -> Option<Self> {
+        Some(value.clone())
+    }
+}
+
+/// Defines how a timezone aware datetime is converted to the
+/// datetime value of a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TzPolicy {
+    /// The datetime is converted to UTC.
+    Utc,
+    /// The local date and time are kept, the offset is dropped.
+    Local,
+}
+
+/// Defines how float, percentage and currency values are written to
+/// office:value. See WorkBook::set_number_policy().
+///
+/// The digits are rounded in decimal, starting from the shortest
+/// representation of the f64, so 2.675 is treated as 2.675 and not as
+/// the 2.67499999... it is stored as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberPolicy {
+    /// The shortest representation that reads back as the same f64.
+    #[default]
+    Exact,
+    /// At most n significant digits, halves are rounded away from zero.
+    Significant(u8),
+    /// At most n significant digits, halves are rounded to even
+    /// (banker's rounding).
+    SignificantEven(u8),
 }
 
 impl Default for Value {
@@ -2693,7 +5833,7 @@ macro_rules! currency {
     };
 }
 
-/// currency value
+/// percentage value, given as fraction.
 #[macro_export]
 macro_rules! percent {
     ($v:expr) => {
@@ -2915,3 +6055,102 @@ impl From<Option<Duration>> for Value {
         }
     }
 }
+
+/// Uses TzPolicy::Utc.
+impl From<DateTime<Utc>> for Value {
+    fn from(dt: DateTime<Utc>) -> Self {
+        Value::new_datetime_tz(&dt, TzPolicy::Utc)
+    }
+}
+
+/// Uses TzPolicy::Utc. Use Value::new_datetime_tz() to keep the local time.
+impl From<DateTime<FixedOffset>> for Value {
+    fn from(dt: DateTime<FixedOffset>) -> Self {
+        Value::new_datetime_tz(&dt, TzPolicy::Utc)
+    }
+}
+
+/// Uses TzPolicy::Utc.
+impl From<std::time::SystemTime> for Value {
+    fn from(st: std::time::SystemTime) -> Self {
+        Value::from(DateTime::<Utc>::from(st))
+    }
+}
+
+/// Durations beyond the range of chrono::Duration are clamped.
+impl From<std::time::Duration> for Value {
+    fn from(d: std::time::Duration) -> Self {
+        Value::TimeDuration(
+            Duration::from_std(d).unwrap_or_else(|_| Duration::milliseconds(i64::MAX)),
+        )
+    }
+}
+
+#[cfg(feature = "use_time")]
+impl From<time::Date> for Value {
+    fn from(d: time::Date) -> Self {
+        Value::DateTime(time_date(d).and_hms_opt(0, 0, 0).expect("valid time"))
+    }
+}
+
+#[cfg(feature = "use_time")]
+impl From<time::Time> for Value {
+    fn from(t: time::Time) -> Self {
+        Value::DateTime(NaiveDateTime::new(
+            NaiveDate::from_ymd_opt(1900, 1, 1).expect("valid date"),
+            time_time(t),
+        ))
+    }
+}
+
+#[cfg(feature = "use_time")]
+impl From<time::PrimitiveDateTime> for Value {
+    fn from(dt: time::PrimitiveDateTime) -> Self {
+        Value::DateTime(NaiveDateTime::new(
+            time_date(dt.date()),
+            time_time(dt.time()),
+        ))
+    }
+}
+
+/// Uses TzPolicy::Utc.
+#[cfg(feature = "use_time")]
+impl From<time::OffsetDateTime> for Value {
+    fn from(dt: time::OffsetDateTime) -> Self {
+        let dt = dt.to_offset(time::UtcOffset::UTC);
+        Value::DateTime(NaiveDateTime::new(
+            time_date(dt.date()),
+            time_time(dt.time()),
+        ))
+    }
+}
+
+/// Durations beyond the range of chrono::Duration are clamped.
+#[cfg(feature = "use_time")]
+impl From<time::Duration> for Value {
+    fn from(d: time::Duration) -> Self {
+        let secs = d
+            .whole_seconds()
+            .clamp(-i64::MAX / 1000 + 1, i64::MAX / 1000 - 1);
+        Value::TimeDuration(
+            Duration::seconds(secs) + Duration::nanoseconds(d.subsec_nanoseconds() as i64),
+        )
+    }
+}
+
+#[cfg(feature = "use_time")]
+// time::Date always fits into a NaiveDate.
+fn time_date(d: time::Date) -> NaiveDate {
+    NaiveDate::from_ymd_opt(d.year(), d.month() as u32, d.day() as u32).expect("valid date")
+}
+
+#[cfg(feature = "use_time")]
+fn time_time(t: time::Time) -> NaiveTime {
+    NaiveTime::from_hms_nano_opt(
+        t.hour() as u32,
+        t.minute() as u32,
+        t.second() as u32,
+        t.nanosecond(),
+    )
+    .expect("valid time")
+}
@@ -58,3 +58,56 @@ lazy_static! {
 pub(crate) fn localized_format(locale: Locale) -> Option<&'static dyn LocalizedValueFormat> {
     LOCALE_DATA.get(&locale).copied()
 }
+
+/// Currency symbols by country and ISO 4217 currency code.
+/// Used as fallback if a currency-symbol has no explicit symbol text.
+const CURRENCY_SYMBOLS: &[(&str, &str, &str)] = &[
+    ("AT", "EUR", "€"),
+    ("BE", "EUR", "€"),
+    ("DE", "EUR", "€"),
+    ("ES", "EUR", "€"),
+    ("FI", "EUR", "€"),
+    ("FR", "EUR", "€"),
+    ("GR", "EUR", "€"),
+    ("IE", "EUR", "€"),
+    ("IT", "EUR", "€"),
+    ("LU", "EUR", "€"),
+    ("NL", "EUR", "€"),
+    ("PT", "EUR", "€"),
+    ("SI", "EUR", "€"),
+    ("SK", "EUR", "€"),
+    ("US", "USD", "$"),
+    ("CA", "CAD", "$"),
+    ("AU", "AUD", "$"),
+    ("GB", "GBP", "£"),
+    ("JP", "JPY", "¥"),
+    ("CN", "CNY", "¥"),
+    ("IN", "INR", "₹"),
+    ("KR", "KRW", "₩"),
+    ("RU", "RUB", "₽"),
+    ("TR", "TRY", "₺"),
+    ("BR", "BRL", "R$"),
+    ("CH", "CHF", "CHF"),
+    ("PL", "PLN", "zł"),
+    ("CZ", "CZK", "Kč"),
+    ("HU", "HUF", "Ft"),
+    ("SE", "SEK", "kr"),
+    ("NO", "NOK", "kr"),
+    ("DK", "DKK", "kr"),
+];
+
+/// Currency symbol for a country code.
+pub(crate) fn currency_symbol_for_country(country: &str) -> Option<&'static str> {
+    CURRENCY_SYMBOLS
+        .iter()
+        .find(|(c, _, _)| *c == country)
+        .map(|(_, _, s)| *s)
+}
+
+/// Currency symbol for an ISO 4217 currency code.
+pub(crate) fn currency_symbol_for_code(code: &str) -> Option<&'static str> {
+    CURRENCY_SYMBOLS
+        .iter()
+        .find(|(_, c, _)| *c == code)
+        .map(|(_, _, s)| *s)
+}
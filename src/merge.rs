@@ -0,0 +1,922 @@
+//!
+//! Appends one workbook to another.
+//!
+
+use crate::attrmap2::AttrMap2;
+use crate::condition::Condition;
+use crate::format::{
+    ValueFormatBoolean, ValueFormatCurrency, ValueFormatDateTime, ValueFormatNumber,
+    ValueFormatPercentage, ValueFormatText, ValueFormatTimeDuration, ValueFormatTrait,
+};
+use crate::formula::rename_name;
+use crate::io::filebuf::FileBufEntry;
+use crate::style::{
+    CellStyle, ColStyle, FontFaceDecl, GraphicStyle, HeaderFooter, MasterPage, PageStyle,
+    ParagraphStyle, RowStyle, StyleUse, TableStyle, TextStyle,
+};
+use crate::text::TextTag;
+use crate::validation::Validation;
+use crate::xmltree::{XmlContent, XmlTag};
+use crate::{is_media_path, names, tableref, Sheet, Value, WorkBook};
+use std::collections::{HashMap, HashSet};
+
+/// Moves the sheets and styles of other into book.
+pub(crate) fn append(book: &mut WorkBook, mut other: WorkBook) {
+    // Renamed sheets get the references in the formulas of other fixed
+    // before they are moved. Sheet names are compared without regard
+    // to case.
+    let existing: HashSet<String> = book.sheets.iter().map(|v| v.name.to_lowercase()).collect();
+    let mut used = existing.clone();
+    used.extend(other.sheets.iter().map(|v| v.name.to_lowercase()));
+    for idx in 0..other.sheets.len() {
+        let name = other.sheet(idx).name.clone();
+        if existing.contains(&name.to_lowercase()) {
+            let new_name = unique_name(&name, |v| used.contains(&v.to_lowercase()));
+            used.insert(new_name.to_lowercase());
+            tableref::rename_sheet(&mut other, idx, new_name).expect("unique sheet name");
+        }
+    }
+
+    move_names(book, &mut other);
+    move_media(book, &mut other);
+    move_extra(book, &mut other);
+
+    let renames = Renames::new(book, &other, false);
+    rebind_styles(&mut other, &renames);
+    move_all_styles(book, &mut other, &renames);
+
+    for (value_type, mut style) in other.def_styles {
+        if let Some(name) = renames.cellstyles.get(style.as_str()) {
+            style = name.into();
+        }
+        book.def_styles.entry(value_type).or_insert(style);
+    }
+
+    move_styles(
+        &mut book.validations,
+        other.validations,
+        &renames.validations,
+    );
+
+    for sheet in other.sheets {
+        let mut sheet = sheet.take();
+        rebind_sheet(&mut sheet, &renames);
+        // new to this book.
+        sheet.mark_all_dirty();
+        book.sheets.push(sheet.into());
+    }
+}
+
+// Moves the named ranges and named expressions of the workbook. A name
+// that exists with another definition is renamed, and the formulas of
+// other are changed, except in sheets that have a local name that
+// hides it. Identical definitions are dropped.
+fn move_names(book: &mut WorkBook, other: &mut WorkBook) {
+    let mut moved = Vec::new();
+    for tag in other
+        .extra
+        .iter_mut()
+        .filter(|v| v.name() == "table:named-expressions")
+    {
+        for content in std::mem::take(tag.content_mut()) {
+            if let XmlContent::Tag(t) = content {
+                moved.push(t);
+            }
+        }
+    }
+    other
+        .extra
+        .retain(|v| v.name() != "table:named-expressions");
+
+    let mut used: HashSet<String> = named_tags(&book.extra)
+        .chain(moved.iter())
+        .filter_map(|v| v.attrmap().attr("table:name"))
+        .map(|v| v.to_lowercase())
+        .collect();
+    let mut renames = Vec::new();
+    for tag in moved.iter_mut() {
+        let name = match tag.attrmap().attr("table:name") {
+            Some(name) => name.clone(),
+            None => continue,
+        };
+        let existing = named_tags(&book.extra).find(
+            |v| matches!(v.attrmap().attr("table:name"), Some(v) if v.eq_ignore_ascii_case(&name)),
+        );
+        if let Some(existing) = existing {
+            if existing.name() == tag.name() && same_attrs(existing, tag) {
+                tag.set_name("");
+                continue;
+            }
+            let new_name = unique_name(&name, |v| used.contains(&v.to_lowercase()));
+            used.insert(new_name.to_lowercase());
+            tag.set_attr("table:name", new_name.clone());
+            renames.push((name, new_name));
+        }
+    }
+
+    for (name, new_name) in &renames {
+        for tag in moved.iter_mut() {
+            if let Some(expr) = tag.attrmap().attr("table:expression") {
+                let expr = rename_name(expr, name, new_name);
+                tag.set_attr("table:expression", expr);
+            }
+        }
+        for valid in other.validations.values_mut() {
+            let condition = rename_name(valid.condition(), name, new_name);
+            if condition != valid.condition() {
+                valid.set_condition(Condition::new(condition));
+            }
+        }
+        for idx in 0..other.sheets.len() {
+            if names::named_range(&other.sheet(idx).extra, name).is_some() {
+                continue;
+            }
+            let sheet = other.sheet_mut(idx);
+            for cell in sheet.data.values_mut() {
+                if let Some(formula) = &mut cell.formula {
+                    *formula = rename_name(formula, name, new_name);
+                }
+            }
+        }
+    }
+
+    moved.retain(|v| !v.name().is_empty());
+    if moved.is_empty() {
+        return;
+    }
+    let idx = match book
+        .extra
+        .iter()
+        .position(|v| v.name() == "table:named-expressions")
+    {
+        Some(idx) => idx,
+        None => {
+            book.extra.push(XmlTag::new("table:named-expressions"));
+            book.extra.len() - 1
+        }
+    };
+    for tag in moved {
+        book.extra[idx].add_tag(tag);
+    }
+}
+
+// The named ranges and named expressions.
+fn named_tags(extra: &[XmlTag]) -> impl Iterator<Item = &XmlTag> {
+    extra
+        .iter()
+        .filter(|v| v.name() == "table:named-expressions")
+        .flat_map(|v| v.content())
+        .filter_map(|v| match v {
+            XmlContent::Tag(t) => Some(t),
+            XmlContent::Text(_) => None,
+        })
+}
+
+fn same_attrs(a: &XmlTag, b: &XmlTag) -> bool {
+    a.attrmap().iter().count() == b.attrmap().iter().count()
+        && a.attrmap()
+            .iter()
+            .all(|(k, v)| b.attrmap().attr(k) == Some(v))
+}
+
+// Moves the pictures, media and embedded objects. Files that exist in
+// book are renamed, and the xlink:href of the shapes of other are
+// changed accordingly.
+fn move_media(book: &mut WorkBook, other: &mut WorkBook) {
+    let mut renames: Vec<(String, String)> = Vec::new();
+    for entry in other.filebuf.iter() {
+        let path = match entry {
+            FileBufEntry::File(path, _) if is_media_path(path) => path,
+            _ => continue,
+        };
+        let (dir, file) = path.split_once('/').unwrap_or(("", path));
+        // Embedded objects are renamed as a whole directory, together
+        // with their replacement image.
+        let (old, replacement) = if dir.starts_with("Object ") {
+            (dir.to_string(), Some(format!("ObjectReplacements/{}", dir)))
+        } else if dir == "ObjectReplacements" && file.starts_with("Object ") {
+            (file.to_string(), Some(path.clone()))
+        } else {
+            (path.clone(), None)
+        };
+        if renames.iter().any(|(v, _)| *v == old) {
+            continue;
+        }
+        let is_used = |v: &str| {
+            book.filebuf.contains_path(v)
+                || book.filebuf.contains_path(format!("{}/", v))
+                || other.filebuf.contains_path(v)
+                || other.filebuf.contains_path(format!("{}/", v))
+                || renames.iter().any(|(_, n)| n == v)
+        };
+        if !is_used_in(book, &old) {
+            continue;
+        }
+        let new = match old.rsplit_once('.') {
+            Some((stem, ext)) if !stem.ends_with('/') && !ext.contains('/') => {
+                let stem = unique_name(stem, |v| is_used(&format!("{}.{}", v, ext)));
+                format!("{}.{}", stem, ext)
+            }
+            _ => unique_name(&old, is_used),
+        };
+        if let Some(replacement) = replacement {
+            let new_name = new.rsplit('/').next().unwrap_or(&new).to_string();
+            renames.push((replacement, format!("ObjectReplacements/{}", new_name)));
+            renames.push((old.rsplit('/').next().unwrap_or(&old).to_string(), new_name));
+        } else {
+            renames.push((old, new));
+        }
+    }
+
+    for entry in other.filebuf.iter() {
+        match entry {
+            FileBufEntry::Dir(path) if is_media_path(path) => {
+                let path = renamed_path(path, &renames);
+                if !book.filebuf.contains(&path) {
+                    book.filebuf.push_dir(path);
+                }
+            }
+            FileBufEntry::File(path, buf) if is_media_path(path) => {
+                book.filebuf
+                    .push_shared(renamed_path(path, &renames), buf.clone());
+            }
+            _ => {}
+        }
+    }
+    for manifest in other.manifest.iter() {
+        if is_media_path(&manifest.full_path) {
+            let mut manifest = manifest.clone();
+            manifest.full_path = renamed_path(&manifest.full_path, &renames);
+            if !book
+                .manifest
+                .iter()
+                .any(|v| v.full_path == manifest.full_path)
+            {
+                book.manifest.push(manifest);
+            }
+        }
+    }
+
+    if renames.is_empty() {
+        return;
+    }
+    for tag in other.extra.iter_mut() {
+        rename_hrefs(tag, &renames);
+    }
+    for idx in 0..other.sheets.len() {
+        let sheet = other.sheet(idx);
+        let has_shapes = !sheet.extra.is_empty()
+            || sheet
+                .data
+                .values()
+                .any(|v| matches!(&v.extra, Some(extra) if !extra.shapes.is_empty()));
+        if !has_shapes {
+            continue;
+        }
+        let sheet = other.sheet_mut(idx);
+        for tag in sheet.extra.iter_mut() {
+            rename_hrefs(tag, &renames);
+        }
+        for cell in sheet.data.values_mut() {
+            if let Some(extra) = &mut cell.extra {
+                for tag in extra.shapes.iter_mut() {
+                    rename_hrefs(tag, &renames);
+                }
+            }
+        }
+    }
+}
+
+// Is there a file or directory with this name.
+fn is_used_in(book: &WorkBook, path: &str) -> bool {
+    book.filebuf.contains_path(path) || book.filebuf.contains_path(format!("{}/", path))
+}
+
+// Applies the first matching rename to a path or to the directory
+// the path is in.
+fn renamed_path(path: &str, renames: &[(String, String)]) -> String {
+    for (old, new) in renames {
+        if path == old {
+            return new.clone();
+        }
+        if let Some(rest) = path.strip_prefix(old.as_str()) {
+            if rest.starts_with('/') {
+                return format!("{}{}", new, rest);
+            }
+        }
+    }
+    path.to_string()
+}
+
+// Changes the links to renamed files.
+fn rename_hrefs(tag: &mut XmlTag, renames: &[(String, String)]) {
+    if let Some(href) = tag.attrmap().attr("xlink:href") {
+        let (prefix, path) = match href.strip_prefix("./") {
+            Some(path) => ("./", path),
+            None => ("", href.as_str()),
+        };
+        let new_path = renamed_path(path, renames);
+        if new_path != path {
+            let href = format!("{}{}", prefix, new_path);
+            tag.set_attr("xlink:href", href);
+        }
+    }
+    for content in tag.content_mut() {
+        if let XmlContent::Tag(t) = content {
+            rename_hrefs(t, renames);
+        }
+    }
+}
+
+// Moves the other content of office:spreadsheet. The database ranges,
+// pilot tables, DDE links and label ranges are added to the existing
+// ones, for all other elements the one of book is kept.
+fn move_extra(book: &mut WorkBook, other: &mut WorkBook) {
+    for mut tag in std::mem::take(&mut other.extra) {
+        let merged = matches!(
+            tag.name(),
+            "table:database-ranges"
+                | "table:data-pilot-tables"
+                | "table:dde-links"
+                | "table:label-ranges"
+        );
+        match book.extra.iter_mut().find(|v| v.name() == tag.name()) {
+            Some(existing) if merged => {
+                for content in std::mem::take(tag.content_mut()) {
+                    if let XmlContent::Tag(mut t) = content {
+                        if add_extra_name(existing, &mut t) {
+                            existing.add_tag(t);
+                        }
+                    }
+                }
+            }
+            Some(_) => {}
+            None => book.extra.push(tag),
+        }
+    }
+}
+
+// Database ranges and pilot tables need a unique name, DDE links to
+// the same source exist only once. Returns false if the element is
+// not needed.
+fn add_extra_name(container: &XmlTag, tag: &mut XmlTag) -> bool {
+    let children = || {
+        container.content().iter().filter_map(|v| match v {
+            XmlContent::Tag(t) => Some(t),
+            XmlContent::Text(_) => None,
+        })
+    };
+    if tag.name() == "table:dde-link" {
+        let source = dde_source(tag);
+        return source.is_none() || !children().any(|v| dde_source(v) == source);
+    }
+    if let Some(name) = tag.attrmap().attr("table:name").cloned() {
+        let is_used = |v: &str| {
+            children().any(|t| t.attrmap().attr("table:name").map(|n| n.as_str()) == Some(v))
+        };
+        if is_used(&name) {
+            let new_name = unique_name(&name, is_used);
+            tag.set_attr("table:name", new_name);
+        }
+    }
+    true
+}
+
+fn dde_source(tag: &XmlTag) -> Option<&String> {
+    tag.content().iter().find_map(|v| match v {
+        XmlContent::Tag(t) if t.name() == "office:dde-source" => t.attrmap().attr("office:name"),
+        _ => None,
+    })
+}
+
+/// Moves the styles of other into book. Existing styles with the same
+/// name are replaced, except for automatic styles. These are renamed
+/// the same way as with append.
+pub(crate) fn import_styles(book: &mut WorkBook, mut other: WorkBook) {
+    let renames = Renames::new(book, &other, true);
+    rebind_styles(&mut other, &renames);
+    move_all_styles(book, &mut other, &renames);
+}
+
+// New names for the styles of other, per style family.
+#[derive(Default)]
+struct Renames {
+    // All value formats share one namespace.
+    formats: HashMap<String, String>,
+    fonts: HashMap<String, String>,
+    tablestyles: HashMap<String, String>,
+    rowstyles: HashMap<String, String>,
+    colstyles: HashMap<String, String>,
+    cellstyles: HashMap<String, String>,
+    paragraphstyles: HashMap<String, String>,
+    textstyles: HashMap<String, String>,
+    graphicstyles: HashMap<String, String>,
+    pagestyles: HashMap<String, String>,
+    masterpages: HashMap<String, String>,
+    validations: HashMap<String, String>,
+}
+
+impl Renames {
+    // With only_automatic only the automatic styles of other are renamed,
+    // all others replace the existing ones.
+    fn new(book: &WorkBook, other: &WorkBook, only_automatic: bool) -> Self {
+        Self {
+            formats: renames(
+                format_names(book, false),
+                format_names(other, only_automatic),
+            ),
+            fonts: style_renames(&book.fonts, &other.fonts, only_automatic),
+            tablestyles: style_renames(&book.tablestyles, &other.tablestyles, only_automatic),
+            rowstyles: style_renames(&book.rowstyles, &other.rowstyles, only_automatic),
+            colstyles: style_renames(&book.colstyles, &other.colstyles, only_automatic),
+            cellstyles: style_renames(&book.cellstyles, &other.cellstyles, only_automatic),
+            paragraphstyles: style_renames(
+                &book.paragraphstyles,
+                &other.paragraphstyles,
+                only_automatic,
+            ),
+            textstyles: style_renames(&book.textstyles, &other.textstyles, only_automatic),
+            graphicstyles: style_renames(&book.graphicstyles, &other.graphicstyles, only_automatic),
+            pagestyles: style_renames(&book.pagestyles, &other.pagestyles, only_automatic),
+            masterpages: style_renames(&book.masterpages, &other.masterpages, only_automatic),
+            validations: style_renames(&book.validations, &other.validations, only_automatic),
+        }
+    }
+}
+
+// Moves all styles and formats except the validations.
+fn move_all_styles(book: &mut WorkBook, other: &mut WorkBook, renames: &Renames) {
+    let formats = &renames.formats;
+    move_styles(
+        &mut book.formats_boolean,
+        std::mem::take(&mut *other.formats_boolean),
+        formats,
+    );
+    move_styles(
+        &mut book.formats_number,
+        std::mem::take(&mut *other.formats_number),
+        formats,
+    );
+    move_styles(
+        &mut book.formats_percentage,
+        std::mem::take(&mut *other.formats_percentage),
+        formats,
+    );
+    move_styles(
+        &mut book.formats_currency,
+        std::mem::take(&mut *other.formats_currency),
+        formats,
+    );
+    move_styles(
+        &mut book.formats_text,
+        std::mem::take(&mut *other.formats_text),
+        formats,
+    );
+    move_styles(
+        &mut book.formats_datetime,
+        std::mem::take(&mut *other.formats_datetime),
+        formats,
+    );
+    move_styles(
+        &mut book.formats_timeduration,
+        std::mem::take(&mut *other.formats_timeduration),
+        formats,
+    );
+
+    move_styles(
+        &mut book.fonts,
+        std::mem::take(&mut *other.fonts),
+        &renames.fonts,
+    );
+    move_styles(
+        &mut book.tablestyles,
+        std::mem::take(&mut *other.tablestyles),
+        &renames.tablestyles,
+    );
+    move_styles(
+        &mut book.rowstyles,
+        std::mem::take(&mut *other.rowstyles),
+        &renames.rowstyles,
+    );
+    move_styles(
+        &mut book.colstyles,
+        std::mem::take(&mut *other.colstyles),
+        &renames.colstyles,
+    );
+    move_styles(
+        &mut book.cellstyles,
+        std::mem::take(&mut *other.cellstyles),
+        &renames.cellstyles,
+    );
+    move_styles(
+        &mut book.paragraphstyles,
+        std::mem::take(&mut *other.paragraphstyles),
+        &renames.paragraphstyles,
+    );
+    move_styles(
+        &mut book.textstyles,
+        std::mem::take(&mut *other.textstyles),
+        &renames.textstyles,
+    );
+    move_styles(
+        &mut book.graphicstyles,
+        std::mem::take(&mut *other.graphicstyles),
+        &renames.graphicstyles,
+    );
+    move_styles(
+        &mut book.pagestyles,
+        std::mem::take(&mut *other.pagestyles),
+        &renames.pagestyles,
+    );
+    move_styles(
+        &mut book.masterpages,
+        std::mem::take(&mut *other.masterpages),
+        &renames.masterpages,
+    );
+}
+
+// Replaces a renamed style reference.
+macro_rules! rebind {
+    ($style:expr, $renames:expr) => {
+        if let Some(name) = $style.as_ref().and_then(|v| $renames.get(v.as_str())) {
+            $style = Some(name.into());
+        }
+    };
+}
+
+// Changes all references between the styles.
+fn rebind_styles(book: &mut WorkBook, r: &Renames) {
+    rebind_formats(&mut book.formats_boolean, &r.formats);
+    rebind_formats(&mut book.formats_number, &r.formats);
+    rebind_formats(&mut book.formats_percentage, &r.formats);
+    rebind_formats(&mut book.formats_currency, &r.formats);
+    rebind_formats(&mut book.formats_text, &r.formats);
+    rebind_formats(&mut book.formats_datetime, &r.formats);
+    rebind_formats(&mut book.formats_timeduration, &r.formats);
+
+    for style in book.tablestyles.values_mut() {
+        rename(
+            style.attrmap_mut(),
+            "style:parent-style-name",
+            &r.tablestyles,
+        );
+        rename(
+            style.attrmap_mut(),
+            "style:master-page-name",
+            &r.masterpages,
+        );
+    }
+    for style in book.rowstyles.values_mut() {
+        rename(style.attrmap_mut(), "style:parent-style-name", &r.rowstyles);
+    }
+    for style in book.colstyles.values_mut() {
+        rename(style.attrmap_mut(), "style:parent-style-name", &r.colstyles);
+    }
+    for style in book.cellstyles.values_mut() {
+        rename(
+            style.attrmap_mut(),
+            "style:parent-style-name",
+            &r.cellstyles,
+        );
+        rename(style.attrmap_mut(), "style:data-style-name", &r.formats);
+        rename_fonts(style.textstyle_mut(), &r.fonts);
+        if style.stylemaps().is_some() {
+            for stylemap in style.stylemaps_mut() {
+                if let Some(name) = r.cellstyles.get(stylemap.applied_style()) {
+                    stylemap.set_applied_style(name.as_str());
+                }
+            }
+        }
+    }
+    for style in book.paragraphstyles.values_mut() {
+        rename(
+            style.attrmap_mut(),
+            "style:parent-style-name",
+            &r.paragraphstyles,
+        );
+        rename(
+            style.attrmap_mut(),
+            "style:next-style-name",
+            &r.paragraphstyles,
+        );
+        rename(
+            style.attrmap_mut(),
+            "style:master-page-name",
+            &r.masterpages,
+        );
+        rename_fonts(style.textstyle_mut(), &r.fonts);
+    }
+    for style in book.textstyles.values_mut() {
+        rename(
+            style.attrmap_mut(),
+            "style:parent-style-name",
+            &r.textstyles,
+        );
+        rename_fonts(style.textstyle_mut(), &r.fonts);
+    }
+    for style in book.graphicstyles.values_mut() {
+        rename(
+            style.attrmap_mut(),
+            "style:parent-style-name",
+            &r.graphicstyles,
+        );
+        rename_fonts(style.textstyle_mut(), &r.fonts);
+    }
+    for masterpage in book.masterpages.values_mut() {
+        if let Some(name) = r.pagestyles.get(masterpage.pagestyle()) {
+            masterpage.set_pagestyle(&name.into());
+        }
+        rebind_header_footer(masterpage.header_mut(), r);
+        rebind_header_footer(masterpage.header_first_mut(), r);
+        rebind_header_footer(masterpage.header_left_mut(), r);
+        rebind_header_footer(masterpage.footer_mut(), r);
+        rebind_header_footer(masterpage.footer_first_mut(), r);
+        rebind_header_footer(masterpage.footer_left_mut(), r);
+    }
+}
+
+fn rebind_header_footer(hf: &mut HeaderFooter, r: &Renames) {
+    for tag in hf.left_mut() {
+        rebind_text(tag, r);
+    }
+    for tag in hf.center_mut() {
+        rebind_text(tag, r);
+    }
+    for tag in hf.right_mut() {
+        rebind_text(tag, r);
+    }
+    for tag in hf.content_mut() {
+        rebind_text(tag, r);
+    }
+}
+
+fn rebind_formats<T: ValueFormatTrait>(
+    formats: &mut HashMap<String, T>,
+    renames: &HashMap<String, String>,
+) {
+    for format in formats.values_mut() {
+        if format.stylemaps().is_some() {
+            for stylemap in format.stylemaps_mut() {
+                if let Some(name) = renames.get(stylemap.applied_style()) {
+                    stylemap.set_applied_style(name.as_str());
+                }
+            }
+        }
+    }
+}
+
+// Changes all the style references of the sheet.
+fn rebind_sheet(sheet: &mut Sheet, r: &Renames) {
+    rebind!(sheet.style, r.tablestyles);
+    rebind!(sheet.default_cellstyle, r.cellstyles);
+    for header in sheet.col_header.values_mut() {
+        rebind!(header.style, r.colstyles);
+        rebind!(header.cellstyle, r.cellstyles);
+    }
+    for header in sheet.row_header.values_mut() {
+        rebind!(header.style, r.rowstyles);
+        rebind!(header.cellstyle, r.cellstyles);
+    }
+    for tag in sheet.extra.iter_mut() {
+        rebind_text(tag, r);
+    }
+    for cell in sheet.data.values_mut() {
+        rebind!(cell.style, r.cellstyles);
+        if let Value::TextXml(text) = &mut cell.value {
+            for tag in text.iter_mut() {
+                rebind_text(tag, r);
+            }
+        }
+        if let Some(extra) = &mut cell.extra {
+            if let Some(name) = &mut extra.validation_name {
+                if let Some(new_name) = r.validations.get(name) {
+                    *name = new_name.clone();
+                }
+            }
+            for tag in extra.shapes.iter_mut() {
+                rebind_text(tag, r);
+            }
+        }
+    }
+}
+
+// Changes the style references in text and shapes.
+fn rebind_text(tag: &mut TextTag, r: &Renames) {
+    let paragraph = tag.name() == "text:p" || tag.name() == "text:h";
+    let attr = tag.attrmap_mut();
+    if paragraph {
+        rename(attr, "text:style-name", &r.paragraphstyles);
+    } else {
+        rename(attr, "text:style-name", &r.textstyles);
+    }
+    rename(attr, "text:visited-style-name", &r.textstyles);
+    rename(attr, "draw:style-name", &r.graphicstyles);
+    rename(attr, "draw:text-style-name", &r.paragraphstyles);
+
+    for content in tag.content_mut() {
+        if let XmlContent::Tag(t) = content {
+            rebind_text(t, r);
+        }
+    }
+}
+
+// Names of the value formats.
+fn format_names(book: &WorkBook, only_automatic: bool) -> impl Iterator<Item = &String> {
+    names(&book.formats_boolean, only_automatic)
+        .chain(names(&book.formats_number, only_automatic))
+        .chain(names(&book.formats_percentage, only_automatic))
+        .chain(names(&book.formats_currency, only_automatic))
+        .chain(names(&book.formats_text, only_automatic))
+        .chain(names(&book.formats_datetime, only_automatic))
+        .chain(names(&book.formats_timeduration, only_automatic))
+}
+
+fn names<T: Rename>(
+    styles: &HashMap<String, T>,
+    only_automatic: bool,
+) -> impl Iterator<Item = &String> {
+    styles
+        .iter()
+        .filter(move |(_, v)| !only_automatic || v.is_automatic())
+        .map(|(k, _)| k)
+}
+
+// New names for the styles of src that exist in dst.
+fn style_renames<T: Rename>(
+    dst: &HashMap<String, T>,
+    src: &HashMap<String, T>,
+    only_automatic: bool,
+) -> HashMap<String, String> {
+    renames(dst.keys(), names(src, only_automatic))
+}
+
+// Moves the styles and applies the new names.
+fn move_styles<T: Rename>(
+    dst: &mut HashMap<String, T>,
+    src: HashMap<String, T>,
+    renames: &HashMap<String, String>,
+) {
+    for (_, mut style) in src {
+        if let Some(name) = renames.get(style.style_name()) {
+            style.set_style_name(name);
+        }
+        dst.insert(style.style_name().to_string(), style);
+    }
+}
+
+// Name access for everything that is renamed.
+trait Rename {
+    fn style_name(&self) -> &str;
+
+    fn set_style_name(&mut self, name: &str);
+
+    // Automatic styles are renamed by import_styles(), all others
+    // replace the existing ones.
+    fn is_automatic(&self) -> bool;
+}
+
+macro_rules! rename_style {
+    ($t:ty) => {
+        impl Rename for $t {
+            fn style_name(&self) -> &str {
+                self.name()
+            }
+
+            fn set_style_name(&mut self, name: &str) {
+                self.set_name(name);
+            }
+
+            fn is_automatic(&self) -> bool {
+                self.styleuse() == StyleUse::Automatic
+            }
+        }
+    };
+}
+
+rename_style!(TableStyle);
+rename_style!(RowStyle);
+rename_style!(ColStyle);
+rename_style!(CellStyle);
+rename_style!(ParagraphStyle);
+rename_style!(TextStyle);
+rename_style!(GraphicStyle);
+rename_style!(ValueFormatBoolean);
+rename_style!(ValueFormatNumber);
+rename_style!(ValueFormatPercentage);
+rename_style!(ValueFormatCurrency);
+rename_style!(ValueFormatText);
+rename_style!(ValueFormatDateTime);
+rename_style!(ValueFormatTimeDuration);
+
+impl Rename for FontFaceDecl {
+    fn style_name(&self) -> &str {
+        self.name()
+    }
+
+    fn set_style_name(&mut self, name: &str) {
+        self.set_name(name);
+    }
+
+    fn is_automatic(&self) -> bool {
+        false
+    }
+}
+
+// Page layouts are always automatic styles.
+impl Rename for PageStyle {
+    fn style_name(&self) -> &str {
+        self.name()
+    }
+
+    fn set_style_name(&mut self, name: &str) {
+        self.set_name(name);
+    }
+
+    fn is_automatic(&self) -> bool {
+        true
+    }
+}
+
+impl Rename for MasterPage {
+    fn style_name(&self) -> &str {
+        self.name()
+    }
+
+    fn set_style_name(&mut self, name: &str) {
+        self.set_name(name.to_string());
+    }
+
+    fn is_automatic(&self) -> bool {
+        false
+    }
+}
+
+impl Rename for Validation {
+    fn style_name(&self) -> &str {
+        self.name()
+    }
+
+    fn set_style_name(&mut self, name: &str) {
+        self.set_name(name);
+    }
+
+    fn is_automatic(&self) -> bool {
+        false
+    }
+}
+
+// Changes an attribute that contains a style name.
+fn rename(attr: &mut AttrMap2, name: &str, renames: &HashMap<String, String>) {
+    if let Some(new_name) = attr.attr(name).and_then(|v| renames.get(v)) {
+        attr.set_attr(name, new_name.clone());
+    }
+}
+
+// Changes the font references of text properties.
+fn rename_fonts(attr: &mut AttrMap2, renames: &HashMap<String, String>) {
+    for name in [
+        "style:font-name",
+        "style:font-name-asian",
+        "style:font-name-complex",
+    ] {
+        rename(attr, name, renames);
+    }
+}
+
+// New names for all names in src that exist in dst.
+fn renames<'a, I, J>(dst: I, src: J) -> HashMap<String, String>
+where
+    I: IntoIterator<Item = &'a String>,
+    J: IntoIterator<Item = &'a String>,
+{
+    let dst: HashSet<String> = dst.into_iter().cloned().collect();
+    let src: Vec<&String> = src.into_iter().collect();
+
+    let mut used = dst.clone();
+    used.extend(src.iter().map(|v| (*v).clone()));
+
+    let mut renames = HashMap::new();
+    for name in src {
+        if dst.contains(name) {
+            let new_name = unique_name(name, |v| used.contains(v));
+            used.insert(new_name.clone());
+            renames.insert(name.clone(), new_name);
+        }
+    }
+    renames
+}
+
+// Appends _2, _3 ... until the name is not used.
+pub(crate) fn unique_name<F>(name: &str, is_used: F) -> String
+where
+    F: Fn(&str) -> bool,
+{
+    let mut n = 2;
+    loop {
+        let new_name = format!("{}_{}", name, n);
+        if !is_used(&new_name) {
+            return new_name;
+        }
+        n += 1;
+    }
+}
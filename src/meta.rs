@@ -0,0 +1,192 @@
+//!
+//! User-defined fields of meta.xml.
+//!
+//! The fields are written as meta:user-defined elements of the type string.
+//! With set_meta_stamp() the writer adds a hash of the content and the
+//! version of this library, so a pipeline can check whether a file is
+//! up-to-date without comparing the bytes.
+//!
+//! ```
+//! use spreadsheet_ods::meta::{CONTENT_HASH, GENERATOR};
+//! use spreadsheet_ods::{read_ods_buf, write_ods_buf, Sheet, WorkBook};
+//!
+//! let mut wb = WorkBook::new_empty();
+//! let mut sheet = Sheet::new("1");
+//! sheet.set_value(0, 0, "data");
+//! wb.push_sheet(sheet);
+//! wb.set_user_meta("pipeline", "nightly");
+//! wb.set_meta_stamp(true);
+//!
+//! let buf = write_ods_buf(&mut wb, Vec::new()).expect("write");
+//! let mut wb = read_ods_buf(&buf).expect("read");
+//! assert_eq!(wb.user_meta("pipeline"), Some("nightly"));
+//! assert!(wb.user_meta(GENERATOR).is_some());
+//! assert!(wb.is_stamp_current());
+//!
+//! wb.sheet_mut(0).set_value(0, 0, "changed");
+//! assert!(!wb.is_stamp_current());
+//! ```
+//!
+
+use crate::io::write::{format_iso_duration, format_number};
+use crate::xmltree::{XmlContent, XmlTag};
+use crate::{NumberPolicy, Value, WorkBook};
+use std::collections::BTreeMap;
+
+/// Name of the field with the content hash.
+pub const CONTENT_HASH: &str = "spreadsheet-ods:content-hash";
+/// Name of the field with the version of spreadsheet-ods.
+pub const GENERATOR: &str = "spreadsheet-ods:generator";
+
+/// Value of the GENERATOR field.
+pub(crate) fn generator() -> &'static str {
+    concat!("spreadsheet-ods ", env!("CARGO_PKG_VERSION"))
+}
+
+/// FNV-1a, stable between platforms and versions.
+struct Fnv(u64);
+
+impl Fnv {
+    fn bytes(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= u64::from(*b);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn field(&mut self, s: &str) {
+        self.bytes(s.as_bytes());
+        self.bytes(&[0]);
+    }
+}
+
+/// Hash of the sheet names and the values, formulas and style names of
+/// all cells. Styles, settings and media are not included.
+pub(crate) fn content_hash(book: &WorkBook) -> String {
+    let mut h = Fnv(0xcbf2_9ce4_8422_2325);
+    for sheet in book.sheets.iter() {
+        h.field(sheet.name.as_str());
+        for ((row, col), cell) in sheet.data.iter() {
+            h.bytes(&row.to_le_bytes());
+            h.bytes(&col.to_le_bytes());
+            hash_value(&mut h, &cell.value);
+            h.field(cell.formula.as_deref().unwrap_or_default());
+            h.field(cell.style.as_ref().map_or("", |v| v.as_str()));
+        }
+    }
+    format!("{:016x}", h.0)
+}
+
+/// The value type and the value as the writer emits them. Formatted
+/// text is hashed as its plain text, one line per paragraph, the same
+/// as a Value::Text.
+fn hash_value(h: &mut Fnv, value: &Value) {
+    match value {
+        Value::Empty => h.field(""),
+        Value::Text(s) => {
+            h.field("string");
+            h.field(s.as_str());
+        }
+        Value::TextXml(tags) => {
+            let mut buf = String::new();
+            for (i, tag) in tags.iter().enumerate() {
+                if i > 0 {
+                    buf.push('\n');
+                }
+                tag.extract_text(&mut buf);
+            }
+            h.field("string");
+            h.field(buf.as_str());
+        }
+        Value::DateTime(d) => {
+            h.field("date");
+            h.field(d.format("%Y-%m-%dT%H:%M:%S%.f").to_string().as_str());
+        }
+        Value::TimeDuration(d) => {
+            h.field("time");
+            h.field(format_iso_duration(d).as_str());
+        }
+        Value::Boolean(b) => {
+            h.field("boolean");
+            h.field(if *b { "true" } else { "false" });
+        }
+        Value::Currency(v, c) => {
+            h.field("currency");
+            h.field(String::from_utf8_lossy(c).as_ref());
+            h.field(format_number(*v, NumberPolicy::Exact).as_str());
+        }
+        Value::Number(v) => {
+            h.field("float");
+            h.field(format_number(*v, NumberPolicy::Exact).as_str());
+        }
+        Value::Percentage(v) => {
+            h.field("percentage");
+            h.field(format_number(*v, NumberPolicy::Exact).as_str());
+        }
+    }
+}
+
+/// Is this a field of type string?
+fn is_string_field(tag: &XmlTag) -> bool {
+    tag.name() == "meta:user-defined"
+        && matches!(
+            tag.attrmap().attr("meta:value-type").map(|v| v.as_str()),
+            None | Some("string")
+        )
+}
+
+/// Removes the string fields from office:meta and returns them.
+/// Fields of other types are left where they are.
+pub(crate) fn take_user_meta(meta: &mut XmlTag) -> BTreeMap<String, String> {
+    let mut fields = BTreeMap::new();
+    if let Some(office_meta) = office_meta_mut(meta) {
+        office_meta.content_mut().retain(|v| match v {
+            XmlContent::Tag(t) if is_string_field(t) => {
+                if let Some(name) = t.attrmap().attr("meta:name") {
+                    let mut value = String::new();
+                    t.extract_text(&mut value);
+                    fields.insert(name.clone(), value);
+                }
+                false
+            }
+            _ => true,
+        });
+    }
+    fields
+}
+
+/// Adds the fields to office:meta, and replaces fields of other types
+/// with the same name.
+pub(crate) fn put_user_meta(meta: &mut XmlTag, fields: &BTreeMap<String, String>) {
+    if let Some(office_meta) = office_meta_mut(meta) {
+        office_meta.content_mut().retain(|v| match v {
+            XmlContent::Tag(t) if t.name() == "meta:user-defined" => !matches!(
+                t.attrmap().attr("meta:name"),
+                Some(name) if fields.contains_key(name)
+            ),
+            _ => true,
+        });
+        for tag in user_meta_tags(fields) {
+            office_meta.add_tag(tag);
+        }
+    }
+}
+
+/// The fields as meta:user-defined elements.
+pub(crate) fn user_meta_tags(fields: &BTreeMap<String, String>) -> Vec<XmlTag> {
+    fields
+        .iter()
+        .map(|(name, value)| {
+            XmlTag::new("meta:user-defined")
+                .attr("meta:name", name.as_str())
+                .text(value.as_str())
+        })
+        .collect()
+}
+
+fn office_meta_mut(meta: &mut XmlTag) -> Option<&mut XmlTag> {
+    meta.content_mut().iter_mut().find_map(|v| match v {
+        XmlContent::Tag(t) if t.name() == "office:meta" => Some(t),
+        _ => None,
+    })
+}
@@ -0,0 +1,155 @@
+//!
+//! Named ranges.
+//!
+//! A named range belongs either to the workbook or to one sheet. The names
+//! of a sheet hide the names of the workbook for the formulas of this
+//! sheet, the same as LibreOffice does with a table:named-range inside of
+//! a table:table. Names are compared without regard to case.
+//!
+//! ```
+//! use spreadsheet_ods::{CellRange, Sheet, WorkBook};
+//!
+//! let mut wb = WorkBook::new_empty();
+//! wb.add_named_range("rates", CellRange::remote("1", 0, 0, 9, 0));
+//!
+//! let mut sheet = Sheet::new("2");
+//! sheet.add_named_range("rates", CellRange::local(0, 5, 9, 5));
+//! wb.push_sheet(Sheet::new("1"));
+//! wb.push_sheet(sheet);
+//!
+//! // Sheet 1 uses the workbook name, sheet 2 its own.
+//! let rates = wb.resolve_name(0, "Rates").expect("name");
+//! assert_eq!(rates.range().to_string(), "'1'.A1:.A10");
+//! let rates = wb.resolve_name(1, "RATES").expect("name");
+//! assert_eq!(rates.range().to_string(), "'2'.F1:.F10");
+//! ```
+//!
+
+use crate::xmltree::{XmlContent, XmlTag};
+use crate::{CellRange, CellRef};
+use std::convert::TryFrom;
+
+/// A named cell range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedRange {
+    name: String,
+    range: CellRange,
+    base_cell: CellRef,
+}
+
+impl NamedRange {
+    /// Name for the range. The base-cell is the start of the range.
+    pub fn new<S: Into<String>>(name: S, range: CellRange) -> Self {
+        let base_cell = range.start_cellref().absolute();
+        Self {
+            name: name.into(),
+            range,
+            base_cell,
+        }
+    }
+
+    /// Name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The range.
+    pub fn range(&self) -> &CellRange {
+        &self.range
+    }
+
+    /// Relative references in the range are relative to the base-cell.
+    pub fn set_base_cell(&mut self, base_cell: CellRef) {
+        self.base_cell = base_cell;
+    }
+
+    /// Relative references in the range are relative to the base-cell.
+    pub fn base_cell(&self) -> &CellRef {
+        &self.base_cell
+    }
+
+    fn to_xmltag(&self) -> XmlTag {
+        XmlTag::new("table:named-range")
+            .attr("table:name", self.name.as_str())
+            .attr("table:base-cell-address", self.base_cell.to_string())
+            .attr("table:cell-range-address", self.range.to_string())
+    }
+
+    fn from_xmltag(tag: &XmlTag) -> Option<Self> {
+        if tag.name() != "table:named-range" {
+            return None;
+        }
+        let attr = tag.attrmap();
+        let name = attr.attr("table:name")?;
+        let range = CellRange::try_from(attr.attr("table:cell-range-address")?.as_str()).ok()?;
+        let base_cell = match attr.attr("table:base-cell-address") {
+            Some(v) => CellRef::try_from(v.as_str()).ok()?,
+            None => range.start_cellref().absolute(),
+        };
+        Some(Self {
+            name: name.clone(),
+            range,
+            base_cell,
+        })
+    }
+}
+
+/// The named ranges in the table:named-expressions of the extra tags.
+pub(crate) fn named_ranges(extra: &[XmlTag]) -> Vec<NamedRange> {
+    extra
+        .iter()
+        .filter(|v| v.name() == "table:named-expressions")
+        .flat_map(|v| v.content())
+        .filter_map(|v| match v {
+            XmlContent::Tag(t) => NamedRange::from_xmltag(t),
+            XmlContent::Text(_) => None,
+        })
+        .collect()
+}
+
+/// Finds a named range.
+pub(crate) fn named_range(extra: &[XmlTag], name: &str) -> Option<NamedRange> {
+    named_ranges(extra)
+        .into_iter()
+        .find(|v| v.name.eq_ignore_ascii_case(name))
+}
+
+/// Adds the named range and replaces a named range or named expression
+/// with the same name.
+pub(crate) fn add_named_range(extra: &mut Vec<XmlTag>, named_range: NamedRange) {
+    remove_named_range(extra, named_range.name());
+
+    let idx = match extra
+        .iter()
+        .position(|v| v.name() == "table:named-expressions")
+    {
+        Some(idx) => idx,
+        None => {
+            extra.push(XmlTag::new("table:named-expressions"));
+            extra.len() - 1
+        }
+    };
+    extra[idx].add_tag(named_range.to_xmltag());
+}
+
+/// Removes the named range or named expression.
+pub(crate) fn remove_named_range(extra: &mut Vec<XmlTag>, name: &str) -> bool {
+    let mut found = false;
+    for tag in extra
+        .iter_mut()
+        .filter(|v| v.name() == "table:named-expressions")
+    {
+        tag.content_mut().retain(|v| match v {
+            XmlContent::Tag(t)
+                if matches!(t.attrmap().attr("table:name"),
+                    Some(v) if v.eq_ignore_ascii_case(name)) =>
+            {
+                found = true;
+                false
+            }
+            _ => true,
+        });
+    }
+    extra.retain(|v| v.name() != "table:named-expressions" || !v.is_empty());
+    found
+}
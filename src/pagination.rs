@@ -0,0 +1,213 @@
+//!
+//! Computes where the automatic page breaks of a sheet fall when printing.
+//!
+//! This is a simple paginator. It uses the page size and margins of the
+//! page style, the header and footer height and the column widths and
+//! row heights of the sheet. Optimal widths/heights use the defaults of
+//! LibreOffice, scaling and manual page breaks are ignored. Header rows
+//! and header columns are repeated on every page.
+//!
+//! ```
+//! use spreadsheet_ods::{cm, Sheet, WorkBook};
+//! use spreadsheet_ods::style::units::Length;
+//!
+//! let mut wb = WorkBook::new_empty();
+//! let mut sheet = Sheet::new("1");
+//! for col in 0..20 {
+//!     sheet.set_col_width(col, cm!(3));
+//! }
+//! sheet.set_value(0, 19, "far right");
+//! wb.push_sheet(sheet);
+//!
+//! let pages = wb.page_breaks(0).unwrap();
+//! assert_eq!(pages.pages_wide(), 4);
+//! assert_eq!(pages.pages_tall(), 1);
+//! ```
+//!
+
+use crate::style::units::Length;
+use crate::style::{PageStyle, ParseStyleAttr};
+use crate::{OdsError, Sheet, Visibility};
+
+/// Page width of A4 in mm.
+const DEFAULT_PAGE_WIDTH: f64 = 210.0;
+/// Page height of A4 in mm.
+const DEFAULT_PAGE_HEIGHT: f64 = 297.0;
+/// Page margin in mm.
+const DEFAULT_MARGIN: f64 = 20.0;
+/// Default column width in mm.
+const DEFAULT_COL_WIDTH: f64 = 22.58;
+/// Default row height in mm.
+const DEFAULT_ROW_HEIGHT: f64 = 4.52;
+
+/// Result of the pagination of a sheet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageBreaks {
+    col_breaks: Vec<u32>,
+    row_breaks: Vec<u32>,
+}
+
+impl PageBreaks {
+    /// First column of each page except the first one.
+    pub fn col_breaks(&self) -> &Vec<u32> {
+        &self.col_breaks
+    }
+
+    /// First row of each page except the first one.
+    pub fn row_breaks(&self) -> &Vec<u32> {
+        &self.row_breaks
+    }
+
+    /// Number of pages side by side.
+    pub fn pages_wide(&self) -> u32 {
+        self.col_breaks.len() as u32 + 1
+    }
+
+    /// Number of pages from top to bottom.
+    pub fn pages_tall(&self) -> u32 {
+        self.row_breaks.len() as u32 + 1
+    }
+
+    /// Total number of pages.
+    pub fn pages(&self) -> u32 {
+        self.pages_wide() * self.pages_tall()
+    }
+}
+
+/// Computes the page breaks for the used area of the sheet.
+///
+/// Without a page style A4 with 2cm margins is assumed.
+pub fn page_breaks(sheet: &Sheet, page: Option<&PageStyle>) -> Result<PageBreaks, OdsError> {
+    let (width, height) = printable_area(page)?;
+    let (rows, cols) = sheet.used_grid_size();
+
+    let col_breaks = breaks(
+        cols,
+        width,
+        sheet.header_cols.as_ref().map(|v| (v.col(), v.to_col())),
+        |col| {
+            if sheet.col_visible(col) == Visibility::Visible {
+                to_mm(sheet.col_width(col), DEFAULT_COL_WIDTH)
+            } else {
+                0.0
+            }
+        },
+    );
+
+    // Repeated rows share the height of the first one.
+    let row_height = |row: u32| {
+        let header = sheet
+            .row_header
+            .range(..=row)
+            .next_back()
+            .filter(|(r, v)| **r == row || **r + v.repeat() > row);
+        match header {
+            Some((_, v)) if v.visible() != Visibility::Visible => 0.0,
+            Some((_, v)) => to_mm(v.height(), DEFAULT_ROW_HEIGHT),
+            None => DEFAULT_ROW_HEIGHT,
+        }
+    };
+    let row_breaks = breaks(
+        rows,
+        height,
+        sheet.header_rows.as_ref().map(|v| (v.row(), v.to_row())),
+        row_height,
+    );
+
+    Ok(PageBreaks {
+        col_breaks,
+        row_breaks,
+    })
+}
+
+// Fills pages of the given size. The header is repeated at the start
+// of every following page.
+fn breaks<F>(count: u32, size: f64, header: Option<(u32, u32)>, extent: F) -> Vec<u32>
+where
+    F: Fn(u32) -> f64,
+{
+    let header_size = match header {
+        Some((from, to)) => (from..=to).map(&extent).sum(),
+        None => 0.0,
+    };
+    // A header that fills the page can't be repeated.
+    let header_size = if header_size < size { header_size } else { 0.0 };
+
+    let mut breaks = Vec::new();
+    let mut pos = 0.0;
+    let mut empty = true;
+    for idx in 0..count {
+        let v = extent(idx);
+        if !empty && pos + v > size {
+            breaks.push(idx);
+            pos = match header {
+                Some((_, to)) if idx > to => header_size,
+                _ => 0.0,
+            };
+        }
+        pos += v;
+        empty = pos == 0.0;
+    }
+    breaks
+}
+
+// Width and height of the printable area in mm.
+fn printable_area(page: Option<&PageStyle>) -> Result<(f64, f64), OdsError> {
+    let page = match page {
+        Some(page) => page,
+        None => {
+            return Ok((
+                DEFAULT_PAGE_WIDTH - 2.0 * DEFAULT_MARGIN,
+                DEFAULT_PAGE_HEIGHT - 2.0 * DEFAULT_MARGIN,
+            ))
+        }
+    };
+
+    let attr = |name: &str, default: f64| -> Result<f64, OdsError> {
+        let v = Length::parse_attr(page.style().attr(name))?;
+        Ok(v.map_or(default, |v| to_mm(v, default)))
+    };
+    let margin = attr("fo:margin", DEFAULT_MARGIN)?;
+
+    let width = attr("fo:page-width", DEFAULT_PAGE_WIDTH)?
+        - attr("fo:margin-left", margin)?
+        - attr("fo:margin-right", margin)?;
+
+    let header = page.headerstyle().style();
+    let footer = page.footerstyle().style();
+    let height = attr("fo:page-height", DEFAULT_PAGE_HEIGHT)?
+        - attr("fo:margin-top", margin)?
+        - attr("fo:margin-bottom", margin)?
+        - hf_height(
+            header.attr("fo:min-height"),
+            header.attr("fo:margin-bottom"),
+        )?
+        - hf_height(footer.attr("fo:min-height"), footer.attr("fo:margin-top"))?;
+
+    Ok((f64::max(width, 0.0), f64::max(height, 0.0)))
+}
+
+// Space used by a header or footer.
+fn hf_height(height: Option<&String>, spacing: Option<&String>) -> Result<f64, OdsError> {
+    match Length::parse_attr(height)? {
+        Some(height) => {
+            let spacing = Length::parse_attr(spacing)?.unwrap_or_default();
+            Ok(to_mm(height, 0.0) + to_mm(spacing, 0.0))
+        }
+        None => Ok(0.0),
+    }
+}
+
+// Converts to mm.
+fn to_mm(length: Length, default: f64) -> f64 {
+    match length {
+        Length::Default => default,
+        Length::Cm(v) => v * 10.0,
+        Length::Mm(v) => v,
+        Length::In(v) => v * 25.4,
+        Length::Pt(v) => v * 25.4 / 72.0,
+        Length::Pc(v) => v * 25.4 / 6.0,
+        // Assumes a 12pt font.
+        Length::Em(v) => v * 12.0 * 25.4 / 72.0,
+    }
+}
@@ -158,6 +158,227 @@ impl CellRef {
         self.col_abs = true;
         self
     }
+
+    /// Makes this CellReference into a relative reference.
+    pub fn relative(mut self) -> Self {
+        self.col_abs = false;
+        self.row_abs = false;
+        self
+    }
+
+    /// Sets the row and the column to absolute.
+    pub fn make_absolute(&mut self) {
+        self.col_abs = true;
+        self.row_abs = true;
+    }
+
+    /// Sets the row and the column to relative.
+    pub fn make_relative(&mut self) {
+        self.col_abs = false;
+        self.row_abs = false;
+    }
+
+    /// Row and column are absolute.
+    pub fn is_absolute(&self) -> bool {
+        self.col_abs && self.row_abs
+    }
+
+    /// Row and column are relative.
+    pub fn is_relative(&self) -> bool {
+        !self.col_abs && !self.row_abs
+    }
+
+    /// Creates a relative reference to the cell at the offset from the
+    /// base cell. The table is taken from the base cell. Returns None if
+    /// the cell would be outside of the sheet.
+    ///
+    /// ```
+    /// use spreadsheet_ods::CellRef;
+    ///
+    /// let base = CellRef::local(4, 2);
+    /// let above = CellRef::relative_to(&base, -1, 0).unwrap();
+    /// assert_eq!(above.to_formula(), "[.C4]");
+    /// ```
+    pub fn relative_to(base: &CellRef, dr: i32, dc: i32) -> Option<Self> {
+        Some(Self {
+            table: base.table.clone(),
+            row_abs: false,
+            row: offset(base.row, dr as i64)?,
+            col_abs: false,
+            col: offset(base.col, dc as i64)?,
+        })
+    }
+
+    /// Parses a reference in R1C1 notation. Relative parts like R[-1] or
+    /// C are relative to the base cell, absolute parts like R5 count from 1.
+    /// The table is taken from the base cell.
+    ///
+    /// ```
+    /// use spreadsheet_ods::CellRef;
+    ///
+    /// let base = CellRef::local(4, 2);
+    /// let c = CellRef::try_from_r1c1("R1C[-1]", &base).unwrap();
+    /// assert_eq!(c.to_formula(), "[.B$1]");
+    /// ```
+    pub fn try_from_r1c1(s: &str, base: &CellRef) -> Result<Self, OdsError> {
+        parse_r1c1(s, base)
+    }
+
+    /// Returns the reference in R1C1 notation. Relative parts are written
+    /// as offsets from the base cell, the table is not written.
+    pub fn to_r1c1(&self, base: &CellRef) -> String {
+        let mut buf = String::new();
+        push_r1c1(&mut buf, 'R', self.row, self.row_abs, base.row);
+        push_r1c1(&mut buf, 'C', self.col, self.col_abs, base.col);
+        buf
+    }
+}
+
+/// Column name for the column index, e.g. 0 is "A" and 26 is "AA".
+///
+/// ```
+/// use spreadsheet_ods::refs::{cell_from_name, cell_name, col_from_name, col_name};
+///
+/// assert_eq!(col_name(26), "AA");
+/// assert_eq!(col_from_name("AA"), Some(26));
+/// assert_eq!(cell_name(0, 27), "AB1");
+/// assert_eq!(cell_from_name("AB1"), Some((0, 27)));
+/// ```
+pub fn col_name(col: u32) -> String {
+    let mut buf = String::new();
+    push_colname(&mut buf, col);
+    buf
+}
+
+/// Column index for the column name. Lowercase letters are accepted
+/// too. None if this is not a column name.
+pub fn col_from_name(name: &str) -> Option<u32> {
+    if name.is_empty() {
+        return None;
+    }
+    let mut col = 0u64;
+    for c in name.chars() {
+        if !c.is_ascii_alphabetic() {
+            return None;
+        }
+        col = col * 26 + (c.to_ascii_uppercase() as u64 - 'A' as u64 + 1);
+        if col > u32::MAX as u64 + 1 {
+            return None;
+        }
+    }
+    Some((col - 1) as u32)
+}
+
+/// Cell name for row and column without table and $, e.g. "A1".
+pub fn cell_name(row: u32, col: u32) -> String {
+    let mut buf = String::new();
+    push_colname(&mut buf, col);
+    push_rowname(&mut buf, row);
+    buf
+}
+
+/// Row and column for a cell name like "A1". None if this is not a
+/// plain cell name.
+pub fn cell_from_name(name: &str) -> Option<(u32, u32)> {
+    let idx = name.find(|c: char| !c.is_ascii_alphabetic())?;
+    let col = col_from_name(&name[..idx])?;
+    let row = &name[idx..];
+    if !row.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    match row.parse::<u64>() {
+        Ok(row) if row > 0 && row <= u32::MAX as u64 + 1 => Some(((row - 1) as u32, col)),
+        _ => None,
+    }
+}
+
+// Row or column with an offset. None if outside of u32.
+fn offset(pos: u32, delta: i64) -> Option<u32> {
+    u32::try_from(pos as i64 + delta).ok()
+}
+
+/// Parses a reference in R1C1 notation relative to the base cell.
+pub(crate) fn parse_r1c1(buf: &str, base: &CellRef) -> Result<CellRef, OdsError> {
+    let mut pos = 0;
+    let (row, row_abs) = parse_r1c1_part(buf, &mut pos, 'R', base.row)?;
+    let (col, col_abs) = parse_r1c1_part(buf, &mut pos, 'C', base.col)?;
+    if pos != buf.len() {
+        return Err(OdsError::Ods(format!(
+            "Unexpected text in the R1C1 reference {}",
+            buf
+        )));
+    }
+    Ok(CellRef {
+        table: base.table.clone(),
+        row_abs,
+        row,
+        col_abs,
+        col,
+    })
+}
+
+// One of R5, R[-1] or R. Returns the position and the absolute flag.
+fn parse_r1c1_part(
+    buf: &str,
+    pos: &mut usize,
+    marker: char,
+    base: u32,
+) -> Result<(u32, bool), OdsError> {
+    if !buf[*pos..].starts_with(marker) {
+        return Err(OdsError::Ods(format!(
+            "No {} in the R1C1 reference {}",
+            marker, buf
+        )));
+    }
+    *pos += 1;
+
+    let rest = &buf[*pos..];
+    if let Some(rest) = rest.strip_prefix('[') {
+        let len = rest
+            .find(']')
+            .ok_or_else(|| OdsError::Ods(format!("No ] in the R1C1 reference {}", buf)))?;
+        let delta = rest[..len].parse::<i64>()?;
+        *pos += len + 2;
+        match offset(base, delta) {
+            Some(v) => Ok((v, false)),
+            None => Err(OdsError::Ods(format!(
+                "R1C1 reference {} is outside of the sheet",
+                buf
+            ))),
+        }
+    } else {
+        let len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if len == 0 {
+            Ok((base, false))
+        } else {
+            let v = rest[..len].parse::<u32>()?;
+            *pos += len;
+            if v == 0 {
+                return Err(OdsError::Ods(format!(
+                    "R1C1 reference {} starts with 1",
+                    buf
+                )));
+            }
+            Ok((v - 1, true))
+        }
+    }
+}
+
+// Appends one of R5, R[-1] or R.
+fn push_r1c1(buf: &mut String, marker: char, pos: u32, abs: bool, base: u32) {
+    buf.push(marker);
+    if abs {
+        buf.push_str(&(pos as u64 + 1).to_string());
+    } else {
+        let delta = pos as i64 - base as i64;
+        if delta != 0 {
+            buf.push('[');
+            buf.push_str(&delta.to_string());
+            buf.push(']');
+        }
+    }
 }
 
 /// A cell-range.
@@ -233,6 +454,48 @@ impl CellRange {
         }
     }
 
+    /// Creates a range over whole columns, e.g. A:A. The rows go from
+    /// 0 to u32::MAX, so the range grows with the data.
+    ///
+    /// ```
+    /// use spreadsheet_ods::CellRange;
+    ///
+    /// let r = CellRange::whole_cols(0, 1);
+    /// assert!(r.contains(100_000, 1));
+    /// assert_eq!(r.to_formula(), "[.A:.B]");
+    /// ```
+    pub fn whole_cols(col: u32, to_col: u32) -> Self {
+        assert!(col <= to_col);
+        Self {
+            col,
+            to_col,
+            to_row: u32::MAX,
+            ..Self::new()
+        }
+    }
+
+    /// Creates a range over whole rows, e.g. 1:3. The columns go from
+    /// 0 to u32::MAX.
+    pub fn whole_rows(row: u32, to_row: u32) -> Self {
+        assert!(row <= to_row);
+        Self {
+            row,
+            to_row,
+            to_col: u32::MAX,
+            ..Self::new()
+        }
+    }
+
+    /// The range covers whole columns.
+    pub fn is_whole_cols(&self) -> bool {
+        self.row == 0 && self.to_row == u32::MAX
+    }
+
+    /// The range covers whole rows.
+    pub fn is_whole_rows(&self) -> bool {
+        self.col == 0 && self.to_col == u32::MAX
+    }
+
     /// Creates the cell range from origin + spanning data.
     pub fn origin_span(row: u32, col: u32, span: (u32, u32)) -> Self {
         assert!(span.0 > 0);
@@ -374,6 +637,60 @@ impl CellRange {
         self
     }
 
+    /// Makes this CellReference into a relative reference.
+    pub fn relative(mut self) -> Self {
+        self.make_relative();
+        self
+    }
+
+    /// Sets all rows and columns to absolute.
+    pub fn make_absolute(&mut self) {
+        self.col_abs = true;
+        self.row_abs = true;
+        self.to_col_abs = true;
+        self.to_row_abs = true;
+    }
+
+    /// Sets all rows and columns to relative.
+    pub fn make_relative(&mut self) {
+        self.col_abs = false;
+        self.row_abs = false;
+        self.to_col_abs = false;
+        self.to_row_abs = false;
+    }
+
+    /// All rows and columns are absolute.
+    pub fn is_absolute(&self) -> bool {
+        self.col_abs && self.row_abs && self.to_col_abs && self.to_row_abs
+    }
+
+    /// All rows and columns are relative.
+    pub fn is_relative(&self) -> bool {
+        !self.col_abs && !self.row_abs && !self.to_col_abs && !self.to_row_abs
+    }
+
+    /// Start of the range.
+    pub fn start_cellref(&self) -> CellRef {
+        CellRef {
+            table: self.table.clone(),
+            row_abs: self.row_abs,
+            row: self.row,
+            col_abs: self.col_abs,
+            col: self.col,
+        }
+    }
+
+    /// End of the range.
+    pub fn end_cellref(&self) -> CellRef {
+        CellRef {
+            table: self.table.clone(),
+            row_abs: self.to_row_abs,
+            row: self.to_row,
+            col_abs: self.to_col_abs,
+            col: self.to_col,
+        }
+    }
+
     /// Does the range contain the cell.
     /// This is inclusive for to_row and to_col!
     pub fn contains(&self, row: u32, col: u32) -> bool {
@@ -403,6 +720,130 @@ impl Display for CellRange {
     }
 }
 
+/// A list of cell-ranges.
+///
+/// ODS stores these as a blank separated list of range addresses, e.g.
+/// table:print-ranges or the source ranges of charts.
+///
+/// ```
+/// use spreadsheet_ods::{CellRange, CellRangeList};
+/// use std::convert::TryFrom;
+///
+/// let mut list = CellRangeList::try_from("Sheet1.A1:.C3 'Sheet 2'.B1:.B9").unwrap();
+/// list.push(CellRange::remote("Sheet1", 10, 0, 12, 2));
+/// assert_eq!(list.len(), 3);
+/// assert_eq!(
+///     list.to_string(),
+///     "Sheet1.A1:.C3 'Sheet 2'.B1:.B9 Sheet1.A11:.C13"
+/// );
+/// ```
+///
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CellRangeList {
+    ranges: Vec<CellRange>,
+}
+
+impl CellRangeList {
+    /// Empty.
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// Appends a range.
+    pub fn push(&mut self, range: CellRange) {
+        self.ranges.push(range);
+    }
+
+    /// Number of ranges.
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// No ranges.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Iterates the ranges.
+    pub fn iter(&self) -> std::slice::Iter<'_, CellRange> {
+        self.ranges.iter()
+    }
+
+    /// Iterates the ranges.
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, CellRange> {
+        self.ranges.iter_mut()
+    }
+
+    /// The ranges.
+    pub fn ranges(&self) -> &[CellRange] {
+        &self.ranges
+    }
+
+    /// Does any of the ranges contain the cell.
+    pub fn contains(&self, row: u32, col: u32) -> bool {
+        self.ranges.iter().any(|v| v.contains(row, col))
+    }
+}
+
+impl TryFrom<&str> for CellRangeList {
+    type Error = OdsError;
+
+    /// An empty string is an empty list.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut pos = 0usize;
+        let ranges = if value.is_empty() {
+            Vec::new()
+        } else {
+            parse_cellranges(value, &mut pos)?.unwrap_or_default()
+        };
+        Ok(Self { ranges })
+    }
+}
+
+impl Display for CellRangeList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), core::fmt::Error> {
+        write!(f, "{}", cellranges_string(&self.ranges))
+    }
+}
+
+impl From<Vec<CellRange>> for CellRangeList {
+    fn from(ranges: Vec<CellRange>) -> Self {
+        Self { ranges }
+    }
+}
+
+impl From<CellRangeList> for Vec<CellRange> {
+    fn from(list: CellRangeList) -> Self {
+        list.ranges
+    }
+}
+
+impl FromIterator<CellRange> for CellRangeList {
+    fn from_iter<T: IntoIterator<Item = CellRange>>(iter: T) -> Self {
+        Self {
+            ranges: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for CellRangeList {
+    type Item = CellRange;
+    type IntoIter = std::vec::IntoIter<CellRange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ranges.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a CellRangeList {
+    type Item = &'a CellRange;
+    type IntoIter = std::slice::Iter<'a, CellRange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ranges.iter()
+    }
+}
+
 /// A range over columns.
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct ColRange {
@@ -655,99 +1096,125 @@ pub(crate) fn parse_cellref(buf: &str, pos: &mut usize) -> Result<CellRef, OdsEr
     })
 }
 
-/// Parse a range ref.
-pub(crate) fn parse_cellrange(buf: &str, pos: &mut usize) -> Result<CellRange, OdsError> {
-    let cell = {
-        let table = parse_tablename(buf, pos)?;
+// One end of a range.
+enum RangePart {
+    // abs_row, row, abs_col, col
+    Cell(bool, u32, bool, u32),
+    // abs, col
+    Col(bool, u32),
+    // abs, row
+    Row(bool, u32),
+}
 
-        let abs_col = buf[*pos..].starts_with('$');
-        if abs_col {
-            *pos += 1;
-        }
+/// Parse one end of a range, a cell or only a column or a row.
+fn parse_rangepart(buf: &str, pos: &mut usize) -> Result<RangePart, OdsError> {
+    let abs_col = buf[*pos..].starts_with('$');
+    if abs_col {
+        *pos += 1;
+    }
 
-        let col = match parse_colname(buf, pos) {
-            None => {
-                return Err(OdsError::Ods(format!(
-                    "No colname in the cell reference {}",
-                    &buf[*pos..]
-                )))
+    match parse_colname(buf, pos) {
+        Some(col) => {
+            let abs_row = buf[*pos..].starts_with('$');
+            if abs_row {
+                *pos += 1;
             }
-            Some(col) => col,
-        };
 
-        let abs_row = buf[*pos..].starts_with('$');
-        if abs_row {
-            *pos += 1;
-        }
-
-        let row = match parse_rowname(buf, pos) {
-            None => {
-                return Err(OdsError::Ods(format!(
+            match parse_rowname(buf, pos) {
+                Some(row) => Ok(RangePart::Cell(abs_row, row, abs_col, col)),
+                None if !abs_row => Ok(RangePart::Col(abs_col, col)),
+                None => Err(OdsError::Ods(format!(
                     "No rowname in the cell reference {}",
                     &buf[*pos..]
-                )))
+                ))),
             }
-            Some(row) => row,
-        };
+        }
+        // The $ belongs to the row.
+        None => match parse_rowname(buf, pos) {
+            Some(row) => Ok(RangePart::Row(abs_col, row)),
+            None => Err(OdsError::Ods(format!(
+                "No colname in the cell reference {}",
+                &buf[*pos..]
+            ))),
+        },
+    }
+}
 
-        (table, abs_row, row, abs_col, col)
-    };
+/// Parse a range ref. Whole columns (.A:.B) and rows (.1:.3) are
+/// accepted too.
+pub(crate) fn parse_cellrange(buf: &str, pos: &mut usize) -> Result<CellRange, OdsError> {
+    let table = parse_tablename(buf, pos)?;
+    let from = parse_rangepart(buf, pos)?;
 
     // a range can be a single cell too
-    let colon = buf[*pos..].starts_with(':');
-
-    let to_cell = if colon {
+    let to = if buf[*pos..].starts_with(':') {
         *pos += 1;
+        // to_table is ignored. should be the same as table.
+        let _to_table = parse_tablename(buf, pos)?;
+        Some(parse_rangepart(buf, pos)?)
+    } else {
+        None
+    };
 
-        let to_table = parse_tablename(buf, pos)?;
-
-        let abs_to_col = buf[*pos..].starts_with('$');
-        if abs_to_col {
-            *pos += 1;
+    let mut range = match (from, to) {
+        (RangePart::Cell(row_abs, row, col_abs, col), None) => CellRange {
+            table: None,
+            row_abs,
+            row,
+            col_abs,
+            col,
+            to_row_abs: row_abs,
+            to_row: row,
+            to_col_abs: col_abs,
+            to_col: col,
+        },
+        (
+            RangePart::Cell(row_abs, row, col_abs, col),
+            Some(RangePart::Cell(to_row_abs, to_row, to_col_abs, to_col)),
+        ) => CellRange {
+            table: None,
+            row_abs,
+            row,
+            col_abs,
+            col,
+            to_row_abs,
+            to_row,
+            to_col_abs,
+            to_col,
+        },
+        (RangePart::Col(col_abs, col), Some(RangePart::Col(to_col_abs, to_col))) => {
+            // C:A is the same as A:C.
+            let ((col_abs, col), (to_col_abs, to_col)) = if col <= to_col {
+                ((col_abs, col), (to_col_abs, to_col))
+            } else {
+                ((to_col_abs, to_col), (col_abs, col))
+            };
+            let mut range = CellRange::whole_cols(col, to_col);
+            range.col_abs = col_abs;
+            range.to_col_abs = to_col_abs;
+            range
         }
-
-        let to_col = match parse_colname(buf, pos) {
-            None => {
-                return Err(OdsError::Ods(format!(
-                    "No colname in the cell reference {}",
-                    &buf[*pos..]
-                )))
-            }
-            Some(col) => col,
-        };
-
-        let abs_to_row = buf[*pos..].starts_with('$');
-        if abs_to_row {
-            *pos += 1;
+        (RangePart::Row(row_abs, row), Some(RangePart::Row(to_row_abs, to_row))) => {
+            let ((row_abs, row), (to_row_abs, to_row)) = if row <= to_row {
+                ((row_abs, row), (to_row_abs, to_row))
+            } else {
+                ((to_row_abs, to_row), (row_abs, row))
+            };
+            let mut range = CellRange::whole_rows(row, to_row);
+            range.row_abs = row_abs;
+            range.to_row_abs = to_row_abs;
+            range
+        }
+        _ => {
+            return Err(OdsError::Ods(format!(
+                "Start and end of the range don't match {}",
+                buf
+            )))
         }
-
-        let to_row = match parse_rowname(buf, pos) {
-            None => {
-                return Err(OdsError::Ods(format!(
-                    "No rowname in the cell reference {}",
-                    &buf[*pos..]
-                )))
-            }
-            Some(row) => row,
-        };
-
-        (to_table, abs_to_row, to_row, abs_to_col, to_col)
-    } else {
-        cell.clone()
     };
+    range.table = table;
 
-    Ok(CellRange {
-        table: cell.0,
-        row_abs: cell.1,
-        row: cell.2,
-        col_abs: cell.3,
-        col: cell.4,
-        // to_table is ignored. should be the same as table.
-        to_row_abs: to_cell.1,
-        to_row: to_cell.2,
-        to_col_abs: to_cell.3,
-        to_col: to_cell.4,
-    })
+    Ok(range)
 }
 
 /// Parse a list of range refs
@@ -784,6 +1251,53 @@ pub(crate) fn parse_cellranges(
     Ok(v)
 }
 
+/// Replaces the table name in a blank separated list of cell or range
+/// addresses. Returns None if nothing refers to the table or the list
+/// can't be parsed.
+pub(crate) fn rename_table_addresses(buf: &str, table: &str, new_name: &str) -> Option<String> {
+    let mut res = String::new();
+    let mut changed = false;
+
+    let mut pos = 0;
+    loop {
+        let start = pos;
+        let mut range = parse_cellrange(buf, &mut pos).ok()?;
+        let mut cell_pos = start;
+        let cell = match parse_cellref(buf, &mut cell_pos) {
+            Ok(cell) if cell_pos == pos => Some(cell),
+            _ => None,
+        };
+
+        if range.table().map(|v| v.as_str()) == Some(table) {
+            changed = true;
+            if let Some(mut cell) = cell {
+                cell.set_table(new_name);
+                push_cellref(&mut res, &cell);
+            } else {
+                range.set_table(new_name);
+                push_cellrange(&mut res, &range);
+            }
+        } else {
+            res.push_str(&buf[start..pos]);
+        }
+
+        if pos == buf.len() {
+            break;
+        }
+        if !buf[pos..].starts_with(' ') {
+            return None;
+        }
+        res.push(' ');
+        pos += 1;
+    }
+
+    if changed {
+        Some(res)
+    } else {
+        None
+    }
+}
+
 /// Appends the spreadsheet column name.
 pub(crate) fn push_colname(buf: &mut String, mut col: u32) {
     let mut i = 0;
@@ -848,7 +1362,7 @@ pub(crate) fn push_tablename(buf: &mut String, table: Option<&String>, abs: bool
         if abs {
             buf.push('$');
         }
-        if table.contains(|c| c == '\'' || c == ' ' || c == '.') {
+        if needs_quotes(table) {
             buf.push('\'');
             buf.push_str(&table.replace('\'', "''"));
             buf.push('\'');
@@ -861,6 +1375,17 @@ pub(crate) fn push_tablename(buf: &mut String, table: Option<&String>, abs: bool
     }
 }
 
+// Everything but a plain identifier is quoted, e.g. 'Sheet 1', 'Q1-2024'
+// or '2024'.
+fn needs_quotes(table: &str) -> bool {
+    match table.chars().next() {
+        Some(c) if c.is_alphabetic() || c == '_' => {
+            !table.chars().all(|c| c.is_alphanumeric() || c == '_')
+        }
+        _ => true,
+    }
+}
+
 /// Appends the cell reference
 pub(crate) fn push_cellref(buf: &mut String, cellref: &CellRef) {
     push_tablename(
@@ -880,6 +1405,43 @@ pub(crate) fn push_cellref(buf: &mut String, cellref: &CellRef) {
 
 /// Appends the range reference
 pub(crate) fn push_cellrange(buf: &mut String, cellrange: &CellRange) {
+    if cellrange.is_whole_cols() {
+        push_tablename(
+            buf,
+            cellrange.table.as_ref(),
+            cellrange.col_abs || cellrange.to_col_abs,
+        );
+        if cellrange.col_abs {
+            buf.push('$');
+        }
+        push_colname(buf, cellrange.col);
+        buf.push(':');
+        buf.push('.');
+        if cellrange.to_col_abs {
+            buf.push('$');
+        }
+        push_colname(buf, cellrange.to_col);
+        return;
+    }
+    if cellrange.is_whole_rows() {
+        push_tablename(
+            buf,
+            cellrange.table.as_ref(),
+            cellrange.row_abs || cellrange.to_row_abs,
+        );
+        if cellrange.row_abs {
+            buf.push('$');
+        }
+        push_rowname(buf, cellrange.row);
+        buf.push(':');
+        buf.push('.');
+        if cellrange.to_row_abs {
+            buf.push('$');
+        }
+        push_rowname(buf, cellrange.to_row);
+        return;
+    }
+
     push_tablename(
         buf,
         cellrange.table.as_ref(),
@@ -930,10 +1492,11 @@ pub(crate) fn cellranges_string(v: &[CellRange]) -> String {
 #[cfg(test)]
 mod tests {
     use crate::refs::{
-        parse_cellrange, parse_cellranges, parse_cellref, parse_colname, parse_rowname,
-        push_cellrange, push_cellref, push_colname, push_rowname, push_tablename,
+        cell_from_name, cell_name, col_from_name, col_name, parse_cellrange, parse_cellranges,
+        parse_cellref, parse_colname, parse_rowname, push_cellrange, push_cellref, push_colname,
+        push_rowname, push_tablename,
     };
-    use crate::{CellRange, CellRef, OdsError};
+    use crate::{CellRange, CellRangeList, CellRef, OdsError};
 
     #[test]
     fn test_names() {
@@ -1016,6 +1579,158 @@ mod tests {
         buf.clear();
     }
 
+    #[test]
+    fn test_colnames() {
+        for col in [0, 1, 25, 26, 675, 676, 16383, u32::MAX - 1, u32::MAX] {
+            assert_eq!(col_from_name(&col_name(col)), Some(col));
+            assert_eq!(cell_from_name(&cell_name(col, col)), Some((col, col)));
+        }
+        assert_eq!(col_name(16383), "XFD");
+        assert_eq!(col_from_name("xfd"), Some(16383));
+        assert_eq!(col_from_name(""), None);
+        assert_eq!(col_from_name("A1"), None);
+        assert_eq!(col_from_name("MWLQKWW"), None);
+
+        assert_eq!(cell_name(927, 2), "C928");
+        assert_eq!(cell_from_name("C928"), Some((927, 2)));
+        assert_eq!(cell_from_name("C0"), None);
+        assert_eq!(cell_from_name("C"), None);
+        assert_eq!(cell_from_name("12"), None);
+        assert_eq!(cell_from_name("$C$1"), None);
+        assert_eq!(cell_from_name("C1D"), None);
+        assert_eq!(cell_from_name("C+1"), None);
+    }
+
+    #[test]
+    fn test_r1c1() -> Result<(), OdsError> {
+        let base = CellRef::remote("fable", 4, 2);
+
+        let c = CellRef::try_from_r1c1("R[-1]C[2]", &base)?;
+        assert_eq!(c, CellRef::remote("fable", 3, 4));
+        assert_eq!(c.to_r1c1(&base), "R[-1]C[2]");
+
+        let c = CellRef::try_from_r1c1("RC", &base)?;
+        assert_eq!(c, base);
+        assert_eq!(c.to_r1c1(&base), "RC");
+
+        let c = CellRef::try_from_r1c1("R1C3", &base)?;
+        assert_eq!(c, CellRef::remote("fable", 0, 2).absolute());
+        assert_eq!(c.to_r1c1(&base), "R1C3");
+        assert_eq!(c.to_r1c1(&CellRef::local(0, 0)), "R1C3");
+
+        let c = CellRef::try_from_r1c1("R5C[-2]", &base)?;
+        assert_eq!(c.to_string(), "$fable.A$5");
+
+        assert!(CellRef::try_from_r1c1("R[-5]C", &base).is_err());
+        assert!(CellRef::try_from_r1c1("R0C1", &base).is_err());
+        assert!(CellRef::try_from_r1c1("R1C1x", &base).is_err());
+        assert!(CellRef::try_from_r1c1("C1", &base).is_err());
+        assert!(CellRef::try_from_r1c1("R[1C1", &base).is_err());
+
+        assert_eq!(
+            CellRef::relative_to(&base, 1, -2),
+            Some(CellRef::remote("fable", 5, 0))
+        );
+        assert_eq!(CellRef::relative_to(&base, 0, -3), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rangelist() -> Result<(), OdsError> {
+        let list = CellRangeList::try_from("")?;
+        assert!(list.is_empty());
+        assert_eq!(list.to_string(), "");
+
+        let list = CellRangeList::try_from("$'Sheet 1'.$A$1:.$B$2 .C3:.D4")?;
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.to_string(), "$'Sheet 1'.$A$1:.$B$2 .C3:.D4");
+        assert!(list.contains(3, 3));
+        assert!(!list.contains(4, 4));
+        let tables: Vec<_> = list.iter().map(|v| v.table().cloned()).collect();
+        assert_eq!(tables, vec![Some("Sheet 1".to_string()), None]);
+
+        let list: CellRangeList = list.into_iter().skip(1).collect();
+        assert_eq!(list.ranges(), &[CellRange::local(2, 2, 3, 3)]);
+
+        assert!(CellRangeList::try_from(".A1:.B2,.C3").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_whole_cols_rows() -> Result<(), OdsError> {
+        let r = CellRange::try_from(".A:.C")?;
+        assert_eq!(r, CellRange::whole_cols(0, 2));
+        assert!(r.is_whole_cols() && !r.is_whole_rows());
+        assert!(r.contains(1_000_000, 2));
+        assert_eq!(r.to_string(), ".A:.C");
+
+        let r = CellRange::try_from("'Sheet 1'.$B:.B")?;
+        assert_eq!(r.table().map(|v| v.as_str()), Some("Sheet 1"));
+        assert!(r.col_abs() && !r.to_col_abs());
+        assert_eq!(r.to_string(), "$'Sheet 1'.$B:.B");
+
+        let r = CellRange::try_from(".$2:.$5")?;
+        assert_eq!(r.row(), 1);
+        assert_eq!(r.to_row(), 4);
+        assert!(r.is_whole_rows() && r.row_abs() && r.to_row_abs());
+        assert!(r.contains(3, 16_000));
+        assert_eq!(r.to_string(), ".$2:.$5");
+
+        let r = CellRange::try_from(".$C:.A")?;
+        assert_eq!(r.col(), 0);
+        assert_eq!(r.to_col(), 2);
+        assert!(!r.col_abs() && r.to_col_abs());
+        assert_eq!(r.to_string(), ".A:.$C");
+        let r = CellRange::try_from(".5:.2")?;
+        assert_eq!(r, CellRange::whole_rows(1, 4));
+
+        assert!(CellRange::try_from(".A:.5").is_err());
+        assert!(CellRange::try_from(".A1:.B").is_err());
+        assert!(CellRange::try_from(".A").is_err());
+        assert!(CellRange::try_from(".A$:.B").is_err());
+
+        let list = CellRangeList::try_from("Sheet1.A:.A Sheet1.1:.1")?;
+        assert_eq!(list.to_string(), "Sheet1.A:.A Sheet1.1:.1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_absolute() -> Result<(), OdsError> {
+        let mut c = CellRef::remote("fable", 5, 6);
+        assert!(c.is_relative());
+        c.make_absolute();
+        assert!(c.is_absolute());
+        assert_eq!(c.to_string(), "$fable.$G$6");
+        c.set_row_abs(false);
+        assert!(!c.is_absolute() && !c.is_relative());
+        assert_eq!(c.to_string(), "$fable.$G6");
+        c.make_relative();
+        assert_eq!(c.to_string(), "fable.G6");
+        assert_eq!(
+            CellRef::local(5, 6).absolute_col().relative(),
+            CellRef::local(5, 6)
+        );
+
+        let c = CellRef::try_from(".G$6")?;
+        assert!(!c.col_abs() && c.row_abs());
+
+        let mut r = CellRange::try_from(".$G6:.I$8")?;
+        assert!(r.col_abs() && !r.row_abs() && !r.to_col_abs() && r.to_row_abs());
+        assert_eq!(r.start_cellref().to_string(), ".$G6");
+        assert_eq!(r.end_cellref().to_string(), ".I$8");
+        r.make_absolute();
+        assert!(r.is_absolute());
+        assert_eq!(r.to_string(), ".$G$6:.$I$8");
+        r.make_relative();
+        assert!(r.is_relative());
+        assert_eq!(r.to_string(), ".G6:.I8");
+
+        Ok(())
+    }
+
     #[test]
     fn test_parse() -> Result<(), OdsError> {
         fn rowname(row: u32) -> String {
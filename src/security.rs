@@ -0,0 +1,344 @@
+//!
+//! Removes active content from untrusted documents.
+//!
+//! ```
+//! use spreadsheet_ods::WorkBook;
+//! use spreadsheet_ods::security::SecurityPolicy;
+//!
+//! let mut wb = WorkBook::new_empty();
+//! // strips everything
+//! wb.sanitize(&SecurityPolicy::default());
+//! // keep the macros
+//! wb.sanitize(&SecurityPolicy {
+//!     remove_macros: false,
+//!     ..Default::default()
+//! });
+//! ```
+//!
+
+use crate::formula::{tokens, Token};
+use crate::xmltree::{XmlContent, XmlTag};
+use crate::{Sheet, Value, WorkBook};
+use std::collections::HashSet;
+
+/// What is removed by WorkBook::sanitize().
+#[derive(Clone, Debug)]
+pub struct SecurityPolicy {
+    /// Basic and script libraries and dialogs. Hyperlinks in cell
+    /// text and shapes that start a script are replaced with their
+    /// content.
+    pub remove_macros: bool,
+    /// Event listeners of the document, the sheets, form controls,
+    /// shapes and hyperlinks in cell text.
+    pub remove_event_listeners: bool,
+    /// Sheets linked to other documents, formulas with references
+    /// to other documents or with WEBSERVICE(), and linked images
+    /// and objects. The formulas are removed, the last calculated
+    /// value is kept. Named expressions and validations with such
+    /// formulas are removed too. Database ranges lose their import
+    /// from a database, pilot tables with an external source are
+    /// removed.
+    pub remove_external_links: bool,
+    /// DDE sources, DDE links and formulas with DDE(). The formulas
+    /// are removed, the last calculated value is kept. Named expressions
+    /// and validations with such formulas are removed too.
+    pub remove_dde: bool,
+}
+
+impl Default for SecurityPolicy {
+    /// Removes everything.
+    fn default() -> Self {
+        Self {
+            remove_macros: true,
+            remove_event_listeners: true,
+            remove_external_links: true,
+            remove_dde: true,
+        }
+    }
+}
+
+/// Applies the policy.
+pub(crate) fn sanitize(book: &mut WorkBook, policy: &SecurityPolicy) {
+    if policy.remove_macros {
+        for dir in ["Basic/", "Scripts/", "Dialogs/"] {
+            book.remove_media(dir);
+        }
+        for tag in book.extra.iter_mut() {
+            if tag.name() == "office:scripts" {
+                remove_tags(tag, &|v| v.name() == "office:script");
+            }
+        }
+    }
+    if policy.remove_event_listeners {
+        book.clear_event_listeners();
+    }
+    if policy.remove_dde {
+        book.extra
+            .retain(|v| v.name() != "table:dde-links" && v.name() != "text:dde-connection-decls");
+    }
+    if policy.remove_external_links {
+        for tag in book.extra.iter_mut() {
+            match tag.name() {
+                "table:database-ranges" => remove_tags(tag, &is_database_source),
+                "table:data-pilot-tables" => remove_tags(tag, &is_external_pilot_table),
+                _ => {}
+            }
+        }
+    }
+    for tag in book.extra.iter_mut() {
+        remove_named_expressions(tag, policy);
+    }
+
+    let mut validations = HashSet::new();
+    book.validations.retain(|name, v| {
+        if is_active_formula(v.condition(), policy) {
+            validations.insert(name.clone());
+            false
+        } else {
+            true
+        }
+    });
+
+    for i in 0..book.num_sheets() {
+        sanitize_sheet(book.sheet_mut(i), policy, &validations);
+    }
+}
+
+fn sanitize_sheet(sheet: &mut Sheet, policy: &SecurityPolicy, validations: &HashSet<String>) {
+    let extra_size = xml_size(&sheet.extra);
+    let mut extra_changed =
+        policy.remove_macros && unwrap_tag_list(&mut sheet.extra, &is_script_link);
+    if policy.remove_event_listeners {
+        sheet.clear_event_listeners();
+        for tag in sheet.extra.iter_mut() {
+            remove_tags(tag, &is_event_listeners);
+        }
+    }
+    if policy.remove_external_links {
+        sheet.extra.retain(|v| v.name() != "table:table-source");
+        for tag in sheet.extra.iter_mut() {
+            remove_tags(tag, &is_linked_shape);
+        }
+    }
+    if policy.remove_dde {
+        sheet.extra.retain(|v| v.name() != "office:dde-source");
+    }
+    for tag in sheet.extra.iter_mut() {
+        remove_named_expressions(tag, policy);
+    }
+    extra_changed |= xml_size(&sheet.extra) != extra_size;
+    if extra_changed {
+        sheet.mark_dirty();
+    }
+
+    let mut changed = Vec::new();
+    for (pos, cell) in sheet.data.iter_mut() {
+        if let Some(formula) = &cell.formula {
+            if is_active_formula(formula, policy) {
+                cell.formula = None;
+                changed.push(*pos);
+            }
+        }
+        if let Value::TextXml(text) = &mut cell.value {
+            let text_size = xml_size(text);
+            let mut text_changed = policy.remove_macros && unwrap_tag_list(text, &is_script_link);
+            if policy.remove_event_listeners {
+                text.iter_mut().for_each(|v| {
+                    remove_tags(v, &is_event_listeners);
+                });
+            }
+            text_changed |= xml_size(text) != text_size;
+            if text_changed {
+                changed.push(*pos);
+            }
+        }
+        if let Some(extra) = &mut cell.extra {
+            if matches!(&extra.validation_name, Some(v) if validations.contains(v)) {
+                extra.validation_name = None;
+                changed.push(*pos);
+            }
+            let shapes_size = xml_size(&extra.shapes);
+            let mut shapes_changed =
+                policy.remove_macros && unwrap_tag_list(&mut extra.shapes, &is_script_link);
+            if policy.remove_event_listeners {
+                extra.shapes.iter_mut().for_each(|v| {
+                    remove_tags(v, &is_event_listeners);
+                });
+            }
+            if policy.remove_external_links {
+                extra.shapes.retain(|v| !is_linked_shape(v));
+                extra.shapes.iter_mut().for_each(|v| {
+                    remove_tags(v, &is_linked_shape);
+                });
+            }
+            shapes_changed |= xml_size(&extra.shapes) != shapes_size;
+            if shapes_changed {
+                changed.push(*pos);
+            }
+        }
+    }
+    for (row, col) in changed {
+        sheet.mark_cell_dirty(row, col);
+    }
+}
+
+// Cheap check for changes, removing a tag always reduces this.
+fn xml_size(tags: &[XmlTag]) -> usize {
+    tags.iter().map(|v| v.heap_size()).sum()
+}
+
+fn is_event_listeners(tag: &XmlTag) -> bool {
+    tag.name() == "office:event-listeners"
+}
+
+// Formulas that are removed by the policy.
+fn is_active_formula(formula: &str, policy: &SecurityPolicy) -> bool {
+    tokens(formula).into_iter().any(|(_, token)| match token {
+        Token::Function(name) => {
+            (policy.remove_dde && is_function(name, "DDE"))
+                || (policy.remove_external_links && is_function(name, "WEBSERVICE"))
+        }
+        Token::Ref(refstr) => policy.remove_external_links && is_external_ref(refstr),
+        Token::Name(_) => false,
+    })
+}
+
+// Function names are not case-sensitive and may have a namespace
+// like COM.MICROSOFT.WEBSERVICE.
+fn is_function(name: &str, function: &str) -> bool {
+    matches!(name.rsplit('.').next(), Some(v) if v.eq_ignore_ascii_case(function))
+}
+
+// External references look like 'file:///data.ods'#$Sheet1.A1.
+fn is_external_ref(refstr: &str) -> bool {
+    refstr.contains("'#")
+}
+
+// Named expressions and ranges below table:named-expressions.
+fn remove_named_expressions(tag: &mut XmlTag, policy: &SecurityPolicy) {
+    if tag.name() != "table:named-expressions" {
+        return;
+    }
+    tag.content_mut().retain(|v| match v {
+        XmlContent::Tag(t) => {
+            let attr = t.attrmap();
+            let expr = matches!(attr.attr("table:expression"),
+                Some(v) if is_active_formula(v, policy));
+            let range = matches!(attr.attr("table:cell-range-address"),
+                Some(v) if policy.remove_external_links && is_external_ref(v));
+            !expr && !range
+        }
+        XmlContent::Text(_) => true,
+    });
+}
+
+// Hyperlinks that start a script.
+fn is_script_link(tag: &XmlTag) -> bool {
+    (tag.name() == "text:a" || tag.name() == "draw:a")
+        && matches!(tag.attrmap().attr("xlink:href"), Some(v) if is_script_href(v))
+}
+
+fn is_script_href(href: &str) -> bool {
+    let href = href.trim_start();
+    ["vnd.sun.star.script:", "macro:"]
+        .iter()
+        .any(|scheme| matches!(href.get(..scheme.len()), Some(v) if v.eq_ignore_ascii_case(scheme)))
+}
+
+// Frames with an image or object that is not stored in the document.
+fn is_linked_shape(tag: &XmlTag) -> bool {
+    tag.name() == "draw:frame"
+        && tag.content().iter().any(|v| match v {
+            XmlContent::Tag(t) if t.name() == "draw:image" || t.name() == "draw:object" => {
+                matches!(t.attrmap().attr("xlink:href"), Some(v) if is_external_href(v))
+            }
+            _ => false,
+        })
+}
+
+// Only paths inside the package like "./Pictures/..." are internal.
+// Anything with a scheme or a drive, absolute paths and paths that
+// leave the package with ".." are external.
+fn is_external_href(href: &str) -> bool {
+    let href = href.trim();
+    href.contains(':')
+        || href.starts_with('/')
+        || href.starts_with('\\')
+        || href.split(['/', '\\']).any(|v| v == "..")
+}
+
+// Import from a database for a database range or a pilot table.
+fn is_database_source(tag: &XmlTag) -> bool {
+    matches!(
+        tag.name(),
+        "table:database-source-sql" | "table:database-source-table" | "table:database-source-query"
+    )
+}
+
+// Pilot tables that read from a database, a service or another document.
+fn is_external_pilot_table(tag: &XmlTag) -> bool {
+    tag.name() == "table:data-pilot-table"
+        && tag.content().iter().any(|v| match v {
+            XmlContent::Tag(t) if is_database_source(t) => true,
+            XmlContent::Tag(t) if t.name() == "table:source-service" => true,
+            XmlContent::Tag(t) if t.name() == "table:source-cell-range" => {
+                matches!(t.attrmap().attr("table:cell-range-address"),
+                    Some(v) if is_external_ref(v))
+            }
+            _ => false,
+        })
+}
+
+// Replaces matching tags in the list and below with their content.
+// Text directly in a replaced tag is dropped, there is no place for it
+// in the list. Returns true if something was replaced.
+fn unwrap_tag_list(tags: &mut Vec<XmlTag>, test: &dyn Fn(&XmlTag) -> bool) -> bool {
+    let mut changed = false;
+    for mut tag in std::mem::take(tags) {
+        changed |= unwrap_tags(&mut tag, test);
+        if test(&tag) {
+            tags.extend(tag.content_mut().drain(..).filter_map(|v| match v {
+                XmlContent::Tag(t) => Some(t),
+                XmlContent::Text(_) => None,
+            }));
+            changed = true;
+        } else {
+            tags.push(tag);
+        }
+    }
+    changed
+}
+
+// Replaces all matching tags below this one with their content.
+// Returns true if something was replaced.
+fn unwrap_tags(tag: &mut XmlTag, test: &dyn Fn(&XmlTag) -> bool) -> bool {
+    let mut changed = false;
+    for v in std::mem::take(tag.content_mut()) {
+        match v {
+            XmlContent::Tag(mut t) => {
+                changed |= unwrap_tags(&mut t, test);
+                if test(&t) {
+                    tag.content_mut().append(t.content_mut());
+                    changed = true;
+                } else {
+                    tag.content_mut().push(XmlContent::Tag(t));
+                }
+            }
+            v => tag.content_mut().push(v),
+        }
+    }
+    changed
+}
+
+// Removes all matching tags below this one.
+fn remove_tags(tag: &mut XmlTag, test: &dyn Fn(&XmlTag) -> bool) {
+    tag.content_mut().retain(|v| match v {
+        XmlContent::Tag(t) => !test(t),
+        XmlContent::Text(_) => true,
+    });
+    for v in tag.content_mut() {
+        if let XmlContent::Tag(t) = v {
+            remove_tags(t, test);
+        }
+    }
+}
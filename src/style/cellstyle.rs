@@ -12,8 +12,9 @@ use crate::style::units::{
 };
 use crate::style::{
     border_line_width_string, border_string, color_string, shadow_string, text_position,
-    StyleOrigin, StyleUse, TextStyleRef,
+    ParseStyleAttr, StyleOrigin, StyleUse, TextStyleRef,
 };
+use crate::OdsError;
 use color::Rgb;
 use icu_locid::Locale;
 use std::fmt::{Display, Formatter};
@@ -150,6 +151,13 @@ impl CellStyle {
         &mut self.textstyle
     }
 
+    raw_attr!(
+        "style:style" => attr,
+        "style:table-cell-properties" => cellstyle,
+        "style:paragraph-properties" => paragraphstyle,
+        "style:text-properties" => textstyle
+    );
+
     /// Adds a stylemap.
     pub fn push_stylemap(&mut self, stylemap: StyleMap) {
         self.stylemaps.get_or_insert_with(Vec::new).push(stylemap);
@@ -72,6 +72,11 @@ impl ColStyle {
         &mut self.colstyle
     }
 
+    raw_attr!(
+        "style:style" => attr,
+        "style:table-column-properties" => colstyle
+    );
+
     fo_break!(colstyle);
     style_column_width!(colstyle);
     style_rel_column_width!(colstyle);
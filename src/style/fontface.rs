@@ -88,6 +88,8 @@ impl FontFaceDecl {
         &mut self.attr
     }
 
+    raw_attr!("style:font-face" => attr);
+
     style_font_family_generic!(attr);
     style_font_pitch!(attr);
     svg_font_family!(attr);
@@ -1,5 +1,7 @@
 use crate::attrmap2::AttrMap2;
-use crate::style::{StyleOrigin, StyleUse};
+use crate::style::units::{FontSize, FontStyle, FontWeight};
+use crate::style::{color_string, StyleOrigin, StyleUse};
+use color::Rgb;
 use std::fmt::{Display, Formatter};
 
 style_ref!(GraphicStyleRef);
@@ -7,6 +9,10 @@ style_ref!(GraphicStyleRef);
 /// Styles of this type can occur in an odt file.
 /// This is only used as a place to put this stuff when reading the ods.
 ///
+/// In a spreadsheet the named graphic style "Note" is used for the
+/// annotations of cells. The text properties are used for the text of
+/// the annotation.
+///
 #[derive(Debug, Clone)]
 pub struct GraphicStyle {
     /// From where did we get this style.
@@ -20,6 +26,10 @@ pub struct GraphicStyle {
     /// Table style properties
     // ignore these attributes for now.
     graphicstyle: AttrMap2,
+    /// Paragraph style properties
+    paragraphstyle: AttrMap2,
+    /// Text style properties
+    textstyle: AttrMap2,
 }
 
 styles_styles!(GraphicStyle, GraphicStyleRef);
@@ -33,6 +43,8 @@ impl GraphicStyle {
             name: Default::default(),
             attr: Default::default(),
             graphicstyle: Default::default(),
+            paragraphstyle: Default::default(),
+            textstyle: Default::default(),
         }
     }
 
@@ -44,6 +56,8 @@ impl GraphicStyle {
             name: name.into(),
             attr: Default::default(),
             graphicstyle: Default::default(),
+            paragraphstyle: Default::default(),
+            textstyle: Default::default(),
         }
     }
 
@@ -66,4 +80,44 @@ impl GraphicStyle {
     pub fn graphicstyle_mut(&mut self) -> &mut AttrMap2 {
         &mut self.graphicstyle
     }
+
+    /// Paragraph style attributes.
+    pub fn paragraphstyle(&self) -> &AttrMap2 {
+        &self.paragraphstyle
+    }
+
+    /// Paragraph style attributes.
+    pub fn paragraphstyle_mut(&mut self) -> &mut AttrMap2 {
+        &mut self.paragraphstyle
+    }
+
+    /// Text style attributes.
+    pub fn textstyle(&self) -> &AttrMap2 {
+        &self.textstyle
+    }
+
+    /// Text style attributes.
+    pub fn textstyle_mut(&mut self) -> &mut AttrMap2 {
+        &mut self.textstyle
+    }
+
+    raw_attr!(
+        "style:style" => attr,
+        "style:graphic-properties" => graphicstyle,
+        "style:paragraph-properties" => paragraphstyle,
+        "style:text-properties" => textstyle
+    );
+
+    /// Fills the shape with a solid color.
+    pub fn set_fill_color(&mut self, color: Rgb<u8>) {
+        self.graphicstyle.set_attr("draw:fill", "solid".to_string());
+        self.graphicstyle
+            .set_attr("draw:fill-color", color_string(color));
+    }
+
+    fo_color!(textstyle);
+    style_font_name!(textstyle);
+    fo_font_size!(textstyle);
+    fo_font_style!(textstyle);
+    fo_font_weight!(textstyle);
 }
@@ -43,6 +43,23 @@
 //!
 //! ```
 //!
+//! Attributes without a typed setter can be set with set_raw_attr().
+//! They take the xml element too, as most styles write to more than one.
+//! All attributes read from a file are kept, whether the typed API knows
+//! them or not, and are written back unchanged.
+//!
+//! ```
+//! use spreadsheet_ods::style::CellStyle;
+//!
+//! let mut cs = CellStyle::new_empty();
+//! cs.set_raw_attr("style:table-cell-properties", "loext:vertical-justify", "auto".to_string())
+//!     .expect("element");
+//! assert_eq!(
+//!     cs.raw_attr("style:table-cell-properties", "loext:vertical-justify"),
+//!     Some(&"auto".to_string())
+//! );
+//! ```
+//!
 //! From the specification:
 //!
 //! The style:style element represents styles.
@@ -204,6 +221,23 @@ impl ParseStyleAttr<bool> for bool {
     }
 }
 
+impl ParseStyleAttr<Rgb<u8>> for Rgb<u8> {
+    fn parse_attr(attr: Option<&String>) -> Result<Option<Rgb<u8>>, OdsError> {
+        if let Some(s) = attr {
+            match s.strip_prefix('#') {
+                Some(hex) if hex.len() == 6 && hex.is_ascii() => Ok(Some(Rgb::new(
+                    u8::from_str_radix(&hex[0..2], 16)?,
+                    u8::from_str_radix(&hex[2..4], 16)?,
+                    u8::from_str_radix(&hex[4..6], 16)?,
+                ))),
+                _ => Err(OdsError::Parse(format!("invalid color {}", s))),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 pub(crate) fn color_string(color: Rgb<u8>) -> String {
     format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
 }
@@ -117,6 +117,8 @@ impl PageStyle {
         &mut self.style
     }
 
+    raw_attr!("style:page-layout-properties" => style);
+
     fo_page_height!(style);
     fo_page_width!(style);
     style_first_page_number!(style);
@@ -159,6 +161,8 @@ impl HeaderFooterStyle {
         &mut self.style
     }
 
+    raw_attr!("style:header-footer-properties" => style);
+
     fo_background_color!(style);
     fo_border!(style);
     fo_margin!(style);
@@ -10,9 +10,9 @@ use crate::style::units::{
 };
 use crate::style::{
     border_line_width_string, border_string, color_string, shadow_string, text_position,
-    StyleOrigin, StyleUse, TextStyleRef,
+    ParseStyleAttr, StyleOrigin, StyleUse, TextStyleRef,
 };
-use crate::MasterPageRef;
+use crate::{MasterPageRef, OdsError};
 use color::Rgb;
 use icu_locid::Locale;
 use std::fmt::{Display, Formatter};
@@ -101,6 +101,12 @@ impl ParagraphStyle {
         &mut self.textstyle
     }
 
+    raw_attr!(
+        "style:style" => attr,
+        "style:paragraph-properties" => paragraphstyle,
+        "style:text-properties" => textstyle
+    );
+
     style_default_outline_level!(attr);
     style_master_page!(attr);
     style_next_style!(attr);
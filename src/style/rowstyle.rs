@@ -72,6 +72,11 @@ impl RowStyle {
         &mut self.rowstyle
     }
 
+    raw_attr!(
+        "style:style" => attr,
+        "style:table-row-properties" => rowstyle
+    );
+
     fo_background_color!(rowstyle);
     fo_break!(rowstyle);
     fo_keep_together!(rowstyle);
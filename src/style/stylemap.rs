@@ -38,6 +38,12 @@ impl StyleMap {
         &self.condition
     }
 
+    /// Condition as ValueCondition. Use ValueCondition::kind() to look
+    /// at the parts.
+    pub fn value_condition(&self) -> ValueCondition {
+        ValueCondition::new(self.condition.as_str())
+    }
+
     /// Condition
     pub fn set_condition(&mut self, cond: ValueCondition) {
         self.condition = cond.to_string();
@@ -3,7 +3,10 @@ use crate::style::units::{
     Length, Margin, PageBreak, PageNumber, RelativeWidth, TableAlign, TableBorderModel, TextKeep,
     WritingMode,
 };
-use crate::style::{color_string, shadow_string, MasterPageRef, StyleOrigin, StyleUse};
+use crate::style::{
+    color_string, shadow_string, MasterPageRef, ParseStyleAttr, StyleOrigin, StyleUse,
+};
+use crate::OdsError;
 use color::Rgb;
 use std::fmt::{Display, Formatter};
 
@@ -72,6 +75,11 @@ impl TableStyle {
         &mut self.tablestyle
     }
 
+    raw_attr!(
+        "style:style" => attr,
+        "style:table-properties" => tablestyle
+    );
+
     fo_background_color!(tablestyle);
     fo_break!(tablestyle);
     fo_keep_with_next!(tablestyle);
@@ -36,6 +36,8 @@ impl TabStop {
         &mut self.attr
     }
 
+    raw_attr!("style:tab-stop" => attr);
+
     style_char!(attr);
     style_leader_color!(attr);
     style_leader_style!(attr);
@@ -72,6 +72,11 @@ impl TextStyle {
         &mut self.textstyle
     }
 
+    raw_attr!(
+        "style:style" => attr,
+        "style:text-properties" => textstyle
+    );
+
     fo_background_color!(textstyle);
     fo_color!(textstyle);
     fo_locale!(textstyle);
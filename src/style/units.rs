@@ -485,6 +485,21 @@ impl Display for PageBreak {
     }
 }
 
+impl ParseStyleAttr<PageBreak> for PageBreak {
+    fn parse_attr(attr: Option<&String>) -> Result<Option<PageBreak>, OdsError> {
+        if let Some(attr) = attr {
+            match attr.as_str() {
+                "auto" => Ok(Some(PageBreak::Auto)),
+                "column" => Ok(Some(PageBreak::Column)),
+                "page" => Ok(Some(PageBreak::Page)),
+                s => Err(OdsError::Parse(s.to_string())),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// 20.190 fo:font-size
 ///
 /// See §7.8.4 of XSL.
@@ -1211,6 +1226,19 @@ impl Display for PageNumber {
     }
 }
 
+impl ParseStyleAttr<PageNumber> for PageNumber {
+    fn parse_attr(attr: Option<&String>) -> Result<Option<PageNumber>, OdsError> {
+        if let Some(attr) = attr {
+            match attr.as_str() {
+                "auto" => Ok(Some(PageNumber::Auto)),
+                s => Ok(Some(PageNumber::Number(s.parse()?))),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// 20.330 style:print
 ///
 /// The style:print attribute specifies the components in a spreadsheet document to print.
@@ -1925,6 +1953,26 @@ impl Display for WritingMode {
     }
 }
 
+impl ParseStyleAttr<WritingMode> for WritingMode {
+    fn parse_attr(attr: Option<&String>) -> Result<Option<WritingMode>, OdsError> {
+        if let Some(attr) = attr {
+            match attr.as_str() {
+                "lr-tb" => Ok(Some(WritingMode::LrTb)),
+                "rl-tb" => Ok(Some(WritingMode::RlTb)),
+                "tb-rl" => Ok(Some(WritingMode::TbRl)),
+                "tb-lr" => Ok(Some(WritingMode::TbLr)),
+                "lr" => Ok(Some(WritingMode::Lr)),
+                "rl" => Ok(Some(WritingMode::Rl)),
+                "tb" => Ok(Some(WritingMode::Tb)),
+                "page" => Ok(Some(WritingMode::Page)),
+                s => Err(OdsError::Parse(s.to_string())),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// 20.414 table:align
 ///
 /// The table:align attribute specifies the horizontal alignment of a table.
@@ -1956,6 +2004,22 @@ impl Display for TableAlign {
     }
 }
 
+impl ParseStyleAttr<TableAlign> for TableAlign {
+    fn parse_attr(attr: Option<&String>) -> Result<Option<TableAlign>, OdsError> {
+        if let Some(attr) = attr {
+            match attr.as_str() {
+                "center" => Ok(Some(TableAlign::Center)),
+                "left" => Ok(Some(TableAlign::Left)),
+                "right" => Ok(Some(TableAlign::Right)),
+                "margins" => Ok(Some(TableAlign::Margins)),
+                s => Err(OdsError::Parse(s.to_string())),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// 20.415 table:border-model
 ///
 /// The table:border-model attribute specifies what border model to use when creating a table
@@ -1986,6 +2050,20 @@ impl Display for TableBorderModel {
     }
 }
 
+impl ParseStyleAttr<TableBorderModel> for TableBorderModel {
+    fn parse_attr(attr: Option<&String>) -> Result<Option<TableBorderModel>, OdsError> {
+        if let Some(attr) = attr {
+            match attr.as_str() {
+                "collapsing" => Ok(Some(TableBorderModel::Collapsing)),
+                "separating" => Ok(Some(TableBorderModel::Separating)),
+                s => Err(OdsError::Parse(s.to_string())),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 /// 20.426 text:condition
 ///
 /// The text:condition attribute specifies the display of text.
@@ -47,8 +47,12 @@ macro_rules! styles_styles {
 /// Generates a name reference for a style.
 macro_rules! style_ref {
     ($l:ident) => {
-        /// Reference
-        #[derive(Debug, Clone)]
+        /// Typed reference to a named style.
+        ///
+        /// Returned by the corresponding add function of the WorkBook.
+        /// If only the raw name is known, the From conversions can be used
+        /// to create one.
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
         pub struct $l {
             name: String,
         }
@@ -81,6 +85,12 @@ macro_rules! style_ref {
             }
         }
 
+        impl From<&$l> for String {
+            fn from(name: &$l) -> Self {
+                name.to_string()
+            }
+        }
+
         impl AsRef<$l> for $l {
             fn as_ref(&self) -> &$l {
                 self
@@ -101,3 +111,55 @@ macro_rules! style_ref {
         }
     };
 }
+
+/// Generates set_raw_attr(), raw_attr() and clear_raw_attr() for the
+/// attributes without a typed setter. Takes the xml element of each
+/// attribute map.
+macro_rules! raw_attr {
+    ($($element:literal => $acc:ident),+) => {
+        #[doc = concat!(
+            "Sets an attribute that has no typed setter. It is written ",
+            "unchanged to the given xml element.\n\nElements:",
+            $(" ", $element,)+
+        )]
+        ///
+        /// Fails if the element doesn't belong to this style.
+        pub fn set_raw_attr(
+            &mut self,
+            element: &str,
+            name: &str,
+            value: String,
+        ) -> Result<(), crate::OdsError> {
+            $(
+                if element == $element {
+                    self.$acc.set_attr(name, value);
+                    return Ok(());
+                }
+            )+
+            Err(crate::OdsError::Ods(format!(
+                "no element {} for the attribute {}",
+                element, name
+            )))
+        }
+
+        /// Returns an attribute of the xml element, typed or not.
+        pub fn raw_attr(&self, element: &str, name: &str) -> Option<&String> {
+            $(
+                if element == $element {
+                    return self.$acc.attr(name);
+                }
+            )+
+            None
+        }
+
+        /// Removes an attribute of the xml element.
+        pub fn clear_raw_attr(&mut self, element: &str, name: &str) -> Option<String> {
+            $(
+                if element == $element {
+                    return self.$acc.clear_attr(name);
+                }
+            )+
+            None
+        }
+    };
+}
@@ -0,0 +1,382 @@
+//!
+//! Reads a range of a sheet as a table with a header row.
+//!
+//! The columns are found by the text in the header row, so it doesn't
+//! matter if the columns are reordered in the source file. A TableSchema
+//! checks the table before it is processed.
+//!
+//! ```
+//! use spreadsheet_ods::table::TableReader;
+//! use spreadsheet_ods::{CellRange, Sheet};
+//!
+//! let mut sheet = Sheet::new("1");
+//! sheet.set_value(0, 0, "name");
+//! sheet.set_value(0, 1, "amount");
+//! sheet.set_value(1, 0, "apples");
+//! sheet.set_value(1, 1, 3);
+//!
+//! let reader = TableReader::new(&sheet, &CellRange::local(0, 0, u32::MAX, 10));
+//! for row in reader {
+//!     let name = row.get_as::<String>("name").unwrap();
+//!     let amount = row.get_as::<f64>("amount").unwrap();
+//!     assert_eq!(name.as_deref(), Some("apples"));
+//!     assert_eq!(amount, Some(3.0));
+//! }
+//! ```
+//!
+//! ```
+//! use spreadsheet_ods::table::{ColumnSchema, TableSchema};
+//! use spreadsheet_ods::{CellRange, Sheet, ValueType};
+//!
+//! let mut sheet = Sheet::new("1");
+//! sheet.set_value(0, 0, "amount");
+//! sheet.set_value(1, 0, -3);
+//!
+//! let schema = TableSchema::new()
+//!     .column(ColumnSchema::new("name").required())
+//!     .column(
+//!         ColumnSchema::new("amount")
+//!             .value_type(ValueType::Number)
+//!             .min(0.0),
+//!     );
+//! let errors = schema.validate(&sheet, &CellRange::local(0, 0, u32::MAX, 10));
+//! for e in &errors {
+//!     println!("{}", e);
+//! }
+//! assert_eq!(errors.len(), 2);
+//! ```
+//!
+
+use crate::{CellRange, CellRef, FromValue, OdsError, Sheet, Value, ValueType};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::iter::FusedIterator;
+use std::rc::Rc;
+
+/// Iterates the rows of a range after the header row.
+///
+/// The header texts are trimmed, empty header cells are ignored. If a
+/// header occurs twice the first column is used. The range ends with the
+/// last used row of the sheet and rows without any value in one of the
+/// header columns are skipped.
+#[derive(Debug, Clone)]
+pub struct TableReader<'a> {
+    sheet: &'a Sheet,
+    headers: Rc<Headers>,
+    row: u32,
+    to_row: u32,
+}
+
+#[derive(Debug, Default)]
+struct Headers {
+    names: Vec<(String, u32)>,
+    cols: HashMap<String, u32>,
+}
+
+impl<'a> TableReader<'a> {
+    /// Uses the first row of the range as header.
+    pub fn new(sheet: &'a Sheet, range: &CellRange) -> Self {
+        let mut headers = Headers::default();
+        for col in range.col()..=range.to_col() {
+            let name = sheet.value(range.row(), col).as_cow_str_or("");
+            let name = name.trim();
+            if !name.is_empty() && !headers.cols.contains_key(name) {
+                headers.names.push((name.to_string(), col));
+                headers.cols.insert(name.to_string(), col);
+            }
+        }
+
+        let last_row = sheet.data.keys().next_back().map_or(0, |(r, _)| *r);
+
+        Self {
+            sheet,
+            headers: Rc::new(headers),
+            row: range.row().saturating_add(1),
+            to_row: range.to_row().min(last_row),
+        }
+    }
+
+    /// Header names in the order of the columns.
+    pub fn headers(&self) -> impl Iterator<Item = &str> {
+        self.headers.names.iter().map(|(v, _)| v.as_str())
+    }
+
+    /// Column of the header.
+    pub fn col(&self, name: &str) -> Option<u32> {
+        self.headers.cols.get(name).copied()
+    }
+
+    /// Checks that all the headers exist.
+    pub fn require(&self, names: &[&str]) -> Result<(), OdsError> {
+        let missing: Vec<_> = names
+            .iter()
+            .filter(|v| !self.headers.cols.contains_key(**v))
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(OdsError::Ods(format!("Missing columns {:?}", missing)))
+        }
+    }
+}
+
+impl<'a> Iterator for TableReader<'a> {
+    type Item = TableRow<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.row <= self.to_row {
+            let row = self.row;
+            self.row += 1;
+
+            let used = self
+                .headers
+                .names
+                .iter()
+                .any(|(_, col)| !matches!(self.sheet.value(row, *col), Value::Empty));
+            if used {
+                return Some(TableRow {
+                    sheet: self.sheet,
+                    headers: Rc::clone(&self.headers),
+                    row,
+                });
+            }
+        }
+        None
+    }
+}
+
+impl FusedIterator for TableReader<'_> {}
+
+/// One row of a table.
+#[derive(Debug, Clone)]
+pub struct TableRow<'a> {
+    sheet: &'a Sheet,
+    headers: Rc<Headers>,
+    row: u32,
+}
+
+impl<'a> TableRow<'a> {
+    /// Row in the sheet.
+    pub fn row(&self) -> u32 {
+        self.row
+    }
+
+    /// Value for the header. Value::Empty if there is no such column.
+    pub fn get(&self, name: &str) -> &'a Value {
+        match self.headers.cols.get(name) {
+            Some(col) => self.sheet.value(self.row, *col),
+            None => &Value::Empty,
+        }
+    }
+
+    /// Converted value for the header. Empty cells and missing columns
+    /// give Ok(None), a value that can't be converted gives an error
+    /// with the cell reference.
+    pub fn get_as<T: FromValue>(&self, name: &str) -> Result<Option<T>, OdsError> {
+        let col = match self.headers.cols.get(name) {
+            Some(col) => *col,
+            None => return Ok(None),
+        };
+        match self.sheet.value(self.row, col) {
+            Value::Empty => Ok(None),
+            value => match T::from_value(value) {
+                Some(v) => Ok(Some(v)),
+                None => Err(OdsError::Parse(format!(
+                    "{} {}: {:?} is not a {}",
+                    CellRef::local(self.row, col),
+                    name,
+                    value.value_type(),
+                    std::any::type_name::<T>()
+                ))),
+            },
+        }
+    }
+
+    /// All values of the row by header.
+    pub fn to_map(&self) -> HashMap<String, Value> {
+        self.headers
+            .names
+            .iter()
+            .map(|(name, col)| (name.clone(), self.sheet.value(self.row, *col).clone()))
+            .collect()
+    }
+}
+
+/// Expected columns of a table.
+#[derive(Debug, Clone, Default)]
+pub struct TableSchema {
+    columns: Vec<ColumnSchema>,
+}
+
+impl TableSchema {
+    /// Empty schema.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a column.
+    pub fn column(mut self, column: ColumnSchema) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Columns.
+    pub fn columns(&self) -> &Vec<ColumnSchema> {
+        &self.columns
+    }
+
+    /// Checks the table in the range against the schema. The first row of
+    /// the range is the header, see TableReader. Returns all violations,
+    /// the cell references contain the sheet name.
+    pub fn validate(&self, sheet: &Sheet, range: &CellRange) -> Vec<SchemaError> {
+        let reader = TableReader::new(sheet, range);
+
+        let mut errors = Vec::new();
+        let mut columns = Vec::new();
+        for column in &self.columns {
+            match reader.col(&column.name) {
+                Some(col) => columns.push((column, col)),
+                None => errors.push(SchemaError {
+                    cell: CellRef::remote(sheet.name(), range.row(), range.col()),
+                    column: column.name.clone(),
+                    violation: SchemaViolation::MissingColumn,
+                }),
+            }
+        }
+
+        for row in reader {
+            for (column, col) in &columns {
+                if let Some(violation) = column.check(row.get(&column.name)) {
+                    errors.push(SchemaError {
+                        cell: CellRef::remote(sheet.name(), row.row(), *col),
+                        column: column.name.clone(),
+                        violation,
+                    });
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Expected content of a column.
+#[derive(Debug, Clone)]
+pub struct ColumnSchema {
+    name: String,
+    value_type: Option<ValueType>,
+    required: bool,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl ColumnSchema {
+    /// Column with this header. Allows any value.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            value_type: None,
+            required: false,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Header.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Type of the values. Text includes markup text.
+    pub fn value_type(mut self, value_type: ValueType) -> Self {
+        self.value_type = Some(value_type);
+        self
+    }
+
+    /// Empty cells are not allowed.
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Minimum for numbers, percentages and currencies.
+    pub fn min(mut self, min: f64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Maximum for numbers, percentages and currencies.
+    pub fn max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    fn check(&self, value: &Value) -> Option<SchemaViolation> {
+        let value_type = match value.value_type() {
+            ValueType::Empty if self.required => return Some(SchemaViolation::Empty),
+            ValueType::Empty => return None,
+            ValueType::TextXml => ValueType::Text,
+            v => v,
+        };
+        if let Some(expected) = self.value_type {
+            if expected != value_type {
+                return Some(SchemaViolation::WrongType(value_type));
+            }
+        }
+        if let Some(v) = value.as_f64_opt() {
+            if matches!(self.min, Some(min) if v < min) {
+                return Some(SchemaViolation::TooSmall(v));
+            }
+            if matches!(self.max, Some(max) if v > max) {
+                return Some(SchemaViolation::TooLarge(v));
+            }
+        }
+        None
+    }
+}
+
+/// What is wrong.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SchemaViolation {
+    /// No header for the column.
+    MissingColumn,
+    /// Empty cell in a required column.
+    Empty,
+    /// The value has this type.
+    WrongType(ValueType),
+    /// The value is less than the minimum.
+    TooSmall(f64),
+    /// The value is greater than the maximum.
+    TooLarge(f64),
+}
+
+/// One violation of the schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaError {
+    /// The cell. For a missing column this is the start of the range.
+    pub cell: CellRef,
+    /// Column header.
+    pub column: String,
+    /// What is wrong.
+    pub violation: SchemaViolation,
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.violation {
+            SchemaViolation::MissingColumn => {
+                write!(f, "{}: missing column {}", self.cell, self.column)
+            }
+            SchemaViolation::Empty => write!(f, "{} {}: empty", self.cell, self.column),
+            SchemaViolation::WrongType(t) => {
+                write!(f, "{} {}: unexpected {:?}", self.cell, self.column, t)
+            }
+            SchemaViolation::TooSmall(v) => {
+                write!(f, "{} {}: {} is too small", self.cell, self.column, v)
+            }
+            SchemaViolation::TooLarge(v) => {
+                write!(f, "{} {}: {} is too large", self.cell, self.column, v)
+            }
+        }
+    }
+}
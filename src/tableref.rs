@@ -0,0 +1,479 @@
+//!
+//! Fixes references to a sheet when it is renamed or removed.
+//!
+
+use crate::condition::{Condition, ValueCondition};
+use crate::config::ConfigItemType;
+use crate::formula::{invalidate_table_refs, rename_table_refs};
+use crate::refs::rename_table_addresses;
+use crate::xmltree::{XmlContent, XmlTag};
+use crate::{CellRange, CellRangeList, CellRef, OdsError, Sheet, WorkBook};
+
+/// What happens with references to a removed sheet.
+/// See WorkBook::remove_sheet_checked().
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DanglingRefs {
+    /// The references are left as they are.
+    Keep,
+    /// The sheet is not removed if there are any references.
+    Error,
+    /// References in formulas are replaced with #REF!. Print ranges,
+    /// named ranges and other elements with a cell address are removed.
+    Invalidate,
+}
+
+/// Something that references a removed sheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DanglingRef {
+    /// Formula of a cell.
+    Formula(CellRef),
+    /// Print range of the named sheet.
+    PrintRange(String),
+    /// Named range or named expression.
+    Name(String),
+    /// Condition of the validation.
+    Validation(String),
+    /// Condition of a style-map of the cell style.
+    StyleMap(String),
+    /// Any other element, e.g. a database range or a conditional format.
+    /// Contains the tag name.
+    Element(String),
+}
+
+/// Renames the sheet and rewrites all references to the old name.
+/// Fails if another sheet has the new name, compared without regard
+/// to case.
+pub(crate) fn rename_sheet(
+    book: &mut WorkBook,
+    idx: usize,
+    new_name: String,
+) -> Result<(), OdsError> {
+    let old_name = book.sheet(idx).name().clone();
+    if old_name == new_name {
+        return Ok(());
+    }
+    let lower = new_name.to_lowercase();
+    if (0..book.num_sheets()).any(|i| i != idx && book.sheet(i).name().to_lowercase() == lower) {
+        return Err(OdsError::Ods(format!(
+            "sheet name {} is already used",
+            new_name
+        )));
+    }
+    book.sheet_mut(idx).set_name(new_name.as_str());
+
+    let old = old_name.as_str();
+    let new = new_name.as_str();
+
+    for i in 0..book.num_sheets() {
+        // Only sheets with references are touched, the others are
+        // not forked.
+        let changes = sheet_changes(book.sheet(i), old, new);
+        if !changes.is_empty() {
+            apply_sheet_changes(book.sheet_mut(i), changes);
+        }
+    }
+
+    for tag in book.extra.iter_mut() {
+        rename_tag(tag, old, new);
+    }
+
+    for valid in book.validations.values_mut() {
+        let condition = rename_table_refs(valid.condition(), old, new);
+        if condition != valid.condition() {
+            valid.set_condition(Condition::new(condition));
+        }
+        if let Some(base_cell) = rename_cellref(valid.base_cell(), old, new) {
+            valid.set_base_cell(base_cell);
+        }
+    }
+    for style in book.cellstyles.values_mut() {
+        if style.stylemaps().is_none() {
+            continue;
+        }
+        for sm in style.stylemaps_mut() {
+            let condition = rename_table_refs(sm.condition(), old, new);
+            if &condition != sm.condition() {
+                sm.set_condition(ValueCondition::new(condition));
+            }
+            if let Some(base_cell) = rename_cellref(sm.base_cell(), old, new) {
+                sm.set_base_cell(base_cell);
+            }
+        }
+    }
+
+    let config = book.config.as_mut();
+    config
+        .create_path(&[
+            ("ooo:view-settings", ConfigItemType::Set),
+            ("Views", ConfigItemType::Vec),
+            ("0", ConfigItemType::Entry),
+            ("Tables", ConfigItemType::Map),
+        ])
+        .rename(old, new);
+    config
+        .create_path(&[
+            ("ooo:configuration-settings", ConfigItemType::Set),
+            ("ScriptConfiguration", ConfigItemType::Map),
+        ])
+        .rename(old, new);
+    if book.workbook_config.active_table == old {
+        book.workbook_config.active_table = new_name.clone();
+    }
+
+    Ok(())
+}
+
+/// Removes the sheet and looks for references to it in the rest of
+/// the workbook.
+pub(crate) fn remove_sheet(
+    book: &mut WorkBook,
+    idx: usize,
+    dangling: DanglingRefs,
+) -> Result<(Sheet, Vec<DanglingRef>), OdsError> {
+    let table = book.sheet(idx).name().clone();
+
+    let mut refs = Vec::new();
+    let mut sheets = Vec::new();
+    for i in 0..book.num_sheets() {
+        if i != idx {
+            let count = refs.len();
+            sheet_refs(book.sheet(i), &table, &mut refs);
+            if refs.len() != count {
+                sheets.push(i);
+            }
+        }
+    }
+    for tag in book.extra.iter() {
+        tag_refs(tag, &table, &mut refs);
+    }
+    for valid in book.validations.values() {
+        if invalidate_table_refs(valid.condition(), &table) != valid.condition() {
+            refs.push(DanglingRef::Validation(valid.name().to_string()));
+        }
+    }
+    for style in book.cellstyles.values() {
+        if let Some(stylemaps) = style.stylemaps() {
+            if stylemaps
+                .iter()
+                .any(|v| &invalidate_table_refs(v.condition(), &table) != v.condition())
+            {
+                refs.push(DanglingRef::StyleMap(style.name().to_string()));
+            }
+        }
+    }
+
+    if dangling == DanglingRefs::Error && !refs.is_empty() {
+        return Err(OdsError::Ods(format!(
+            "Sheet {} is still referenced by {:?}",
+            table, refs
+        )));
+    }
+
+    if dangling == DanglingRefs::Invalidate && !refs.is_empty() {
+        for i in sheets {
+            invalidate_sheet(book.sheet_mut(i), &table);
+        }
+        book.extra.retain_mut(|v| invalidate_tag(v, &table));
+        for valid in book.validations.values_mut() {
+            let condition = invalidate_table_refs(valid.condition(), &table);
+            if condition != valid.condition() {
+                valid.set_condition(Condition::new(condition));
+            }
+        }
+        for style in book.cellstyles.values_mut() {
+            if style.stylemaps().is_none() {
+                continue;
+            }
+            for sm in style.stylemaps_mut() {
+                let condition = invalidate_table_refs(sm.condition(), &table);
+                if &condition != sm.condition() {
+                    sm.set_condition(ValueCondition::new(condition));
+                }
+            }
+        }
+    }
+
+    let config = book.config.as_mut();
+    config
+        .create_path(&[
+            ("ooo:view-settings", ConfigItemType::Set),
+            ("Views", ConfigItemType::Vec),
+            ("0", ConfigItemType::Entry),
+            ("Tables", ConfigItemType::Map),
+        ])
+        .remove(&table);
+    config
+        .create_path(&[
+            ("ooo:configuration-settings", ConfigItemType::Set),
+            ("ScriptConfiguration", ConfigItemType::Map),
+        ])
+        .remove(&table);
+    if book.workbook_config.active_table == table {
+        book.workbook_config.active_table = String::new();
+    }
+
+    Ok((book.remove_sheet(idx), refs))
+}
+
+fn sheet_refs(sheet: &Sheet, table: &str, refs: &mut Vec<DanglingRef>) {
+    for ((row, col), cell) in sheet.data.iter() {
+        if let Some(formula) = &cell.formula {
+            if &invalidate_table_refs(formula, table) != formula {
+                refs.push(DanglingRef::Formula(CellRef::remote(
+                    sheet.name(),
+                    *row,
+                    *col,
+                )));
+            }
+        }
+        if let Some(extra) = &cell.extra {
+            for tag in extra.shapes.iter() {
+                tag_refs(tag, table, refs);
+            }
+        }
+    }
+    if let Some(print_ranges) = &sheet.print_ranges {
+        if print_ranges.iter().any(|v| is_table(v.table(), table)) {
+            refs.push(DanglingRef::PrintRange(sheet.name().clone()));
+        }
+    }
+    for tag in sheet.extra.iter() {
+        tag_refs(tag, table, refs);
+    }
+}
+
+fn invalidate_sheet(sheet: &mut Sheet, table: &str) {
+    let mut changed = Vec::new();
+    for (pos, cell) in sheet.data.iter_mut() {
+        if let Some(formula) = &cell.formula {
+            let invalid = invalidate_table_refs(formula, table);
+            if &invalid != formula {
+                cell.formula = Some(invalid);
+                changed.push(*pos);
+            }
+        }
+        if let Some(extra) = &mut cell.extra {
+            let mut refs = Vec::new();
+            for tag in extra.shapes.iter() {
+                tag_refs(tag, table, &mut refs);
+            }
+            if !refs.is_empty() {
+                extra.shapes.retain_mut(|v| invalidate_tag(v, table));
+                changed.push(*pos);
+            }
+        }
+    }
+    for (row, col) in changed {
+        sheet.mark_cell_dirty(row, col);
+    }
+    if let Some(print_ranges) = &mut sheet.print_ranges {
+        print_ranges.retain(|v| !is_table(v.table(), table));
+        if print_ranges.is_empty() {
+            sheet.print_ranges = None;
+        }
+    }
+    sheet.extra.retain_mut(|v| invalidate_tag(v, table));
+    sheet.mark_dirty();
+}
+
+// Attributes with addresses like table:cell-range-address.
+fn is_address_attr(name: &str) -> bool {
+    name.contains("address")
+}
+
+fn address_refers_to(value: &str, table: &str) -> bool {
+    matches!(CellRangeList::try_from(value),
+        Ok(ranges) if ranges.iter().any(|v| is_table(v.table(), table)))
+}
+
+fn tag_refs(tag: &XmlTag, table: &str, refs: &mut Vec<DanglingRef>) {
+    let found = tag.attrmap().iter().any(|(k, v)| {
+        if is_address_attr(k) {
+            address_refers_to(v, table)
+        } else {
+            v.contains('[') && &invalidate_table_refs(v, table) != v
+        }
+    });
+    if found {
+        match (tag.name(), tag.attrmap().attr("table:name")) {
+            ("table:named-range" | "table:named-expression", Some(name)) => {
+                refs.push(DanglingRef::Name(name.clone()))
+            }
+            (name, _) => refs.push(DanglingRef::Element(name.to_string())),
+        }
+    } else {
+        for v in tag.content() {
+            if let XmlContent::Tag(t) = v {
+                tag_refs(t, table, refs);
+            }
+        }
+    }
+}
+
+// Returns false if the tag must be removed.
+fn invalidate_tag(tag: &mut XmlTag, table: &str) -> bool {
+    let mut invalid = Vec::new();
+    for (k, v) in tag.attrmap().iter() {
+        if is_address_attr(k) {
+            if address_refers_to(v, table) {
+                return false;
+            }
+        } else if v.contains('[') {
+            let value = invalidate_table_refs(v, table);
+            if &value != v {
+                invalid.push((k.to_string(), value));
+            }
+        }
+    }
+    for (k, v) in invalid {
+        tag.attrmap_mut().set_attr(&k, v);
+    }
+    tag.content_mut().retain_mut(|v| match v {
+        XmlContent::Tag(t) => invalidate_tag(t, table),
+        XmlContent::Text(_) => true,
+    });
+    true
+}
+
+#[derive(Default)]
+struct SheetChanges {
+    formulas: Vec<((u32, u32), String)>,
+    shapes: Vec<((u32, u32), Vec<XmlTag>)>,
+    print_ranges: Option<Vec<CellRange>>,
+    extra: Option<Vec<XmlTag>>,
+}
+
+impl SheetChanges {
+    fn is_empty(&self) -> bool {
+        self.formulas.is_empty()
+            && self.shapes.is_empty()
+            && self.print_ranges.is_none()
+            && self.extra.is_none()
+    }
+}
+
+fn sheet_changes(sheet: &Sheet, old: &str, new: &str) -> SheetChanges {
+    let mut changes = SheetChanges::default();
+
+    for (pos, cell) in sheet.data.iter() {
+        if let Some(formula) = &cell.formula {
+            let renamed = rename_table_refs(formula, old, new);
+            if &renamed != formula {
+                changes.formulas.push((*pos, renamed));
+            }
+        }
+        if let Some(extra) = &cell.extra {
+            if let Some(shapes) = rename_tags(&extra.shapes, old, new) {
+                changes.shapes.push((*pos, shapes));
+            }
+        }
+    }
+
+    if let Some(print_ranges) = &sheet.print_ranges {
+        if print_ranges.iter().any(|v| is_table(v.table(), old)) {
+            let mut print_ranges = print_ranges.clone();
+            for range in print_ranges.iter_mut() {
+                if is_table(range.table(), old) {
+                    range.set_table(new);
+                }
+            }
+            changes.print_ranges = Some(print_ranges);
+        }
+    }
+
+    changes.extra = rename_tags(&sheet.extra, old, new);
+
+    changes
+}
+
+fn apply_sheet_changes(sheet: &mut Sheet, changes: SheetChanges) {
+    for (pos, formula) in changes.formulas {
+        if let Some(cell) = sheet.data.get_mut(&pos) {
+            cell.formula = Some(formula);
+        }
+        sheet.mark_cell_dirty(pos.0, pos.1);
+    }
+    for (pos, shapes) in changes.shapes {
+        if let Some(cell) = sheet.data.get_mut(&pos) {
+            cell.extra_mut().shapes = shapes;
+        }
+        sheet.mark_cell_dirty(pos.0, pos.1);
+    }
+    if changes.print_ranges.is_some() {
+        sheet.print_ranges = changes.print_ranges;
+    }
+    if let Some(extra) = changes.extra {
+        sheet.extra = extra;
+    }
+    sheet.mark_dirty();
+}
+
+fn is_table(table: Option<&String>, name: &str) -> bool {
+    table.map(|v| v.as_str()) == Some(name)
+}
+
+fn rename_cellref(cellref: &CellRef, old: &str, new: &str) -> Option<CellRef> {
+    if is_table(cellref.table(), old) {
+        let mut cellref = cellref.clone();
+        cellref.set_table(new);
+        Some(cellref)
+    } else {
+        None
+    }
+}
+
+// Returns a renamed copy if any of the tags changed.
+fn rename_tags(tags: &[XmlTag], old: &str, new: &str) -> Option<Vec<XmlTag>> {
+    if tags.iter().any(|v| refers_to(v, old, new)) {
+        let mut tags = tags.to_vec();
+        for tag in tags.iter_mut() {
+            rename_tag(tag, old, new);
+        }
+        Some(tags)
+    } else {
+        None
+    }
+}
+
+fn refers_to(tag: &XmlTag, old: &str, new: &str) -> bool {
+    tag.attrmap()
+        .iter()
+        .any(|(k, v)| rename_attr(k, v, old, new).is_some())
+        || tag.content().iter().any(|v| match v {
+            XmlContent::Tag(t) => refers_to(t, old, new),
+            XmlContent::Text(_) => false,
+        })
+}
+
+// Attributes with addresses like table:cell-range-address and
+// attributes with formulas like table:expression.
+fn rename_attr(name: &str, value: &str, old: &str, new: &str) -> Option<String> {
+    if name.contains("address") {
+        rename_table_addresses(value, old, new)
+    } else if value.contains('[') {
+        let renamed = rename_table_refs(value, old, new);
+        if renamed != value {
+            Some(renamed)
+        } else {
+            None
+        }
+    } else {
+        None
+    }
+}
+
+fn rename_tag(tag: &mut XmlTag, old: &str, new: &str) {
+    let renamed: Vec<_> = tag
+        .attrmap()
+        .iter()
+        .filter_map(|(k, v)| rename_attr(k, v, old, new).map(|v| (k.to_string(), v)))
+        .collect();
+    for (k, v) in renamed {
+        tag.attrmap_mut().set_attr(&k, v);
+    }
+    for v in tag.content_mut() {
+        if let XmlContent::Tag(t) = v {
+            rename_tag(t, old, new);
+        }
+    }
+}
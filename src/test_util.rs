@@ -0,0 +1,778 @@
+//!
+//! Generators for synthetic workbooks.
+//!
+//! These are used by the benchmarks and can be used to test and measure
+//! an application with data of a known shape. Needs the feature "test_util".
+//!
+//! reference_workbooks() creates one small document for each feature the
+//! crate supports. Each sheet describes what should be visible next to the
+//! example, so a file can be checked by opening it in LibreOffice.
+//!
+//! With the feature "proptest" there are strategies for arbitrary workbooks.
+//! Together with roundtrip() and compare_workbooks() a downstream crate can
+//! check that whatever it builds survives writing and reading.
+//!
+//! ```ignore
+//! use proptest::prelude::*;
+//! use spreadsheet_ods::test_util::{arb_workbook, assert_roundtrip};
+//!
+//! proptest! {
+//!     #[test]
+//!     fn survives_roundtrip(mut wb in arb_workbook()) {
+//!         assert_roundtrip(&mut wb);
+//!     }
+//! }
+//! ```
+//!
+
+use crate::condition::Condition;
+use crate::format::{
+    create_currency_prefix, create_date_iso_format, create_number_format, create_percentage_format,
+    create_time_interval_format,
+};
+use crate::style::units::{Border, PrintOrientation, TextAlign, WrapOption};
+use crate::style::{CellStyle, CellStyleRef, MasterPage, PageStyle, TableStyle};
+use crate::text::{TextA, TextP};
+use crate::theme::Theme;
+use crate::validation::Validation;
+use crate::xmltree::XmlVec;
+use crate::{
+    read_ods_buf, write_ods, write_ods_buf_uncompressed, CellRange, CellRef, Length, OdsError,
+    Sheet, Value, WorkBook,
+};
+use chrono::{Duration, NaiveDate};
+use color::Rgb;
+use icu_locid::locale;
+use std::path::{Path, PathBuf};
+
+/// One sheet with a value in every cell.
+///
+/// Texts and numbers alternate by row, the text rows have a cell-style.
+/// Every 10th row contains formulas.
+pub fn dense_workbook(rows: u32, cols: u32) -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+    let s0 = wb.add_cellstyle(CellStyle::new_empty());
+
+    let mut sh = Sheet::new("dense");
+    for r in 0..rows {
+        for c in 0..cols {
+            if r % 10 == 0 {
+                sh.set_formula(r, c, "of:=1+1");
+            }
+            if r % 2 == 0 {
+                sh.set_styled_value(r, c, "1234", &s0);
+            } else {
+                sh.set_value(r, c, r * c);
+            }
+        }
+    }
+    wb.push_sheet(sh);
+
+    wb
+}
+
+/// One sheet where only every step-th row and column contains a value.
+pub fn sparse_workbook(rows: u32, cols: u32, step: u32) -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+
+    let mut sh = Sheet::new("sparse");
+    for r in (0..rows).step_by(step as usize) {
+        for c in (0..cols).step_by(step as usize) {
+            sh.set_value(r, c, r * c);
+        }
+    }
+    wb.push_sheet(sh);
+
+    wb
+}
+
+/// One sheet with n_styles different cell-styles used round robin.
+pub fn styled_workbook(rows: u32, cols: u32, n_styles: u32) -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+
+    let styles: Vec<_> = (0..n_styles.max(1))
+        .map(|i| {
+            let mut st = CellStyle::new_empty();
+            st.set_background_color(Rgb::new((i % 256) as u8, (i / 256 % 256) as u8, 128));
+            if i % 2 == 0 {
+                st.set_font_bold();
+            }
+            wb.add_cellstyle(st)
+        })
+        .collect();
+
+    let mut sh = Sheet::new("styled");
+    for r in 0..rows {
+        for c in 0..cols {
+            let st = &styles[((r * cols + c) % n_styles.max(1)) as usize];
+            sh.set_styled_value(r, c, "styled", st);
+        }
+    }
+    wb.push_sheet(sh);
+
+    wb
+}
+
+/// One sheet with numbers, percentages, currencies, dates and durations
+/// in the columns 0 to 4. Each column has a cell-style with a value format,
+/// so writing renders the cell text from the format.
+pub fn formatted_workbook(rows: u32) -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+
+    let f_num = wb.add_number_format(create_number_format("f_num", 2, true));
+    let f_pct = wb.add_percentage_format(create_percentage_format("f_pct", 1));
+    let f_cur = wb.add_currency_format(create_currency_prefix("f_cur", locale!("en_US"), "$"));
+    let f_date = wb.add_datetime_format(create_date_iso_format("f_date"));
+    let f_dur = wb.add_timeduration_format(create_time_interval_format("f_dur"));
+
+    let s_num = wb.add_cellstyle(CellStyle::new("s_num", &f_num));
+    let s_pct = wb.add_cellstyle(CellStyle::new("s_pct", &f_pct));
+    let s_cur = wb.add_cellstyle(CellStyle::new("s_cur", &f_cur));
+    let s_date = wb.add_cellstyle(CellStyle::new("s_date", &f_date));
+    let s_dur = wb.add_cellstyle(CellStyle::new("s_dur", &f_dur));
+
+    let date = NaiveDate::from_ymd_opt(2020, 1, 1)
+        .expect("valid date")
+        .and_hms_opt(0, 0, 0)
+        .expect("valid time");
+
+    let mut sh = Sheet::new("formatted");
+    for r in 0..rows {
+        let v = r as f64 * 1.125;
+        sh.set_styled_value(r, 0, v * 1000.0, &s_num);
+        sh.set_styled_value(r, 1, Value::new_percentage(v / 100.0), &s_pct);
+        sh.set_styled_value(r, 2, Value::new_currency("USD", v), &s_cur);
+        sh.set_styled_value(r, 3, date + Duration::days(r as i64), &s_date);
+        sh.set_styled_value(r, 4, Duration::seconds(r as i64 * 61), &s_dur);
+    }
+    wb.push_sheet(sh);
+
+    wb
+}
+
+/// One sheet with only dates and currencies in the columns 0 to 9, each
+/// column with a cell-style of its own. All the cell text is rendered
+/// from the value formats.
+pub fn rendered_workbook(rows: u32) -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+
+    let f_cur = wb.add_currency_format(create_currency_prefix("f_cur", locale!("en_US"), "$"));
+    let f_date = wb.add_datetime_format(create_date_iso_format("f_date"));
+
+    let styles: Vec<CellStyleRef> = (0..10)
+        .map(|c| {
+            let format = if c % 2 == 0 { &f_date } else { &f_cur };
+            wb.add_cellstyle(CellStyle::new(format!("s_{}", c), format))
+        })
+        .collect();
+
+    let date = NaiveDate::from_ymd_opt(2020, 1, 1)
+        .expect("valid date")
+        .and_hms_opt(0, 0, 0)
+        .expect("valid time");
+
+    let mut sh = Sheet::new("rendered");
+    for r in 0..rows {
+        for (c, style) in styles.iter().enumerate() {
+            if c % 2 == 0 {
+                sh.set_styled_value(r, c as u32, date + Duration::days(r as i64), style);
+            } else {
+                sh.set_styled_value(r, c as u32, Value::new_currency("USD", r as f64), style);
+            }
+        }
+    }
+    wb.push_sheet(sh);
+
+    wb
+}
+
+/// Reference documents, one for each feature. The name of the feature is
+/// used as file name by write_reference_files().
+///
+/// * values: every value type with its default rendering.
+/// * formats: numbers, percentages, currencies, dates and durations
+///   with value formats.
+/// * styles: fonts, colors, borders, alignment and wrapping.
+/// * spans: merged cells, column widths and row heights.
+/// * formulas: formulas with references to cells and other sheets.
+/// * validation: a list validation with a dropdown.
+/// * text: formatted text and hyperlinks.
+/// * layout: page style, header and footer, print ranges and
+///   repeated header rows.
+/// * theme: the named styles of the default Theme.
+///
+/// Column A describes what column B should look like.
+pub fn reference_workbooks() -> Vec<(&'static str, WorkBook)> {
+    vec![
+        ("values", ref_values()),
+        ("formats", ref_formats()),
+        ("styles", ref_styles()),
+        ("spans", ref_spans()),
+        ("formulas", ref_formulas()),
+        ("validation", ref_validation()),
+        ("text", ref_text()),
+        ("layout", ref_layout()),
+        ("theme", ref_theme()),
+    ]
+}
+
+/// Writes the reference documents as "<feature>.ods" into the directory
+/// and returns the paths. The directory must exist.
+pub fn write_reference_files<P: AsRef<Path>>(dir: P) -> Result<Vec<PathBuf>, OdsError> {
+    let mut files = Vec::new();
+    for (name, mut book) in reference_workbooks() {
+        let path = dir.as_ref().join(format!("{}.ods", name));
+        write_ods(&mut book, &path)?;
+        files.push(path);
+    }
+    Ok(files)
+}
+
+fn ref_sheet(name: &str) -> Sheet {
+    let mut sh = Sheet::new(name);
+    sh.set_value(0, 0, "expected");
+    sh.set_value(0, 1, "example");
+    sh.set_col_width(0, cm!(8));
+    sh.set_col_width(1, cm!(6));
+    sh
+}
+
+fn ref_values() -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+
+    let date = NaiveDate::from_ymd_opt(2020, 2, 29).expect("valid date");
+
+    let mut sh = ref_sheet("values");
+    sh.set_value(1, 0, "text");
+    sh.set_value(1, 1, "text");
+    sh.set_value(2, 0, "number 1234.5");
+    sh.set_value(2, 1, 1234.5);
+    sh.set_value(3, 0, "percentage 12.5%");
+    sh.set_value(3, 1, Value::new_percentage(0.125));
+    sh.set_value(4, 0, "currency 99.99 EUR");
+    sh.set_value(4, 1, Value::new_currency("EUR", 99.99));
+    sh.set_value(5, 0, "boolean TRUE");
+    sh.set_value(5, 1, true);
+    sh.set_value(6, 0, "date 2020-02-29");
+    sh.set_value(6, 1, date);
+    sh.set_value(7, 0, "date and time 2020-02-29 13:30:00");
+    sh.set_value(7, 1, date.and_hms_opt(13, 30, 0).expect("valid time"));
+    sh.set_value(8, 0, "duration 01:01:01");
+    sh.set_value(8, 1, Duration::seconds(3661));
+    sh.set_value(9, 0, "empty");
+    wb.push_sheet(sh);
+
+    wb
+}
+
+fn ref_formats() -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+
+    let f_num = wb.add_number_format(create_number_format("f_num", 2, true));
+    let f_pct = wb.add_percentage_format(create_percentage_format("f_pct", 1));
+    let f_cur = wb.add_currency_format(create_currency_prefix("f_cur", locale!("en_US"), "$"));
+    let f_date = wb.add_datetime_format(create_date_iso_format("f_date"));
+    let f_dur = wb.add_timeduration_format(create_time_interval_format("f_dur"));
+
+    let s_num = wb.add_cellstyle(CellStyle::new("s_num", &f_num));
+    let s_pct = wb.add_cellstyle(CellStyle::new("s_pct", &f_pct));
+    let s_cur = wb.add_cellstyle(CellStyle::new("s_cur", &f_cur));
+    let s_date = wb.add_cellstyle(CellStyle::new("s_date", &f_date));
+    let s_dur = wb.add_cellstyle(CellStyle::new("s_dur", &f_dur));
+
+    let date = NaiveDate::from_ymd_opt(2020, 2, 29).expect("valid date");
+
+    let mut sh = ref_sheet("formats");
+    sh.set_value(1, 0, "1,234,567.89");
+    sh.set_styled_value(1, 1, 1234567.891, &s_num);
+    sh.set_value(2, 0, "12.5%");
+    sh.set_styled_value(2, 1, Value::new_percentage(0.125), &s_pct);
+    sh.set_value(3, 0, "$99.99");
+    sh.set_styled_value(3, 1, Value::new_currency("USD", 99.99), &s_cur);
+    sh.set_value(4, 0, "2020-02-29");
+    sh.set_styled_value(4, 1, date, &s_date);
+    sh.set_value(5, 0, "25:01:01");
+    sh.set_styled_value(5, 1, Duration::seconds(90061), &s_dur);
+    wb.push_sheet(sh);
+
+    wb
+}
+
+fn ref_styles() -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+
+    let mut st = CellStyle::new_empty();
+    st.set_name("bold");
+    st.set_font_bold();
+    let s_bold = wb.add_cellstyle(st);
+
+    let mut st = CellStyle::new_empty();
+    st.set_name("italic");
+    st.set_font_italic();
+    let s_italic = wb.add_cellstyle(st);
+
+    let mut st = CellStyle::new_empty();
+    st.set_name("colors");
+    st.set_color(Rgb::new(255, 255, 255));
+    st.set_background_color(Rgb::new(0, 0, 128));
+    let s_colors = wb.add_cellstyle(st);
+
+    let mut st = CellStyle::new_empty();
+    st.set_name("border");
+    st.set_border(pt!(1), Border::Solid, Rgb::new(255, 0, 0));
+    let s_border = wb.add_cellstyle(st);
+
+    let mut st = CellStyle::new_empty();
+    st.set_name("right");
+    st.set_text_align(TextAlign::End);
+    let s_right = wb.add_cellstyle(st);
+
+    let mut st = CellStyle::new_empty();
+    st.set_name("wrap");
+    st.set_wrap_option(WrapOption::Wrap);
+    let s_wrap = wb.add_cellstyle(st);
+
+    let mut sh = ref_sheet("styles");
+    sh.set_value(1, 0, "bold");
+    sh.set_styled_value(1, 1, "bold", &s_bold);
+    sh.set_value(2, 0, "italic");
+    sh.set_styled_value(2, 1, "italic", &s_italic);
+    sh.set_value(3, 0, "white on dark blue");
+    sh.set_styled_value(3, 1, "colors", &s_colors);
+    sh.set_value(4, 0, "red border");
+    sh.set_styled_value(4, 1, "border", &s_border);
+    sh.set_value(5, 0, "aligned right");
+    sh.set_styled_value(5, 1, "right", &s_right);
+    sh.set_value(6, 0, "wrapped over several lines");
+    sh.set_styled_value(
+        6,
+        1,
+        "a long text that is wrapped over several lines",
+        &s_wrap,
+    );
+    wb.push_sheet(sh);
+
+    wb
+}
+
+fn ref_spans() -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+
+    let mut sh = ref_sheet("spans");
+    sh.set_value(1, 0, "merged over 2 columns");
+    sh.set_value(1, 1, "merged");
+    sh.set_col_span(1, 1, 2);
+    sh.set_value(2, 0, "merged over 3 rows");
+    sh.set_value(2, 1, "merged");
+    sh.set_row_span(2, 1, 3);
+    sh.set_value(5, 0, "row 2cm high");
+    sh.set_row_height(5, cm!(2));
+    sh.set_value(6, 0, "column C 1cm wide");
+    sh.set_col_width(2, cm!(1));
+    wb.push_sheet(sh);
+
+    wb
+}
+
+fn ref_formulas() -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+
+    let mut sh = ref_sheet("formulas");
+    sh.set_value(1, 0, "3");
+    sh.set_formula(1, 1, "of:=1+2");
+    sh.set_value(2, 0, "6, the sum of B2 and B2");
+    sh.set_formula(2, 1, "of:=[.B2]+[.B2]");
+    sh.set_value(3, 0, "42, from the sheet data");
+    sh.set_formula(3, 1, "of:=[$data.A1]");
+    sh.set_value(4, 0, "60, the sum of data.A1:A3");
+    sh.set_formula(4, 1, "of:=SUM([$data.A1:.A3])");
+    sh.set_value(5, 0, "abc");
+    sh.set_formula(5, 1, "of:=CONCATENATE(\"a\";\"b\";\"c\")");
+    wb.push_sheet(sh);
+
+    let mut sh = Sheet::new("data");
+    sh.set_value(0, 0, 42);
+    sh.set_value(1, 0, 10);
+    sh.set_value(2, 0, 8);
+    wb.push_sheet(sh);
+
+    wb
+}
+
+fn ref_validation() -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+
+    let mut valid = Validation::new();
+    valid.set_name("choices");
+    valid.set_condition(Condition::content_is_in_list(&["red", "green", "blue"]));
+    let valid = wb.add_validation(valid);
+
+    let mut sh = ref_sheet("validation");
+    sh.set_value(1, 0, "dropdown with red, green, blue");
+    sh.set_value(1, 1, "red");
+    sh.set_validation(1, 1, &valid);
+    wb.push_sheet(sh);
+
+    wb
+}
+
+fn ref_text() -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+
+    let mut sh = ref_sheet("text");
+    sh.set_value(1, 0, "link to example.com");
+    sh.set_value(
+        1,
+        1,
+        TextP::new()
+            .tag(TextA::new().href("https://example.com").text("example.com"))
+            .into_xmltag(),
+    );
+    sh.set_value(2, 0, "link to cell A1");
+    sh.set_value(
+        2,
+        1,
+        TextP::new()
+            .tag(TextA::new().href_cell(&CellRef::local(0, 0)).text("A1"))
+            .into_xmltag(),
+    );
+    sh.set_value(3, 0, "two lines");
+    sh.set_value(
+        3,
+        1,
+        vec![
+            TextP::new().text("first").into_xmltag(),
+            TextP::new().text("second").into_xmltag(),
+        ],
+    );
+    wb.push_sheet(sh);
+
+    wb
+}
+
+fn ref_layout() -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+
+    let mut ps = PageStyle::new("landscape");
+    ps.set_page_width(Length::Cm(29.7));
+    ps.set_page_height(cm!(21));
+    ps.set_print_orientation(PrintOrientation::Landscape);
+    let ps = wb.add_pagestyle(ps);
+
+    let mut mp = MasterPage::new("reference");
+    mp.set_pagestyle(&ps);
+    mp.header_mut().center_mut().add_text("header center");
+    mp.footer_mut().right_mut().add_text("footer right");
+    let mp = wb.add_masterpage(mp);
+
+    let mut ts = TableStyle::new("reference");
+    ts.set_master_page(&mp);
+    let ts = wb.add_tablestyle(ts);
+
+    let mut sh = ref_sheet("layout");
+    sh.set_style(&ts);
+    sh.set_value(1, 0, "landscape A4 in the print preview");
+    sh.set_value(2, 0, "'header center' and 'footer right'");
+    sh.set_value(3, 0, "only A1:B50 is printed");
+    sh.set_value(4, 0, "row 1 is repeated on each page");
+    for r in 5..50 {
+        sh.set_value(r, 1, r);
+    }
+    sh.set_value(60, 1, "not printed");
+    sh.add_print_range(CellRange::local(0, 0, 49, 1));
+    sh.set_header_rows(0, 0);
+    wb.push_sheet(sh);
+
+    wb
+}
+
+fn ref_theme() -> WorkBook {
+    let mut wb = WorkBook::new_empty();
+
+    let theme = Theme::default();
+    wb.install_theme(&theme);
+
+    let date = NaiveDate::from_ymd_opt(2020, 2, 29).expect("valid date");
+
+    let mut sh = ref_sheet("theme");
+    sh.set_styled_value(0, 0, "expected", &theme.header());
+    sh.set_styled_value(0, 1, "example", &theme.header());
+    sh.set_value(1, 0, "base font");
+    sh.set_styled_value(1, 1, "base", &theme.base());
+    sh.set_value(2, 0, "accent background");
+    sh.set_styled_value(2, 1, "accent", &theme.accent());
+    sh.set_value(3, 0, "1234.50");
+    sh.set_styled_value(3, 1, 1234.5, &theme.number());
+    sh.set_value(4, 0, "€ 99.99");
+    sh.set_styled_value(4, 1, Value::new_currency("EUR", 99.99), &theme.currency());
+    sh.set_value(5, 0, "12.50%");
+    sh.set_styled_value(5, 1, Value::new_percentage(0.125), &theme.percent());
+    sh.set_value(6, 0, "a date");
+    sh.set_styled_value(6, 1, date, &theme.date());
+    wb.push_sheet(sh);
+
+    wb
+}
+
+/// Writes the workbook to a buffer and reads it back.
+pub fn roundtrip(book: &mut WorkBook) -> Result<WorkBook, OdsError> {
+    let buf = write_ods_buf_uncompressed(book, Vec::new())?;
+    read_ods_buf(&buf)
+}
+
+/// Writes and reads the workbook and compares the result with the original.
+///
+/// Panics with the first difference.
+pub fn assert_roundtrip(book: &mut WorkBook) {
+    let read = match roundtrip(book) {
+        Ok(v) => v,
+        Err(e) => panic!("roundtrip failed: {}", e),
+    };
+    if let Err(e) = compare_workbooks(book, &read) {
+        panic!("roundtrip changed the workbook: {}", e);
+    }
+}
+
+/// Compares the sheets and cells of two workbooks.
+///
+/// Values, formulas and cell-styles are compared after a normalization:
+/// * Cells without value, formula and style are ignored.
+/// * An empty text is the same as an empty value.
+/// * Numbers are compared by value, so 0.0 and -0.0 are equal.
+///
+/// Returns a description of the first difference.
+pub fn compare_workbooks(left: &WorkBook, right: &WorkBook) -> Result<(), String> {
+    if left.num_sheets() != right.num_sheets() {
+        return Err(format!(
+            "number of sheets {} != {}",
+            left.num_sheets(),
+            right.num_sheets()
+        ));
+    }
+    for idx in 0..left.num_sheets() {
+        compare_sheets(left.sheet(idx), right.sheet(idx))?;
+    }
+    Ok(())
+}
+
+fn compare_sheets(left: &Sheet, right: &Sheet) -> Result<(), String> {
+    if left.name() != right.name() {
+        return Err(format!("sheet name {} != {}", left.name(), right.name()));
+    }
+
+    let l_cells = used_cells(left);
+    let r_cells = used_cells(right);
+
+    for idx in 0..l_cells.len().max(r_cells.len()) {
+        let (l_pos, (l_value, l_formula, l_style)) = match l_cells.get(idx) {
+            Some(v) => *v,
+            None => {
+                return Err(format!(
+                    "{}: extra cell {:?} in the right workbook",
+                    left.name(),
+                    r_cells[idx].0
+                ))
+            }
+        };
+        let (r_pos, (r_value, r_formula, r_style)) = match r_cells.get(idx) {
+            Some(v) => *v,
+            None => {
+                return Err(format!(
+                    "{}: extra cell {:?} in the left workbook",
+                    left.name(),
+                    l_pos
+                ))
+            }
+        };
+
+        if l_pos != r_pos {
+            return Err(format!(
+                "{}: cell {:?} != cell {:?}",
+                left.name(),
+                l_pos,
+                r_pos
+            ));
+        }
+        if !same_value(l_value, r_value) {
+            return Err(format!(
+                "{}: {:?} value {:?} != {:?}",
+                left.name(),
+                l_pos,
+                l_value,
+                r_value
+            ));
+        }
+        if l_formula != r_formula {
+            return Err(format!(
+                "{}: {:?} formula {:?} != {:?}",
+                left.name(),
+                l_pos,
+                l_formula,
+                r_formula
+            ));
+        }
+        if l_style != r_style {
+            return Err(format!(
+                "{}: {:?} style {:?} != {:?}",
+                left.name(),
+                l_pos,
+                l_style,
+                r_style
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+type UsedCell<'a> = (
+    (u32, u32),
+    (&'a Value, Option<&'a String>, Option<&'a CellStyleRef>),
+);
+
+fn used_cells(sheet: &Sheet) -> Vec<UsedCell<'_>> {
+    sheet
+        .iter()
+        .filter(|(_, c)| !is_empty_value(c.value()) || c.formula().is_some() || c.style().is_some())
+        .map(|(k, c)| (k, (c.value(), c.formula(), c.style())))
+        .collect()
+}
+
+fn is_empty_value(value: &Value) -> bool {
+    match value {
+        Value::Empty => true,
+        Value::Text(s) => s.is_empty(),
+        _ => false,
+    }
+}
+
+fn same_value(left: &Value, right: &Value) -> bool {
+    if is_empty_value(left) && is_empty_value(right) {
+        return true;
+    }
+    match (left, right) {
+        (Value::Boolean(l), Value::Boolean(r)) => l == r,
+        (Value::Number(l), Value::Number(r)) => l == r,
+        (Value::Percentage(l), Value::Percentage(r)) => l == r,
+        (Value::Currency(l, lc), Value::Currency(r, rc)) => l == r && lc == rc,
+        (Value::Text(l), Value::Text(r)) => l == r,
+        (Value::TextXml(l), Value::TextXml(r)) => format!("{:?}", l) == format!("{:?}", r),
+        (Value::DateTime(l), Value::DateTime(r)) => l == r,
+        (Value::TimeDuration(l), Value::TimeDuration(r)) => l == r,
+        _ => false,
+    }
+}
+
+#[cfg(feature = "proptest")]
+pub use self::strategies::*;
+
+#[cfg(feature = "proptest")]
+mod strategies {
+    use crate::style::{CellStyle, CellStyleRef};
+    use crate::{Sheet, Value, WorkBook};
+    use chrono::{Duration, NaiveDate, NaiveDateTime};
+    use proptest::collection::{btree_map, btree_set, vec};
+    use proptest::option;
+    use proptest::prelude::*;
+
+    /// Arbitrary values of every type except TextXml.
+    ///
+    /// Dates are restricted to the years 1900 to 2100 with millisecond
+    /// precision, durations to milliseconds too. Texts are single lines.
+    pub fn arb_value() -> impl Strategy<Value = Value> {
+        let number = prop::num::f64::NORMAL | prop::num::f64::ZERO;
+        prop_oneof![
+            Just(Value::Empty),
+            any::<bool>().prop_map(Value::Boolean),
+            number.prop_map(Value::Number),
+            number.prop_map(Value::Percentage),
+            (number, "[A-Z]{3}").prop_map(|(v, c)| Value::new_currency(c, v)),
+            "[a-zA-Z0-9äöüß.,;:!?&<>'\"/+-]{1,24}".prop_map(Value::Text),
+            arb_datetime().prop_map(Value::DateTime),
+            (-1_000_000_000_000i64..1_000_000_000_000i64)
+                .prop_map(|v| Value::TimeDuration(Duration::milliseconds(v))),
+        ]
+    }
+
+    fn arb_datetime() -> impl Strategy<Value = NaiveDateTime> {
+        let start = NaiveDate::from_ymd_opt(1900, 1, 1)
+            .expect("valid date")
+            .and_hms_opt(0, 0, 0)
+            .expect("valid time");
+        (0i64..200 * 365 * 86_400_000).prop_map(move |v| start + Duration::milliseconds(v))
+    }
+
+    /// Arbitrary formulas in the "of:" namespace.
+    pub fn arb_formula() -> impl Strategy<Value = String> {
+        "[A-Z]{1,2}[1-9][0-9]{0,2}".prop_map(|v| format!("of:=[.{}]+1", v))
+    }
+
+    /// A sheet with up to max_cells cells in the given grid.
+    /// The cell-styles are picked from the given list.
+    pub fn arb_sheet(
+        name: String,
+        rows: u32,
+        cols: u32,
+        max_cells: usize,
+        styles: Vec<CellStyleRef>,
+    ) -> impl Strategy<Value = Sheet> {
+        let n_styles = styles.len();
+        let style = if n_styles > 0 {
+            option::of(0..n_styles).boxed()
+        } else {
+            Just(None).boxed()
+        };
+        btree_map(
+            (0..rows.max(1), 0..cols.max(1)),
+            (arb_value(), option::weighted(0.1, arb_formula()), style),
+            0..=max_cells,
+        )
+        .prop_map(move |cells| {
+            let mut sheet = Sheet::new(name.as_str());
+            for ((row, col), (value, formula, style)) in cells {
+                sheet.set_value(row, col, value);
+                if let Some(formula) = formula {
+                    sheet.set_formula(row, col, formula);
+                }
+                if let Some(style) = style {
+                    sheet.set_cellstyle(row, col, &styles[style]);
+                }
+            }
+            sheet
+        })
+    }
+
+    /// A workbook with one to three sheets and up to three cell-styles.
+    pub fn arb_workbook() -> impl Strategy<Value = WorkBook> {
+        (
+            btree_set("[A-Za-z][A-Za-z0-9_]{0,12}", 1..4),
+            vec(any::<bool>(), 0..4),
+        )
+            .prop_flat_map(|(names, styles)| {
+                let mut wb = WorkBook::new_empty();
+                let styles: Vec<_> = styles
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, bold)| {
+                        let mut st = CellStyle::new_empty();
+                        st.set_name(format!("arb{}", idx));
+                        if bold {
+                            st.set_font_bold();
+                        }
+                        wb.add_cellstyle(st)
+                    })
+                    .collect();
+                let sheets: Vec<_> = names
+                    .into_iter()
+                    .map(|name| arb_sheet(name, 64, 16, 64, styles.clone()))
+                    .collect();
+                (Just(wb), sheets)
+            })
+            .prop_map(|(mut wb, sheets)| {
+                for sheet in sheets {
+                    wb.push_sheet(sheet);
+                }
+                wb
+            })
+    }
+}
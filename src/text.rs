@@ -23,6 +23,7 @@
 //! ```
 //!
 
+use crate::refs::{push_colname, push_rowname, push_tablename, CellRange, CellRef};
 use crate::style::{ParagraphStyleRef, TextStyleRef};
 use crate::xmltree::{XmlContent, XmlTag};
 use std::fmt::{Display, Formatter};
@@ -225,6 +226,65 @@ impl TextA {
         self.xml.set_attr("xlink:href", uri.into());
         self
     }
+
+    /// Link to a cell in this document. Without a table name in the
+    /// reference the link goes to the cell in the current sheet.
+    ///
+    /// ```
+    /// use spreadsheet_ods::{CellRef, Sheet};
+    /// use spreadsheet_ods::text::{TextA, TextP};
+    ///
+    /// let mut sheet = Sheet::new("index");
+    /// let link = TextA::new()
+    ///     .href_cell(&CellRef::remote("Sheet 2", 4, 1))
+    ///     .text("go to B5");
+    /// sheet.set_value(0, 0, TextP::new().tag(link).into_xmltag());
+    /// ```
+    pub fn href_cell(mut self, cellref: &CellRef) -> Self {
+        let mut buf = String::new();
+        push_tablename(&mut buf, cellref.table(), false);
+        push_colname(&mut buf, cellref.col());
+        push_rowname(&mut buf, cellref.row());
+        self.xml.set_attr("xlink:href", internal_href(&buf));
+        self
+    }
+
+    /// Link to a range of cells in this document. Without a table name in
+    /// the reference the link goes to the range in the current sheet.
+    pub fn href_range(mut self, range: &CellRange) -> Self {
+        let mut buf = String::new();
+        push_tablename(&mut buf, range.table(), false);
+        push_colname(&mut buf, range.col());
+        push_rowname(&mut buf, range.row());
+        buf.push(':');
+        push_colname(&mut buf, range.to_col());
+        push_rowname(&mut buf, range.to_row());
+        self.xml.set_attr("xlink:href", internal_href(&buf));
+        self
+    }
+
+    /// Link to a named range or any other named target in this document.
+    pub fn href_name<S: AsRef<str>>(mut self, name: S) -> Self {
+        self.xml
+            .set_attr("xlink:href", internal_href(name.as_ref()));
+        self
+    }
+}
+
+/// Creates the href for a target within the document.
+/// Everything that is not allowed in the fragment part of an URI
+/// is percent encoded.
+fn internal_href(target: &str) -> String {
+    let mut buf = String::with_capacity(target.len() + 1);
+    buf.push('#');
+    for b in target.bytes() {
+        if b.is_ascii_alphanumeric() || b"-._~!$&'()*+,;=:@/?".contains(&b) {
+            buf.push(b as char);
+        } else {
+            buf.push_str(&format!("%{:02X}", b));
+        }
+    }
+    buf
 }
 
 // The <text:s> element is used to represent the [UNICODE] character “ “ (U+0020, SPACE).
@@ -0,0 +1,285 @@
+//!
+//! A theme is a set of fonts, colors, cell styles and value formats
+//! that is installed into a workbook with one call.
+//!
+//! All styles are named styles in styles.xml and derive from the base
+//! style of the theme, so changing the font of the base style changes
+//! all of them. The names start with the name of the theme.
+//!
+//! The styles for hyperlinks and annotations use the names LibreOffice
+//! looks for, see DefaultStyle::internet_link() and DefaultStyle::note().
+//!
+//! ```
+//! use spreadsheet_ods::theme::Theme;
+//! use spreadsheet_ods::{Sheet, WorkBook};
+//! use color::Rgb;
+//!
+//! let theme = Theme {
+//!     name: "corp".to_string(),
+//!     font: "Source Sans Pro".to_string(),
+//!     accent_color: Rgb::new(0, 84, 159),
+//!     ..Default::default()
+//! };
+//!
+//! let mut wb = WorkBook::new_empty();
+//! wb.install_theme(&theme);
+//!
+//! let mut sheet = Sheet::new("1");
+//! sheet.set_styled_value(0, 0, "amount", &theme.header());
+//! sheet.set_styled_value(1, 0, 1234.5, &theme.currency());
+//! wb.push_sheet(sheet);
+//! ```
+//!
+
+use crate::defaultstyles::DefaultStyle;
+use crate::format::{
+    create_currency_prefix, create_date_iso_format, create_number_format_fixed,
+    create_percentage_format, ValueFormatRef,
+};
+use crate::style::units::{FontFamilyGeneric, LineStyle, LineWidth};
+use crate::style::{
+    FontFaceDecl, GraphicStyle, GraphicStyleRef, StyleOrigin, StyleUse, TextStyle, TextStyleRef,
+};
+use crate::{CellStyle, CellStyleRef, Length, ValueType, WorkBook};
+use color::Rgb;
+use icu_locid::{locale, Locale};
+
+/// Fonts, colors and formats of a theme.
+#[derive(Clone, Debug)]
+pub struct Theme {
+    /// Prefix for the names of all styles and formats.
+    pub name: String,
+    /// Font family.
+    pub font: String,
+    /// Generic font family, used if the font is not available.
+    pub font_generic: FontFamilyGeneric,
+    /// Font size in pt.
+    pub font_size: f64,
+    /// Text color.
+    pub text_color: Rgb<u8>,
+    /// Background of the header style.
+    pub header_background: Rgb<u8>,
+    /// Text color of the header style.
+    pub header_color: Rgb<u8>,
+    /// Background of the accent style.
+    pub accent_color: Rgb<u8>,
+    /// Color of hyperlinks.
+    pub link_color: Rgb<u8>,
+    /// Color of visited hyperlinks.
+    pub visited_link_color: Rgb<u8>,
+    /// Background of annotations.
+    pub note_color: Rgb<u8>,
+    /// Decimal places for numbers.
+    pub decimal_places: u8,
+    /// Currency symbol.
+    pub currency_symbol: String,
+    /// Locale of the currency symbol.
+    pub currency_locale: Locale,
+}
+
+impl Default for Theme {
+    /// Liberation Sans 10pt, black on white with grey headers.
+    fn default() -> Self {
+        Self {
+            name: "theme".to_string(),
+            font: "Liberation Sans".to_string(),
+            font_generic: FontFamilyGeneric::Swiss,
+            font_size: 10.0,
+            text_color: Rgb::new(0, 0, 0),
+            header_background: Rgb::new(221, 221, 221),
+            header_color: Rgb::new(0, 0, 0),
+            accent_color: Rgb::new(255, 242, 204),
+            link_color: Rgb::new(0, 0, 128),
+            visited_link_color: Rgb::new(128, 0, 0),
+            note_color: Rgb::new(255, 255, 192),
+            decimal_places: 2,
+            currency_symbol: "€".to_string(),
+            currency_locale: locale!("de_AT"),
+        }
+    }
+}
+
+impl Theme {
+    /// Base style. Font and text color.
+    pub fn base(&self) -> CellStyleRef {
+        CellStyleRef::from(self.name.as_str())
+    }
+
+    /// Bold text with the header colors.
+    pub fn header(&self) -> CellStyleRef {
+        CellStyleRef::from(format!("{}-header", self.name))
+    }
+
+    /// Background with the accent color.
+    pub fn accent(&self) -> CellStyleRef {
+        CellStyleRef::from(format!("{}-accent", self.name))
+    }
+
+    /// Number with the decimal places of the theme.
+    pub fn number(&self) -> CellStyleRef {
+        CellStyleRef::from(format!("{}-number", self.name))
+    }
+
+    /// Currency with the symbol of the theme.
+    pub fn currency(&self) -> CellStyleRef {
+        CellStyleRef::from(format!("{}-currency", self.name))
+    }
+
+    /// Percentage.
+    pub fn percent(&self) -> CellStyleRef {
+        CellStyleRef::from(format!("{}-percent", self.name))
+    }
+
+    /// ISO date.
+    pub fn date(&self) -> CellStyleRef {
+        CellStyleRef::from(format!("{}-date", self.name))
+    }
+
+    /// Text style for hyperlinks.
+    pub fn link(&self) -> TextStyleRef {
+        DefaultStyle::internet_link()
+    }
+
+    /// Text style for visited hyperlinks.
+    pub fn visited_link(&self) -> TextStyleRef {
+        DefaultStyle::visited_internet_link()
+    }
+
+    /// Graphic style for annotations. Font and text color of the theme
+    /// with the note color as background.
+    pub fn note(&self) -> GraphicStyleRef {
+        DefaultStyle::note()
+    }
+
+    /// Value format of the number style.
+    pub fn number_format(&self) -> ValueFormatRef {
+        ValueFormatRef::from(format!("{}-number", self.name))
+    }
+
+    /// Value format of the currency style.
+    pub fn currency_format(&self) -> ValueFormatRef {
+        ValueFormatRef::from(format!("{}-currency", self.name))
+    }
+
+    /// Value format of the percent style.
+    pub fn percent_format(&self) -> ValueFormatRef {
+        ValueFormatRef::from(format!("{}-percent", self.name))
+    }
+
+    /// Value format of the date style.
+    pub fn date_format(&self) -> ValueFormatRef {
+        ValueFormatRef::from(format!("{}-date", self.name))
+    }
+}
+
+/// Adds the font, formats and styles and uses them as default styles
+/// for numbers, currencies, percentages and dates. Existing styles with
+/// the same name are replaced.
+pub(crate) fn install(book: &mut WorkBook, theme: &Theme) {
+    let mut font = FontFaceDecl::new(theme.font.as_str());
+    font.set_origin(StyleOrigin::Styles);
+    font.set_font_family(font_family(&theme.font));
+    font.set_font_family_generic(theme.font_generic);
+    book.add_font(font);
+
+    book.add_number_format(create_number_format_fixed(
+        theme.number_format().to_string(),
+        theme.decimal_places,
+        true,
+    ));
+    book.add_currency_format(create_currency_prefix(
+        theme.currency_format().to_string(),
+        theme.currency_locale.clone(),
+        theme.currency_symbol.as_str(),
+    ));
+    book.add_percentage_format(create_percentage_format(
+        theme.percent_format().to_string(),
+        theme.decimal_places,
+    ));
+    book.add_datetime_format(create_date_iso_format(theme.date_format().to_string()));
+
+    let mut base = named_style(theme.base(), None);
+    base.set_font_name(theme.font.as_str());
+    base.set_font_size(Length::Pt(theme.font_size).into());
+    base.set_color(theme.text_color);
+    book.add_cellstyle(base);
+
+    let mut header = named_style(theme.header(), Some(theme));
+    header.set_font_bold();
+    header.set_color(theme.header_color);
+    header.set_background_color(theme.header_background);
+    book.add_cellstyle(header);
+
+    let mut accent = named_style(theme.accent(), Some(theme));
+    accent.set_background_color(theme.accent_color);
+    book.add_cellstyle(accent);
+
+    for (style, format, value_type) in [
+        (theme.number(), theme.number_format(), ValueType::Number),
+        (
+            theme.currency(),
+            theme.currency_format(),
+            ValueType::Currency,
+        ),
+        (
+            theme.percent(),
+            theme.percent_format(),
+            ValueType::Percentage,
+        ),
+        (theme.date(), theme.date_format(), ValueType::DateTime),
+    ] {
+        let mut st = named_style(style.clone(), Some(theme));
+        st.set_value_format(&format);
+        book.add_cellstyle(st);
+        book.add_def_style(value_type, &style);
+    }
+
+    for (style, display_name, color) in [
+        (theme.link(), "Internet Link", theme.link_color),
+        (
+            theme.visited_link(),
+            "Visited Internet Link",
+            theme.visited_link_color,
+        ),
+    ] {
+        let mut st = TextStyle::new_empty();
+        st.set_name(style.as_str());
+        st.set_display_name(display_name);
+        st.set_origin(StyleOrigin::Styles);
+        st.set_styleuse(StyleUse::Named);
+        st.set_color(color);
+        st.set_text_underline_style(LineStyle::Solid);
+        st.set_text_underline_width(LineWidth::Auto);
+        book.add_textstyle(st);
+    }
+
+    let mut note = GraphicStyle::new(theme.note().as_str());
+    note.set_origin(StyleOrigin::Styles);
+    note.set_styleuse(StyleUse::Named);
+    note.set_fill_color(theme.note_color);
+    note.set_font_name(theme.font.as_str());
+    note.set_font_size(Length::Pt(theme.font_size).into());
+    note.set_color(theme.text_color);
+    book.add_graphicstyle(note);
+}
+
+// Named style in styles.xml, derived from the base style.
+fn named_style(style: CellStyleRef, theme: Option<&Theme>) -> CellStyle {
+    let mut st = CellStyle::new_empty();
+    st.set_name(style.as_str());
+    st.set_origin(StyleOrigin::Styles);
+    st.set_styleuse(StyleUse::Named);
+    if let Some(theme) = theme {
+        st.set_parent_style(&theme.base());
+    }
+    st
+}
+
+// Font families with spaces are quoted.
+fn font_family(font: &str) -> String {
+    if font.contains(' ') {
+        format!("'{}'", font)
+    } else {
+        font.to_string()
+    }
+}
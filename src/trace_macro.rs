@@ -0,0 +1,20 @@
+//!
+//! Instrumentation with tracing. Without the feature "tracing"
+//! these expand to nothing.
+//!
+
+/// Enters a span until the end of the current block.
+macro_rules! trace_span {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!($($arg)*).entered();
+    };
+}
+
+/// Emits an event.
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
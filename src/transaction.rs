@@ -0,0 +1,374 @@
+//!
+//! Records the edits of a WorkBook to allow undo.
+//!
+//! A Transaction wraps the WorkBook and records the reverse operation for
+//! each edit. It can be rolled back, or committed which returns an EditLog
+//! that can be used to undo the edits later.
+//!
+//! ```
+//! use spreadsheet_ods::{Sheet, WorkBook};
+//! use spreadsheet_ods::transaction::Transaction;
+//!
+//! let mut wb = WorkBook::new_empty();
+//! wb.push_sheet(Sheet::new("1"));
+//!
+//! let mut tx = Transaction::new(&mut wb);
+//! tx.set_value(0, 0, 0, "first");
+//! tx.insert_rows(0, 0, 1);
+//! let log = tx.commit();
+//!
+//! assert_eq!(wb.sheet(0).value(1, 0).as_str_or(""), "first");
+//!
+//! log.undo(&mut wb);
+//! assert!(wb.sheet(0).is_empty(0, 0));
+//! ```
+//!
+
+use crate::style::CellStyleRef;
+use crate::{CellContent, CellData, CellRange, CellSpan, ColHeader, RowHeader, Value, WorkBook};
+use std::collections::BTreeSet;
+use std::fmt::{Debug, Formatter};
+use std::mem;
+
+/// Reverse operations.
+#[derive(Debug)]
+enum UndoOp {
+    /// Restore the cell.
+    Cell {
+        sheet: usize,
+        row: u32,
+        col: u32,
+        content: Option<CellContent>,
+    },
+    /// Delete the inserted rows.
+    InsertRows { sheet: usize, row: u32, n: u32 },
+    /// Delete the inserted columns.
+    InsertCols { sheet: usize, col: u32, n: u32 },
+    /// Insert the rows and restore their content.
+    DeleteRows {
+        sheet: usize,
+        row: u32,
+        n: u32,
+        deleted: Box<Deleted<RowHeader>>,
+    },
+    /// Insert the columns and restore their content.
+    DeleteCols {
+        sheet: usize,
+        col: u32,
+        n: u32,
+        deleted: Box<Deleted<ColHeader>>,
+    },
+}
+
+/// Everything that is lost when deleting rows or columns.
+#[derive(Debug)]
+struct Deleted<H> {
+    cells: Vec<((u32, u32), CellData)>,
+    headers: Vec<(u32, H)>,
+    // Original spans of cells that were shortened.
+    spans: Vec<((u32, u32), CellSpan)>,
+}
+
+impl UndoOp {
+    fn undo(self, book: &mut WorkBook) {
+        match self {
+            UndoOp::Cell {
+                sheet,
+                row,
+                col,
+                content,
+            } => {
+                let sheet = book.sheet_mut(sheet);
+                match content {
+                    Some(content) => sheet.add_cell(row, col, content),
+                    None => {
+                        sheet.remove_cell(row, col);
+                    }
+                }
+            }
+            UndoOp::InsertRows { sheet, row, n } => {
+                book.sheet_mut(sheet).delete_rows(row, n);
+            }
+            UndoOp::InsertCols { sheet, col, n } => {
+                book.sheet_mut(sheet).delete_cols(col, n);
+            }
+            UndoOp::DeleteRows {
+                sheet,
+                row,
+                n,
+                deleted,
+            } => {
+                let sheet = book.sheet_mut(sheet);
+                sheet.insert_rows(row, n);
+                sheet.data.extend(deleted.cells);
+                // Replaces the parts of repeated rows.
+                for (r, header) in deleted.headers {
+                    let end = r.saturating_add(header.repeat());
+                    let parts: Vec<u32> = sheet.row_header.range(r..end).map(|(k, _)| *k).collect();
+                    for k in parts {
+                        sheet.row_header.remove(&k);
+                    }
+                    sheet.row_header.insert(r, header);
+                }
+                for (pos, span) in deleted.spans {
+                    if let Some(cell) = sheet.data.get_mut(&pos) {
+                        cell.extra_mut().span = span;
+                    }
+                }
+            }
+            UndoOp::DeleteCols {
+                sheet,
+                col,
+                n,
+                deleted,
+            } => {
+                let sheet = book.sheet_mut(sheet);
+                sheet.insert_cols(col, n);
+                sheet.data.extend(deleted.cells);
+                sheet.col_header.extend(deleted.headers);
+                for (pos, span) in deleted.spans {
+                    if let Some(cell) = sheet.data.get_mut(&pos) {
+                        cell.extra_mut().span = span;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The reverse operations of a committed transaction.
+#[derive(Debug, Default)]
+pub struct EditLog {
+    ops: Vec<UndoOp>,
+}
+
+impl EditLog {
+    /// Number of recorded operations.
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// No operations recorded.
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Reverts all the edits.
+    ///
+    /// This must be applied to the same workbook in the state directly
+    /// after the transaction. Otherwise the result is unspecified.
+    /// Guarded cells are restored too, see Sheet::guard_range().
+    pub fn undo(mut self, book: &mut WorkBook) {
+        while let Some(op) = self.ops.pop() {
+            op.undo(book);
+        }
+    }
+}
+
+/// Wraps a WorkBook and records the reverse operation for each edit.
+///
+/// If the transaction is dropped without commit() all the edits
+/// are rolled back.
+pub struct Transaction<'a> {
+    book: &'a mut WorkBook,
+    log: EditLog,
+}
+
+impl Debug for Transaction<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:?}", self.log)?;
+        Ok(())
+    }
+}
+
+impl<'a> Transaction<'a> {
+    /// Starts a transaction.
+    pub fn new(book: &'a mut WorkBook) -> Self {
+        Self {
+            book,
+            log: Default::default(),
+        }
+    }
+
+    /// Read access to the workbook.
+    pub fn book(&self) -> &WorkBook {
+        self.book
+    }
+
+    /// Ends the transaction and keeps the edits.
+    /// The returned log can undo the edits.
+    pub fn commit(mut self) -> EditLog {
+        mem::take(&mut self.log)
+    }
+
+    /// Ends the transaction and reverts all the edits.
+    pub fn rollback(mut self) {
+        mem::take(&mut self.log).undo(self.book);
+    }
+
+    // Remember the current state of a cell.
+    fn record_cell(&mut self, sheet: usize, row: u32, col: u32) {
+        let content = self.book.sheet(sheet).cell(row, col);
+        self.log.ops.push(UndoOp::Cell {
+            sheet,
+            row,
+            col,
+            content,
+        });
+    }
+
+    /// Sets a value for the specified cell.
+    pub fn set_value<V: Into<Value>>(&mut self, sheet: usize, row: u32, col: u32, value: V) {
+        self.record_cell(sheet, row, col);
+        self.book.sheet_mut(sheet).set_value(row, col, value);
+    }
+
+    /// Sets a value and a style for the specified cell.
+    pub fn set_styled_value<V: Into<Value>>(
+        &mut self,
+        sheet: usize,
+        row: u32,
+        col: u32,
+        value: V,
+        style: &CellStyleRef,
+    ) {
+        self.record_cell(sheet, row, col);
+        self.book
+            .sheet_mut(sheet)
+            .set_styled_value(row, col, value, style);
+    }
+
+    /// Sets a formula for the specified cell.
+    pub fn set_formula<V: Into<String>>(&mut self, sheet: usize, row: u32, col: u32, formula: V) {
+        self.record_cell(sheet, row, col);
+        self.book.sheet_mut(sheet).set_formula(row, col, formula);
+    }
+
+    /// Sets the cell-style for the specified cell.
+    pub fn set_cellstyle(&mut self, sheet: usize, row: u32, col: u32, style: &CellStyleRef) {
+        self.record_cell(sheet, row, col);
+        self.book.sheet_mut(sheet).set_cellstyle(row, col, style);
+    }
+
+    /// Replaces the cell.
+    pub fn add_cell(&mut self, sheet: usize, row: u32, col: u32, cell: CellContent) {
+        self.record_cell(sheet, row, col);
+        self.book.sheet_mut(sheet).add_cell(row, col, cell);
+    }
+
+    /// Removes the cell.
+    pub fn remove_cell(&mut self, sheet: usize, row: u32, col: u32) -> Option<CellContent> {
+        self.record_cell(sheet, row, col);
+        self.book.sheet_mut(sheet).remove_cell(row, col)
+    }
+
+    /// Inserts n empty rows. See Sheet::insert_rows().
+    pub fn insert_rows(&mut self, sheet: usize, row: u32, n: u32) {
+        self.book.sheet_mut(sheet).insert_rows(row, n);
+        self.log.ops.push(UndoOp::InsertRows { sheet, row, n });
+    }
+
+    /// Inserts n empty columns. See Sheet::insert_cols().
+    pub fn insert_cols(&mut self, sheet: usize, col: u32, n: u32) {
+        self.book.sheet_mut(sheet).insert_cols(col, n);
+        self.log.ops.push(UndoOp::InsertCols { sheet, col, n });
+    }
+
+    /// Deletes n rows. See Sheet::delete_rows().
+    pub fn delete_rows(&mut self, sheet: usize, row: u32, n: u32) {
+        let end = row.saturating_add(n);
+        let sh = self.book.sheet(sheet);
+        let deleted = Deleted {
+            cells: sh
+                .data
+                .range((row, 0)..(end, 0))
+                .map(|(k, v)| (*k, v.clone()))
+                .collect(),
+            // Repeated rows that are shortened too.
+            headers: sh
+                .row_header
+                .range(..end)
+                .filter(|(k, v)| k.saturating_add(v.repeat()) > row)
+                .map(|(k, v)| (*k, v.clone()))
+                .collect(),
+            spans: sh
+                .data
+                .range(..(row, 0))
+                .filter(|((r, _), v)| r.saturating_add(v.span().row_span) > row)
+                .map(|(k, v)| (*k, v.span()))
+                .collect(),
+        };
+        self.book.sheet_mut(sheet).delete_rows(row, n);
+        self.log.ops.push(UndoOp::DeleteRows {
+            sheet,
+            row,
+            n,
+            deleted: Box::new(deleted),
+        });
+    }
+
+    /// Deletes n columns. See Sheet::delete_cols().
+    pub fn delete_cols(&mut self, sheet: usize, col: u32, n: u32) {
+        let end = col.saturating_add(n);
+        let sh = self.book.sheet(sheet);
+        let deleted = Deleted {
+            cells: sh
+                .data
+                .iter()
+                .filter(|((_, c), _)| *c >= col && *c < end)
+                .map(|(k, v)| (*k, v.clone()))
+                .collect(),
+            headers: sh
+                .col_header
+                .range(col..end)
+                .map(|(k, v)| (*k, v.clone()))
+                .collect(),
+            spans: sh
+                .data
+                .iter()
+                .filter(|((_, c), v)| *c < col && c + v.span().col_span > col)
+                .map(|(k, v)| (*k, v.span()))
+                .collect(),
+        };
+        self.book.sheet_mut(sheet).delete_cols(col, n);
+        self.log.ops.push(UndoOp::DeleteCols {
+            sheet,
+            col,
+            n,
+            deleted: Box::new(deleted),
+        });
+    }
+
+    /// Copies the cells of the range. See Sheet::copy_range().
+    pub fn copy_range(&mut self, sheet: usize, range: &CellRange, row: u32, col: u32) {
+        let to_row = row + (range.to_row() - range.row());
+        let to_col = col + (range.to_col() - range.col());
+
+        // Cells that are overwritten or cleared.
+        let sh = self.book.sheet(sheet);
+        let mut touched = BTreeSet::new();
+        touched.extend(
+            sh.data
+                .range((range.row(), 0)..=(range.to_row(), u32::MAX))
+                .filter(|((_, c), _)| *c >= range.col() && *c <= range.to_col())
+                .map(|((r, c), _)| (r - range.row() + row, c - range.col() + col)),
+        );
+        touched.extend(
+            sh.data
+                .range((row, 0)..=(to_row, u32::MAX))
+                .filter(|((_, c), _)| *c >= col && *c <= to_col)
+                .map(|(k, _)| *k),
+        );
+        for (r, c) in touched {
+            self.record_cell(sheet, r, c);
+        }
+
+        self.book.sheet_mut(sheet).copy_range(range, row, col);
+    }
+}
+
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        mem::take(&mut self.log).undo(self.book);
+    }
+}
@@ -48,6 +48,7 @@ use crate::attrmap2::AttrMap2;
 use crate::text::TextP;
 use crate::OdsError;
 use std::fmt::{Display, Formatter};
+use std::mem::size_of;
 
 /// Defines a XML tag and it's children.
 #[derive(Debug, Clone, Default)]
@@ -190,6 +191,21 @@ impl XmlTag {
         &mut self.content
     }
 
+    /// Estimated heap memory.
+    pub(crate) fn heap_size(&self) -> usize {
+        self.name.capacity()
+            + self.attr.heap_size()
+            + self.content.capacity() * size_of::<XmlContent>()
+            + self
+                .content
+                .iter()
+                .map(|v| match v {
+                    XmlContent::Text(t) => t.capacity(),
+                    XmlContent::Tag(t) => t.heap_size(),
+                })
+                .sum::<usize>()
+    }
+
     /// Extracts the plain text from this tag and its content.
     pub fn extract_text(&self, buf: &mut String) {
         for c in &self.content {
@@ -0,0 +1,60 @@
+//!
+//! Helpers shared by the tests.
+//!
+
+#![allow(dead_code)]
+
+use spreadsheet_ods::OdsError;
+use std::io::{Cursor, Read, Write};
+use zip::write::FileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+/// Copies an ODS-file and lets `edit` change the xml-files on the way.
+/// The `extra` files are appended.
+pub fn rewrite_ods<F>(
+    buf: Vec<u8>,
+    mut edit: F,
+    extra: &[(&str, &[u8])],
+) -> Result<Vec<u8>, OdsError>
+where
+    F: FnMut(&str, String) -> String,
+{
+    let mut zip = ZipArchive::new(Cursor::new(buf))?;
+    let mut out = ZipWriter::new(Cursor::new(Vec::new()));
+
+    for idx in 0..zip.len() {
+        let mut ze = zip.by_index(idx)?;
+        if ze.is_dir() {
+            out.add_directory(ze.name(), FileOptions::default())?;
+        } else {
+            let mut buf = Vec::new();
+            ze.read_to_end(&mut buf)?;
+            if ze.name().ends_with(".xml") {
+                buf = edit(ze.name(), String::from_utf8(buf).unwrap()).into_bytes();
+            }
+            out.start_file(ze.name(), FileOptions::default())?;
+            out.write_all(&buf)?;
+        }
+    }
+    for (name, buf) in extra {
+        out.start_file(*name, FileOptions::default())?;
+        out.write_all(buf)?;
+    }
+
+    Ok(out.finish()?.into_inner())
+}
+
+/// Inserts the xml after the start tag of the first table.
+pub fn insert_in_table(content: &str, xml: &str) -> String {
+    let pos = content.find("<table:table ").unwrap();
+    let pos = pos + content[pos..].find('>').unwrap() + 1;
+    format!("{}{}{}", &content[..pos], xml, &content[pos..])
+}
+
+/// Adds the file-entries to the manifest.
+pub fn add_to_manifest(manifest: &str, entries: &str) -> String {
+    manifest.replace(
+        "</manifest:manifest>",
+        &format!("{}</manifest:manifest>", entries),
+    )
+}
@@ -0,0 +1,42 @@
+use spreadsheet_ods::builder::{SheetBuilder, WorkBookBuilder};
+use spreadsheet_ods::style::CellStyle;
+use spreadsheet_ods::{cm, read_ods_buf, values, write_ods_buf, CellStyleRef, Length, OdsError};
+
+#[test]
+fn test_builder() -> Result<(), OdsError> {
+    let mut header = CellStyle::new_empty();
+    header.set_name("header");
+    header.set_font_bold();
+
+    let wb = WorkBookBuilder::new()
+        .cellstyle(header)
+        .sheet(
+            SheetBuilder::new("Orders")
+                .styled_row(["name", "amount"], &CellStyleRef::from("header"))
+                .freeze_header()
+                .col_width(0, cm!(4))
+                .row(values!["apples", 3])
+                .skip_rows(1)
+                .row(values!["pears", 5.5, true]),
+        )
+        .sheet(SheetBuilder::new("Empty"))
+        .build();
+
+    assert_eq!(wb.num_sheets(), 2);
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0).as_str_or(""), "name");
+    assert_eq!(sh.cellstyle(0, 1), Some(&CellStyleRef::from("header")));
+    assert_eq!(sh.cellstyle(1, 0), None);
+    assert_eq!(sh.value(1, 1).as_i32_or(0), 3);
+    assert!(sh.is_empty(2, 0));
+    assert_eq!(sh.value(3, 1).as_f64_or(0.0), 5.5);
+    assert!(sh.value(3, 2).as_bool_or(false));
+    assert_eq!(sh.col_width(0), cm!(4));
+
+    let buf = write_ods_buf(&mut wb.clone(), Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.sheet(0).value(3, 0).as_str_or(""), "pears");
+    assert!(wb.cellstyle("header").is_some());
+
+    Ok(())
+}
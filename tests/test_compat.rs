@@ -0,0 +1,109 @@
+use spreadsheet_ods::compat::{CompatIssue, Conformance, WriteProfile};
+use spreadsheet_ods::format::create_number_format_fixed;
+use spreadsheet_ods::{
+    read_ods_buf, write_ods_buf_uncompressed, CellStyle, OdsError, Sheet, Value, WorkBook,
+};
+
+#[test]
+fn test_compat_report() {
+    let mut wb = WorkBook::new_empty();
+    assert!(wb.compat_report().is_strict());
+
+    let mut sheet = Sheet::new("Data");
+    sheet.set_value(0, 0, 1);
+    sheet.set_formula(0, 1, "of:=SUM([.A1]) + ORG.LIBREOFFICE.RAWSUBTRACT(1;2)");
+    sheet.set_formula(0, 2, "of:=\"ORG.LIBREOFFICE.NONE\"&[.A1]");
+    sheet.set_metadata(0, 0, "id", "17");
+    wb.push_sheet(sheet);
+
+    let mut st = CellStyle::new_empty();
+    st.set_name("lo");
+    st.cellstyle_mut()
+        .set_attr("calcext:text-properties", "x".to_string());
+    wb.add_cellstyle(st);
+
+    let report = wb.compat_report();
+    assert!(report.uses_extensions());
+    assert!(!report.is_strict());
+    assert_eq!(
+        report.extensions().into_iter().collect::<Vec<_>>(),
+        vec!["ORG.LIBREOFFICE", "calcext", "sods"]
+    );
+    assert!(report.issues().contains(&CompatIssue {
+        conformance: Conformance::Extension("ORG.LIBREOFFICE".to_string()),
+        location: "Data.B1".to_string(),
+        name: "ORG.LIBREOFFICE.RAWSUBTRACT".to_string(),
+    }));
+    assert_eq!(report.issues().len(), 3);
+    assert_eq!(report.min_version(), "1.2");
+}
+
+#[test]
+fn test_compat_version() {
+    let mut wb = WorkBook::new_empty();
+    wb.add_number_format(create_number_format_fixed("fixed", 2, true));
+
+    let report = wb.compat_report();
+    assert!(!report.uses_extensions());
+    assert_eq!(report.min_version(), "1.3");
+    assert!(report.is_strict());
+
+    wb.set_version("1.2".to_string());
+    let report = wb.compat_report();
+    assert_eq!(report.version(), "1.2");
+    assert!(!report.is_strict());
+}
+
+#[test]
+fn test_excel_profile() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let fixed = wb.add_number_format(create_number_format_fixed("fixed", 2, true));
+
+    let mut st = CellStyle::new_empty();
+    st.set_name("lo");
+    st.set_value_format(&fixed);
+    st.cellstyle_mut()
+        .set_attr("calcext:text-properties", "x".to_string());
+    let st = wb.add_cellstyle(st);
+
+    let mut sheet = Sheet::new("Data");
+    sheet.set_styled_value(0, 0, 1, &st);
+    sheet.set_formula(0, 1, "=[.A1]*2");
+    sheet.set_formula_value(0, 2, "=[.A1]*3", 3);
+    sheet.set_metadata(0, 0, "id", "17");
+    wb.push_sheet(sheet);
+
+    wb.set_write_profile(WriteProfile::Excel);
+    let buf = write_ods_buf_uncompressed(&mut wb, Vec::new())?;
+    let content = String::from_utf8_lossy(&buf);
+    assert!(!content.contains("calcext:text-properties"));
+    assert!(!content.contains("number:min-decimal-places"));
+    assert!(!content.contains("sods:id"));
+    assert!(!content.contains(r#"office:version="1.3""#));
+    // No made up value for a formula without a cached value.
+    assert!(content.contains(r#"<table:table-cell table:formula="of:=[.A1]*2"/>"#));
+    assert!(content
+        .contains(r#"table:formula="of:=[.A1]*3" office:value-type="float" office:value="3""#));
+
+    // The workbook itself is unchanged.
+    assert_eq!(wb.version(), "1.3");
+    let wb2 = read_ods_buf(&buf)?;
+    assert!(matches!(
+        wb2.sheet(0).cached_value(0, 1),
+        Some(Value::Empty)
+    ));
+    assert_eq!(wb2.sheet(0).value(0, 2).as_f64_or(0.0), 3.0);
+    assert_eq!(
+        wb2.sheet(0).formula(0, 1).map(|v| v.as_str()),
+        Some("of:=[.A1]*2")
+    );
+
+    wb.set_write_profile(WriteProfile::Default);
+    let buf = write_ods_buf_uncompressed(&mut wb, Vec::new())?;
+    let content = String::from_utf8_lossy(&buf);
+    assert!(content.contains("calcext:text-properties"));
+    assert!(content.contains("sods:id"));
+    assert!(content.contains(r#"table:formula="=[.A1]*2""#));
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use spreadsheet_ods::{read_ods_buf, write_ods_buf, OdsError, Sheet, WorkBook};
+
+mod common;
+use common::{insert_in_table, rewrite_ods};
+
+const DDE_SOURCE: &str = r#"<office:dde-source office:dde-application="soffice" office:dde-topic="file:///tmp/linked.ods" office:dde-item="Sheet1" office:automatic-update="false"/>"#;
+
+const DDE_LINKS: &str = r#"<table:dde-links><table:dde-link><office:dde-source office:name="soffice|file:///tmp/x.ods!Sheet1.A1" office:dde-application="soffice" office:dde-topic="file:///tmp/x.ods" office:dde-item="Sheet1.A1" office:conversion-mode="into-default-style-data-style"/><table:table><table:table-column/><table:table-row><table:table-cell office:value-type="float" office:value="42"/></table:table-row></table:table></table:dde-link></table:dde-links>"#;
+
+// Adds DDE sources.
+fn ods_with_dde(buf: Vec<u8>) -> Result<Vec<u8>, OdsError> {
+    rewrite_ods(
+        buf,
+        |name, xml| match name {
+            "content.xml" => {
+                let xml = xml.replace(
+                    "</office:spreadsheet>",
+                    &format!("{}</office:spreadsheet>", DDE_LINKS),
+                );
+                insert_in_table(&xml, DDE_SOURCE)
+            }
+            _ => xml,
+        },
+        &[],
+    )
+}
+
+#[test]
+fn test_dde_sources() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sheet = Sheet::new("1");
+    sheet.set_value(0, 0, 42);
+    sheet.set_formula(
+        0,
+        0,
+        "of:=DDE(\"soffice\";\"file:///tmp/x.ods\";\"Sheet1.A1\")",
+    );
+    wb.push_sheet(sheet);
+
+    let mut wb = read_ods_buf(&ods_with_dde(write_ods_buf(&mut wb, Vec::new())?)?)?;
+    // round trip
+    let wb = read_ods_buf(&write_ods_buf(&mut wb, Vec::new())?)?;
+
+    let sources = wb.dde_sources();
+    assert_eq!(sources.len(), 2);
+    assert_eq!(sources[0].application(), Some("soffice"));
+    assert_eq!(sources[0].topic(), Some("file:///tmp/x.ods"));
+    assert_eq!(sources[0].item(), Some("Sheet1.A1"));
+    assert!(sources[0].automatic_update());
+    assert_eq!(
+        sources[0].name(),
+        Some("soffice|file:///tmp/x.ods!Sheet1.A1")
+    );
+
+    let linked = wb.sheet(0).dde_source().unwrap();
+    assert_eq!(linked.topic(), Some("file:///tmp/linked.ods"));
+    assert_eq!(linked.item(), Some("Sheet1"));
+    assert!(!linked.automatic_update());
+
+    Ok(())
+}
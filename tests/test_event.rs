@@ -0,0 +1,68 @@
+use spreadsheet_ods::{read_ods_buf, write_ods_buf, OdsError, Sheet, WorkBook};
+
+mod common;
+use common::{insert_in_table, rewrite_ods};
+
+const SCRIPTS: &str = r#"<office:scripts><office:event-listeners>
+<script:event-listener script:language="ooo:script" script:event-name="dom:load" xlink:href="vnd.sun.star.script:Standard.Module1.Main?language=Basic&amp;location=document" xlink:type="simple"/>
+<script:event-listener script:language="ooo:script" script:event-name="office:new" xlink:href="vnd.sun.star.script:Standard.Module1.New?language=Basic&amp;location=document" xlink:type="simple"/>
+</office:event-listeners></office:scripts>"#;
+
+const SHEET_EVENTS: &str = r#"<office:event-listeners>
+<script:event-listener script:language="ooo:script" script:event-name="office:focus" xlink:href="vnd.sun.star.script:Standard.Module1.Focus?language=Basic&amp;location=document" xlink:type="simple"/>
+</office:event-listeners>"#;
+
+// Inserts the event listeners into content.xml
+fn ods_with_events(buf: Vec<u8>) -> Result<Vec<u8>, OdsError> {
+    rewrite_ods(
+        buf,
+        |name, xml| match name {
+            "content.xml" => {
+                insert_in_table(&xml.replace("<office:scripts/>", SCRIPTS), SHEET_EVENTS)
+            }
+            _ => xml,
+        },
+        &[],
+    )
+}
+
+#[test]
+fn test_event_listeners() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sheet = Sheet::new("1");
+    sheet.set_value(0, 0, "A");
+    wb.push_sheet(sheet);
+
+    let buf = ods_with_events(write_ods_buf(&mut wb, Vec::new())?)?;
+    let mut wb = read_ods_buf(&buf)?;
+    // round trip
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut wb = read_ods_buf(&buf)?;
+
+    let listeners = wb.event_listeners();
+    assert_eq!(listeners.len(), 2);
+    assert_eq!(listeners[0].event_name(), Some("dom:load"));
+    assert_eq!(listeners[0].language(), Some("ooo:script"));
+    assert_eq!(
+        listeners[0].href(),
+        Some("vnd.sun.star.script:Standard.Module1.Main?language=Basic&location=document")
+    );
+
+    let listeners = wb.sheet(0).event_listeners();
+    assert_eq!(listeners.len(), 1);
+    assert_eq!(listeners[0].event_name(), Some("office:focus"));
+    assert_eq!(wb.sheet(0).value(0, 0).as_str_or(""), "A");
+
+    assert_eq!(wb.remove_event_listener("dom:load"), 1);
+    assert_eq!(wb.remove_event_listener("dom:load"), 0);
+    assert_eq!(wb.event_listeners().len(), 1);
+    wb.clear_event_listeners();
+    wb.sheet_mut(0).clear_event_listeners();
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    assert!(wb.event_listeners().is_empty());
+    assert!(wb.sheet(0).event_listeners().is_empty());
+
+    Ok(())
+}
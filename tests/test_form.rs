@@ -0,0 +1,75 @@
+use spreadsheet_ods::xmltree::XmlTag;
+use spreadsheet_ods::{
+    read_ods_buf, write_ods_buf, CellContent, CellRef, OdsError, Sheet, WorkBook,
+};
+
+mod common;
+use common::{insert_in_table, rewrite_ods};
+
+const FORMS: &str = r#"<office:forms form:automatic-focus="false" form:apply-design-mode="false">
+<form:form form:name="Form" form:apply-filter="true" form:command-type="table">
+<form:checkbox form:name="Check Box 1" form:control-implementation="ooo:com.sun.star.form.component.CheckBox" xml:id="control1" form:id="control1" form:linked-cell="Sheet1.B2" form:current-state="checked" form:image-position="center">
+<form:properties><form:property form:property-name="DefaultControl" office:value-type="string" office:string-value="com.sun.star.form.control.CheckBox"/></form:properties>
+</form:checkbox>
+<form:button form:name="Push Button 1" form:id="control2" form:label="Go"/>
+</form:form>
+</office:forms>"#;
+
+// Inserts the forms into the first table.
+fn ods_with_forms(buf: Vec<u8>) -> Result<Vec<u8>, OdsError> {
+    rewrite_ods(
+        buf,
+        |name, xml| match name {
+            "content.xml" => insert_in_table(&xml, FORMS),
+            _ => xml,
+        },
+        &[],
+    )
+}
+
+#[test]
+fn test_form_controls() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sheet = Sheet::new("Sheet1");
+    sheet.set_value(1, 1, true);
+    let mut cell = CellContent::new();
+    cell.shapes.push(
+        XmlTag::new("draw:control")
+            .attr("draw:control", "control1")
+            .attr("svg:width", "2cm")
+            .attr("svg:height", "0.5cm"),
+    );
+    sheet.add_cell(0, 0, cell);
+    wb.push_sheet(sheet);
+
+    let buf = ods_with_forms(write_ods_buf(&mut wb, Vec::new())?)?;
+    let mut wb = read_ods_buf(&buf)?;
+
+    // round trip
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let sheet = wb.sheet(0);
+    let controls = sheet.form_controls();
+    assert_eq!(controls.len(), 2);
+
+    let checkbox = controls[0];
+    assert_eq!(checkbox.kind(), "form:checkbox");
+    assert_eq!(checkbox.name(), Some("Check Box 1"));
+    assert_eq!(checkbox.id(), Some("control1"));
+    assert_eq!(checkbox.form_name(), Some("Form"));
+    assert_eq!(checkbox.current_state(), Some("checked"));
+    let cell = checkbox.linked_cell()?.unwrap();
+    assert_eq!(cell, CellRef::remote("Sheet1", 1, 1));
+    assert!(sheet.value(cell.row(), cell.col()).as_bool_or(false));
+
+    let button = controls[1];
+    assert_eq!(button.kind(), "form:button");
+    assert_eq!(button.attr("form:label"), Some("Go"));
+    assert_eq!(button.linked_cell()?, None);
+
+    let shapes = sheet.shapes(0, 0).unwrap();
+    assert_eq!(shapes[0].name(), "draw:control");
+
+    Ok(())
+}
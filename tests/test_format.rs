@@ -1,11 +1,17 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use icu_locid::locale;
 
-use spreadsheet_ods::format::{FormatCalendarStyle, FormatNumberStyle};
+use spreadsheet_ods::format::{
+    create_date_iso_format, create_engineering_format, create_number_format,
+    create_scientific_format, create_text_format, create_text_format_affix,
+    create_week_date_format, FormatCalendarStyle, FormatNumberStyle, FormatPart, FormatPartType,
+    FormatSections, RenderContext, ValueFormatTrait,
+};
 use spreadsheet_ods::style::CellStyle;
 use spreadsheet_ods::{
-    write_ods, OdsError, Sheet, ValueFormatBoolean, ValueFormatCurrency, ValueFormatDateTime,
-    ValueFormatNumber, ValueFormatPercentage, WorkBook,
+    read_ods_buf, write_ods, write_ods_buf, write_ods_buf_uncompressed, OdsError, Sheet, Value,
+    ValueFormatBoolean, ValueFormatCurrency, ValueFormatDateTime, ValueFormatNumber,
+    ValueFormatPercentage, ValueFormatRef, ValueType, WorkBook,
 };
 
 #[test]
@@ -122,3 +128,341 @@ fn write_format() -> Result<(), OdsError> {
         write_ods(&mut wb, path)
     }
 }
+
+#[test]
+fn write_currency_text() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut v1 = ValueFormatCurrency::new_named("f1");
+    v1.part_number().fixed_decimal_places(2).build();
+    v1.part_text(" ").build();
+    v1.part_currency().locale(locale!("de_AT")).build();
+    let v1 = wb.add_currency_format(v1);
+    let s1 = wb.add_cellstyle(CellStyle::new("s1", &v1));
+
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, Value::new_currency("EUR", 12.5), &s1);
+    sh.set_value(1, 0, Value::new_currency("GBP", 7.0));
+    sh.set_value(2, 0, Value::new_currency("XYZ", 1.0));
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf_uncompressed(&mut wb, Vec::new())?;
+    let buf = String::from_utf8_lossy(&buf);
+    assert!(buf.contains("<text:p>12.5 €</text:p>"));
+    assert!(buf.contains("<text:p>£ 7</text:p>"));
+    assert!(buf.contains("<text:p>XYZ 1</text:p>"));
+
+    Ok(())
+}
+
+#[test]
+fn formatted_value() {
+    let mut wb = WorkBook::new_empty();
+
+    let mut v1 = ValueFormatCurrency::new_named("f1");
+    v1.part_number().fixed_decimal_places(2).build();
+    v1.part_text(" ").build();
+    v1.part_currency().locale(locale!("de_AT")).build();
+    let v1 = wb.add_currency_format(v1);
+    let s1 = wb.add_cellstyle(CellStyle::new("s1", &v1));
+    let v2 = wb.add_datetime_format(create_date_iso_format("iso"));
+    let s2 = wb.add_cellstyle(CellStyle::new("s2", &v2));
+    wb.add_def_style(ValueType::DateTime, &s2);
+
+    let d = NaiveDate::from_ymd_opt(2021, 1, 2)
+        .unwrap()
+        .and_hms_opt(10, 0, 0)
+        .unwrap();
+
+    let mut ctx = RenderContext::new(&wb);
+    let currency = Value::new_currency("EUR", 12.5);
+    assert_eq!(ctx.formatted_value(&currency, Some(&s1)), "12.5 €");
+    assert_eq!(ctx.formatted_value(&currency, None), "€ 12.5");
+    // The default style for the value type.
+    assert_eq!(ctx.formatted_value(&Value::from(d), None), "2021-01-02");
+    assert_eq!(ctx.formatted_value(&Value::from(1.5), Some(&s1)), "1.5");
+    assert_eq!(ctx.formatted_value(&Value::from(true), None), "true");
+    assert_eq!(ctx.formatted_value(&Value::from("a\nb"), None), "a\nb");
+    assert_eq!(ctx.formatted_value(&Value::Empty, None), "");
+
+    let currency = ctx.currency_format(Some(&s1)).expect("format");
+    assert_eq!(currency.render("3.25", b"EUR"), "3.25 €");
+    assert!(ctx.currency_format(Some(&s2)).is_none());
+
+    let iso = create_date_iso_format("iso");
+    let iso = RenderContext::compile_datetime(&iso);
+    assert_eq!(iso.render(&d), "2021-01-02");
+}
+
+#[test]
+fn write_datetime_text() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let v1 = wb.add_datetime_format(create_date_iso_format("iso"));
+    let s1 = wb.add_cellstyle(CellStyle::new("s1", &v1));
+    let v2 = wb.add_datetime_format(create_week_date_format("week"));
+    let s2 = wb.add_cellstyle(CellStyle::new("s2", &v2));
+    let mut v3 = ValueFormatDateTime::new_named("time");
+    v3.part_hours().build();
+    v3.part_text(":").build();
+    v3.part_minutes().long_style().build();
+    v3.part_text(":").build();
+    v3.part_seconds().long_style().decimal_places(2).build();
+    v3.part_text(" ").build();
+    v3.part_am_pm().build();
+    let v3 = wb.add_datetime_format(v3);
+    // two styles with the same format.
+    let s3 = wb.add_cellstyle(CellStyle::new("s3", &v3));
+    let s4 = wb.add_cellstyle(CellStyle::new("s4", &v3));
+
+    let d = NaiveDate::from_ymd_opt(2021, 1, 2)
+        .unwrap()
+        .and_hms_opt(10, 0, 0)
+        .unwrap();
+
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, d, &s1);
+    sh.set_styled_value(1, 0, d, &s2);
+    sh.set_value(2, 0, d);
+    let t = NaiveDate::from_ymd_opt(2021, 1, 2)
+        .unwrap()
+        .and_hms_milli_opt(15, 4, 5, 250)
+        .unwrap();
+    sh.set_styled_value(3, 0, t, &s3);
+    sh.set_styled_value(4, 0, d, &s4);
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf_uncompressed(&mut wb, Vec::new())?;
+    let buf = String::from_utf8_lossy(&buf);
+    assert!(buf.contains("<text:p>2021-01-02</text:p>"));
+    assert!(buf.contains("<text:p>2020-W53-6</text:p>"));
+    assert!(buf.contains("<text:p>2021-01-02T10:00:00</text:p>"));
+    assert!(buf.contains("<text:p>3:04:05.25 PM</text:p>"));
+    assert!(buf.contains("<text:p>10:00:00.00 AM</text:p>"));
+
+    Ok(())
+}
+
+#[test]
+fn write_missing_format_text() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let missing = ValueFormatRef::from("missing");
+    let s1 = wb.add_cellstyle(CellStyle::new("s1", &missing));
+    let number = wb.add_number_format(create_number_format("num", 2, false));
+    let s2 = wb.add_cellstyle(CellStyle::new("s2", &number));
+
+    let d = NaiveDate::from_ymd_opt(2021, 1, 2)
+        .unwrap()
+        .and_hms_opt(10, 0, 0)
+        .unwrap();
+
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, Value::new_currency("EUR", 12.5), &s1);
+    sh.set_styled_value(1, 0, d, &s1);
+    sh.set_styled_value(2, 0, Value::new_currency("EUR", 1.0), &"nostyle".into());
+    sh.set_styled_value(3, 0, d, &s2);
+    sh.set_styled_value(4, 0, d, &s2);
+    wb.push_sheet(sh);
+
+    let mut ctx = RenderContext::new(&wb);
+    assert_eq!(
+        ctx.formatted_value(&d.into(), Some(&s2)),
+        "2021-01-02T10:00:00"
+    );
+    assert_eq!(ctx.warnings().len(), 1);
+    assert_eq!(ctx.warnings()[0].found, Some(ValueType::Number));
+
+    let buf = write_ods_buf_uncompressed(&mut wb, Vec::new())?;
+    let buf = String::from_utf8_lossy(&buf);
+    assert!(buf.contains("<text:p>€ 12.5</text:p>"));
+    assert!(buf.contains("<text:p>2021-01-02T10:00:00</text:p>"));
+    assert!(buf.contains("<text:p>€ 1</text:p>"));
+
+    // Once per style and value type.
+    let warnings = wb.format_warnings();
+    assert_eq!(warnings.len(), 3);
+    let missing = warnings
+        .iter()
+        .find(|v| v.style == "s1" && v.value_type == ValueType::Currency)
+        .expect("warning");
+    assert_eq!(missing.format, "missing");
+    assert_eq!(missing.found, None);
+    assert!(warnings
+        .iter()
+        .any(|v| v.style == "s1" && v.value_type == ValueType::DateTime));
+    let wrong_type = warnings.iter().find(|v| v.style == "s2").expect("warning");
+    assert_eq!(wrong_type.value_type, ValueType::DateTime);
+    assert_eq!(wrong_type.found, Some(ValueType::Number));
+
+    Ok(())
+}
+
+#[test]
+fn write_read_text_format() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let v1 = wb.add_text_format(create_text_format("txt"));
+    let s1 = wb.add_cellstyle(CellStyle::new("s1", &v1));
+    let v2 = wb.add_text_format(create_text_format_affix("id", "ID-", " (old)"));
+    let s2 = wb.add_cellstyle(CellStyle::new("s2", &v2));
+
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, "00042", &s1);
+    sh.set_styled_value(1, 0, "00043", &s2);
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0).as_str_or(""), "00042");
+    assert_eq!(sh.cellstyle(0, 0), Some(&s1));
+
+    let st = wb.cellstyle("s2").unwrap();
+    assert_eq!(st.value_format(), Some(&"id".to_string()));
+    let parts: Vec<_> = wb
+        .text_format("id")
+        .unwrap()
+        .parts()
+        .iter()
+        .map(|v| (v.part_type(), v.content().cloned()))
+        .collect();
+    assert_eq!(
+        parts,
+        vec![
+            (FormatPartType::Text, Some("ID-".to_string())),
+            (FormatPartType::TextContent, None),
+            (FormatPartType::Text, Some(" (old)".to_string())),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn write_read_scientific_format() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let v1 = wb.add_number_format(create_scientific_format("sci", 3, 2));
+    let s1 = wb.add_cellstyle(CellStyle::new("s1", &v1));
+    let v2 = wb.add_number_format(create_engineering_format("eng", 2, 1));
+    let s2 = wb.add_cellstyle(CellStyle::new("s2", &v2));
+
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, 12345.678, &s1);
+    sh.set_styled_value(1, 0, 12345.678, &s2);
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let sci = &wb.number_format("sci").unwrap().parts()[0];
+    assert_eq!(sci.part_type(), FormatPartType::ScientificNumber);
+    assert_eq!(sci.attr_def("number:decimal-places", ""), "3");
+    assert_eq!(sci.attr_def("number:min-exponent-digits", ""), "2");
+    assert_eq!(sci.attr_def("number:exponent-interval", "1"), "1");
+
+    let eng = &wb.number_format("eng").unwrap().parts()[0];
+    assert_eq!(eng.part_type(), FormatPartType::ScientificNumber);
+    assert_eq!(eng.attr_def("number:decimal-places", ""), "2");
+    assert_eq!(eng.attr_def("number:exponent-interval", ""), "3");
+
+    Ok(())
+}
+
+#[test]
+fn write_read_loext_attr() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut v1 = ValueFormatNumber::new_named("f1");
+    let mut p1 = FormatPart::new(FormatPartType::Number);
+    p1.set_attr("number:decimal-places", "3".to_string());
+    p1.set_attr("loext:min-decimal-places", "1".to_string());
+    p1.set_attr("loext:not-yet-known", "keep".to_string());
+    v1.push_part(p1);
+    let mut p2 = FormatPart::new(FormatPartType::Text);
+    p2.set_content(" ");
+    p2.set_blank_width_char(")");
+    v1.push_part(p2);
+    let v1 = wb.add_number_format(v1);
+    let s1 = wb.add_cellstyle(CellStyle::new("s1", &v1));
+
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, 1.5, &s1);
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut wb = read_ods_buf(&buf)?;
+
+    let f1 = wb.number_format_mut("f1").unwrap();
+    let p1 = &mut f1.parts_mut()[0];
+    assert_eq!(p1.min_decimal_places(), Some(1));
+    assert_eq!(p1.attr("loext:not-yet-known"), Some(&"keep".to_string()));
+    p1.set_min_decimal_places(2);
+    assert_eq!(p1.attr("number:min-decimal-places"), Some(&"2".to_string()));
+    assert_eq!(p1.attr("loext:min-decimal-places"), Some(&"2".to_string()));
+    assert_eq!(f1.parts()[1].blank_width_char(), Some(")"));
+
+    Ok(())
+}
+
+#[test]
+fn write_read_format_sections() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut negative = ValueFormatNumber::new_empty();
+    negative.part_text("-").build();
+    negative.part_number().fixed_decimal_places(2).build();
+    let mut zero = ValueFormatNumber::new_empty();
+    zero.part_text("zero").build();
+
+    let sections = FormatSections::new("amount", create_number_format("", 2, true))
+        .negative(negative)
+        .zero(zero)
+        .text(create_text_format(""));
+    let amount = wb.add_number_sections(sections);
+    assert_eq!(amount.as_str(), "amount");
+
+    let sections = FormatSections::new("signed", create_number_format("", 0, false))
+        .negative(create_number_format("", 1, false));
+    wb.add_number_sections(sections);
+
+    let mut sheet = Sheet::new("one");
+    sheet.set_value(0, 0, -1.5);
+    wb.push_sheet(sheet);
+
+    let wb = read_ods_buf(&write_ods_buf(&mut wb, Vec::new())?)?;
+
+    let text = wb.text_format("amount").expect("amount");
+    let maps: Vec<_> = text
+        .stylemaps()
+        .expect("stylemaps")
+        .iter()
+        .map(|v| (v.condition().as_str(), v.applied_style().as_str()))
+        .collect();
+    assert_eq!(
+        maps,
+        vec![
+            ("value()>0", "amountP0"),
+            ("value()<0", "amountP1"),
+            ("value()=0", "amountP2"),
+        ]
+    );
+    assert!(wb.number_format("amountP0").is_some());
+    assert_eq!(
+        wb.number_format("amountP2").expect("zero").parts()[0]
+            .content()
+            .map(|v| v.as_str()),
+        Some("zero")
+    );
+
+    let signed = wb.number_format("signed").expect("signed");
+    assert_eq!(signed.parts()[0].attr_def("number:decimal-places", ""), "1");
+    let maps = signed.stylemaps().expect("stylemaps");
+    assert_eq!(maps.len(), 1);
+    assert_eq!(maps[0].condition(), "value()>=0");
+    assert_eq!(maps[0].applied_style(), "signedP0");
+
+    Ok(())
+}
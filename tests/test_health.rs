@@ -0,0 +1,161 @@
+use spreadsheet_ods::health::DroppedElement;
+use spreadsheet_ods::{
+    read_ods, read_ods_buf, read_ods_buf_lenient, read_ods_content, read_ods_lenient,
+    write_ods_buf, ContentType, OdsError, Sheet, WorkBook,
+};
+
+mod common;
+use common::rewrite_ods;
+
+#[test]
+fn test_strict() {
+    assert!(read_ods("tests/damaged.ods").is_err());
+    // Nothing of a lenient read carries over.
+    assert!(read_ods_lenient("tests/damaged.ods").is_ok());
+    assert!(read_ods("tests/damaged.ods").is_err());
+}
+
+#[test]
+fn test_lenient() -> Result<(), OdsError> {
+    let (wb, health) = read_ods_lenient("tests/damaged.ods")?;
+    assert!(!health.is_healthy());
+
+    let sh = wb.sheet(0);
+    assert_eq!(sh.name(), "damaged");
+    assert!(sh.print());
+    assert_eq!(sh.value(0, 0).as_str_or(""), "abc");
+    assert_eq!(sh.value(0, 1).as_f64_or(0.0), 2.5);
+    assert_eq!(sh.value(0, 2).as_str_or(""), "yesterday");
+    assert_eq!(sh.value(1, 0).as_str_or(""), "ok");
+
+    let coerced: Vec<_> = health
+        .coerced()
+        .iter()
+        .map(|v| (v.location.as_str(), v.attr.as_str(), v.value.as_str()))
+        .collect();
+    assert_eq!(
+        coerced,
+        vec![
+            ("table damaged", "table:print", "maybe"),
+            ("damaged.A1", "office:value", "abc"),
+            ("damaged.A1", "office:value-type", "Number"),
+            ("damaged.C1", "office:value-type", "DateTime"),
+            ("damaged row 2", "table:number-rows-repeated", "x"),
+        ]
+    );
+
+    assert_eq!(health.unknown_elements().get("foo:extra"), Some(&1));
+    assert_eq!(health.unknown_elements().get("foo:child"), Some(&1));
+    assert_eq!(health.unknown_element_count(), 2);
+    assert_eq!(
+        health.unknown_attrs().get("table:table-cell@foo:color"),
+        Some(&1)
+    );
+
+    assert_eq!(health.missing_styles().len(), 1);
+    assert_eq!(health.missing_styles()[0].kind, "cell style");
+    assert_eq!(health.missing_styles()[0].name, "missing");
+    assert_eq!(health.missing_styles()[0].uses, 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_healthy() -> Result<(), OdsError> {
+    let (_wb, health) = read_ods_lenient("tests/orders.ods")?;
+    assert!(health.missing_styles().is_empty());
+    assert!(health.coerced().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_dropped() -> Result<(), OdsError> {
+    let (wb, _health) = read_ods_lenient("tests/damaged.ods")?;
+    let dropped: Vec<_> = wb
+        .dropped_elements()
+        .iter()
+        .map(|v| (v.name.as_str(), v.location(), v.count))
+        .collect();
+    assert_eq!(
+        dropped,
+        vec![
+            ("foo:child", "table damaged".to_string(), 1),
+            ("foo:extra", "table damaged".to_string(), 1),
+        ]
+    );
+
+    let wb = read_ods_content(
+        br#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:foo="urn:foo" office:version="1.3">
+<foo:prelude/>
+<office:body><office:spreadsheet><table:table table:name="one">
+<table:table-row><table:table-cell/><table:table-cell office:value-type="string"><text:p>a</text:p><foo:note/><foo:note/></table:table-cell></table:table-row>
+</table:table></office:spreadsheet></office:body></office:document-content>"#,
+    )?;
+    assert_eq!(
+        wb.dropped_elements(),
+        &[
+            DroppedElement {
+                name: "foo:note".to_string(),
+                sheet: Some("one".to_string()),
+                cell: Some((0, 1)),
+                count: 2,
+            },
+            DroppedElement {
+                name: "foo:prelude".to_string(),
+                sheet: None,
+                cell: None,
+                count: 1,
+            },
+        ]
+    );
+    assert_eq!(wb.dropped_elements()[0].location(), "one.B1");
+
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "a");
+    wb.push_sheet(sh);
+    let wb = read_ods_buf(&write_ods_buf(&mut wb, Vec::new())?)?;
+    assert!(wb.dropped_elements().iter().all(|v| v.sheet.is_none()));
+
+    Ok(())
+}
+
+#[test]
+fn test_unknown_content_type() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "007");
+    sh.set_content_type(0, 0, ContentType::String);
+    sh.set_value(0, 1, "a");
+    sh.set_content_type(0, 1, ContentType::String);
+    wb.push_sheet(sh);
+
+    let buf = rewrite_ods(
+        write_ods_buf(&mut wb, Vec::new())?,
+        |name, xml| match name {
+            "content.xml" => xml.replacen(
+                r#"calcext:value-type="string""#,
+                r#"calcext:value-type="rich-text""#,
+                1,
+            ),
+            _ => xml,
+        },
+        &[],
+    )?;
+
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.sheet(0).value(0, 0).as_str_or(""), "007");
+    assert_eq!(wb.sheet(0).content_type(0, 0), None);
+    assert_eq!(wb.sheet(0).content_type(0, 1), Some(ContentType::String));
+
+    let (_wb, health) = read_ods_buf_lenient(&buf)?;
+    let coerced: Vec<_> = health
+        .coerced()
+        .iter()
+        .map(|v| (v.location.as_str(), v.attr.as_str(), v.value.as_str()))
+        .collect();
+    assert_eq!(coerced, vec![("'1'.A1", "calcext:value-type", "rich-text")]);
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use spreadsheet_ods::hook::WriteHook;
+use spreadsheet_ods::xmltree::XmlTag;
+use spreadsheet_ods::{write_ods_buf_uncompressed, OdsError, Sheet, WorkBook};
+
+struct Hook;
+
+impl WriteHook for Hook {
+    fn sheet(&self, _book: &WorkBook, sheet: &Sheet) -> Vec<XmlTag> {
+        vec![XmlTag::new("loext:sheet-hook").attr("loext:name", sheet.name().as_str())]
+    }
+
+    fn spreadsheet(&self, book: &WorkBook) -> Vec<XmlTag> {
+        vec![XmlTag::new("loext:book-hook").attr("loext:sheets", book.num_sheets().to_string())]
+    }
+}
+
+struct StyleHook;
+
+impl WriteHook for StyleHook {
+    fn styles(&self, _book: &WorkBook) -> Vec<XmlTag> {
+        vec![XmlTag::new("loext:style-hook").text("<1>")]
+    }
+}
+
+#[test]
+fn test_write_hook() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sheet = Sheet::new("1");
+    sheet.set_value(0, 0, 1);
+    wb.push_sheet(sheet);
+    wb.push_sheet(Sheet::new("2"));
+    wb.add_write_hook(Hook);
+    wb.add_write_hook(StyleHook);
+
+    let buf = write_ods_buf_uncompressed(&mut wb, Vec::new())?;
+    let buf = String::from_utf8_lossy(&buf);
+    assert!(buf.contains(r#"</table:table-row><loext:sheet-hook loext:name="1"/></table:table>"#));
+    assert!(buf.contains(r#"<loext:sheet-hook loext:name="2"/></table:table>"#));
+    assert!(buf.contains(r#"<loext:book-hook loext:sheets="2"/></office:spreadsheet>"#));
+    assert!(buf.contains("<loext:style-hook>&lt;1&gt;</loext:style-hook></office:styles>"));
+
+    wb.clear_write_hooks();
+    let buf = write_ods_buf_uncompressed(&mut wb, Vec::new())?;
+    let buf = String::from_utf8_lossy(&buf);
+    assert!(!buf.contains("hook"));
+
+    Ok(())
+}
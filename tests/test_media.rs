@@ -0,0 +1,187 @@
+use spreadsheet_ods::xmltree::XmlTag;
+use spreadsheet_ods::{read_ods_buf, write_ods_buf, CellContent, OdsError, WorkBook};
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+mod common;
+use common::{add_to_manifest, rewrite_ods};
+
+// Adds a picture and an embedded object to rw.ods
+fn ods_with_media() -> Result<Vec<u8>, OdsError> {
+    rewrite_ods(
+        std::fs::read("tests/rw.ods")?,
+        |name, xml| match name {
+            "META-INF/manifest.xml" => add_to_manifest(
+                &xml,
+                r#"<manifest:file-entry manifest:full-path="Pictures/logo.png" manifest:media-type="image/png"/>
+<manifest:file-entry manifest:full-path="Object 1/" manifest:version="1.3" manifest:media-type="application/vnd.oasis.opendocument.formula"/>
+<manifest:file-entry manifest:full-path="Object 1/content.xml" manifest:media-type="text/xml"/>
+"#,
+            ),
+            _ => xml,
+        },
+        &[
+            ("Pictures/logo.png", &[0x89, b'P', b'N', b'G']),
+            ("Pictures/other.jpg", &[0xff, 0xd8]),
+            ("Object 1/content.xml", b"<math/>"),
+        ],
+    )
+}
+
+fn manifest(buf: Vec<u8>) -> Result<String, OdsError> {
+    let mut zip = ZipArchive::new(Cursor::new(buf))?;
+    let mut manifest = String::new();
+    zip.by_name("META-INF/manifest.xml")?
+        .read_to_string(&mut manifest)?;
+    Ok(manifest)
+}
+
+#[test]
+fn test_media() -> Result<(), OdsError> {
+    let mut wb = read_ods_buf(&ods_with_media()?)?;
+
+    let media = wb.media();
+    assert_eq!(media.len(), 3);
+    let logo = media
+        .iter()
+        .find(|v| v.path == "Pictures/logo.png")
+        .unwrap();
+    assert_eq!(logo.media_type, "image/png");
+    assert_eq!(logo.data, &[0x89, b'P', b'N', b'G']);
+    let other = media
+        .iter()
+        .find(|v| v.path == "Pictures/other.jpg")
+        .unwrap();
+    assert_eq!(other.media_type, "image/jpeg");
+    let object = media
+        .iter()
+        .find(|v| v.path == "Object 1/content.xml")
+        .unwrap();
+    assert_eq!(object.media_type, "text/xml");
+
+    // manifest entries survive
+    let manifest_xml = manifest(write_ods_buf(&mut wb.clone(), Vec::new())?)?;
+    assert!(manifest_xml.contains("Pictures/logo.png"));
+    assert!(manifest_xml.contains("Object 1/"));
+    assert_eq!(manifest_xml.matches("\"content.xml\"").count(), 1);
+
+    assert!(wb.remove_media("Pictures/logo.png"));
+    assert!(wb.remove_media("Object 1/"));
+    assert!(!wb.remove_media("Pictures/logo.png"));
+    assert_eq!(wb.media().len(), 1);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.media().len(), 1);
+    let manifest_xml = manifest(buf)?;
+    assert!(!manifest_xml.contains("Pictures/logo.png"));
+    assert!(!manifest_xml.contains("Object 1/"));
+
+    Ok(())
+}
+
+#[test]
+fn test_embedded_object() -> Result<(), OdsError> {
+    let mut wb = read_ods_buf(&ods_with_media()?)?;
+
+    let frame = XmlTag::new("draw:frame")
+        .attr("svg:width", "2cm")
+        .attr("svg:height", "1cm")
+        .tag(
+            XmlTag::new("draw:object")
+                .attr("xlink:href", "./Object 1")
+                .attr("xlink:type", "simple"),
+        )
+        .tag(XmlTag::new("draw:image").attr("xlink:href", "./Pictures/logo.png"));
+    let mut cell = CellContent::new();
+    cell.shapes.push(frame);
+    wb.sheet_mut(0).add_cell(30, 1, cell);
+    wb.sheet_mut(0).set_value(31, 1, "below");
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let shapes = wb.sheet(0).shapes(30, 1).unwrap();
+    assert_eq!(shapes.len(), 1);
+    assert_eq!(shapes[0].name(), "draw:frame");
+    assert_eq!(shapes[0].content().len(), 2);
+    assert_eq!(wb.sheet(0).value(31, 1).as_str_or(""), "below");
+    assert!(wb.media().iter().any(|v| v.path == "Object 1/content.xml"));
+    assert!(manifest(buf)?.contains("Object 1/"));
+
+    Ok(())
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_write_media() -> Result<(), OdsError> {
+    let mut wb = read_ods_buf(&ods_with_media()?)?;
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+
+    let mut zip = ZipArchive::new(Cursor::new(buf.clone()))?;
+    let names = zip.file_names().map(|v| v.to_string()).collect::<Vec<_>>();
+    for name in [
+        "mimetype",
+        "Pictures/logo.png",
+        "Object 1/content.xml",
+        "settings.xml",
+        "styles.xml",
+        "content.xml",
+    ] {
+        assert!(names.iter().any(|v| v == name), "{}", name);
+    }
+    let mut logo = Vec::new();
+    zip.by_name("Pictures/logo.png")?.read_to_end(&mut logo)?;
+    assert_eq!(logo, [0x89, b'P', b'N', b'G']);
+
+    let wb2 = read_ods_buf(&buf)?;
+    assert_eq!(wb2.media().len(), 3);
+    assert_eq!(wb2.num_sheets(), wb.num_sheets());
+    assert_eq!(wb2.sheet(0).iter().count(), wb.sheet(0).iter().count());
+
+    Ok(())
+}
+
+#[test]
+fn test_append_media() -> Result<(), OdsError> {
+    fn book() -> Result<WorkBook, OdsError> {
+        let mut wb = read_ods_buf(&ods_with_media()?)?;
+        let frame = XmlTag::new("draw:frame")
+            .tag(XmlTag::new("draw:object").attr("xlink:href", "./Object 1"))
+            .tag(XmlTag::new("draw:image").attr("xlink:href", "Pictures/logo.png"));
+        let mut cell = CellContent::new();
+        cell.shapes.push(frame);
+        wb.sheet_mut(0).add_cell(30, 1, cell);
+        Ok(wb)
+    }
+
+    let mut wb = book()?;
+    let n = wb.num_sheets();
+    wb.append(book()?);
+
+    // Files with the same name are renamed.
+    let media = wb.media();
+    assert_eq!(media.len(), 6);
+    for path in [
+        "Pictures/logo.png",
+        "Pictures/logo_2.png",
+        "Pictures/other_2.jpg",
+        "Object 1_2/content.xml",
+    ] {
+        assert!(media.iter().any(|v| v.path == path), "{}", path);
+    }
+
+    let frame = wb.sheet(n).shapes(30, 1).unwrap()[0].to_string();
+    assert!(frame.contains("\"./Object 1_2\""), "{}", frame);
+    assert!(frame.contains("\"Pictures/logo_2.png\""), "{}", frame);
+    let frame = wb.sheet(0).shapes(30, 1).unwrap()[0].to_string();
+    assert!(frame.contains("\"./Object 1\""), "{}", frame);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let manifest_xml = manifest(buf.clone())?;
+    assert!(manifest_xml.contains("\"Pictures/logo_2.png\""));
+    assert!(manifest_xml.contains("\"Object 1_2/\""));
+    assert_eq!(read_ods_buf(&buf)?.media().len(), 6);
+
+    Ok(())
+}
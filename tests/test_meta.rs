@@ -0,0 +1,88 @@
+use spreadsheet_ods::meta::{CONTENT_HASH, GENERATOR};
+use spreadsheet_ods::text::TextP;
+use spreadsheet_ods::{read_ods, read_ods_buf, write_ods_buf, OdsError, Sheet, Value, WorkBook};
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+fn meta_xml(buf: &[u8]) -> Result<String, OdsError> {
+    let mut zip = ZipArchive::new(Cursor::new(buf))?;
+    let mut meta = String::new();
+    zip.by_name("meta.xml")?.read_to_string(&mut meta)?;
+    Ok(meta)
+}
+
+#[test]
+fn test_user_meta() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+    wb.set_user_meta("build", "42");
+    wb.set_user_meta("source", "a < b & c");
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.user_meta("build"), Some("42"));
+    assert_eq!(wb.user_meta("source"), Some("a < b & c"));
+    assert_eq!(wb.user_meta(CONTENT_HASH), None);
+
+    assert_eq!(wb.remove_user_meta("build"), Some("42".to_string()));
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.user_meta_fields().len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_meta_stamp() -> Result<(), OdsError> {
+    let mut wb = read_ods("tests/rw.ods")?;
+    assert!(!wb.is_stamp_current());
+    let hash = wb.content_hash();
+    assert_eq!(hash, wb.clone().content_hash());
+
+    wb.set_meta_stamp(true);
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+
+    // The rest of the original meta.xml is kept.
+    let meta = meta_xml(&buf)?;
+    assert!(meta.contains("<meta:initial-creator>Thomas Scharler</meta:initial-creator>"));
+    assert!(meta.contains(CONTENT_HASH));
+
+    let mut wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.user_meta(CONTENT_HASH), Some(hash.as_str()));
+    assert!(wb
+        .user_meta(GENERATOR)
+        .unwrap()
+        .starts_with("spreadsheet-ods "));
+    assert!(wb.is_stamp_current());
+
+    wb.sheet_mut(0).set_formula(0, 0, "of:=1+1");
+    assert!(!wb.is_stamp_current());
+
+    Ok(())
+}
+
+#[test]
+fn test_content_hash_values() {
+    let hash = |value: Value| {
+        let mut wb = WorkBook::new_empty();
+        let mut sheet = Sheet::new("1");
+        sheet.set_value(0, 0, value);
+        wb.push_sheet(sheet);
+        wb.content_hash()
+    };
+
+    // Formatted text hashes as its plain text.
+    let p1 = TextP::new().text("a").into_xmltag();
+    let p2 = TextP::new().text("b").into_xmltag();
+    assert_eq!(
+        hash(Value::TextXml(vec![p1, p2])),
+        hash(Value::from("a\nb"))
+    );
+    // The value type counts.
+    assert_ne!(hash(Value::from(1.0)), hash(Value::Percentage(1.0)));
+    assert_ne!(hash(Value::from("1")), hash(Value::from(1.0)));
+    assert_ne!(
+        hash(Value::new_currency("EUR", 1.0)),
+        hash(Value::new_currency("USD", 1.0))
+    );
+}
@@ -0,0 +1,105 @@
+use spreadsheet_ods::{read_ods_buf, write_ods_buf, CellRange, CellRef, OdsError, Sheet, WorkBook};
+
+#[test]
+fn test_scopes() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.add_named_range("total", CellRange::remote("a", 0, 0, 0, 0).absolute());
+    wb.add_named_range("items", CellRange::remote("a", 1, 0, 9, 0).absolute());
+
+    let mut sh = Sheet::new("b");
+    sh.add_named_range("Total", CellRange::local(0, 1, 0, 1).absolute());
+    wb.push_sheet(Sheet::new("a"));
+    wb.push_sheet(sh);
+
+    assert_eq!(wb.named_ranges().len(), 2);
+    assert_eq!(wb.sheet(1).named_ranges().len(), 1);
+
+    let v = wb.resolve_name(0, "TOTAL").expect("name");
+    assert_eq!(v.range(), &CellRange::remote("a", 0, 0, 0, 0).absolute());
+    let v = wb.resolve_name(1, "total").expect("name");
+    assert_eq!(v.name(), "Total");
+    assert_eq!(v.range(), &CellRange::remote("b", 0, 1, 0, 1).absolute());
+    assert_eq!(v.base_cell(), &CellRef::remote("b", 0, 1).absolute());
+    let v = wb.resolve_name(1, "items").expect("name");
+    assert_eq!(v.range().table().map(|v| v.as_str()), Some("a"));
+    assert!(wb.resolve_name(1, "none").is_none());
+
+    // The names of the sheet stay with the sheet.
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.named_ranges().len(), 2);
+    assert_eq!(wb.sheet(0).named_ranges().len(), 0);
+    assert_eq!(wb.sheet(1).named_ranges().len(), 1);
+    let v = wb.resolve_name(1, "total").expect("name");
+    assert_eq!(v.range(), &CellRange::remote("b", 0, 1, 0, 1).absolute());
+
+    // Replace and remove.
+    wb.sheet_mut(1)
+        .add_named_range("TOTAL", CellRange::local(5, 5, 5, 5));
+    assert_eq!(wb.sheet(1).named_ranges().len(), 1);
+    assert_eq!(wb.resolve_name(1, "total").expect("name").name(), "TOTAL");
+    assert!(wb.sheet_mut(1).remove_named_range("total"));
+    assert!(!wb.sheet_mut(1).remove_named_range("total"));
+    assert_eq!(
+        wb.resolve_name(1, "total").expect("name").range().table(),
+        Some(&"a".to_string())
+    );
+    assert!(wb.remove_named_range("total"));
+    assert!(wb.resolve_name(1, "total").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_rename() {
+    let mut wb = WorkBook::new_empty();
+    wb.add_named_range("items", CellRange::remote("a", 1, 0, 9, 0).absolute());
+    wb.push_sheet(Sheet::new("a"));
+
+    wb.rename_sheet(0, "x").expect("rename");
+    let v = wb.named_range("items").expect("name");
+    assert_eq!(v.range().table().map(|v| v.as_str()), Some("x"));
+}
+
+#[test]
+fn test_append() {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("a"));
+    wb.add_named_range("total", CellRange::remote("a", 0, 0, 0, 0).absolute());
+    wb.add_named_range("shared", CellRange::remote("x", 0, 0, 0, 0).absolute());
+
+    let mut other = WorkBook::new_empty();
+    let mut sh = Sheet::new("b");
+    sh.set_formula(0, 1, "of:=SUM(Total)*2");
+    other.push_sheet(sh);
+    let mut sh = Sheet::new("c");
+    sh.add_named_range("total", CellRange::local(5, 5, 5, 5));
+    sh.set_formula(0, 1, "of:=total");
+    other.push_sheet(sh);
+    other.add_named_range("TOTAL", CellRange::remote("b", 0, 0, 0, 0).absolute());
+    other.add_named_range("shared", CellRange::remote("x", 0, 0, 0, 0).absolute());
+    other.add_named_range("items", CellRange::remote("b", 1, 0, 9, 0).absolute());
+
+    wb.append(other);
+
+    // Identical names exist only once, the others get a new name.
+    assert_eq!(wb.named_ranges().len(), 4);
+    assert_eq!(
+        wb.named_range("total").expect("name").range(),
+        &CellRange::remote("a", 0, 0, 0, 0).absolute()
+    );
+    assert_eq!(
+        wb.named_range("TOTAL_2").expect("name").range(),
+        &CellRange::remote("b", 0, 0, 0, 0).absolute()
+    );
+    assert!(wb.named_range("items").is_some());
+    assert_eq!(
+        wb.sheet(1).formula(0, 1).map(String::as_str),
+        Some("of:=SUM(TOTAL_2)*2")
+    );
+    // The name of the sheet hides the one of the workbook.
+    assert_eq!(
+        wb.sheet(2).formula(0, 1).map(String::as_str),
+        Some("of:=total")
+    );
+}
@@ -1,9 +1,9 @@
 use color::Rgb;
 
-use spreadsheet_ods::style::units::Length;
+use spreadsheet_ods::style::units::{Length, Margin};
 use spreadsheet_ods::style::{MasterPage, PageStyle, TableStyle};
 use spreadsheet_ods::xmltree::XmlVec;
-use spreadsheet_ods::{cm, read_ods, write_ods, OdsError, Sheet, WorkBook};
+use spreadsheet_ods::{cm, read_ods, write_ods, OdsError, Sheet, Visibility, WorkBook};
 
 #[test]
 fn test_pagelayout() -> Result<(), OdsError> {
@@ -60,3 +60,50 @@ fn test_crpagelayout() -> Result<(), OdsError> {
 
     Ok(())
 }
+
+#[test]
+fn test_page_breaks() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut ps = PageStyle::new("ps1");
+    ps.set_page_width(cm!(10));
+    ps.set_page_height(cm!(10));
+    ps.set_margin(Margin::Length(cm!(1)));
+    let ps = wb.add_pagestyle(ps);
+
+    let mut mp = MasterPage::new("mp1");
+    mp.set_pagestyle(&ps);
+    let mp = wb.add_masterpage(mp);
+
+    let mut ts = TableStyle::new("ts1");
+    ts.set_master_page(&mp);
+    let ts = wb.add_tablestyle(ts);
+
+    let mut sheet = Sheet::new("1");
+    sheet.set_style(&ts);
+    for col in 0..10 {
+        sheet.set_col_width(col, cm!(2));
+    }
+    for row in 0..20 {
+        sheet.set_row_height(row, cm!(1));
+    }
+    sheet.set_value(19, 9, "last");
+    sheet.set_header_rows(0, 0);
+    wb.push_sheet(sheet);
+
+    let pages = wb.page_breaks(0)?;
+    assert_eq!(pages.col_breaks(), &vec![4, 8]);
+    assert_eq!(pages.row_breaks(), &vec![8, 15]);
+    assert_eq!(pages.pages(), 9);
+
+    wb.sheet_mut(0).set_col_visible(1, Visibility::Collapsed);
+    let pages = wb.page_breaks(0)?;
+    assert_eq!(pages.col_breaks(), &vec![5, 9]);
+
+    // no page style at all
+    let pages = spreadsheet_ods::pagination::page_breaks(wb.sheet(0), None)?;
+    assert_eq!(pages.col_breaks(), &vec![9]);
+    assert_eq!(pages.pages_tall(), 1);
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "proptest")]
+
+use proptest::prelude::*;
+use spreadsheet_ods::test_util::{arb_workbook, assert_roundtrip, compare_workbooks};
+use spreadsheet_ods::{Sheet, WorkBook};
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn test_roundtrip(mut wb in arb_workbook()) {
+        assert_roundtrip(&mut wb);
+    }
+}
+
+#[test]
+fn test_compare() {
+    let mut wb0 = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, "text");
+    sh.set_value(1, 1, 0.0);
+    wb0.push_sheet(sh);
+
+    let mut wb1 = WorkBook::new_empty();
+    let mut sh = Sheet::new("one");
+    sh.set_value(0, 0, "text");
+    sh.set_value(1, 1, -0.0);
+    sh.set_value(2, 2, "");
+    wb1.push_sheet(sh);
+
+    assert!(compare_workbooks(&wb0, &wb1).is_ok());
+
+    wb1.sheet_mut(0).set_value(1, 1, 1.0);
+    assert!(compare_workbooks(&wb0, &wb1).is_err());
+}
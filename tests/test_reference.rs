@@ -0,0 +1,27 @@
+#![cfg(feature = "test_util")]
+
+use spreadsheet_ods::read_ods;
+use spreadsheet_ods::test_util::{assert_roundtrip, reference_workbooks, write_reference_files};
+use std::fs;
+
+#[test]
+fn test_reference_roundtrip() {
+    for (name, mut wb) in reference_workbooks() {
+        assert!(wb.num_sheets() > 0, "{}", name);
+        assert_eq!(wb.sheet(0).name(), name);
+        assert_roundtrip(&mut wb);
+    }
+}
+
+#[test]
+fn test_reference_files() {
+    let dir = "test_out/reference";
+    fs::create_dir_all(dir).expect("create dir");
+
+    let files = write_reference_files(dir).expect("write");
+    assert_eq!(files.len(), reference_workbooks().len());
+    for path in files {
+        let wb = read_ods(&path).expect("read");
+        assert_eq!(wb.sheet(0).value(0, 0).as_str_or(""), "expected");
+    }
+}
@@ -0,0 +1,382 @@
+use spreadsheet_ods::condition::Condition;
+use spreadsheet_ods::security::SecurityPolicy;
+use spreadsheet_ods::text::{TextA, TextP};
+use spreadsheet_ods::validation::Validation;
+use spreadsheet_ods::xmltree::{XmlContent, XmlTag};
+use spreadsheet_ods::{read_ods_buf, write_ods_buf, CellContent, OdsError, Sheet, Value, WorkBook};
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+mod common;
+use common::{add_to_manifest, insert_in_table, rewrite_ods};
+
+const SCRIPTS: &str = r#"<office:scripts><office:script script:language="ooo:Basic"><ooo:libraries/></office:script><office:event-listeners>
+<script:event-listener script:language="ooo:script" script:event-name="dom:load" xlink:href="vnd.sun.star.script:Standard.Module1.Main?language=Basic&amp;location=document" xlink:type="simple"/>
+</office:event-listeners></office:scripts>"#;
+
+const TABLE: &str = r#"<table:table-source xlink:type="simple" xlink:href="file:///tmp/other.ods" table:table-name="Sheet1"/>
+<office:dde-source office:dde-application="soffice" office:dde-topic="file:///tmp/x.ods" office:dde-item="Sheet1.A1"/>
+<office:event-listeners><script:event-listener script:language="ooo:script" script:event-name="office:focus" xlink:href="vnd.sun.star.script:Standard.Module1.Focus?language=Basic&amp;location=document" xlink:type="simple"/></office:event-listeners>"#;
+
+const DDE_LINKS: &str = r#"<table:dde-links><table:dde-link><office:dde-source office:name="x" office:dde-application="soffice" office:dde-topic="file:///tmp/x.ods" office:dde-item="Sheet1.A1"/><table:table/></table:dde-link></table:dde-links>"#;
+
+const NAMES: &str = r#"<table:named-expressions><table:named-range table:name="keep" table:base-cell-address="$'1'.$A$1" table:cell-range-address="$'1'.$A$1"/><table:named-expression table:name="feed" table:base-cell-address="$'1'.$A$1" table:expression="of:=dde(&quot;soffice&quot;;&quot;file:///tmp/x.ods&quot;;&quot;Sheet1.A1&quot;)"/><table:named-expression table:name="web" table:base-cell-address="$'1'.$A$1" table:expression="of:=COM.MICROSOFT.WEBSERVICE(&quot;http://example.com&quot;)"/></table:named-expressions>"#;
+
+const MODULE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<script:module xmlns:script="http://openoffice.org/2000/script" script:name="Module1" script:language="StarBasic">Sub Main
+End Sub</script:module>"#;
+
+// Adds macros, events, links and DDE.
+fn ods_with_active_content(buf: Vec<u8>) -> Result<Vec<u8>, OdsError> {
+    rewrite_ods(
+        buf,
+        |name, xml| match name {
+            "content.xml" => {
+                let xml = xml.replace("<office:scripts/>", SCRIPTS).replace(
+                    "</office:spreadsheet>",
+                    &format!("{}{}</office:spreadsheet>", NAMES, DDE_LINKS),
+                );
+                insert_in_table(&xml, TABLE)
+            }
+            "META-INF/manifest.xml" => add_to_manifest(
+                &xml,
+                r#"<manifest:file-entry manifest:full-path="Basic/Standard/Module1.xml" manifest:media-type="text/xml"/>
+"#,
+            ),
+            _ => xml,
+        },
+        &[("Basic/Standard/Module1.xml", MODULE.as_bytes())],
+    )
+}
+
+fn sample() -> Result<WorkBook, OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sheet = Sheet::new("1");
+    sheet.set_value(0, 0, 1);
+    sheet.set_formula(0, 0, "of:=['file:///tmp/other.ods'#$Sheet1.A1]");
+    sheet.set_value(1, 0, 2);
+    sheet.set_formula(
+        1,
+        0,
+        "of:=DDE(\"soffice\";\"file:///tmp/x.ods\";\"Sheet1.A1\")",
+    );
+    sheet.set_value(2, 0, 3);
+    sheet.set_formula(2, 0, "of:=[.A1]+[.A2]");
+
+    let mut cell = CellContent::new();
+    cell.shapes.push(
+        XmlTag::new("draw:frame")
+            .tag(XmlTag::new("draw:image").attr("xlink:href", "http://example.com/tracker.png")),
+    );
+    cell.shapes.push(
+        XmlTag::new("draw:frame")
+            .tag(XmlTag::new("draw:image").attr("xlink:href", "./Pictures/local.png")),
+    );
+    sheet.add_cell(3, 0, cell);
+    wb.push_sheet(sheet);
+
+    read_ods_buf(&ods_with_active_content(write_ods_buf(
+        &mut wb,
+        Vec::new(),
+    )?)?)
+}
+
+#[test]
+fn test_sanitize() -> Result<(), OdsError> {
+    let wb = sample()?;
+    assert_eq!(wb.event_listeners().len(), 1);
+    assert_eq!(wb.sheet(0).event_listeners().len(), 1);
+    assert_eq!(wb.media().len(), 0);
+
+    let mut wb = wb.clone();
+    wb.sanitize(&SecurityPolicy::default());
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    assert!(wb.event_listeners().is_empty());
+    assert!(wb.sheet(0).event_listeners().is_empty());
+    let sheet = wb.sheet(0);
+    assert_eq!(sheet.formula(0, 0), None);
+    assert_eq!(sheet.value(0, 0).as_f64_or(0.0), 1.0);
+    assert_eq!(sheet.formula(1, 0), None);
+    assert_eq!(sheet.formula(2, 0).unwrap(), "of:=[.A1]+[.A2]");
+    assert_eq!(sheet.shapes(3, 0).unwrap().len(), 1);
+
+    let mut zip = ZipArchive::new(Cursor::new(buf))?;
+    assert!(zip.by_name("Basic/Standard/Module1.xml").is_err());
+    let mut content = String::new();
+    zip.by_name("content.xml")?.read_to_string(&mut content)?;
+    assert!(!content.contains("office:script "));
+    assert!(!content.contains("table:table-source"));
+    assert!(!content.contains("dde"));
+    let mut manifest = String::new();
+    zip.by_name("META-INF/manifest.xml")?
+        .read_to_string(&mut manifest)?;
+    assert!(!manifest.contains("Basic/"));
+
+    Ok(())
+}
+
+#[test]
+fn test_sanitize_partial() -> Result<(), OdsError> {
+    let mut wb = sample()?;
+    wb.sanitize(&SecurityPolicy {
+        remove_event_listeners: false,
+        remove_dde: false,
+        ..Default::default()
+    });
+
+    assert_eq!(wb.event_listeners().len(), 1);
+    assert_eq!(wb.sheet(0).event_listeners().len(), 1);
+    assert_eq!(wb.sheet(0).formula(0, 0), None);
+    assert!(wb.sheet(0).formula(1, 0).is_some());
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut zip = ZipArchive::new(Cursor::new(buf))?;
+    let mut content = String::new();
+    zip.by_name("content.xml")?.read_to_string(&mut content)?;
+    assert!(content.contains("\"feed\""));
+    assert!(!content.contains("\"web\""));
+
+    Ok(())
+}
+
+#[test]
+fn test_sanitize_function_case() {
+    let mut wb = WorkBook::new_empty();
+    let mut sheet = Sheet::new("1");
+    sheet.set_formula(
+        0,
+        0,
+        "of:=dde(\"soffice\";\"file:///tmp/x.ods\";\"Sheet1.A1\")",
+    );
+    sheet.set_formula(
+        1,
+        0,
+        "of:=Dde (\"soffice\";\"file:///tmp/x.ods\";\"Sheet1.A1\")",
+    );
+    sheet.set_formula(2, 0, "of:=webservice(\"http://example.com\")");
+    sheet.set_formula(3, 0, "of:=COM.MICROSOFT.WEBSERVICE(\"http://example.com\")");
+    sheet.set_formula(4, 0, "of:=['FILE:///tmp/other.ods'#$Sheet1.A1]");
+    // Only looks like it.
+    sheet.set_formula(5, 0, "of:=\"DDE(\"&\"WEBSERVICE(\"&\"'#\"");
+    wb.push_sheet(sheet);
+
+    wb.sanitize(&SecurityPolicy::default());
+
+    let sheet = wb.sheet(0);
+    for row in 0..5 {
+        assert_eq!(sheet.formula(row, 0), None, "row {}", row);
+    }
+    assert!(sheet.formula(5, 0).is_some());
+}
+
+#[test]
+fn test_sanitize_script_links() {
+    let script = "VND.SUN.STAR.SCRIPT:Standard.Module1.Main?language=Basic&location=document";
+
+    let mut wb = WorkBook::new_empty();
+    let mut sheet = Sheet::new("1");
+    sheet.set_value(
+        0,
+        0,
+        TextP::new()
+            .text("run ")
+            .tag(TextA::new().href(script).text("this"))
+            .text(" or ")
+            .tag(TextA::new().href("http://example.com").text("that"))
+            .into_xmltag(),
+    );
+    let mut cell = CellContent::new();
+    cell.shapes.push(
+        XmlTag::new("draw:a").attr("xlink:href", script).tag(
+            XmlTag::new("draw:frame")
+                .tag(XmlTag::new("draw:image").attr("xlink:href", "./Pictures/local.png")),
+        ),
+    );
+    sheet.add_cell(1, 0, cell);
+    wb.push_sheet(sheet);
+
+    wb.sanitize(&SecurityPolicy::default());
+
+    let sheet = wb.sheet(0);
+    match sheet.value(0, 0) {
+        Value::TextXml(text) => {
+            assert_eq!(text.len(), 1);
+            let mut buf = String::new();
+            text[0].extract_text(&mut buf);
+            assert_eq!(buf, "run this or that");
+            let links: Vec<_> = text[0]
+                .content()
+                .iter()
+                .filter(|v| matches!(v, XmlContent::Tag(t) if t.name() == "text:a"))
+                .collect();
+            assert_eq!(links.len(), 1);
+        }
+        v => panic!("{:?}", v),
+    }
+    let shapes = sheet.shapes(1, 0).unwrap();
+    assert_eq!(shapes.len(), 1);
+    assert_eq!(shapes[0].name(), "draw:frame");
+}
+
+#[test]
+fn test_sanitize_names() -> Result<(), OdsError> {
+    let mut wb = sample()?;
+    wb.sanitize(&SecurityPolicy::default());
+
+    assert!(wb.named_range("keep").is_some());
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut zip = ZipArchive::new(Cursor::new(buf))?;
+    let mut content = String::new();
+    zip.by_name("content.xml")?.read_to_string(&mut content)?;
+    assert!(content.contains("\"keep\""));
+    assert!(!content.contains("\"feed\""));
+    assert!(!content.contains("\"web\""));
+
+    Ok(())
+}
+
+#[test]
+fn test_sanitize_validations() {
+    let mut wb = WorkBook::new_empty();
+    let mut sheet = Sheet::new("1");
+
+    let mut valid = Validation::new();
+    valid.set_condition(Condition::is_true_formula(
+        "webservice(\"http://example.com\")<>\"\"",
+    ));
+    let web = wb.add_validation(valid);
+    let mut valid = Validation::new();
+    valid.set_condition(Condition::is_true_formula(
+        "['file:///tmp/other.ods'#$Sheet1.A1]>0",
+    ));
+    let external = wb.add_validation(valid);
+    let mut valid = Validation::new();
+    valid.set_condition(Condition::content_text_length_lt(10));
+    let length = wb.add_validation(valid);
+
+    sheet.set_value(0, 0, 1);
+    sheet.set_validation(0, 0, &web);
+    sheet.set_value(1, 0, 1);
+    sheet.set_validation(1, 0, &external);
+    sheet.set_value(2, 0, 1);
+    sheet.set_validation(2, 0, &length);
+    wb.push_sheet(sheet);
+
+    wb.sanitize(&SecurityPolicy::default());
+
+    assert!(wb.validation(web.as_str()).is_none());
+    assert!(wb.validation(external.as_str()).is_none());
+    assert!(wb.validation(length.as_str()).is_some());
+    let sheet = wb.sheet(0);
+    assert_eq!(sheet.validation(0, 0), None);
+    assert_eq!(sheet.validation(1, 0), None);
+    assert_eq!(sheet.validation(2, 0), Some(&length.to_string()));
+}
+
+#[test]
+fn test_sanitize_hrefs() {
+    let mut wb = WorkBook::new_empty();
+    let mut sheet = Sheet::new("1");
+    let mut cell = CellContent::new();
+    for href in [
+        "./Pictures/local.png",
+        "Pictures/local.png",
+        "../logo.png",
+        "Pictures/../../logo.png",
+        "/srv/logo.png",
+        "\\\\server\\share\\logo.png",
+        "C:\\logo.png",
+        "file:///srv/logo.png",
+    ] {
+        cell.shapes.push(
+            XmlTag::new("draw:frame").tag(XmlTag::new("draw:image").attr("xlink:href", href)),
+        );
+    }
+    sheet.add_cell(0, 0, cell);
+    wb.push_sheet(sheet);
+
+    wb.sanitize(&SecurityPolicy::default());
+
+    // Only the files inside the package are kept.
+    assert_eq!(wb.sheet(0).shapes(0, 0).unwrap().len(), 2);
+}
+
+const DATABASE: &str = r#"<table:database-ranges><table:database-range table:name="import" table:target-range-address="'1'.A1:'1'.B2"><table:database-source-sql table:database-name="Bibliography" table:sql-statement="select * from biblio"/></table:database-range><table:database-range table:name="local" table:target-range-address="'1'.A1:'1'.B2"/></table:database-ranges>
+<table:data-pilot-tables><table:data-pilot-table table:name="remote" table:target-range-address="'1'.D1:'1'.E2"><table:database-source-table table:database-name="Bibliography" table:database-table-name="biblio"/></table:data-pilot-table><table:data-pilot-table table:name="linked" table:target-range-address="'1'.D4:'1'.E5"><table:source-cell-range table:cell-range-address="'file:///tmp/x.ods'#$Sheet1.A1:.B2"/></table:data-pilot-table><table:data-pilot-table table:name="pivot" table:target-range-address="'1'.G1:'1'.H2"><table:source-cell-range table:cell-range-address="'1'.A1:'1'.B2"/></table:data-pilot-table></table:data-pilot-tables>"#;
+
+#[test]
+fn test_sanitize_database_sources() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+    let buf = rewrite_ods(
+        write_ods_buf(&mut wb, Vec::new())?,
+        |name, xml| match name {
+            "content.xml" => xml.replace(
+                "</office:spreadsheet>",
+                &format!("{}</office:spreadsheet>", DATABASE),
+            ),
+            _ => xml,
+        },
+        &[],
+    )?;
+    let mut wb = read_ods_buf(&buf)?;
+
+    wb.sanitize(&SecurityPolicy::default());
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut zip = ZipArchive::new(Cursor::new(buf))?;
+    let mut content = String::new();
+    zip.by_name("content.xml")?.read_to_string(&mut content)?;
+    // The database range stays, only the import is removed.
+    assert!(content.contains("\"import\""));
+    assert!(content.contains("\"local\""));
+    assert!(!content.contains("table:database-source"));
+    assert!(!content.contains("\"remote\""));
+    assert!(!content.contains("\"linked\""));
+    assert!(content.contains("\"pivot\""));
+
+    Ok(())
+}
+
+#[test]
+fn test_sanitize_link_events() {
+    let mut wb = WorkBook::new_empty();
+    let mut sheet = Sheet::new("1");
+    sheet.set_value(
+        0,
+        0,
+        TextP::new()
+            .tag(
+                TextA::new()
+                    .href("http://example.com")
+                    .tag(
+                        XmlTag::new("office:event-listeners").tag(
+                            XmlTag::new("script:event-listener")
+                                .attr("script:language", "ooo:script")
+                                .attr("script:event-name", "dom:click")
+                                .attr(
+                                    "xlink:href",
+                                    "vnd.sun.star.script:Standard.Module1.Main?language=Basic&location=document",
+                                ),
+                        ),
+                    )
+                    .text("link"),
+            )
+            .into_xmltag(),
+    );
+    wb.push_sheet(sheet);
+
+    wb.sanitize(&SecurityPolicy::default());
+
+    match wb.sheet(0).value(0, 0) {
+        Value::TextXml(text) => {
+            let buf = text[0].to_string();
+            assert!(!buf.contains("office:event-listeners"), "{}", buf);
+            assert!(buf.contains("http://example.com"), "{}", buf);
+        }
+        v => panic!("{:?}", v),
+    }
+}
@@ -1,6 +1,11 @@
+use chrono::{FixedOffset, NaiveDate, TimeZone, Utc};
+use spreadsheet_ods::fill::Series;
+use spreadsheet_ods::style::CellStyle;
+use spreadsheet_ods::xmltree::XmlTag;
 use spreadsheet_ods::{
-    cm, currency, percent, read_ods, write_ods, CellRange, ColRange, Length, OdsError, RowRange,
-    Sheet, Value, ValueType, WorkBook,
+    cm, currency, percent, read_ods, read_ods_buf, read_ods_content, write_ods, write_ods_buf,
+    CellContent, CellRange, CellStyleRef, ColRange, ContentType, Length, OdsError, RowRange, Sheet,
+    SplitMode, TzPolicy, Value, ValueType, WorkBook,
 };
 
 #[test]
@@ -40,6 +45,88 @@ fn test_cell() {
     // assert_eq!(x.value().as_f64_or(0.0), 3.0);
 }
 
+#[test]
+fn test_cached_value() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 21);
+    sh.set_formula_value(0, 1, "of:=[.A1]*2", 42);
+    sh.set_formula(0, 2, "of:=[.A1]*3");
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+
+    assert!(sh.cached_value(0, 0).is_none());
+    assert_eq!(sh.formula(0, 1).map(|v| v.as_str()), Some("of:=[.A1]*2"));
+    assert_eq!(sh.cached_value(0, 1).and_then(|v| v.as_i32_opt()), Some(42));
+    assert!(matches!(sh.cached_value(0, 2), Some(Value::Empty)));
+
+    let cell = sh.cell(0, 1).expect("cell");
+    assert_eq!(cell.cached_value().and_then(|v| v.as_i32_opt()), Some(42));
+
+    Ok(())
+}
+
+#[test]
+fn test_content_type() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "007");
+    sh.set_content_type(0, 0, ContentType::String);
+    sh.set_value(0, 1, "#DIV/0!");
+    sh.set_formula(0, 1, "of:=1/0");
+    sh.set_content_type(0, 1, ContentType::Error);
+    // Doesn't match the value.
+    sh.set_value(0, 2, 7);
+    sh.set_content_type(0, 2, ContentType::String);
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+
+    assert_eq!(sh.value(0, 0).as_str_or(""), "007");
+    assert_eq!(sh.content_type(0, 0), Some(ContentType::String));
+    assert_eq!(sh.content_type(0, 1), Some(ContentType::Error));
+    assert_eq!(sh.content_type(0, 2), None);
+    assert_eq!(
+        sh.cell(0, 1).and_then(|v| v.content_type()),
+        Some(ContentType::Error)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_col_values_as() {
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 1, "amount");
+    sh.set_value(1, 1, 1.5);
+    sh.set_value(2, 1, currency!("EUR", 2));
+    sh.set_value(3, 1, "n/a");
+    sh.set_value(5, 1, 4);
+    sh.set_value(6, 0, "other column");
+
+    let values = sh.col_values_as::<f64>(1, &RowRange::new(1, 100));
+    assert_eq!(values.len(), 6);
+    assert_eq!(values[0].as_ref().ok(), Some(&Some(1.5)));
+    assert_eq!(values[1].as_ref().ok(), Some(&Some(2.0)));
+    assert!(values[2].is_err());
+    assert!(values[2].as_ref().unwrap_err().to_string().contains("B4"));
+    assert_eq!(values[3].as_ref().ok(), Some(&None));
+    assert_eq!(values[4].as_ref().ok(), Some(&Some(4.0)));
+    assert_eq!(values[5].as_ref().ok(), Some(&None));
+
+    let names = sh.col_values_as::<String>(1, &RowRange::new(0, 0));
+    assert_eq!(names[0].as_ref().ok(), Some(&Some("amount".to_string())));
+
+    assert!(sh.col_values_as::<f64>(1, &RowRange::new(7, 10)).is_empty());
+}
+
 #[test]
 fn test_row_repeat() -> Result<(), OdsError> {
     let mut wb = WorkBook::new_empty();
@@ -76,6 +163,61 @@ fn test_percentage() {
 
     sh.set_value(0, 0, percent!(17.22));
     assert_eq!(sh.value(0, 0).value_type(), ValueType::Percentage);
+
+    let v = Value::percent_from_fraction(0.15);
+    assert_eq!(v.as_fraction(), Some(0.15));
+    assert_eq!(v.as_percent(), Some(15.0));
+    let v = Value::percent_from_percent(15.0);
+    assert_eq!(v.as_fraction(), Some(0.15));
+    assert_eq!(v.as_percent(), Some(15.0));
+    assert_eq!(Value::Number(0.15).as_fraction(), None);
+    assert_eq!(Value::Number(0.15).as_percent(), None);
+}
+
+#[test]
+fn test_datetime_interop() {
+    let dt = NaiveDate::from_ymd_opt(2022, 10, 1)
+        .unwrap()
+        .and_hms_opt(12, 30, 0)
+        .unwrap();
+    let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+    let dt_tz = tz.from_local_datetime(&dt).unwrap();
+
+    assert_eq!(
+        Value::from(dt_tz).as_datetime_opt(),
+        Some(dt - chrono::Duration::hours(2))
+    );
+    assert_eq!(
+        Value::new_datetime_tz(&dt_tz, TzPolicy::Local).as_datetime_opt(),
+        Some(dt)
+    );
+    assert_eq!(
+        Value::from(dt_tz.with_timezone(&Utc)).as_datetime_opt(),
+        Some(dt - chrono::Duration::hours(2))
+    );
+
+    #[cfg(feature = "use_time")]
+    {
+        let t = time::PrimitiveDateTime::new(
+            time::Date::from_calendar_date(2022, time::Month::October, 1).unwrap(),
+            time::Time::from_hms(12, 30, 0).unwrap(),
+        );
+        assert_eq!(Value::from(t).as_datetime_opt(), Some(dt));
+        assert_eq!(
+            Value::from(t.assume_offset(time::UtcOffset::from_hms(2, 0, 0).unwrap()))
+                .as_datetime_opt(),
+            Some(dt - chrono::Duration::hours(2))
+        );
+
+        assert_eq!(
+            Value::from(time::Duration::milliseconds(1500)).as_timeduration_opt(),
+            Some(chrono::Duration::milliseconds(1500))
+        );
+    }
+    assert_eq!(
+        Value::from(std::time::Duration::from_millis(1500)).as_timeduration_opt(),
+        Some(chrono::Duration::milliseconds(1500))
+    );
 }
 
 #[test]
@@ -133,6 +275,73 @@ fn test_span() -> Result<(), OdsError> {
     Ok(())
 }
 
+#[test]
+fn test_insert_delete() {
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "span");
+    sh.set_row_span(0, 0, 3);
+    sh.set_col_span(0, 0, 2);
+    sh.set_value(2, 1, 21);
+    sh.set_validation(2, 1, &"v1".into());
+    sh.set_row_height(2, cm!(2));
+    sh.set_col_width(1, cm!(3));
+
+    sh.insert_rows(1, 2);
+    assert_eq!(sh.row_span(0, 0), 5);
+    assert_eq!(sh.value(4, 1).as_i32_or(0), 21);
+    assert_eq!(sh.validation(4, 1), Some(&"v1".to_string()));
+    assert_eq!(sh.row_height(4), cm!(2));
+    assert!(sh.is_empty(2, 1));
+
+    sh.insert_cols(1, 1);
+    assert_eq!(sh.col_span(0, 0), 3);
+    assert_eq!(sh.value(4, 2).as_i32_or(0), 21);
+    assert_eq!(sh.col_width(2), cm!(3));
+
+    sh.delete_rows(3, 2);
+    assert_eq!(sh.row_span(0, 0), 3);
+    assert!(sh.is_empty(4, 2));
+
+    sh.delete_cols(0, 1);
+    assert!(sh.is_empty(0, 0));
+
+    // A repeated row is split, cells at the end are dropped.
+    let mut sh = Sheet::new("1");
+    sh.set_row_repeat(2, 4);
+    sh.set_row_height(2, cm!(1));
+    sh.set_value(u32::MAX - 1, 0, "last");
+    sh.set_value(0, u32::MAX, "last");
+    sh.set_row_span(1, 1, u32::MAX);
+    sh.insert_rows(4, 3);
+    assert_eq!(sh.row_repeat(2), 2);
+    assert_eq!(sh.row_repeat(7), 2);
+    assert_eq!(sh.row_height(7), cm!(1));
+    assert!(sh.is_empty(u32::MAX - 1, 0));
+    assert_eq!(sh.row_span(1, 1), u32::MAX);
+    // and joined again.
+    sh.delete_rows(4, 3);
+    assert_eq!(sh.row_repeat(2), 4);
+    assert_eq!(sh.row_repeat(4), 0);
+    sh.delete_rows(1, 2);
+    assert_eq!(sh.row_repeat(1), 3);
+    assert_eq!(sh.row_height(1), cm!(1));
+    sh.insert_cols(1, 1);
+    assert!(sh.is_empty(0, u32::MAX));
+
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 1);
+    sh.set_value(0, 1, 2);
+    sh.set_validation(0, 1, &"v1".into());
+    sh.set_value(5, 5, 99);
+    sh.set_value(6, 6, 99);
+    sh.copy_range(&CellRange::local(0, 0, 1, 1), 5, 5);
+    assert_eq!(sh.value(5, 5).as_i32_or(0), 1);
+    assert_eq!(sh.value(5, 6).as_i32_or(0), 2);
+    assert_eq!(sh.validation(5, 6), Some(&"v1".to_string()));
+    assert!(sh.is_empty(6, 6));
+    assert_eq!(sh.value(0, 1).as_i32_or(0), 2);
+}
+
 #[test]
 fn test_header() -> Result<(), OdsError> {
     let mut wb = WorkBook::new_empty();
@@ -247,8 +456,45 @@ fn split_table() -> Result<(), OdsError> {
     sh.split_horizontal(250);
     wb.push_sheet(sh);
 
+    let mut sh = Sheet::new("Freeze");
+    sh.set_value(0, 0, "header");
+    sh.set_value(1, 1, 4);
+    sh.freeze_header_row();
+    sh.freeze_first_col();
+    wb.push_sheet(sh);
+
     write_ods(&mut wb, "test_out/split_table.ods")?;
 
+    let wb = read_ods("test_out/split_table.ods")?;
+    let cfg = wb.sheet(2).config();
+    assert!(matches!(cfg.vert_split_mode, SplitMode::Heading));
+    assert_eq!(cfg.vert_split_pos, 1);
+    assert!(matches!(cfg.hor_split_mode, SplitMode::Heading));
+    assert_eq!(cfg.hor_split_pos, 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_style_ref() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let st = wb.add_cellstyle(CellStyle::new_empty());
+
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, 1, &st);
+    sh.set_cellstyle(0, 1, &CellStyleRef::from(st.as_str()));
+    sh.set_row_cellstyle(1, &st);
+    assert_eq!(sh.cellstyle(0, 0), Some(&st));
+    assert_eq!(sh.cellstyle(0, 1), Some(&st));
+    assert_eq!(sh.row_cellstyle(1), Some(&st));
+    assert_eq!(sh.cell(0, 0).and_then(|v| v.style), Some(st.clone()));
+    wb.push_sheet(sh);
+
+    write_ods(&mut wb, "test_out/style_ref.ods")?;
+
+    let wb = read_ods("test_out/style_ref.ods")?;
+    assert_eq!(wb.sheet(0).cellstyle(0, 0), Some(&st));
+
     Ok(())
 }
 
@@ -272,3 +518,736 @@ fn test_iterator() {
         }
     }
 }
+
+#[test]
+fn test_retain_rows() {
+    let mut sh = Sheet::new("data");
+    sh.set_value(0, 0, "header");
+    for r in 1..10 {
+        sh.set_value(r, 0, r);
+    }
+    sh.set_value(10, 0, "span");
+    sh.set_row_span(10, 0, 3);
+    sh.set_value(12, 0, "end");
+    sh.set_formula(10, 1, "of:=SUM([.A2:.A10])+[.A3]+[data.A4]+[other.A4]");
+    sh.set_formula(10, 2, "of=[.A2]&\"[.A2]\"");
+    sh.set_row_height(4, cm!(2));
+
+    let mut sh2 = sh.clone();
+
+    // drop the odd numbers and row 11.
+    sh.retain_rows(|row, mut cells| {
+        row != 11
+            && match cells.next() {
+                Some((_, cell)) => cell.value().as_i32_or(0) % 2 == 0,
+                None => true,
+            }
+    });
+    assert_eq!(sh.value(0, 0).as_str_or(""), "header");
+    assert_eq!(sh.value(1, 0).as_i32_or(0), 2);
+    assert_eq!(sh.value(4, 0).as_i32_or(0), 8);
+    assert_eq!(sh.value(5, 0).as_str_or(""), "span");
+    assert_eq!(sh.row_span(5, 0), 2);
+    assert_eq!(sh.value(6, 0).as_str_or(""), "end");
+    assert_eq!(sh.row_height(2), cm!(2));
+    assert_eq!(
+        sh.formula(5, 1).map(String::as_str),
+        Some("of:=SUM([.A2:.A10])+[.A3]+[data.A4]+[other.A4]")
+    );
+
+    sh2.retain_rows_fix_formulas(|row, mut cells| {
+        row != 11
+            && match cells.next() {
+                Some((_, cell)) => cell.value().as_i32_or(0) % 2 == 0,
+                None => true,
+            }
+    });
+    assert_eq!(
+        sh2.formula(5, 1).map(String::as_str),
+        Some("of:=SUM([.A2:.A5])+[.A2]+#REF!+[other.A4]")
+    );
+    assert_eq!(
+        sh2.formula(5, 2).map(String::as_str),
+        Some("of=#REF!&\"[.A2]\"")
+    );
+
+    // A repeated row is shortened by the deleted rows.
+    let mut sh = Sheet::new("data");
+    sh.set_row_height(0, cm!(1));
+    sh.set_row_height(2, cm!(1));
+    sh.set_row_repeat(2, 6);
+    sh.set_row_height(8, cm!(2));
+    sh.set_value(9, 0, 1);
+    sh.retain_rows(|row, _| ![1, 2, 4, 5].contains(&row));
+    // joined with the first row.
+    assert_eq!(sh.row_repeat(0), 4);
+    assert_eq!(sh.row_height(0), cm!(1));
+    assert_eq!(sh.row_height(4), cm!(2));
+    assert_eq!(sh.value(5, 0).as_i32_or(0), 1);
+}
+
+#[test]
+fn test_move_rows_cols() {
+    let mut sh = Sheet::new("data");
+    for r in 0..6 {
+        sh.set_value(r, 0, r);
+    }
+    sh.set_row_height(4, cm!(2));
+    sh.set_formula(0, 1, "of:=[.A5]+SUM([.A1:.A3])+[other.A5]");
+
+    // 0 1 2 3 4 5 -> 0 4 5 1 2 3
+    sh.move_rows(&RowRange::new(4, 5), 1);
+    let values: Vec<_> = (0..6).map(|r| sh.value(r, 0).as_i32_or(-1)).collect();
+    assert_eq!(values, vec![0, 4, 5, 1, 2, 3]);
+    assert_eq!(sh.row_height(1), cm!(2));
+    assert_eq!(
+        sh.formula(0, 1).map(String::as_str),
+        Some("of:=[.A2]+SUM([.A1:.A5])+[other.A5]")
+    );
+
+    // and back
+    sh.move_rows(&RowRange::new(1, 2), 6);
+    let values: Vec<_> = (0..6).map(|r| sh.value(r, 0).as_i32_or(-1)).collect();
+    assert_eq!(values, vec![0, 1, 2, 3, 4, 5]);
+    assert_eq!(
+        sh.formula(0, 1).map(String::as_str),
+        Some("of:=[.A5]+SUM([.A1:.A3])+[other.A5]")
+    );
+
+    sh.set_col_width(0, cm!(3));
+    sh.move_cols(&ColRange::new(0, 0), 3);
+    assert_eq!(sh.value(1, 2).as_i32_or(-1), 1);
+    assert_eq!(sh.col_width(2), cm!(3));
+    assert_eq!(
+        sh.formula(0, 0).map(String::as_str),
+        Some("of:=[.C5]+SUM([.C1:.C3])+[other.A5]")
+    );
+
+    // A reversed range is the same as the normal one.
+    let mut sh = Sheet::new("data");
+    for r in 0..6 {
+        sh.set_value(r, 0, r);
+    }
+    let mut range = RowRange::new(0, 0);
+    range.set_row(5);
+    range.set_to_row(4);
+    sh.move_rows(&range, 1);
+    let values: Vec<_> = (0..6).map(|r| sh.value(r, 0).as_i32_or(-1)).collect();
+    assert_eq!(values, vec![0, 4, 5, 1, 2, 3]);
+    sh.move_rows(&RowRange::new(0, u32::MAX), 3);
+    assert_eq!(sh.value(1, 0).as_i32_or(-1), 4);
+
+    // Spans are cut where the rows or columns don't stay together.
+    let mut sh = Sheet::new("data");
+    sh.set_row_span(2, 0, 3);
+    sh.set_row_span(5, 1, 2);
+    sh.set_row_span(5, 2, 4);
+    sh.set_col_span(0, 2, 3);
+    sh.move_rows(&RowRange::new(5, 6), 3);
+    assert_eq!(sh.row_span(2, 0), 1);
+    assert_eq!(sh.row_span(3, 1), 2);
+    assert_eq!(sh.row_span(3, 2), 2);
+    sh.move_cols(&ColRange::new(3, 3), 0);
+    assert_eq!(sh.col_span(0, 3), 1);
+
+    // A repeated row is split at the edges.
+    let mut sh = Sheet::new("data");
+    sh.set_row_height(2, cm!(1));
+    sh.set_row_repeat(2, 6);
+    sh.set_row_height(8, cm!(2));
+    sh.move_rows(&RowRange::new(4, 5), 0);
+    assert_eq!(sh.row_repeat(0), 2);
+    assert_eq!(sh.row_height(0), cm!(1));
+    assert_eq!(sh.row_repeat(4), 2);
+    assert_eq!(sh.row_height(4), cm!(1));
+    assert_eq!(sh.row_repeat(6), 2);
+    assert_eq!(sh.row_height(8), cm!(2));
+    assert_eq!(sh.row_repeat(2), 0);
+}
+
+#[test]
+fn test_dedup_rows() {
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "key");
+    for (r, (k, v)) in [("a", 1), ("b", 2), ("a", 3), ("1", 4), ("b", 5)]
+        .iter()
+        .enumerate()
+    {
+        sh.set_value(r as u32 + 1, 0, *k);
+        sh.set_value(r as u32 + 1, 1, *v);
+    }
+    sh.set_value(6, 0, 1);
+    sh.set_row_height(5, cm!(2));
+    sh.set_row_height(6, cm!(3));
+
+    assert_eq!(sh.dedup_rows(&RowRange::new(1, 100), &[0]), 2);
+    let keys: Vec<_> = (0..5).map(|r| sh.value(r, 0).as_cow_str_or("-")).collect();
+    assert_eq!(keys, vec!["key", "a", "b", "1", "-"]);
+    assert_eq!(sh.value(4, 0).as_i32_or(0), 1);
+    assert_eq!(sh.value(2, 1).as_i32_or(0), 2);
+    // row data moves with the rows.
+    assert_eq!(sh.row_height(4), cm!(3));
+
+    // rows without cells are not duplicates.
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "a");
+    sh.set_value(3, 0, "b");
+    assert_eq!(sh.dedup_rows(&RowRange::new(0, 100), &[0]), 0);
+    assert_eq!(sh.value(3, 0).as_str_or(""), "b");
+}
+
+#[test]
+fn test_remove_empty_rows() {
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 1);
+    sh.set_cellstyle(1, 0, &CellStyleRef::from("s"));
+    sh.set_formula(3, 1, "of:=1");
+    sh.set_value(5, 0, 2);
+    sh.set_value(7, 0, 3);
+    let mut cell = CellContent::new();
+    cell.shapes.push(XmlTag::new("draw:frame"));
+    sh.add_cell(8, 0, cell);
+    sh.set_value(9, 0, 4);
+
+    assert_eq!(sh.remove_empty_rows(&RowRange::new(0, 9)), 3);
+    assert_eq!(sh.value(0, 0).as_i32_or(0), 1);
+    // styled cells are kept.
+    assert!(sh.cellstyle(1, 0).is_some());
+    assert!(sh.formula(2, 1).is_some());
+    assert_eq!(sh.value(3, 0).as_i32_or(0), 2);
+    assert_eq!(sh.value(4, 0).as_i32_or(0), 3);
+    // shapes are kept.
+    assert!(sh.shapes(5, 0).is_some());
+    assert_eq!(sh.value(6, 0).as_i32_or(0), 4);
+}
+
+#[test]
+fn test_dirty() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 1);
+    sh.set_value(1, 0, 2);
+    assert!(sh.is_dirty());
+    assert!(sh.all_cells_dirty());
+    wb.push_sheet(sh);
+    wb.push_sheet(Sheet::new("2"));
+    assert_eq!(wb.dirty_sheets(), vec![0, 1]);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut wb = read_ods_buf(&buf)?;
+    assert!(wb.dirty_sheets().is_empty());
+
+    let sh = wb.sheet_mut(0);
+    sh.set_value(0, 0, 5);
+    sh.remove_cell(1, 0);
+    sh.set_col_width(0, cm!(2));
+    assert!(sh.is_dirty());
+    assert!(!sh.all_cells_dirty());
+    assert!(sh.is_cell_dirty(1, 0));
+    assert!(!sh.is_cell_dirty(2, 0));
+    assert_eq!(
+        sh.dirty_cells().iter().copied().collect::<Vec<_>>(),
+        vec![(0, 0), (1, 0)]
+    );
+    assert_eq!(wb.dirty_sheets(), vec![0]);
+
+    wb.clear_dirty();
+    let sh = wb.sheet_mut(0);
+    assert!(!sh.is_dirty());
+    sh.copy_range(&CellRange::local(0, 0, 0, 0), 4, 4);
+    assert_eq!(
+        sh.dirty_cells().iter().copied().collect::<Vec<_>>(),
+        vec![(4, 4)]
+    );
+    sh.insert_rows(0, 1);
+    assert!(sh.all_cells_dirty());
+    assert!(sh.is_cell_dirty(100, 100));
+
+    let sh = wb.sheet_mut(1);
+    sh.add_named_range("n", CellRange::local(0, 0, 1, 1));
+    assert!(sh.is_dirty());
+    sh.clear_dirty();
+    sh.add_named_range("n", CellRange::local(0, 0, 1, 1));
+    assert!(!sh.remove_named_range("m"));
+    assert!(!sh.is_dirty());
+    assert!(sh.remove_named_range("n"));
+    assert!(sh.is_dirty());
+
+    Ok(())
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_iter_cells() {
+    use rayon::prelude::*;
+
+    let mut sh = Sheet::new("1");
+    for r in 0..1000 {
+        for c in 0..10 {
+            sh.set_value(r, c, r * c);
+        }
+    }
+
+    let sum: f64 = sh
+        .par_iter_cells()
+        .map(|(_, cell)| cell.value().as_f64_or(0.0))
+        .sum();
+    let seq: f64 = sh.iter().map(|(_, cell)| cell.value().as_f64_or(0.0)).sum();
+    assert_eq!(sum, seq);
+    assert_eq!(sh.par_iter_cells().count(), 10000);
+}
+
+#[test]
+fn test_default_cellstyle() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut st = CellStyle::new_empty();
+    st.set_name("background");
+    st.set_background_color(color::Rgb::new(240, 240, 255));
+    let background = wb.add_cellstyle(st);
+    let mut st = CellStyle::new_empty();
+    st.set_name("highlight");
+    let highlight = wb.add_cellstyle(st);
+
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "a");
+    sh.set_value(3, 2, "b");
+    sh.set_col_cellstyle(1, &highlight);
+    sh.set_default_cellstyle(&background);
+    assert_eq!(sh.default_cellstyle(), Some(&background));
+    wb.push_sheet(sh);
+
+    let wb = read_ods_buf(&write_ods_buf(&mut wb, Vec::new())?)?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.default_cellstyle(), Some(&background));
+    assert_eq!(sh.col_cellstyle(0), None);
+    assert_eq!(sh.col_cellstyle(1), Some(&highlight));
+    assert_eq!(sh.col_cellstyle(2), None);
+    assert_eq!(sh.col_cellstyle(100), None);
+    assert_eq!(sh.value(3, 2).as_str_or(""), "b");
+
+    Ok(())
+}
+
+#[test]
+fn test_guard_range() {
+    let mut sh = Sheet::new("1");
+    sh.set_formula(10, 1, "of:=SUM([.B1:.B10])");
+    sh.guard_range(CellRange::local(10, 0, 10, 5));
+
+    assert!(sh.is_guarded(10, 5));
+    assert!(!sh.is_guarded(9, 1));
+    assert!(sh.try_set_value(9, 1, 5).is_ok());
+    assert!(sh.try_set_value(10, 1, 5).is_err());
+    assert!(sh.try_set_formula(10, 2, "of:=1").is_err());
+    assert_eq!(
+        sh.formula(10, 1).map(|v| v.as_str()),
+        Some("of:=SUM([.B1:.B10])")
+    );
+
+    // Styles can still be changed.
+    sh.set_cellstyle(10, 1, &CellStyleRef::from("total"));
+
+    // The guard is only checked by the try_ variants.
+    sh.set_value(10, 1, 0);
+    assert_eq!(sh.value(10, 1).as_i32_or(-1), 0);
+
+    let mut sh = Sheet::new("1");
+    sh.guard_range(CellRange::local(0, 0, 0, 0));
+    assert!(sh.unguard_range(&CellRange::local(0, 0, 0, 0)));
+    assert!(sh.guarded_ranges().is_empty());
+    sh.set_value(0, 0, 1);
+}
+
+#[test]
+fn test_guard_structural() {
+    let mut sh = Sheet::new("1");
+    sh.set_value(5, 2, 1);
+    sh.set_value(7, 7, 1);
+    sh.guard_range(CellRange::local(5, 2, 5, 2));
+
+    let fails = |f: &dyn Fn(&mut Sheet) -> Result<(), OdsError>| {
+        let mut sh = sh.clone();
+        let result = f(&mut sh);
+        if result.is_err() {
+            // Nothing changed.
+            assert_eq!(sh.value(5, 2).as_i32_or(0), 1);
+            assert_eq!(sh.value(7, 7).as_i32_or(0), 1);
+        }
+        result.is_err()
+    };
+
+    assert!(fails(&|sh| sh.try_remove_cell(5, 2).map(|_| ())));
+    assert!(fails(&|sh| sh.try_clear_formula(5, 2)));
+    assert!(fails(&|sh| sh.try_set_formula_value(5, 2, "of:=1", 1)));
+    assert!(fails(&|sh| sh.try_add_cell(5, 2, CellContent::new())));
+    assert!(fails(&|sh| sh.try_insert_rows(0, 1)));
+    assert!(fails(&|sh| sh.try_delete_rows(3, 1)));
+    assert!(fails(&|sh| sh.try_insert_cols(2, 1)));
+    assert!(fails(&|sh| sh.try_delete_cols(0, 1)));
+    assert!(fails(&|sh| sh.try_move_rows(&RowRange::new(0, 0), 7)));
+    assert!(fails(&|sh| sh.try_move_cols(&ColRange::new(3, 4), 0)));
+    assert!(fails(&|sh| sh.try_copy_range(
+        &CellRange::local(0, 0, 1, 1),
+        4,
+        1
+    )));
+    assert!(fails(&|sh| sh.try_retain_rows(|row, _| row != 1)));
+    assert!(fails(
+        &|sh| sh.try_retain_rows_fix_formulas(|row, _| row != 1)
+    ));
+    assert!(fails(&|sh| sh
+        .try_remove_empty_rows(&RowRange::new(0, 10))
+        .map(|_| ())));
+    assert!(fails(&|sh| {
+        // row 5 duplicates row 3.
+        sh.set_value(3, 7, 1);
+        sh.try_dedup_rows(&RowRange::new(0, 10), &[0]).map(|_| ())
+    }));
+    assert!(fails(
+        &|sh| sh.try_fill_down(&CellRange::local(4, 2, 4, 2), 1)
+    ));
+    assert!(fails(&|sh| sh.try_fill_series(
+        3,
+        2,
+        3,
+        &Series::Linear(1.0, 1.0)
+    )));
+
+    assert!(!fails(&|sh| sh.try_remove_cell(7, 7).map(|_| ())));
+    assert!(!fails(&|sh| sh.try_insert_rows(6, 1)));
+    assert!(!fails(&|sh| sh.try_insert_rows(0, 0)));
+    assert!(!fails(&|sh| sh.try_delete_cols(3, 1)));
+    assert!(!fails(&|sh| sh.try_move_rows(&RowRange::new(0, 1), 3)));
+    assert!(!fails(&|sh| sh.try_move_cols(&ColRange::new(3, 4), 6)));
+    assert!(!fails(&|sh| sh.try_copy_range(
+        &CellRange::local(0, 0, 1, 1),
+        0,
+        0
+    )));
+    assert!(!fails(&|sh| sh.try_retain_rows(|row, _| row != 6)));
+    assert!(!fails(&|sh| sh.try_fill_series(
+        0,
+        2,
+        5,
+        &Series::Linear(1.0, 1.0)
+    )));
+
+    // Without try_ the guard is not checked.
+    sh.insert_rows(0, 1);
+    assert_eq!(sh.value(6, 2).as_i32_or(0), 1);
+}
+
+#[test]
+fn test_fill_down() {
+    let mut sheet = Sheet::new("1");
+    let style = CellStyleRef::from("num");
+    sheet.set_styled_value(0, 0, 1, &style);
+    sheet.set_formula(0, 1, "of:=[.A1]+[.$A$1]+SUM([.A$1:.A1])");
+    sheet.set_value(1, 0, 2);
+    sheet.set_formula(1, 1, "of:=[.A1]");
+    sheet.set_value(3, 1, "stale");
+
+    sheet.fill_down(&CellRange::local(0, 0, 1, 1), 3);
+
+    assert_eq!(sheet.value(2, 0).as_f64_or(0.0), 1.0);
+    assert_eq!(sheet.cellstyle(2, 0), Some(&style));
+    assert_eq!(
+        sheet.formula(2, 1).unwrap(),
+        "of:=[.A3]+[.$A$1]+SUM([.A$1:.A3])"
+    );
+    assert_eq!(sheet.value(3, 0).as_f64_or(0.0), 2.0);
+    assert_eq!(sheet.formula(3, 1).unwrap(), "of:=[.A3]");
+    assert_eq!(
+        sheet.formula(4, 1).unwrap(),
+        "of:=[.A5]+[.$A$1]+SUM([.A$1:.A5])"
+    );
+    assert!(sheet.is_empty(5, 0));
+}
+
+#[test]
+fn test_fill_series() {
+    use spreadsheet_ods::fill::Series;
+
+    let mut sheet = Sheet::new("1");
+    let style = CellStyleRef::from("date");
+    sheet.set_cellstyle(0, 1, &style);
+
+    sheet.fill_series(0, 0, 4, &Series::Linear(10.0, -2.5));
+    sheet.fill_series(
+        0,
+        1,
+        3,
+        &Series::Months(NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(), 1),
+    );
+    sheet.fill_series(
+        0,
+        2,
+        2,
+        &Series::Days(NaiveDate::from_ymd_opt(2024, 2, 28).unwrap(), 1),
+    );
+
+    assert_eq!(sheet.value(3, 0).as_f64_or(0.0), 2.5);
+    assert_eq!(
+        sheet.value(1, 1).as_date_opt(),
+        NaiveDate::from_ymd_opt(2024, 1, 31)
+    );
+    assert_eq!(
+        sheet.value(2, 1).as_date_opt(),
+        NaiveDate::from_ymd_opt(2024, 2, 29)
+    );
+    assert_eq!(sheet.cellstyle(2, 1), Some(&style));
+    assert_eq!(
+        sheet.value(1, 2).as_date_opt(),
+        NaiveDate::from_ymd_opt(2024, 2, 29)
+    );
+    assert!(sheet.is_empty(4, 0));
+}
+
+#[test]
+fn test_remap_styles() {
+    use spreadsheet_ods::style::{ColStyleRef, RowStyleRef};
+
+    let ce1 = CellStyleRef::from("ce1");
+    let ce2 = CellStyleRef::from("ce2");
+    let co1 = ColStyleRef::from("co1");
+    let ro1 = RowStyleRef::from("ro1");
+
+    let mut sheet = Sheet::new("1");
+    sheet.set_styled_value(0, 0, 1, &ce1);
+    sheet.set_styled_value(1, 0, 2, &ce2);
+    sheet.set_value(2, 0, 3);
+    sheet.set_col_cellstyle(3, &ce1);
+    sheet.set_colstyle(0, &co1);
+    sheet.set_rowstyle(0, &ro1);
+    sheet.set_rowstyle(1, &ro1);
+
+    assert_eq!(
+        sheet.used_cellstyles().into_iter().collect::<Vec<_>>(),
+        vec![ce1.clone(), ce2.clone()]
+    );
+    assert_eq!(sheet.used_colstyles().len(), 1);
+    assert_eq!(sheet.used_rowstyles().len(), 1);
+
+    sheet.clear_dirty();
+    let ce3 = CellStyleRef::from("ce3");
+    assert_eq!(sheet.replace_cellstyle(&ce1, &ce3), 2);
+    assert_eq!(sheet.cellstyle(0, 0), Some(&ce3));
+    assert_eq!(sheet.col_cellstyle(3), Some(&ce3));
+    assert_eq!(sheet.cellstyle(1, 0), Some(&ce2));
+    assert!(sheet.is_cell_dirty(0, 0));
+    assert!(!sheet.is_cell_dirty(1, 0));
+
+    let ro2 = RowStyleRef::from("ro2");
+    assert_eq!(sheet.replace_rowstyle(&ro1, &ro2), 2);
+    assert_eq!(sheet.rowstyle(1), Some(&ro2));
+    assert_eq!(sheet.remap_colstyles(|_| Some(ColStyleRef::from("co2"))), 1);
+    assert_eq!(sheet.colstyle(0).map(|v| v.as_str()), Some("co2"));
+}
+
+#[test]
+fn test_whole_col_refs() {
+    use spreadsheet_ods::formula::{fcolsref, frowsref};
+
+    assert_eq!(fcolsref(0, 0), "[.A:.A]");
+    assert_eq!(frowsref(2, 3), "[.3:.4]");
+
+    let mut sh = Sheet::new("data");
+    sh.set_formula(0, 3, "of:=SUM([.A:.B])+SUM([.$2:.3])+[.A5]");
+    sh.move_rows(&RowRange::new(4, 5), 1);
+    assert_eq!(
+        sh.formula(0, 3).map(String::as_str),
+        Some("of:=SUM([.A:.B])+SUM([.$4:.5])+[.A2]")
+    );
+    sh.move_cols(&ColRange::new(0, 0), 3);
+    assert_eq!(
+        sh.formula(0, 3).map(String::as_str),
+        Some("of:=SUM([.A:.C])+SUM([.$4:.5])+[.C2]")
+    );
+
+    sh.fill_down(&CellRange::local(0, 3, 0, 3), 1);
+    assert_eq!(
+        sh.formula(1, 3).map(String::as_str),
+        Some("of:=SUM([.A:.C])+SUM([.$4:.6])+[.C3]")
+    );
+}
+
+#[test]
+fn test_whole_ranges_clipped() {
+    let mut sh = Sheet::new("data");
+    sh.set_value(0, 0, 1);
+    sh.set_formula(0, 1, "of:=[.A1]*2");
+    sh.set_value(2, 0, 3);
+
+    sh.fill_down(&CellRange::whole_rows(0, 0), 1);
+    assert_eq!(sh.value(1, 0).as_f64_or(0.0), 1.0);
+    assert_eq!(sh.formula(1, 1).map(String::as_str), Some("of:=[.A2]*2"));
+
+    // Rows 0..=2 are used, they are repeated once below.
+    sh.fill_down(&CellRange::whole_cols(0, 0), 3);
+    assert_eq!(sh.value(3, 0).as_f64_or(0.0), 1.0);
+    assert_eq!(sh.value(5, 0).as_f64_or(0.0), 3.0);
+    assert_eq!(sh.used_grid_size(), (6, 2));
+
+    sh.copy_range(&CellRange::whole_cols(0, 0), 0, 3);
+    assert_eq!(sh.value(5, 3).as_f64_or(0.0), 3.0);
+    assert_eq!(sh.used_grid_size(), (6, 4));
+
+    // Cells beyond the last row are dropped.
+    sh.copy_range(&CellRange::local(0, 0, 2, 0), u32::MAX - 1, 0);
+    assert_eq!(sh.value(u32::MAX, 0).as_f64_or(0.0), 1.0);
+    assert_eq!(sh.value(u32::MAX - 1, 0).as_f64_or(0.0), 1.0);
+    sh.fill_down(&CellRange::local(u32::MAX - 1, 0, u32::MAX - 1, 0), 5);
+    sh.copy_range(&CellRange::whole_cols(0, 0), 0, 5);
+    assert_eq!(sh.value(u32::MAX, 5).as_f64_or(0.0), 1.0);
+
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("data");
+    sh.set_value(0, 0, 1);
+    sh.set_value(3, 2, 1);
+    wb.push_sheet(sh);
+    // Not editable, a new name is needed.
+    let mut style = CellStyle::new_empty();
+    style.set_name("Default_editable");
+    wb.add_cellstyle(style);
+    wb.set_editable_range(0, &CellRange::whole_rows(0, 0));
+    wb.set_editable_range(0, &CellRange::whole_cols(2, 2));
+    let sh = wb.sheet(0);
+    assert_eq!(
+        sh.cellstyle(0, 0).map(|v| v.as_str()),
+        Some("Default_editable_2")
+    );
+    assert_eq!(
+        sh.row_cellstyle(0).map(|v| v.as_str()),
+        Some("Default_editable_2")
+    );
+    assert_eq!(
+        sh.col_cellstyle(2).map(|v| v.as_str()),
+        Some("Default_editable_2")
+    );
+    assert_eq!(
+        sh.cellstyle(3, 2).map(|v| v.as_str()),
+        Some("Default_editable_2")
+    );
+    // The row default goes first.
+    assert_eq!(
+        sh.cellstyle(0, 2).map(|v| v.as_str()),
+        Some("Default_editable_2")
+    );
+    assert_eq!(sh.cellstyle(0, 3), None);
+    assert_eq!(sh.cellstyle(4, 2), None);
+    assert_eq!(sh.used_grid_size(), (4, 3));
+    assert_eq!(
+        wb.cellstyle("Default_editable_2")
+            .expect("style")
+            .attrmap()
+            .attr("style:parent-style-name")
+            .map(|v| v.as_str()),
+        Some("Default")
+    );
+}
+
+#[test]
+fn test_observer() {
+    use spreadsheet_ods::hook::CellObserver;
+    use std::sync::{Arc, Mutex};
+
+    struct Log(Arc<Mutex<Vec<String>>>);
+
+    impl CellObserver for Log {
+        fn value_changed(&self, sheet: &str, row: u32, col: u32, old: &Value, new: &Value) {
+            self.0.lock().unwrap().push(format!(
+                "{} {} {} {:?} -> {:?}",
+                sheet,
+                row,
+                col,
+                old.as_f64_opt(),
+                new.as_f64_opt()
+            ));
+        }
+
+        fn formula_changed(
+            &self,
+            sheet: &str,
+            row: u32,
+            col: u32,
+            old: Option<&str>,
+            new: Option<&str>,
+        ) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("{} {} {} {:?} -> {:?}", sheet, row, col, old, new));
+        }
+    }
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 1);
+    sh.add_observer(Log(Arc::clone(&log)));
+
+    sh.set_value(0, 0, 2);
+    sh.set_formula(0, 1, "of:=[.A1]");
+    sh.set_formula_value(0, 1, "of:=[.A1]*2", 4);
+    sh.clear_formula(0, 1);
+    sh.clear_formula(5, 5);
+    sh.set_styled_value(1, 0, 3, &CellStyleRef::from("s"));
+    assert!(sh.try_set_value(2, 0, 4).is_ok());
+    sh.insert_rows(0, 1);
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "1 0 0 Some(1.0) -> Some(2.0)",
+            "1 0 1 None -> Some(\"of:=[.A1]\")",
+            "1 0 1 Some(\"of:=[.A1]\") -> Some(\"of:=[.A1]*2\")",
+            "1 0 1 None -> Some(4.0)",
+            "1 0 1 Some(\"of:=[.A1]*2\") -> None",
+            "1 1 0 None -> Some(3.0)",
+            "1 2 0 None -> Some(4.0)",
+        ]
+    );
+
+    log.lock().unwrap().clear();
+    let mut cell = CellContent::new();
+    cell.value = 7.into();
+    cell.formula = Some("of:=7".into());
+    sh.add_cell(9, 0, cell);
+    sh.remove_cell(9, 0);
+    sh.remove_cell(9, 0);
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "1 9 0 None -> Some(\"of:=7\")",
+            "1 9 0 None -> Some(7.0)",
+            "1 9 0 Some(\"of:=7\") -> None",
+            "1 9 0 Some(7.0) -> None",
+        ]
+    );
+
+    sh.clear_observers();
+    sh.set_value(0, 0, 5);
+    assert_eq!(log.lock().unwrap().len(), 4);
+}
+
+#[test]
+fn test_read_repeated_col_cellstyles() -> Result<(), OdsError> {
+    let wb = read_ods_content(
+        br#"<?xml version="1.0" encoding="UTF-8"?>
+<office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" office:version="1.3">
+<office:body><office:spreadsheet><table:table table:name="one">
+<table:table-column table:number-columns-repeated="5"/>
+<table:table-column table:number-columns-repeated="1995" table:default-cell-style-name="Blue"/>
+<table:table-column table:number-columns-repeated="14384" table:default-cell-style-name="Default"/>
+<table:table-row><table:table-cell office:value-type="string"><text:p>a</text:p></table:table-cell></table:table-row>
+</table:table></office:spreadsheet></office:body></office:document-content>"#,
+    )?;
+    let sh = wb.sheet(0);
+    assert_eq!(sh.col_cellstyle(4), None);
+    assert_eq!(sh.col_cellstyle(5).map(|v| v.as_str()), Some("Blue"));
+    assert_eq!(sh.col_cellstyle(10).map(|v| v.as_str()), Some("Blue"));
+    assert_eq!(sh.col_cellstyle(1999).map(|v| v.as_str()), Some("Blue"));
+    assert_eq!(sh.col_cellstyle(2000), None);
+    assert_eq!(sh.default_cellstyle().map(|v| v.as_str()), Some("Default"));
+
+    Ok(())
+}
@@ -1,16 +1,20 @@
 use color::Rgb;
 
-use spreadsheet_ods::condition::ValueCondition;
+use spreadsheet_ods::condition::{CompareOp, ValueCondition, ValueConditionKind};
+use spreadsheet_ods::format::ValueFormatNumber;
 use spreadsheet_ods::style::stylemap::StyleMap;
 use spreadsheet_ods::style::units::{
     Angle, Border, CellAlignVertical, FontFamilyGeneric, FontPitch, FontWeight, Length, PageBreak,
-    ParaAlignVertical, RotationAlign, TextAlignSource, TextKeep, TextPosition, TextRelief,
-    TextTransform, WrapOption, WritingMode,
+    PageNumber, ParaAlignVertical, RotationAlign, TableAlign, TableBorderModel, TextAlignSource,
+    TextKeep, TextPosition, TextRelief, TextTransform, WrapOption, WritingMode,
 };
 use spreadsheet_ods::style::{
     CellStyle, ColStyle, FontFaceDecl, PageStyle, RowStyle, StyleOrigin, StyleUse, TableStyle,
 };
-use spreadsheet_ods::{cm, deg, mm, pt, write_ods, CellRef, OdsError, Sheet, WorkBook};
+use spreadsheet_ods::{
+    cm, deg, mm, pt, read_ods, read_ods_buf, write_ods, write_ods_buf, CellRef, OdsError, Sheet,
+    WorkBook,
+};
 
 #[test]
 fn test_attr1() {
@@ -347,7 +351,7 @@ fn test_stylemap() -> Result<(), OdsError> {
     ce11.set_styleuse(StyleUse::Named);
     ce11.set_display_name("CC11");
     ce11.set_color(Rgb::new(0, 192, 128));
-    wb.add_cellstyle(ce11);
+    let ce11 = wb.add_cellstyle(ce11);
 
     let mut ce13 = CellStyle::new("ce13", &"num4".into());
     ce13.push_stylemap(StyleMap::new(
@@ -357,7 +361,12 @@ fn test_stylemap() -> Result<(), OdsError> {
     ));
     ce13.push_stylemap(StyleMap::new(
         ValueCondition::content_eq("CC"),
-        "ce11",
+        &ce11,
+        CellRef::remote("s0", 4, 3),
+    ));
+    ce13.push_stylemap(StyleMap::new(
+        ValueCondition::content_is_between(-5, 0),
+        &ce11,
         CellRef::remote("s0", 4, 3),
     ));
     let ce13 = wb.add_cellstyle(ce13);
@@ -371,5 +380,252 @@ fn test_stylemap() -> Result<(), OdsError> {
 
     write_ods(&mut wb, "test_out/styles.ods")?;
 
+    let wb = read_ods("test_out/styles.ods")?;
+    let ce13 = wb.cellstyle(ce13.as_str()).expect("style");
+    let kinds: Vec<_> = ce13
+        .stylemaps()
+        .expect("stylemaps")
+        .iter()
+        .map(|v| (v.value_condition().kind(), v.applied_style().clone()))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (
+                Some(ValueConditionKind::Content(
+                    CompareOp::Eq,
+                    "\"BB\"".to_string()
+                )),
+                "ce12".to_string()
+            ),
+            (
+                Some(ValueConditionKind::Content(
+                    CompareOp::Eq,
+                    "\"CC\"".to_string()
+                )),
+                "ce11".to_string()
+            ),
+            (
+                Some(ValueConditionKind::ContentIsBetween(
+                    "-5".to_string(),
+                    "0".to_string()
+                )),
+                "ce11".to_string()
+            ),
+        ]
+    );
+    assert_eq!(
+        ce13.stylemaps().expect("stylemaps")[0].base_cell(),
+        &CellRef::remote("s0", 4, 3)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_value_condition_kind() {
+    assert_eq!(
+        ValueCondition::value_cmp(CompareOp::Gte, 0).kind(),
+        Some(ValueConditionKind::Value(CompareOp::Gte, "0".to_string()))
+    );
+    assert_eq!(
+        ValueCondition::content_ne("a,b").kind(),
+        Some(ValueConditionKind::Content(
+            CompareOp::Ne,
+            "\"a,b\"".to_string()
+        ))
+    );
+    assert_eq!(
+        ValueCondition::content_is_not_between("a,b", "c").kind(),
+        Some(ValueConditionKind::ContentIsNotBetween(
+            "\"a,b\"".to_string(),
+            "\"c\"".to_string()
+        ))
+    );
+    assert_eq!(
+        ValueCondition::is_true_formula("of:[.A1]>1").kind(),
+        Some(ValueConditionKind::TrueFormula("of:[.A1]>1".to_string()))
+    );
+}
+
+#[test]
+fn test_tablestyle_attr() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut ts = TableStyle::new("ts1");
+    assert!(ts.display()?);
+    assert!(ts.may_break_between_rows()?);
+    assert_eq!(ts.tab_color()?, None);
+    assert_eq!(ts.writing_mode()?, None);
+    assert_eq!(ts.width()?, Length::Default);
+
+    ts.set_display(false);
+    ts.set_tab_color(Rgb::new(255, 0, 128));
+    ts.set_writing_mode(WritingMode::RlTb);
+    ts.set_page_number(PageNumber::Number(3));
+    ts.set_may_break_between_rows(false);
+    ts.set_align(TableAlign::Margins);
+    ts.set_border_model(TableBorderModel::Collapsing);
+    ts.set_width(cm!(17));
+    let ts = wb.add_tablestyle(ts);
+
+    let mut sh = Sheet::new("1");
+    sh.set_style(&ts);
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let ts = wb.tablestyle("ts1").expect("style");
+    assert!(!ts.display()?);
+    assert_eq!(ts.tab_color()?, Some(Rgb::new(255, 0, 128)));
+    assert_eq!(ts.writing_mode()?, Some(WritingMode::RlTb));
+    assert_eq!(ts.page_number()?, Some(PageNumber::Number(3)));
+    assert!(!ts.may_break_between_rows()?);
+    assert_eq!(ts.align()?, Some(TableAlign::Margins));
+    assert_eq!(ts.border_model()?, Some(TableBorderModel::Collapsing));
+    assert_eq!(ts.width()?, cm!(17));
+
+    let mut ts = TableStyle::new("ts2");
+    ts.tablestyle_mut()
+        .set_attr("table:tab-color", "red".to_string());
+    assert!(ts.tab_color().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_rowstyle_attr() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut rs = RowStyle::new("rs1");
+    assert_eq!(rs.break_before()?, None);
+    assert!(!rs.use_optimal_row_height()?);
+
+    rs.set_break_before(PageBreak::Page);
+    rs.set_break_after(PageBreak::Auto);
+    rs.set_use_optimal_row_height(true);
+    let rs = wb.add_rowstyle(rs);
+
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "header");
+    sh.set_rowstyle(0, &rs);
+    sh.set_value(5, 0, "total");
+    sh.set_rowstyle(5, &rs);
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut wb = read_ods_buf(&buf)?;
+
+    let rs = wb.rowstyle("rs1").expect("style");
+    assert_eq!(rs.break_before()?, Some(PageBreak::Page));
+    assert_eq!(rs.break_after()?, Some(PageBreak::Auto));
+    assert!(rs.use_optimal_row_height()?);
+    assert_eq!(wb.sheet(0).row_height(5), Length::Default);
+
+    // An explicit height switches off the optimal height.
+    wb.sheet_mut(0).set_row_height(5, cm!(2));
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let rs = wb.rowstyle("rs1").expect("style");
+    assert!(!rs.use_optimal_row_height()?);
+    assert_eq!(rs.break_before()?, Some(PageBreak::Page));
+    assert_eq!(wb.sheet(0).row_height(5), cm!(2));
+
+    let mut rs = RowStyle::new("rs2");
+    rs.rowstyle_mut()
+        .set_attr("fo:break-after", "never".to_string());
+    assert!(rs.break_after().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_raw_attr() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut cs = CellStyle::new_empty();
+    cs.set_name("raw");
+    cs.set_raw_attr("style:style", "loext:linked-style-name", "x".to_string())?;
+    cs.set_raw_attr(
+        "style:table-cell-properties",
+        "loext:vertical-justify",
+        "auto".to_string(),
+    )?;
+    cs.set_raw_attr(
+        "style:text-properties",
+        "officeooo:rsid",
+        "\"a&b\"".to_string(),
+    )?;
+    assert!(cs
+        .set_raw_attr("style:table-row-properties", "x:y", "z".to_string())
+        .is_err());
+    wb.add_cellstyle(cs);
+
+    let mut ps = PageStyle::new("raw");
+    ps.set_raw_attr(
+        "style:page-layout-properties",
+        "loext:margin-gutter",
+        "0&1".to_string(),
+    )?;
+    ps.headerstyle_mut().set_raw_attr(
+        "style:header-footer-properties",
+        "x:y",
+        "<z>".to_string(),
+    )?;
+    wb.add_pagestyle(ps);
+
+    let mut vf = ValueFormatNumber::new_named("raw");
+    vf.part_number().decimal_places(1).build();
+    vf.set_raw_attr("number:number-style", "loext:extra", "1".to_string())?;
+    assert!(vf
+        .set_raw_attr("number:date-style", "loext:extra", "1".to_string())
+        .is_err());
+    wb.add_number_format(vf);
+
+    wb.push_sheet(Sheet::new("1"));
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let cs = wb.cellstyle("raw").expect("style");
+    assert_eq!(
+        cs.raw_attr("style:style", "loext:linked-style-name"),
+        Some(&"x".to_string())
+    );
+    assert_eq!(
+        cs.raw_attr("style:table-cell-properties", "loext:vertical-justify"),
+        Some(&"auto".to_string())
+    );
+    assert_eq!(
+        cs.raw_attr("style:text-properties", "officeooo:rsid"),
+        Some(&"\"a&b\"".to_string())
+    );
+
+    let ps = wb.pagestyle("raw").expect("style");
+    assert_eq!(
+        ps.raw_attr("style:page-layout-properties", "loext:margin-gutter"),
+        Some(&"0&1".to_string())
+    );
+    assert_eq!(
+        ps.headerstyle()
+            .raw_attr("style:header-footer-properties", "x:y"),
+        Some(&"<z>".to_string())
+    );
+
+    let vf = wb.number_format("raw").expect("format");
+    assert_eq!(
+        vf.raw_attr("number:number-style", "loext:extra"),
+        Some(&"1".to_string())
+    );
+
+    let mut cs = cs.clone();
+    assert_eq!(
+        cs.clear_raw_attr("style:style", "loext:linked-style-name"),
+        Some("x".to_string())
+    );
+    assert_eq!(cs.raw_attr("style:style", "loext:linked-style-name"), None);
+
     Ok(())
 }
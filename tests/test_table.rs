@@ -0,0 +1,99 @@
+use spreadsheet_ods::table::{ColumnSchema, SchemaViolation, TableReader, TableSchema};
+use spreadsheet_ods::{CellRange, OdsError, Sheet, Value, ValueType};
+
+#[test]
+fn test_table_reader() -> Result<(), OdsError> {
+    let mut sh = Sheet::new("1");
+    sh.set_value(1, 1, "name");
+    sh.set_value(1, 2, " amount ");
+    sh.set_value(1, 4, "name");
+    sh.set_value(2, 1, "apples");
+    sh.set_value(2, 2, 3);
+    sh.set_value(4, 1, "pears");
+    sh.set_value(4, 2, "many");
+    // outside of the range
+    sh.set_value(5, 5, "x");
+
+    let reader = TableReader::new(&sh, &CellRange::local(1, 1, 100, 4));
+    assert_eq!(reader.headers().collect::<Vec<_>>(), vec!["name", "amount"]);
+    assert_eq!(reader.col("name"), Some(1));
+    assert!(reader.require(&["amount", "name"]).is_ok());
+    assert!(reader.require(&["price"]).is_err());
+
+    let rows: Vec<_> = reader.collect();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].row(), 2);
+    assert_eq!(rows[0].get_as::<String>("name")?.as_deref(), Some("apples"));
+    assert_eq!(rows[0].get_as::<f64>("amount")?, Some(3.0));
+    assert_eq!(rows[0].get_as::<f64>("price")?, None);
+    assert!(matches!(rows[0].get("price"), Value::Empty));
+    assert!(rows[1].get_as::<f64>("amount").is_err());
+
+    let map = rows[1].to_map();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map["name"].as_str_or(""), "pears");
+
+    Ok(())
+}
+
+#[test]
+fn test_table_reader_reordered() -> Result<(), OdsError> {
+    let mut sh1 = Sheet::new("1");
+    sh1.set_value(0, 0, "a");
+    sh1.set_value(0, 1, "b");
+    sh1.set_value(1, 0, 1);
+    sh1.set_value(1, 1, 2);
+
+    let mut sh2 = Sheet::new("2");
+    sh2.set_value(0, 0, "b");
+    sh2.set_value(0, 1, "a");
+    sh2.set_value(1, 0, 2);
+    sh2.set_value(1, 1, 1);
+
+    for sh in [&sh1, &sh2] {
+        let row = TableReader::new(sh, &CellRange::local(0, 0, u32::MAX, 10))
+            .next()
+            .expect("row");
+        assert_eq!(row.get_as::<i32>("a")?, Some(1));
+        assert_eq!(row.get_as::<i32>("b")?, Some(2));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_table_schema() {
+    let mut sh = Sheet::new("data");
+    sh.set_value(0, 0, "amount");
+    sh.set_value(0, 1, "name");
+    sh.set_value(1, 0, 5);
+    sh.set_value(1, 1, "a");
+    sh.set_value(2, 0, "five");
+    sh.set_value(3, 0, 500);
+    sh.set_value(3, 1, "c");
+
+    let schema = TableSchema::new()
+        .column(
+            ColumnSchema::new("amount")
+                .value_type(ValueType::Number)
+                .min(0.0)
+                .max(100.0),
+        )
+        .column(ColumnSchema::new("name").required())
+        .column(ColumnSchema::new("date"));
+
+    let errors = schema.validate(&sh, &CellRange::local(0, 0, u32::MAX, 10));
+    let errors: Vec<_> = errors
+        .iter()
+        .map(|v| (v.cell.row(), v.cell.col(), v.violation))
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            (0, 0, SchemaViolation::MissingColumn),
+            (2, 0, SchemaViolation::WrongType(ValueType::Text)),
+            (2, 1, SchemaViolation::Empty),
+            (3, 0, SchemaViolation::TooLarge(500.0)),
+        ]
+    );
+}
@@ -1,5 +1,6 @@
 use spreadsheet_ods::style::ParagraphStyleRef;
-use spreadsheet_ods::text::{AuthorName, CreationDate, TextH, TextP, TextS, TextTag};
+use spreadsheet_ods::text::{AuthorName, CreationDate, TextA, TextH, TextP, TextS, TextTag};
+use spreadsheet_ods::{CellRange, CellRef};
 
 #[test]
 fn test_text() {
@@ -44,3 +45,27 @@ whatever
 "#
     )
 }
+
+#[test]
+fn test_internal_link() {
+    let link = TextA::new().href_cell(&CellRef::remote("Sheet2", 4, 1));
+    assert!(link.to_string().contains(r##"xlink:href="#Sheet2.B5""##));
+
+    let link = TextA::new().href_cell(&CellRef::local(0, 0));
+    assert!(link.to_string().contains(r##"xlink:href="#.A1""##));
+
+    let link = TextA::new().href_cell(&CellRef::remote("Sales 2020", 0, 0).absolute());
+    assert!(link
+        .to_string()
+        .contains(r##"xlink:href="#'Sales%202020'.A1""##));
+
+    let link = TextA::new().href_range(&CellRange::remote("Sheet2", 0, 0, 9, 2));
+    assert!(link
+        .to_string()
+        .contains(r##"xlink:href="#Sheet2.A1:C10""##));
+
+    let link = TextA::new().href_name("Übersicht");
+    assert!(link
+        .to_string()
+        .contains(r##"xlink:href="#%C3%9Cbersicht""##));
+}
@@ -0,0 +1,94 @@
+use color::Rgb;
+use spreadsheet_ods::style::StyleUse;
+use spreadsheet_ods::theme::Theme;
+use spreadsheet_ods::{read_ods_buf, write_ods_buf, OdsError, Sheet, ValueType, WorkBook};
+
+#[test]
+fn test_theme() -> Result<(), OdsError> {
+    let theme = Theme {
+        name: "corp".to_string(),
+        font: "DejaVu Sans".to_string(),
+        accent_color: Rgb::new(0, 84, 159),
+        currency_symbol: "$".to_string(),
+        ..Default::default()
+    };
+
+    let mut wb = WorkBook::new_empty();
+    wb.install_theme(&theme);
+    assert_eq!(
+        wb.def_style(ValueType::Number).map(|v| v.as_str()),
+        Some("corp-number")
+    );
+
+    let mut sheet = Sheet::new("1");
+    sheet.set_styled_value(0, 0, "amount", &theme.header());
+    sheet.set_value(1, 0, 1234.5);
+    wb.push_sheet(sheet);
+
+    let wb = read_ods_buf(&write_ods_buf(&mut wb, Vec::new())?)?;
+
+    let font = wb.font("DejaVu Sans").expect("font");
+    assert_eq!(
+        font.attrmap().attr("svg:font-family").map(|v| v.as_str()),
+        Some("'DejaVu Sans'")
+    );
+
+    let base = wb.cellstyle("corp").expect("base");
+    assert_eq!(base.styleuse(), StyleUse::Named);
+    assert_eq!(
+        base.textstyle().attr("style:font-name").map(|v| v.as_str()),
+        Some("DejaVu Sans")
+    );
+
+    let header = wb.cellstyle("corp-header").expect("header");
+    assert_eq!(
+        header
+            .attrmap()
+            .attr("style:parent-style-name")
+            .map(|v| v.as_str()),
+        Some("corp")
+    );
+    assert_eq!(
+        header
+            .textstyle()
+            .attr("fo:font-weight")
+            .map(|v| v.as_str()),
+        Some("bold")
+    );
+
+    let currency = wb.cellstyle("corp-currency").expect("currency");
+    assert_eq!(
+        currency.value_format().map(|v| v.as_str()),
+        Some("corp-currency")
+    );
+    assert!(wb.currency_format("corp-currency").is_some());
+
+    let link = wb.textstyle("Internet_20_link").expect("link");
+    assert_eq!(link.styleuse(), StyleUse::Named);
+    assert_eq!(
+        link.attrmap()
+            .attr("style:display-name")
+            .map(|v| v.as_str()),
+        Some("Internet Link")
+    );
+    assert_eq!(
+        link.textstyle().attr("fo:color").map(|v| v.as_str()),
+        Some("#000080")
+    );
+    assert!(wb.textstyle("Visited_20_Internet_20_Link").is_some());
+
+    let note = wb.graphicstyle("Note").expect("note");
+    assert_eq!(note.styleuse(), StyleUse::Named);
+    assert_eq!(
+        note.graphicstyle()
+            .attr("draw:fill-color")
+            .map(|v| v.as_str()),
+        Some("#ffffc0")
+    );
+    assert_eq!(
+        note.textstyle().attr("style:font-name").map(|v| v.as_str()),
+        Some("DejaVu Sans")
+    );
+
+    Ok(())
+}
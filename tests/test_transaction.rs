@@ -0,0 +1,173 @@
+use spreadsheet_ods::transaction::Transaction;
+use spreadsheet_ods::{cm, CellRange, Length, Sheet, WorkBook};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+#[test]
+fn test_rollback() {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 1);
+    wb.push_sheet(sh);
+
+    let mut tx = Transaction::new(&mut wb);
+    tx.set_value(0, 0, 0, 2);
+    tx.set_formula(0, 1, 1, "of:=1+1");
+    tx.rollback();
+
+    assert_eq!(wb.sheet(0).value(0, 0).as_i32_or(0), 1);
+    assert!(wb.sheet(0).is_empty(1, 1));
+
+    {
+        let mut tx = Transaction::new(&mut wb);
+        tx.set_value(0, 0, 0, 3);
+    }
+    assert_eq!(wb.sheet(0).value(0, 0).as_i32_or(0), 1);
+}
+
+#[test]
+fn test_undo() {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "span");
+    sh.set_row_span(0, 0, 4);
+    sh.set_value(1, 0, 1);
+    sh.set_value(2, 1, 2);
+    sh.set_value(5, 0, 5);
+    wb.push_sheet(sh);
+
+    let mut tx = Transaction::new(&mut wb);
+    tx.delete_rows(0, 1, 2);
+    tx.set_value(0, 3, 0, "new");
+    tx.delete_cols(0, 0, 1);
+    let log = tx.commit();
+    assert_eq!(log.len(), 3);
+    assert_eq!(wb.sheet(0).value(3, 0).as_i32_or(0), 0);
+
+    log.undo(&mut wb);
+    let sh = wb.sheet(0);
+    assert_eq!(sh.value(0, 0).as_str_or(""), "span");
+    assert_eq!(sh.row_span(0, 0), 4);
+    assert_eq!(sh.value(1, 0).as_i32_or(0), 1);
+    assert_eq!(sh.value(2, 1).as_i32_or(0), 2);
+    assert_eq!(sh.value(5, 0).as_i32_or(0), 5);
+    assert!(sh.is_empty(3, 0));
+}
+
+#[test]
+fn test_undo_guarded() {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 1);
+    sh.set_value(3, 0, 3);
+    wb.push_sheet(sh);
+
+    let mut tx = Transaction::new(&mut wb);
+    tx.set_value(0, 0, 0, 2);
+    tx.insert_rows(0, 1, 1);
+    tx.remove_cell(0, 4, 0);
+    let log = tx.commit();
+
+    // Undo restores guarded cells too.
+    wb.sheet_mut(0).guard_range(CellRange::local(0, 0, 9, 9));
+    log.undo(&mut wb);
+    assert_eq!(wb.sheet(0).value(0, 0).as_i32_or(0), 1);
+    assert_eq!(wb.sheet(0).value(3, 0).as_i32_or(0), 3);
+
+    // A panic rolls back the transaction.
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let mut tx = Transaction::new(&mut wb);
+        tx.set_value(0, 0, 0, "a");
+        tx.set_value(0, 5, 0, "b");
+        panic!("stop");
+    }));
+    assert!(result.is_err());
+    assert!(wb.sheet(0).is_empty(0, 0));
+    assert!(wb.sheet(0).is_empty(5, 0));
+}
+
+#[test]
+fn test_undo_repeated_rows() {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_row_height(2, cm!(1));
+    sh.set_row_repeat(2, 5);
+    sh.set_value(8, 0, 8);
+    wb.push_sheet(sh);
+
+    let mut tx = Transaction::new(&mut wb);
+    tx.insert_rows(0, 4, 2);
+    let log = tx.commit();
+    assert_eq!(wb.sheet(0).row_repeat(2), 2);
+    assert_eq!(wb.sheet(0).row_repeat(6), 3);
+
+    log.undo(&mut wb);
+    let sh = wb.sheet(0);
+    assert_eq!(sh.row_repeat(2), 5);
+    assert_eq!(sh.row_repeat(6), 0);
+    assert_eq!(sh.row_height(2), cm!(1));
+    assert_eq!(sh.value(8, 0).as_i32_or(0), 8);
+
+    let mut tx = Transaction::new(&mut wb);
+    tx.delete_rows(0, 5, 2);
+    let log = tx.commit();
+    assert_eq!(wb.sheet(0).row_repeat(2), 3);
+    assert_eq!(wb.sheet(0).value(6, 0).as_i32_or(0), 8);
+
+    log.undo(&mut wb);
+    let sh = wb.sheet(0);
+    assert_eq!(sh.row_repeat(2), 5);
+    assert_eq!(sh.row_repeat(5), 0);
+    assert_eq!(sh.value(8, 0).as_i32_or(0), 8);
+}
+
+#[test]
+fn test_rollback_observer() {
+    use spreadsheet_ods::hook::CellObserver;
+    use spreadsheet_ods::Value;
+    use std::sync::{Arc, Mutex};
+
+    struct Log(Arc<Mutex<Vec<String>>>);
+
+    impl CellObserver for Log {
+        fn value_changed(&self, _sheet: &str, row: u32, col: u32, old: &Value, new: &Value) {
+            self.0.lock().unwrap().push(format!(
+                "{} {} {:?} -> {:?}",
+                row,
+                col,
+                old.as_f64_opt(),
+                new.as_f64_opt()
+            ));
+        }
+    }
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 1);
+    sh.add_observer(Log(Arc::clone(&log)));
+    wb.push_sheet(sh);
+
+    let mut tx = Transaction::new(&mut wb);
+    tx.set_value(0, 0, 0, 2);
+    tx.set_value(0, 1, 1, 3);
+    tx.rollback();
+
+    {
+        let mut tx = Transaction::new(&mut wb);
+        tx.set_value(0, 0, 0, 4);
+    }
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "0 0 Some(1.0) -> Some(2.0)",
+            "1 1 None -> Some(3.0)",
+            "1 1 Some(3.0) -> None",
+            "0 0 Some(2.0) -> Some(1.0)",
+            "0 0 Some(1.0) -> Some(4.0)",
+            "0 0 Some(4.0) -> Some(1.0)",
+        ]
+    );
+}
@@ -1,4 +1,14 @@
-use spreadsheet_ods::{Sheet, ValueType, WorkBook};
+use spreadsheet_ods::format::create_number_format;
+use spreadsheet_ods::formula::fcellref_table;
+use spreadsheet_ods::style::{
+    CellStyle, FontFaceDecl, MasterPage, PageStyle, StyleOrigin, StyleUse, TableStyle, TextStyle,
+};
+use spreadsheet_ods::text::{TextP, TextSpan};
+use spreadsheet_ods::{
+    cm, read_ods_buf, write_ods, write_ods_buf, write_ods_buf_uncompressed, CellRange, CellRef,
+    DanglingRef, DanglingRefs, Length, OdsError, Sheet, SheetConfig, Value, ValueType, WorkBook,
+    WorkBookConfig,
+};
 
 #[test]
 fn test_workbook() {
@@ -23,6 +33,569 @@ fn test_def_style() {
     let mut wb = WorkBook::new_empty();
 
     wb.add_def_style(ValueType::Number, &"val0".into());
-    assert_eq!(wb.def_style(ValueType::Number), Some(&"val0".to_string()));
+    assert_eq!(wb.def_style(ValueType::Number), Some(&"val0".into()));
     assert!(wb.def_style(ValueType::Text).is_none());
 }
+
+#[test]
+fn test_style_registry() {
+    let mut wb = WorkBook::new_empty();
+
+    let f1 = wb.add_number_format(create_number_format("num2", 2, false));
+    let s1 = wb.add_cellstyle(CellStyle::new("s1", &f1));
+    let s2 = wb.add_cellstyle(CellStyle::new("s2", &f1));
+    let s3 = wb.add_cellstyle(CellStyle::new_empty());
+
+    assert!(wb.number_format("num2").is_some());
+    assert_eq!(wb.iter_cellstyles().count(), 3);
+    assert_eq!(
+        wb.iter_cellstyles()
+            .filter(|v| v.origin() == StyleOrigin::Content)
+            .count(),
+        3
+    );
+
+    let mut sh = Sheet::new("1");
+    sh.set_styled_value(0, 0, 1, &s1);
+    sh.set_styled_value(1, 0, 2, &s1);
+    sh.set_styled_value(2, 0, 3, &s2);
+    wb.push_sheet(sh);
+    let mut sh = Sheet::new("2");
+    sh.set_styled_value(0, 0, 1, &s1);
+    wb.push_sheet(sh);
+
+    let count = wb.cellstyle_refcount();
+    assert_eq!(count.get(&s1), Some(&3));
+    assert_eq!(count.get(&s2), Some(&1));
+    assert_eq!(count.get(&s3), None);
+
+    let count = wb.format_refcount();
+    assert_eq!(count.get(&f1), Some(&2));
+}
+
+#[test]
+fn test_shared_read() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    for r in 0..100 {
+        sh.set_value(r, 0, r);
+    }
+    wb.push_sheet(sh);
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    let wb = &wb;
+    std::thread::scope(|s| {
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                s.spawn(move || -> Result<f64, OdsError> {
+                    let sum = wb
+                        .sheet(0)
+                        .iter()
+                        .map(|(_, v)| v.value().as_f64_or(0.0))
+                        .sum();
+                    // writing needs a clone.
+                    let mut fork = wb.clone();
+                    write_ods_buf(&mut fork, Vec::new())?;
+                    Ok(sum)
+                })
+            })
+            .collect();
+        for t in threads {
+            assert_eq!(t.join().expect("thread")?, 4950.0);
+        }
+        Ok(())
+    })
+}
+
+#[test]
+fn test_fork() {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "template");
+    wb.push_sheet(sh);
+    wb.push_sheet(Sheet::new("2"));
+    let f1 = wb.add_number_format(create_number_format("num2", 2, false));
+    wb.add_cellstyle(CellStyle::new("s1", &f1));
+
+    let mut fork = wb.clone();
+    fork.sheet_mut(0).set_value(0, 0, "fork");
+    let sh = fork.detach_sheet(1);
+    fork.attach_sheet(sh);
+    fork.cellstyle_mut("s1").expect("style").set_font_bold();
+    fork.add_cellstyle(CellStyle::new("s2", &f1));
+
+    assert_eq!(wb.sheet(0).value(0, 0).as_str_or(""), "template");
+    assert_eq!(fork.sheet(0).value(0, 0).as_str_or(""), "fork");
+    assert_eq!(wb.num_sheets(), 2);
+    let bold = |wb: &WorkBook| {
+        wb.cellstyle("s1")
+            .expect("style")
+            .textstyle()
+            .attr("fo:font-weight")
+            .is_some()
+    };
+    assert!(!bold(&wb));
+    assert!(bold(&fork));
+    assert!(wb.cellstyle("s2").is_none());
+    assert!(WorkBook::default().cellstyle("s1").is_none());
+}
+
+#[test]
+fn test_memory_footprint() {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "x".repeat(1000));
+    wb.push_sheet(sh);
+    wb.push_sheet(Sheet::new("2"));
+
+    let f = wb.memory_footprint();
+    assert_eq!(f.sheets.len(), 2);
+    assert!(f.strings >= 1000);
+    assert!(f.sheets[0] > f.sheets[1] + 1000);
+    assert!(f.total() >= f.sheets[0] + f.sheets[1]);
+
+    let sh = wb.detach_sheet(0);
+    let f = wb.memory_footprint();
+    assert_eq!(f.sheets[0], 0);
+    assert!(f.strings < 1000);
+    wb.attach_sheet(sh);
+}
+
+#[test]
+fn test_split_sheet() {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("before"));
+
+    let mut sh = Sheet::new("Data");
+    sh.set_value(0, 0, "header");
+    sh.set_header_rows(0, 0);
+    sh.set_col_width(0, cm!(5));
+    for r in 1..=25 {
+        sh.set_value(r, 0, r);
+    }
+    sh.set_row_height(12, cm!(2));
+    sh.set_value(9, 1, "span");
+    sh.set_row_span(9, 1, 4);
+    wb.push_sheet(sh);
+    wb.push_sheet(Sheet::new("after"));
+
+    assert_eq!(wb.split_sheet_by_rows(1, 10), 3);
+    assert_eq!(wb.num_sheets(), 5);
+    assert_eq!(wb.sheet(0).name(), "before");
+    assert_eq!(wb.sheet(1).name(), "Data_1");
+    assert_eq!(wb.sheet(2).name(), "Data_2");
+    assert_eq!(wb.sheet(3).name(), "Data_3");
+    assert_eq!(wb.sheet(4).name(), "after");
+
+    for idx in 1..=3 {
+        let sh = wb.sheet(idx);
+        assert_eq!(sh.value(0, 0).as_str_or(""), "header");
+        assert_eq!(sh.col_width(0), cm!(5));
+    }
+    assert_eq!(wb.sheet(1).value(10, 0).as_i32_or(0), 10);
+    assert_eq!(wb.sheet(1).row_span(9, 1), 2);
+    assert_eq!(wb.sheet(2).value(1, 0).as_i32_or(0), 11);
+    assert_eq!(wb.sheet(2).row_height(2), cm!(2));
+    assert_eq!(wb.sheet(3).value(5, 0).as_i32_or(0), 25);
+    assert!(wb.sheet(3).is_empty(6, 0));
+
+    // The names of other sheets are not used twice.
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("data_2"));
+    let mut sh = Sheet::new("Data");
+    for r in 0..30 {
+        sh.set_value(r, 0, r);
+    }
+    wb.push_sheet(sh);
+    assert_eq!(wb.split_sheet_by_rows(1, 10), 3);
+    assert_eq!(wb.sheet(1).name(), "Data_1");
+    assert_eq!(wb.sheet(2).name(), "Data_2_2");
+    assert_eq!(wb.sheet(3).name(), "Data_3");
+}
+
+#[test]
+fn test_append() {
+    fn region(name: &str, decimals: u8) -> WorkBook {
+        let mut wb = WorkBook::new_empty();
+        let f = wb.add_number_format(create_number_format("num", decimals, false));
+        let mut st = CellStyle::new("value", &f);
+        st.set_font_bold();
+        let st = wb.add_cellstyle(st);
+
+        let mut sh = Sheet::new("Data");
+        sh.set_styled_value(0, 0, name, &st);
+        wb.push_sheet(sh);
+        wb
+    }
+
+    let mut wb = region("north", 1);
+    wb.append(region("south", 2));
+    wb.append(region("east", 3));
+
+    assert_eq!(wb.num_sheets(), 3);
+    assert_eq!(wb.sheet(0).name(), "Data");
+    assert_eq!(wb.sheet(1).name(), "Data_2");
+    assert_eq!(wb.sheet(2).name(), "Data_3");
+
+    let st = wb.sheet(2).cellstyle(0, 0).expect("style").clone();
+    assert_eq!(st.as_str(), "value_3");
+    let st = wb.cellstyle(st.as_str()).expect("style");
+    assert_eq!(st.value_format().map(String::as_str), Some("num_3"));
+    assert!(wb.number_format("num_3").is_some());
+    assert_eq!(
+        wb.sheet(1).cellstyle(0, 0).expect("style").as_str(),
+        "value_2"
+    );
+    assert_eq!(
+        wb.sheet(0).cellstyle(0, 0).expect("style").as_str(),
+        "value"
+    );
+
+    let buf = write_ods_buf(&mut wb, Vec::new()).expect("write");
+    let wb = read_ods_buf(&buf).expect("read");
+    assert_eq!(wb.sheet(2).value(0, 0).as_str_or(""), "east");
+    assert_eq!(
+        wb.sheet(2).cellstyle(0, 0).expect("style").as_str(),
+        "value_3"
+    );
+}
+
+#[test]
+fn test_append_collisions() {
+    fn book(italic: bool) -> WorkBook {
+        let mut wb = WorkBook::new_empty();
+        wb.add_font(FontFaceDecl::new("Mono"));
+        let mut ts = TextStyle::new_empty();
+        ts.set_name("T1");
+        ts.set_font_name("Mono");
+        if italic {
+            ts.set_font_italic();
+        }
+        let ts = wb.add_textstyle(ts);
+        let ps = wb.add_pagestyle(PageStyle::new("pm1"));
+        let mut mp = MasterPage::new("Default");
+        mp.set_pagestyle(&ps);
+        let mp = wb.add_masterpage(mp);
+        let mut tab = TableStyle::new("ta1");
+        tab.set_master_page(&mp);
+        let tab = wb.add_tablestyle(tab);
+
+        let mut sh = Sheet::new("1");
+        sh.set_style(&tab);
+        sh.set_value(0, 0, 1);
+        sh.set_value(
+            0,
+            1,
+            TextP::new()
+                .tag(TextSpan::new().style_name(&ts).text("x"))
+                .into_xmltag(),
+        );
+        wb.push_sheet(sh);
+        wb
+    }
+
+    let mut wb = book(false);
+    let mut other = book(true);
+    let mut sh = Sheet::new("2");
+    sh.set_formula(0, 0, format!("of:={}", fcellref_table("1", 0, 0)));
+    other.push_sheet(sh);
+    wb.append(other);
+
+    assert_eq!(wb.num_sheets(), 3);
+    assert_eq!(wb.sheet(1).name(), "1_2");
+    assert_eq!(
+        wb.sheet(2).formula(0, 0).map(String::as_str),
+        Some(format!("of:={}", fcellref_table("1_2", 0, 0)).as_str())
+    );
+
+    assert!(wb.font("Mono_2").is_some());
+    let ts = wb.textstyle("T1_2").expect("style");
+    assert_eq!(
+        ts.textstyle().attr("style:font-name").map(String::as_str),
+        Some("Mono_2")
+    );
+    assert!(wb
+        .textstyle("T1")
+        .expect("style")
+        .textstyle()
+        .attr("fo:font-style")
+        .is_none());
+    match wb.sheet(1).value(0, 1) {
+        Value::TextXml(text) => {
+            let buf = text[0].to_string();
+            assert!(buf.contains("\"T1_2\""), "{}", buf);
+        }
+        v => panic!("{:?}", v),
+    }
+
+    assert_eq!(wb.masterpage("Default").expect("mp").pagestyle(), "pm1");
+    assert_eq!(wb.masterpage("Default_2").expect("mp").pagestyle(), "pm1_2");
+    assert_eq!(wb.sheet(1).style().map(|v| v.as_str()), Some("ta1_2"));
+    assert_eq!(
+        wb.tablestyle("ta1_2")
+            .expect("style")
+            .attrmap()
+            .attr("style:master-page-name")
+            .map(String::as_str),
+        Some("Default_2")
+    );
+}
+
+#[test]
+fn test_import_styles() -> Result<(), OdsError> {
+    let mut template = WorkBook::new_empty();
+    let f = template.add_number_format(create_number_format("amount", 2, true));
+    let mut st = CellStyle::new("Heading", &f);
+    st.set_origin(StyleOrigin::Styles);
+    st.set_styleuse(StyleUse::Named);
+    st.set_font_bold();
+    template.add_cellstyle(st);
+    let mut ps = PageStyle::new("pm1");
+    ps.set_page_width(cm!(10));
+    let ps = template.add_pagestyle(ps);
+    let mut mp = MasterPage::new("Default");
+    mp.set_pagestyle(&ps);
+    template.add_masterpage(mp);
+    let mut sheet = Sheet::new("template");
+    sheet.set_value(0, 0, "ignored");
+    template.push_sheet(sheet);
+    write_ods(&mut template, "test_out/import_styles.ods")?;
+
+    let mut wb = WorkBook::new_empty();
+    let mut st = CellStyle::new_empty();
+    st.set_name("Heading");
+    st.set_origin(StyleOrigin::Styles);
+    st.set_styleuse(StyleUse::Named);
+    wb.add_cellstyle(st);
+    let mut sheet = Sheet::new("data");
+    sheet.set_styled_value(0, 0, "sum", &"Heading".into());
+    wb.push_sheet(sheet);
+
+    wb.import_styles_from("test_out/import_styles.ods")?;
+
+    assert_eq!(wb.num_sheets(), 1);
+    let st = wb.cellstyle("Heading").expect("style");
+    assert_eq!(st.value_format().map(String::as_str), Some("amount"));
+    assert_eq!(
+        st.textstyle().attr("fo:font-weight").map(String::as_str),
+        Some("bold")
+    );
+    assert!(wb.number_format("amount").is_some());
+    assert_eq!(wb.masterpage("Default").expect("mp").pagestyle(), "pm1");
+    assert!(wb.pagestyle("pm1").is_some());
+
+    let wb = read_ods_buf(&write_ods_buf(&mut wb, Vec::new())?)?;
+    assert!(wb.cellstyle("Heading").is_some());
+    assert_eq!(wb.sheet(0).value(0, 0).as_str_or(""), "sum");
+
+    Ok(())
+}
+
+#[test]
+fn test_import_automatic_styles() -> Result<(), OdsError> {
+    fn styles(wb: &mut WorkBook, width: Length, italic: bool) {
+        let mut ts = TextStyle::new_empty();
+        ts.set_name("T1");
+        ts.set_origin(StyleOrigin::Styles);
+        ts.set_styleuse(StyleUse::Automatic);
+        if italic {
+            ts.set_font_italic();
+        }
+        let ts = wb.add_textstyle(ts);
+        let mut ps = PageStyle::new("pm1");
+        ps.set_page_width(width);
+        let ps = wb.add_pagestyle(ps);
+        let mut mp = MasterPage::new("Default");
+        mp.set_pagestyle(&ps);
+        mp.header_mut().center_mut().push(
+            TextP::new()
+                .tag(TextSpan::new().style_name(&ts).text("head"))
+                .into_xmltag(),
+        );
+        wb.add_masterpage(mp);
+    }
+
+    let mut template = WorkBook::new_empty();
+    styles(&mut template, cm!(10), true);
+    template.push_sheet(Sheet::new("template"));
+    write_ods(&mut template, "test_out/import_automatic.ods")?;
+
+    let mut wb = WorkBook::new_empty();
+    styles(&mut wb, cm!(20), false);
+    wb.import_styles_from("test_out/import_automatic.ods")?;
+
+    // The automatic styles of the book are kept.
+    assert_eq!(
+        wb.pagestyle("pm1")
+            .expect("ps")
+            .style()
+            .attr("fo:page-width")
+            .map(String::as_str),
+        Some("20cm")
+    );
+    assert!(wb
+        .textstyle("T1")
+        .expect("ts")
+        .textstyle()
+        .attr("fo:font-style")
+        .is_none());
+    assert!(wb.pagestyle("pm1_2").is_some());
+    assert!(wb.textstyle("T1_2").is_some());
+
+    // The named master-page is replaced and uses the renamed ones.
+    let mp = wb.masterpage("Default").expect("mp");
+    assert_eq!(mp.pagestyle(), "pm1_2");
+    let head = mp.header().center()[0].to_string();
+    assert!(head.contains("\"T1_2\""), "{}", head);
+
+    Ok(())
+}
+
+#[test]
+fn test_rename_sheet() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+
+    let mut sheet = Sheet::new("Data");
+    sheet.set_value(0, 0, 1);
+    sheet.add_print_range(CellRange::remote("Data", 0, 0, 9, 1));
+    wb.push_sheet(sheet);
+
+    let mut sheet = Sheet::new("Sum");
+    sheet.set_formula(0, 0, "of:=SUM([$Data.$A$1:.$B$10])+[Data.A1]+[.A2]");
+    sheet.set_formula(1, 0, "of:=\"[Data.A1]\"");
+    wb.push_sheet(sheet);
+    wb.config_mut().active_table = "Data".to_string();
+
+    assert!(wb.rename_sheet(0, "sum").is_err());
+    assert_eq!(wb.sheet(0).name(), "Data");
+    wb.rename_sheet(0, "Data 2024")?;
+
+    assert_eq!(wb.sheet(0).name(), "Data 2024");
+    assert_eq!(
+        wb.sheet(1).formula(0, 0).map(String::as_str),
+        Some("of:=SUM([$'Data 2024'.$A$1:.$B$10])+['Data 2024'.A1]+[.A2]")
+    );
+    assert_eq!(
+        wb.sheet(1).formula(1, 0).map(String::as_str),
+        Some("of:=\"[Data.A1]\"")
+    );
+    assert_eq!(
+        wb.sheet(0).print_ranges(),
+        Some(&vec![CellRange::remote("Data 2024", 0, 0, 9, 1)])
+    );
+    assert_eq!(wb.config().active_table, "Data 2024");
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    assert_eq!(wb.sheet(0).name(), "Data 2024");
+    assert_eq!(
+        wb.sheet(1).formula(0, 0).map(String::as_str),
+        Some("of:=SUM([$'Data 2024'.$A$1:.$B$10])+['Data 2024'.A1]+[.A2]")
+    );
+    assert_eq!(
+        wb.sheet(0).print_ranges(),
+        Some(&vec![CellRange::remote("Data 2024", 0, 0, 9, 1)])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_remove_sheet_checked() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("Data"));
+    let mut sheet = Sheet::new("Sum");
+    sheet.set_formula(0, 0, "of:=SUM([Data.A1:.B10])+[.A2]");
+    sheet.set_formula(1, 0, "of:=[.A3]");
+    sheet.add_print_range(CellRange::remote("Data", 0, 0, 9, 1));
+    wb.push_sheet(sheet);
+
+    let mut wb2 = wb.clone();
+    assert!(wb2.remove_sheet_checked(0, DanglingRefs::Error).is_err());
+    assert_eq!(wb2.num_sheets(), 2);
+
+    let (sheet, refs) = wb2.remove_sheet_checked(0, DanglingRefs::Keep)?;
+    assert_eq!(sheet.name(), "Data");
+    assert_eq!(
+        refs,
+        vec![
+            DanglingRef::Formula(CellRef::remote("Sum", 0, 0)),
+            DanglingRef::PrintRange("Sum".to_string()),
+        ]
+    );
+    assert_eq!(
+        wb2.sheet(0).formula(0, 0).map(String::as_str),
+        Some("of:=SUM([Data.A1:.B10])+[.A2]")
+    );
+
+    let (_, refs) = wb.remove_sheet_checked(0, DanglingRefs::Invalidate)?;
+    assert_eq!(refs.len(), 2);
+    assert_eq!(
+        wb.sheet(0).formula(0, 0).map(String::as_str),
+        Some("of:=SUM(#REF!)+[.A2]")
+    );
+    assert_eq!(
+        wb.sheet(0).formula(1, 0).map(String::as_str),
+        Some("of:=[.A3]")
+    );
+    assert_eq!(wb.sheet(0).print_ranges(), None);
+
+    let (_, refs) = wb.remove_sheet_checked(0, DanglingRefs::Error)?;
+    assert!(refs.is_empty());
+    assert_eq!(wb.num_sheets(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_namespaces() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+    wb.add_namespace("ext", "urn:example:old");
+    wb.add_namespace("ext", "urn:example:ext");
+    wb.add_namespace("office", "urn:example:office");
+    wb.add_namespace("gone", "urn:example:gone");
+    wb.remove_namespace("gone");
+
+    let buf = write_ods_buf_uncompressed(&mut wb, Vec::new())?;
+    let content = String::from_utf8_lossy(&buf);
+    assert_eq!(content.matches(r#"xmlns:ext="urn:example:ext""#).count(), 2);
+    assert!(!content.contains("urn:example:office"));
+    assert!(!content.contains("gone"));
+
+    let wb = read_ods_buf(&buf)?;
+    assert!(wb
+        .namespaces()
+        .iter()
+        .any(|(p, u)| p == "ext" && u == "urn:example:ext"));
+    assert!(!wb.namespaces().iter().any(|(p, _)| p == "office"));
+
+    Ok(())
+}
+
+#[test]
+fn test_view_presets() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    wb.push_sheet(Sheet::new("1"));
+    *wb.config_mut() = WorkBookConfig::formula_view();
+    *wb.sheet_mut(0).config_mut() = SheetConfig::zoom(150);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    assert!(wb.config().show_formulas);
+    assert!(!wb.config().show_page_break_preview);
+    assert_eq!(wb.sheet(0).config().zoom_value, 150);
+
+    let mut wb = wb.clone();
+    *wb.config_mut() = WorkBookConfig::print_preview();
+    *wb.sheet_mut(0).config_mut() = SheetConfig::print_preview();
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    assert!(!wb.config().show_formulas);
+    assert!(wb.config().show_page_break_preview);
+    assert!(!wb.sheet(0).config().show_grid);
+
+    Ok(())
+}
@@ -2,9 +2,11 @@ use std::fs::File;
 use std::io::{Read, Write};
 use std::path::Path;
 
+use chrono::Duration;
 use spreadsheet_ods::{
-    read_ods, read_ods_buf, write_ods, write_ods_buf, OdsError, Sheet, SplitMode, ValueType,
-    WorkBook,
+    format_iso_duration, parse_iso_duration, read_ods, read_ods_buf, read_ods_content,
+    read_ods_styles, write_ods, write_ods_buf, CellRange, CellStyle, NumberPolicy, OdsError, Sheet,
+    SplitMode, Value, ValueType, WorkBook,
 };
 use std::time::Instant;
 
@@ -27,6 +29,31 @@ fn test_write_read() -> Result<(), OdsError> {
     Ok(())
 }
 
+#[test]
+fn test_write_read_duration() -> Result<(), OdsError> {
+    let d0 = Duration::hours(26) + Duration::microseconds(1_000_005);
+    let d1 = -Duration::milliseconds(90_005);
+
+    assert_eq!(format_iso_duration(&d0), "PT26H0M1.000005S");
+    assert_eq!(format_iso_duration(&d1), "-PT0H1M30.005S");
+    assert_eq!(parse_iso_duration(&format_iso_duration(&d0))?, d0);
+    assert_eq!(parse_iso_duration(&format_iso_duration(&d1))?, d1);
+
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, d0);
+    sh.set_value(1, 0, d1);
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+
+    assert_eq!(wb.sheet(0).value(0, 0).as_timeduration_opt(), Some(d0));
+    assert_eq!(wb.sheet(0).value(1, 0).as_timeduration_opt(), Some(d1));
+
+    Ok(())
+}
+
 #[test]
 fn read_text() -> Result<(), OdsError> {
     let wb = read_ods("tests/text.ods")?;
@@ -144,3 +171,129 @@ fn test_read_buf() -> Result<(), OdsError> {
     write_ods(&mut wb, "test_out/orders.ods")?;
     Ok(())
 }
+
+#[test]
+fn test_read_content_styles() -> Result<(), OdsError> {
+    let mut zip = zip::ZipArchive::new(File::open("tests/orders.ods")?)?;
+
+    let mut buf = Vec::new();
+    zip.by_name("content.xml")?.read_to_end(&mut buf)?;
+    let wb = read_ods_content(&buf)?;
+    assert!(wb.num_sheets() > 0);
+
+    let mut buf = Vec::new();
+    zip.by_name("styles.xml")?.read_to_end(&mut buf)?;
+    let wb = read_ods_styles(&buf)?;
+    assert_eq!(wb.num_sheets(), 0);
+
+    // Garbage must not panic.
+    let _ = read_ods_content(b"<office:document-content><table:table>");
+    let _ = read_ods_content(&[0xff, 0xfe, 0x00]);
+
+    Ok(())
+}
+
+#[test]
+fn test_write_read_metadata() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, "A");
+    sh.set_metadata(0, 0, "source-id", "rec<4711>");
+    sh.set_metadata(0, 0, "batch", "7");
+    sh.set_metadata(1, 1, "empty_cell", "yes");
+    wb.push_sheet(sh);
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let mut wb = read_ods_buf(&buf)?;
+
+    let sh = wb.sheet_mut(0);
+    assert_eq!(
+        sh.metadata(0, 0, "source-id").map(String::as_str),
+        Some("rec<4711>")
+    );
+    assert_eq!(sh.metadata(0, 0, "batch").map(String::as_str), Some("7"));
+    assert_eq!(
+        sh.metadata(1, 1, "empty_cell").map(String::as_str),
+        Some("yes")
+    );
+    assert_eq!(sh.clear_metadata(0, 0, "batch").as_deref(), Some("7"));
+    assert_eq!(sh.metadata(0, 0, "batch"), None);
+
+    sh.set_metadata(0, 0, "no:prefix", "x");
+    assert!(write_ods_buf(&mut wb, Vec::new()).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_write_read_protection() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let bold = wb.add_cellstyle(CellStyle::new_empty());
+
+    let mut sh = Sheet::new("1");
+    sh.set_protected(true);
+    sh.set_protection_key(
+        "jZAbZcycnkXwB6cmbsIeTGKzPlUtQvYwW3bnNu0tipU=",
+        "http://www.w3.org/2000/09/xmldsig#sha256",
+    );
+    sh.set_formula(0, 2, "of:=SUM([.A1:.B2])");
+    sh.set_styled_value(0, 0, 1, &bold);
+    wb.push_sheet(sh);
+
+    wb.set_editable_range(0, &CellRange::local(0, 0, 1, 1));
+
+    let wb = read_ods_buf(&write_ods_buf(&mut wb, Vec::new())?)?;
+    let sh = wb.sheet(0);
+    assert!(sh.protected());
+    assert_eq!(
+        sh.protection_key().unwrap(),
+        "jZAbZcycnkXwB6cmbsIeTGKzPlUtQvYwW3bnNu0tipU="
+    );
+    assert_eq!(
+        sh.protection_key_digest().unwrap(),
+        "http://www.w3.org/2000/09/xmldsig#sha256"
+    );
+
+    let style = format!("{}_editable", bold);
+    assert_eq!(sh.cellstyle(0, 0).unwrap().as_str(), style);
+    assert_eq!(sh.cellstyle(1, 1).unwrap().as_str(), "Default_editable");
+    assert!(sh.cellstyle(0, 2).is_none());
+    assert_eq!(
+        wb.cellstyle(&style)
+            .unwrap()
+            .cellstyle()
+            .attr("style:cell-protect")
+            .unwrap(),
+        "none"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_write_number_policy() -> Result<(), OdsError> {
+    let mut wb = WorkBook::new_empty();
+    let mut sh = Sheet::new("1");
+    sh.set_value(0, 0, 0.1 + 0.2);
+    sh.set_value(1, 0, 0.125);
+    sh.set_value(2, 0, 9.996);
+    sh.set_value(3, 0, Value::Percentage(0.33333333));
+    sh.set_value(4, 0, 0.125);
+    sh.set_number_policy(4, 0, NumberPolicy::Significant(2));
+    sh.set_value(5, 0, 0.135);
+    wb.push_sheet(sh);
+    wb.set_number_policy(NumberPolicy::SignificantEven(2));
+
+    let buf = write_ods_buf(&mut wb, Vec::new())?;
+    let wb = read_ods_buf(&buf)?;
+    let sh = wb.sheet(0);
+
+    assert_eq!(sh.value(0, 0).as_f64_or(0.0), 0.3);
+    assert_eq!(sh.value(1, 0).as_f64_or(0.0), 0.12);
+    assert_eq!(sh.value(2, 0).as_f64_or(0.0), 10.0);
+    assert_eq!(sh.value(3, 0).as_f64_or(0.0), 0.33);
+    assert_eq!(sh.value(4, 0).as_f64_or(0.0), 0.13);
+    assert_eq!(sh.value(5, 0).as_f64_or(0.0), 0.14);
+
+    Ok(())
+}